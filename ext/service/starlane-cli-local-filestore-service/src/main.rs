@@ -1,6 +1,6 @@
 use clap::Parser;
-use starlane::executor::dialect::filestore::{FileStoreCli, RootDir, FILE_STORE_ROOT};
-use starlane::executor::dialect::filestore::FileStoreCommand;
+use starlane_filestore_protocol::{FileStoreCli, RootDir, RootDirErr, FILE_STORE_ROOT};
+use starlane_filestore_protocol::FileStoreCommand;
 use std::fs::File;
 use std::io::{Read, Write};
 use std::path::{absolute, PathBuf, StripPrefixError};
@@ -8,7 +8,6 @@ use std::{env, fs, io};
 use std::env::VarError;
 use std::process::{ExitCode, Termination};
 use thiserror::Error;
-use starlane::err::HypErr;
 
 
 
@@ -125,7 +124,7 @@ pub enum Error{
     #[error("{0}")]
     VarError(#[from] VarError),
     #[error("{0}")]
-    ThisErr(#[from] HypErr)
+    RootDir(#[from] RootDirErr)
 }
 
 impl From<String> for Error {
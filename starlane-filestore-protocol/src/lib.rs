@@ -0,0 +1,117 @@
+//! the side of the FileStore dialect a service implements.  Shared by starlane and
+//! the filestore service, which is also built for `wasm32-wasip1`
+
+use clap::{Parser, Subcommand};
+use path_clean::PathClean;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf, StripPrefixError};
+use strum_macros::EnumString;
+use thiserror::Error;
+
+/// the directory the service stores to
+pub const FILE_STORE_ROOT: &str = "FILE_STORE_ROOT";
+
+#[derive(Clone, Debug, Parser)]
+#[command(version, about, long_about = None)]
+pub struct FileStoreCli {
+    #[command(subcommand)]
+    pub command: FileStoreCommand,
+}
+
+#[derive(Clone, Debug, Subcommand, EnumString, strum_macros::Display)]
+pub enum FileStoreCommand {
+    Init,
+    Write { path: PathBuf },
+    Read { path: PathBuf },
+    Mkdir { path: PathBuf },
+    Remove { path: PathBuf },
+    List { path: PathBuf },
+    Exists { path: PathBuf },
+    Pwd,
+}
+
+impl FileStoreCli {
+    pub fn new(command: FileStoreCommand) -> Self {
+        FileStoreCli { command }
+    }
+}
+
+impl From<FileStoreCli> for Vec<String> {
+    fn from(cli: FileStoreCli) -> Self {
+        match &cli.command {
+            FileStoreCommand::Init => vec!["init".to_string()],
+            FileStoreCommand::Write { path } => {
+                vec!["write".to_string(), to_str(path)]
+            }
+            FileStoreCommand::Read { path } => {
+                vec!["read".to_string(), to_str(path)]
+            }
+            FileStoreCommand::Mkdir { path } => {
+                vec!["mkdir".to_string(), to_str(path)]
+            }
+            FileStoreCommand::Remove { path } => {
+                vec!["remove".to_string(), to_str(path)]
+            }
+            FileStoreCommand::List { path } => {
+                vec!["list".to_string(), to_str(path)]
+            }
+            FileStoreCommand::Exists { path } => {
+                vec!["exists".to_string(), to_str(path)]
+            }
+            FileStoreCommand::Pwd => {
+                vec!["pwd".to_string()]
+            }
+        }
+    }
+}
+
+pub fn to_str(path: &Path) -> String {
+    path.to_str().unwrap().to_string()
+}
+
+impl Display for FileStoreCli {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.command.fmt(f)
+    }
+}
+
+pub struct RootDir {
+    root: PathBuf,
+}
+
+impl RootDir {
+    pub fn new(root: PathBuf) -> Self {
+        let root = root.clean();
+        Self { root }
+    }
+}
+
+impl RootDir {
+    pub fn norm(&self, sub_path: &PathBuf) -> Result<PathBuf, RootDirErr> {
+        let sub_path = sub_path.clean();
+
+        let path: PathBuf = match sub_path.starts_with("/") {
+            true => sub_path.strip_prefix("/")?.into(),
+            false => sub_path.clone(),
+        };
+        let normed: PathBuf = self.root.join(path).clean();
+        let parent = match normed.parent() {
+            None => PathBuf::from("/"),
+            Some(parent) => parent.into(),
+        };
+
+        if !parent.starts_with(&self.root) {
+            return Err(RootDirErr::PathEscapesFileStoreBoundary(sub_path));
+        }
+
+        Ok(normed)
+    }
+}
+
+#[derive(Error, Debug, Clone)]
+pub enum RootDirErr {
+    #[error("path '{0}' escapes FileStore boundaries")]
+    PathEscapesFileStoreBoundary(PathBuf),
+    #[error("could not strip prefix of path '{0}'")]
+    StripPrefixError(#[from] StripPrefixError),
+}
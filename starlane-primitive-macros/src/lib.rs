@@ -1,7 +1,6 @@
 /*#![feature(proc_macro_quote)]*/
 #![crate_type = "lib"]
 #![allow(warnings)]
-#[feature("proc_macro_lib2")]
 #[macro_use]
 extern crate quote;
 
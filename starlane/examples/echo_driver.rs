@@ -0,0 +1,31 @@
+//! a reference driver process for `starlane::hyperspace::driver::process`.
+//!
+//! It reads length prefixed [Wave] frames from stdin, encoded as named by
+//! `DRIVER_CODEC`, and reflects each [DirectedWave] on stdout with the body it was sent.
+//! That acknowledges the `Hyp<Init>` and `Hyp<Assign>` handshakes too.  A driver
+//! in another language does the same: a big endian `u32` length then the payload
+//!
+//! Waves can go the other way as well.  A process may write a [DirectedWave] of its
+//! own to stdout.  The driver sends it from the driver's point and writes the
+//! reflections back to stdin.  This driver never does, so `serve` skips them.
+//!
+//! [Wave]: starlane::space::wave::Wave
+//! [DirectedWave]: starlane::space::wave::DirectedWave
+use starlane::hyperspace::driver::process::{serve, WaveCodec, DRIVER_CODEC_ENV};
+use starlane::space::err::SpaceErr;
+use starlane::space::wave::core::ReflectedCore;
+use std::str::FromStr;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<SpaceErr>> {
+    let codec = std::env::var(DRIVER_CODEC_ENV)
+        .ok()
+        .and_then(|codec| WaveCodec::from_str(codec.as_str()).ok())
+        .unwrap_or_default();
+    eprintln!("echo driver speaking {}", codec);
+    serve(tokio::io::stdin(), tokio::io::stdout(), codec, |wave| {
+        ReflectedCore::ok_body(wave.core().body.clone())
+    })
+    .await
+    .map_err(Box::new)
+}
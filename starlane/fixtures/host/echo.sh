@@ -0,0 +1,8 @@
+#!/bin/sh
+# a minimal host executable: answers every request line with its own body
+# and notes each request on stderr
+while IFS= read -r line; do
+  id=$(printf '%s\n' "$line" | sed -n 's/^{"id":\([0-9]*\),.*/\1/p')
+  echo "request $id" >&2
+  printf '%s\n' "$line" | sed 's/^{"id":\([0-9]*\),"method":"[^"]*","uri":"[^"]*",/{"id":\1,"status":200,/'
+done
@@ -0,0 +1,24 @@
+;; a minimal mechtron guest: copies stdin to stdout so whatever body the host
+;; delivers is reflected back unchanged
+;;
+;; rebuild `echo.wasm` with: wat2wasm echo.wat -o echo.wasm
+(module
+  (import "wasi_snapshot_preview1" "fd_read"
+    (func $fd_read (param i32 i32 i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "fd_write"
+    (func $fd_write (param i32 i32 i32 i32) (result i32)))
+  (memory (export "memory") 1)
+
+  ;; iovec at 0 (buf ptr, len), read/write counts at 8 and 12, buffer at 16
+  (func (export "_start")
+    (i32.store (i32.const 0) (i32.const 16))
+    (block $done
+      (loop $copy
+        (i32.store (i32.const 4) (i32.const 4096))
+        (br_if $done
+          (call $fd_read (i32.const 0) (i32.const 0) (i32.const 1) (i32.const 8)))
+        (br_if $done (i32.eqz (i32.load (i32.const 8))))
+        (i32.store (i32.const 4) (i32.load (i32.const 8)))
+        (br_if $done
+          (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 12)))
+        (br $copy)))))
@@ -1,5 +1,11 @@
-use crate::env::STARLANE_HOME;
-use crate::hyperspace::hyperlane::tcp::HyperlaneTcpClient;
+use crate::env::{context, context_dir, server_certs_dir, tokens_path, STARLANE_HOME};
+use crate::hyperspace::hyperlane::tcp::{
+    cert_info, parse_certs, CertGenerator, CertPaths, Heartbeat, HyperlaneTcpClient,
+};
+use crate::hyperspace::hyperlane::token::TokenStore;
+use crate::hyperspace::hyperlane::capture::{
+    self, CapturePair, CaptureReader, CaptureWriter, CapturedWave,
+};
 use crate::hyperspace::hyperlane::HyperwayEndpointFactory;
 use clap::clap_derive::{Args, Subcommand};
 use clap::Parser;
@@ -7,13 +13,17 @@ use crate::hyperspace::driver::control::{ControlCliSession, ControlClient};
 use starlane_primitive_macros::logger;
 use crate::space::command::{CmdTransfer, RawCommand};
 use crate::space::err::SpaceErr;
-use crate::space::hyper::Knock;
+use crate::space::hyper::{InterchangeKind, Knock};
+use crate::space::loc::ToSurface;
 use crate::space::parse::util::result;
 use crate::space::parse::{upload_blocks, SkewerCase};
 use crate::space::point::Point;
+use crate::space::selector::Selector;
 use crate::space::parse::util::new_span;
-use crate::space::substance::Substance;
+use crate::space::substance::{Substance, Token};
 use crate::space::wave::core::ReflectedCore;
+use crate::server::ClientAuthConfig;
+use anyhow::anyhow;
 use std::fs::File;
 use std::io::{Cursor, Read, Seek, Write};
 use std::path::Path;
@@ -21,6 +31,8 @@ use std::str::FromStr;
 use std::time::Duration;
 use strum_macros::EnumString;
 use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+use std::net::{IpAddr, SocketAddr};
 use walkdir::{DirEntry, WalkDir};
 use zip::write::FileOptions;
 
@@ -53,6 +65,154 @@ pub enum Commands {
         all: bool,
     },
     Context(ContextArgs),
+    #[strum(disabled)]
+    Certs(CertsArgs),
+    #[strum(disabled)]
+    Token(TokenArgs),
+    #[strum(disabled)]
+    Capture(CaptureArgs),
+    #[strum(disabled)]
+    Replay(ReplayArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct CertsArgs {
+    #[clap(subcommand)]
+    pub command: CertsCmd,
+}
+
+#[derive(Debug, Subcommand, EnumString, strum_macros::Display)]
+pub enum CertsCmd {
+    /// issue a client certificate for mutual TLS that authenticates as `agent`
+    Client {
+        #[arg(long)]
+        agent: String,
+        /// subject name of the certificate (defaults to the agent point)
+        #[arg(long)]
+        name: Option<String>,
+        /// directory to write the certificate and key to
+        #[arg(long)]
+        out: Option<String>,
+    },
+    /// replace the control port certificate with a newly generated one
+    Rotate,
+    /// print the subject, names and expiry of a certificate
+    Show {
+        /// certificate file or directory (defaults to the control port certificate)
+        cert: Option<String>,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct TokenArgs {
+    #[clap(subcommand)]
+    pub command: TokenCmd,
+}
+
+#[derive(Debug, Subcommand, EnumString, strum_macros::Display)]
+pub enum TokenCmd {
+    /// issue a token that authenticates control connections as `agent`
+    Create {
+        #[arg(long)]
+        agent: String,
+        /// lifetime of the token i.e. `30m`, `12h`, `7d` (never expires if omitted)
+        #[arg(long)]
+        ttl: Option<String>,
+    },
+    List {
+        #[arg(long)]
+        agent: Option<String>,
+    },
+    /// revoke a single token or every token issued to `agent`
+    Revoke {
+        token: Option<String>,
+        #[arg(long)]
+        agent: Option<String>,
+    },
+}
+
+/// how to reach the control port of a running machine
+#[derive(Debug, Args)]
+pub struct ConnectArgs {
+    #[arg(long)]
+    host: Option<String>,
+
+    /// directory holding the certificate of the machine
+    #[arg(long)]
+    certs: Option<String>,
+
+    /// directory holding a client certificate issued by `starlane certs client`
+    #[arg(long)]
+    client_cert: Option<String>,
+
+    /// token issued by `starlane token create`
+    #[arg(long)]
+    token: Option<String>,
+
+    /// seconds between heartbeats
+    #[arg(long)]
+    heartbeat: Option<u64>,
+
+    /// seconds without anything from the machine before the connection is dropped
+    #[arg(long)]
+    idle_timeout: Option<u64>,
+}
+
+impl ConnectArgs {
+    fn tcp_client(self, kind: InterchangeKind) -> Result<HyperlaneTcpClient, SpaceErr> {
+        let host = self.host.unwrap_or("localhost".to_string());
+        let certs = self.certs.unwrap_or(server_certs_dir());
+        let logger = logger!(Point::from_str("starlane-cli")?);
+        let mut knock = Knock::default();
+        knock.kind = kind;
+        knock.remote = Some(Point::from_str("starlane-cli")?.to_surface());
+        if let Some(token) = self.token {
+            knock.auth = Box::new(Substance::Token(Token::new(token)));
+        }
+        let mut tcp_client =
+            HyperlaneTcpClient::new(control_addr(&host), certs, knock, false, logger)
+                .with_heartbeat(Heartbeat::from_secs(self.heartbeat, self.idle_timeout));
+        if let Some(client_cert) = self.client_cert {
+            tcp_client = tcp_client.with_client_cert(client_cert);
+        }
+        Ok(tcp_client)
+    }
+}
+
+#[derive(Debug, Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct CaptureArgs {
+    #[clap(subcommand)]
+    pub command: Option<CaptureCmd>,
+
+    /// only record waves from or to a point matching this selector
+    #[arg(long)]
+    point: Option<String>,
+
+    /// file to write the capture to
+    #[arg(long)]
+    out: Option<String>,
+
+    #[command(flatten)]
+    connect: ConnectArgs,
+}
+
+#[derive(Debug, Subcommand, EnumString, strum_macros::Display)]
+pub enum CaptureCmd {
+    /// print the directed waves of a capture each followed by its reflections
+    Show { trace: String },
+}
+
+#[derive(Debug, Args)]
+pub struct ReplayArgs {
+    trace: String,
+
+    /// wait between waves as long as was originally recorded
+    #[arg(long)]
+    paced: bool,
+
+    #[command(flatten)]
+    connect: ConnectArgs,
 }
 
 #[derive(Debug, Args)]
@@ -87,6 +247,22 @@ pub struct TermArgs {
 
     #[arg(long)]
     history_log: Option<String>,
+
+    /// directory holding a client certificate issued by `starlane certs client`
+    #[arg(long)]
+    client_cert: Option<String>,
+
+    /// token issued by `starlane token create`
+    #[arg(long)]
+    token: Option<String>,
+
+    /// seconds between heartbeats
+    #[arg(long)]
+    heartbeat: Option<u64>,
+
+    /// seconds without anything from the machine before the session reconnects
+    #[arg(long)]
+    idle_timeout: Option<u64>,
 }
 
 impl Default for TermArgs {
@@ -95,6 +271,10 @@ impl Default for TermArgs {
             host: None,
             certs: None,
             history_log: None,
+            client_cert: None,
+            token: None,
+            heartbeat: None,
+            idle_timeout: None,
         }
     }
 }
@@ -106,7 +286,7 @@ pub async fn term(args: TermArgs) -> Result<(), SpaceErr> {
     };
 
     let certs = match args.certs.as_ref() {
-        None => format!("{}/localhost/certs", STARLANE_HOME.to_string()),
+        None => server_certs_dir(),
         Some(certs) => certs.clone(),
     };
 
@@ -115,7 +295,10 @@ pub async fn term(args: TermArgs) -> Result<(), SpaceErr> {
         Some(host) => host.clone(),
     };
 
-    let session = Session::new(host, certs).await?;
+    let token = args.token.map(Token::new);
+
+    let heartbeat = Heartbeat::from_secs(args.heartbeat, args.idle_timeout);
+    let session = Session::new(host, certs, args.client_cert, token, heartbeat).await?;
 
     let mut rl = rustyline::DefaultEditor::new().unwrap();
     rl.add_history_entry(history_log.as_str());
@@ -137,21 +320,292 @@ pub async fn term(args: TermArgs) -> Result<(), SpaceErr> {
     }
 }
 
+pub async fn certs(args: CertsArgs) -> Result<(), anyhow::Error> {
+    match args.command {
+        CertsCmd::Client { agent, name, out } => {
+            let agent = Point::from_str(agent.as_str())?;
+            let name = name.unwrap_or(agent.to_string());
+            let mut config = crate::env::config()?.ok_or(anyhow!(
+                "no starlane config found. please run `starlane install`"
+            ))?;
+
+            let client_auth = config.client_auth.get_or_insert_with(|| {
+                ClientAuthConfig::new(format!("{}/certs/client-ca", context_dir()))
+            });
+
+            let ca = match CertGenerator::read_from_dir(client_auth.ca_dir.clone()).await {
+                Ok(ca) => ca,
+                Err(_) => {
+                    let ca = CertGenerator::gen_ca(format!("starlane {} client ca", context()))?;
+                    ca.write_pem(&CertPaths::in_dir(client_auth.ca_dir.clone()))
+                        .await?;
+                    ca
+                }
+            };
+
+            let out = out.unwrap_or(format!(
+                "{}/certs/clients/{}",
+                context_dir(),
+                name.replace(":", "_")
+            ));
+            CertGenerator::gen_client(name.clone(), &ca)?
+                .write_pem(&CertPaths::in_dir(out.clone()))
+                .await?;
+
+            client_auth.agents.insert(name.clone(), agent.clone());
+            crate::env::config_save(config)?;
+
+            println!(
+                "client certificate '{}' for agent '{}' written to '{}'",
+                name,
+                agent.to_string(),
+                out
+            );
+            Ok(())
+        }
+        CertsCmd::Rotate => {
+            let config = crate::env::config()?.unwrap_or_default();
+            let info = config.tls.rotate().await?;
+            println!(
+                "certificate '{}' rotated. it expires on {}",
+                config.tls.paths()?.cert,
+                info.not_after.to_rfc3339()
+            );
+            println!("restart starlane for the control port to present the new certificate");
+            Ok(())
+        }
+        CertsCmd::Show { cert } => {
+            let path = match cert {
+                None => crate::env::config()?.unwrap_or_default().tls.paths()?.cert,
+                Some(cert) if std::path::Path::new(&cert).is_dir() => {
+                    CertPaths::in_dir(cert).cert
+                }
+                Some(cert) => cert,
+            };
+            let certs = parse_certs(&std::fs::read(path.as_str())?)?;
+            for der in certs {
+                let info = cert_info(&der).map_err(|err| anyhow!(err.to_string()))?;
+                let expires = if info.is_expired() {
+                    "expired".to_string()
+                } else {
+                    format!("{} days remaining", info.remaining().num_days())
+                };
+                println!("certificate: {}", path);
+                println!("subject:     {}", info.subject);
+                println!("issuer:      {}", info.issuer);
+                println!("names:       {}", info.subject_alt_names.join(", "));
+                println!("not before:  {}", info.not_before.to_rfc3339());
+                println!("not after:   {} ({})", info.not_after.to_rfc3339(), expires);
+            }
+            Ok(())
+        }
+    }
+}
+
+pub async fn token(args: TokenArgs) -> Result<(), anyhow::Error> {
+    let store = TokenStore::new(tokens_path());
+    match args.command {
+        TokenCmd::Create { agent, ttl } => {
+            let agent = Point::from_str(agent.as_str())?;
+            let ttl = match ttl {
+                None => None,
+                Some(ttl) => Some(parse_ttl(ttl.as_str())?),
+            };
+            let record = store.create(agent, ttl)?;
+            println!("{}", record.token.to_string());
+        }
+        TokenCmd::List { agent } => {
+            let agent = match agent {
+                None => None,
+                Some(agent) => Some(Point::from_str(agent.as_str())?),
+            };
+            for record in store.list()? {
+                if agent.as_ref().map(|a| *a != record.agent).unwrap_or(false) {
+                    continue;
+                }
+                let expires = match &record.expires {
+                    None => "never".to_string(),
+                    Some(_) if record.is_expired() => "expired".to_string(),
+                    Some(expires) => expires.to_rfc3339(),
+                };
+                println!(
+                    "{}\t{}\t{}",
+                    record.token.to_string(),
+                    record.agent.to_string(),
+                    expires
+                );
+            }
+        }
+        TokenCmd::Revoke { token, agent } => {
+            if token.is_none() && agent.is_none() {
+                return Err(anyhow!("expected a token or --agent to revoke"));
+            }
+            let token = token.map(Token::new);
+            let agent = match agent {
+                None => None,
+                Some(agent) => Some(Point::from_str(agent.as_str())?),
+            };
+            let revoked = store.revoke(token.as_ref(), agent.as_ref())?;
+            println!("{} token(s) revoked", revoked);
+        }
+    }
+    Ok(())
+}
+
+/// records the waves routed by a running machine until interrupted
+pub async fn capture(args: CaptureArgs) -> Result<(), anyhow::Error> {
+    if let Some(CaptureCmd::Show { trace }) = args.command {
+        return capture_show(trace).await;
+    }
+
+    let out = args
+        .out
+        .ok_or(anyhow!("expected --out <file> to write the capture to"))?;
+    let selector = match args.point {
+        None => None,
+        Some(point) => Some(Selector::from_str(point.as_str())?),
+    };
+
+    let client = args.connect.tcp_client(InterchangeKind::Capture)?;
+    let (status_tx, mut status_rx) = mpsc::channel(32);
+    tokio::spawn(async move { while let Some(_) = status_rx.recv().await {} });
+    let mut endpoint = client.create(status_tx).await?;
+    let mut writer = CaptureWriter::create(out.as_str()).await?;
+
+    println!("capturing to '{}' (ctrl-c to stop)", out);
+    let mut count = 0usize;
+    loop {
+        tokio::select! {
+            wave = endpoint.rx.recv() => match wave {
+                None => break,
+                Some(wave) => {
+                    let captured = CapturedWave::new(wave);
+                    if selector.as_ref().map(|s| captured.is_match(s)).unwrap_or(true) {
+                        writer.write(&captured).await?;
+                        count += 1;
+                    }
+                }
+            },
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+    writer.flush().await?;
+    println!("captured {} waves", count);
+    Ok(())
+}
+
+async fn capture_show(trace: String) -> Result<(), anyhow::Error> {
+    let captures = CaptureReader::open(trace.as_str()).await?.read_all().await?;
+    for pair in CapturePair::pairs(&captures) {
+        match &pair.directed {
+            Some(directed) => {
+                let wave = directed.inner();
+                println!(
+                    "{} {} {} -> {} {}",
+                    directed.timestamp.to_rfc3339(),
+                    wave.id(),
+                    wave.from().to_string(),
+                    wave.to().to_string(),
+                    wave.desc()
+                );
+            }
+            None => println!("<directed wave was not captured>"),
+        }
+        for reflected in pair.reflected.iter() {
+            let wave = reflected.inner();
+            let elapsed = match &pair.directed {
+                Some(directed) => format!(
+                    "+{}ms",
+                    (reflected.timestamp - directed.timestamp).num_milliseconds()
+                ),
+                None => reflected.timestamp.to_rfc3339(),
+            };
+            println!(
+                "    {} {} {} -> {} {}",
+                elapsed,
+                wave.id(),
+                wave.from().to_string(),
+                wave.to().to_string(),
+                wave.desc()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// sends the directed waves of a capture to a running machine
+pub async fn replay(args: ReplayArgs) -> Result<(), anyhow::Error> {
+    let captures = CaptureReader::open(args.trace.as_str())
+        .await?
+        .read_all()
+        .await?;
+    let client = args.connect.tcp_client(InterchangeKind::DefaultControl)?;
+    let sent = capture::replay(captures, &client, args.paced).await?;
+    println!("replayed {} waves", sent);
+    Ok(())
+}
+
+/// the control port of `host`, which may be a name or an IPv4 or IPv6 address
+fn control_addr(host: &String) -> String {
+    match IpAddr::from_str(host.as_str()) {
+        Ok(ip) => SocketAddr::new(ip, 4343).to_string(),
+        Err(_) => format!("{}:{}", host, 4343),
+    }
+}
+
+/// parses a ttl such as `90s`, `30m`, `12h` or `7d`.  A number without a unit is seconds
+fn parse_ttl(ttl: &str) -> Result<Duration, anyhow::Error> {
+    let ttl = ttl.trim();
+    let split = ttl
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(ttl.len());
+    let (value, unit) = ttl.split_at(split);
+    let value: u64 = value
+        .parse()
+        .map_err(|_| anyhow!("invalid ttl '{}'", ttl))?;
+    let scale: u64 = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 60 * 60 * 24,
+        _ => return Err(anyhow!("invalid ttl unit '{}' expected one of: s, m, h, d", unit)),
+    };
+    let secs = value
+        .checked_mul(scale)
+        .ok_or(anyhow!("ttl '{}' is too large", ttl))?;
+    Ok(Duration::from_secs(secs))
+}
+
 pub struct Session {
     pub client: ControlClient,
     pub cli: ControlCliSession,
 }
 
 impl Session {
-    pub async fn new(host: String, certs: String) -> Result<Self, SpaceErr> {
+    pub async fn new(
+        host: String,
+        certs: String,
+        client_cert: Option<String>,
+        token: Option<Token>,
+        heartbeat: Heartbeat,
+    ) -> Result<Self, SpaceErr> {
         let logger = logger!(Point::from_str("starlane-cli")?);
-        let tcp_client: Box<dyn HyperwayEndpointFactory> = Box::new(HyperlaneTcpClient::new(
-            format!("{}:{}", host, 4343),
+        let mut knock = Knock::default();
+        if let Some(token) = token {
+            knock.auth = Box::new(Substance::Token(token));
+        }
+        let mut tcp_client = HyperlaneTcpClient::new(
+            control_addr(&host),
             certs,
-            Knock::default(),
+            knock,
             false,
             logger,
-        ));
+        )
+        .with_heartbeat(heartbeat);
+        if let Some(client_cert) = client_cert {
+            tcp_client = tcp_client.with_client_cert(client_cert);
+        }
+        let tcp_client: Box<dyn HyperwayEndpointFactory> = Box::new(tcp_client);
 
         let client = ControlClient::new(tcp_client)?;
 
@@ -301,3 +755,18 @@ where
     let result = zip.finish()?;
     Result::Ok(result)
 }
+
+#[cfg(test)]
+pub mod test {
+    use crate::cli::parse_ttl;
+    use std::time::Duration;
+
+    #[test]
+    pub fn test_parse_ttl() {
+        assert_eq!(Duration::from_secs(90), parse_ttl("90").unwrap());
+        assert_eq!(Duration::from_secs(2 * 60 * 60), parse_ttl("2h").unwrap());
+        assert_eq!(Duration::from_secs(7 * 24 * 60 * 60), parse_ttl("7d").unwrap());
+        assert!(parse_ttl("3w").is_err());
+        assert!(parse_ttl(format!("{}d", u64::MAX / 2).as_str()).is_err());
+    }
+}
@@ -13,6 +13,7 @@ use std::sync::Arc;
 use uuid::Uuid;
 use crate::hyperspace::err::HypErr;
 use crate::hyperspace::shutdown::panic_shutdown;
+use crate::hyperspace::topology::TopologyConfig;
 use crate::server::StarlaneConfig;
 
 pub fn context() -> String {
@@ -130,6 +131,34 @@ pub fn config_path_context(context: String) -> String {
     format!("{}/{}/config.yaml", STARLANE_HOME.as_str(), context).to_string()
 }
 
+/// the optional topology of this machine's stars, see [TopologyConfig]
+pub fn topology_path() -> String {
+    format!("{}/topology.yaml", context_dir()).to_string()
+}
+
+pub fn topology() -> Result<Option<TopologyConfig>, anyhow::Error> {
+    let file = topology_path();
+    if !fs::exists(file.clone())? {
+        return Ok(None);
+    }
+    let topology = std::fs::read_to_string(file.clone())?;
+    let topology: TopologyConfig = serde_yaml::from_str(topology.as_str())
+        .map_err(|err| anyhow!("could not process topology '{}': '{}'", file, err))?;
+    topology
+        .validate()
+        .map_err(|err| anyhow!("topology '{}' is invalid: {}", file, err.to_string()))?;
+    Ok(Some(topology))
+}
+
+pub fn tokens_path() -> String {
+    format!("{}/tokens.yaml", context_dir()).to_string()
+}
+
+/// where the control port certificate is generated when the config does not name one
+pub fn server_certs_dir() -> String {
+    format!("{}/certs/server", context_dir()).to_string()
+}
+
 pub fn config_exists(context: String) -> bool {
     fs::exists(config_path_context(context)).unwrap_or(false)
 }
@@ -144,7 +173,13 @@ pub fn config() -> Result<Option<StarlaneConfig>, HypErr> {
 
             let mut config: StarlaneConfig  = serde_yaml::from_str(config.as_str()).map_err(|err| anyhow!("starlane config found: '{}' yet Starlane encountered an error when attempting to process the config: '{}'", config_path(), err))?;
             config.context = context();
-
+            config.service_templates().map_err(|err| {
+                anyhow!(
+                    "starlane config '{}' is invalid: '{}'",
+                    config_path(),
+                    err.to_string()
+                )
+            })?;
             Ok(Some(config))
         }
         false => Ok(None),
@@ -0,0 +1,447 @@
+use crate::hyperspace::hyperlane::tcp::HyperlaneTcpClient;
+use crate::space::err::SpaceErr;
+use crate::space::hyper::{InterchangeKind, Knock};
+use crate::space::kind::StarStub;
+use crate::space::loc::{Layer, MachineName, StarKey, ToPoint, ToSurface};
+use crate::space::log::Logger;
+use crate::space::substance::Substance;
+use serde::{Deserialize, Serialize};
+
+/// a machine in the cluster that this machine reaches over the tcp hyperlane
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RemoteMachine {
+    pub name: MachineName,
+    /// `host:port` of the remote machine's hyperlane.  the host must match a subject
+    /// alternative name of the remote machine's certificate
+    pub host: String,
+    /// directory holding a copy of the remote machine's `cert.der`
+    pub cert_dir: String,
+    pub stars: Vec<StarStub>,
+}
+
+/// declares the stars hosted by this machine and the remote machines it joins.
+/// Every star that is not a [StarSub::Nexus] connects to every Nexus in the cluster
+/// and every Nexus receives every other star, so a [StarKey] must be unique across
+/// all machines in the cluster
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ClusterConfig {
+    /// the name of this machine which must be unique within the cluster
+    pub name: MachineName,
+    pub stars: Vec<StarStub>,
+    /// directory holding the client certificate this machine presents to the other
+    /// machines.  it must be issued by the authority of their `client_auth` and name
+    /// this machine
+    pub cert_dir: String,
+    #[serde(default)]
+    pub machines: Vec<RemoteMachine>,
+}
+
+impl ClusterConfig {
+    pub fn new(name: MachineName, stars: Vec<StarStub>, cert_dir: String) -> Self {
+        Self {
+            name,
+            stars,
+            cert_dir,
+            machines: vec![],
+        }
+    }
+
+    pub fn with_machine(mut self, machine: RemoteMachine) -> Self {
+        self.machines.push(machine);
+        self
+    }
+
+    /// every star hosted by a remote machine
+    pub fn remote_stars(&self) -> Vec<StarStub> {
+        self.machines
+            .iter()
+            .flat_map(|machine| machine.stars.iter().cloned())
+            .collect()
+    }
+
+    pub fn machine_for_star(&self, star: &StarKey) -> Option<&RemoteMachine> {
+        self.machines
+            .iter()
+            .find(|machine| machine.stars.iter().any(|stub| stub.key == *star))
+    }
+
+    /// a client that knocks on the [InterchangeKind::Star] gate of `to` on behalf of `from`
+    /// presenting this machine's client certificate
+    pub fn connection_factory(
+        &self,
+        from: &StarKey,
+        to: &StarKey,
+        logger: Logger,
+    ) -> Result<HyperlaneTcpClient, SpaceErr> {
+        let machine = self.machine_for_star(to).ok_or(SpaceErr::new(
+            404,
+            format!(
+                "star '{}' is not hosted by any machine in the cluster",
+                to.to_string()
+            ),
+        ))?;
+        let knock = Knock::new(
+            InterchangeKind::Star(to.clone()),
+            from.to_point().to_surface().with_layer(Layer::Gravity),
+            Substance::Empty,
+        );
+        Ok(HyperlaneTcpClient::new(
+            machine.host.clone(),
+            machine.cert_dir.clone(),
+            knock,
+            true,
+            logger,
+        )
+        .with_client_cert(self.cert_dir.clone()))
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::hyperspace::cluster::{ClusterConfig, RemoteMachine};
+    #[cfg(feature = "postgres")]
+    use crate::hyperspace::driver::star::Wrangler;
+    #[cfg(feature = "postgres")]
+    use crate::hyperspace::harness::{config, TestMachine, TestRegistry, READY_TIMEOUT};
+    use crate::hyperspace::hyperlane::tcp::{CertGenerator, CertPaths, HyperlaneTcpServer};
+    use crate::hyperspace::hyperlane::{
+        HyperGate, HyperGateSelector, HyperwayEndpoint, HyperwayEndpointFactory, HyperwayStub,
+    };
+    use crate::hyperspace::machine::MachineTemplate;
+    use crate::hyperspace::test_util::temp_dir;
+    #[cfg(feature = "postgres")]
+    use crate::server::ClientAuthConfig;
+    use crate::server::StarlaneConfig;
+    use crate::space::err::SpaceErr;
+    #[cfg(feature = "postgres")]
+    use crate::space::hyper::Search;
+    use crate::space::hyper::{InterchangeKind, Knock};
+    use crate::space::kind::{StarStub, StarSub};
+    use crate::space::loc::{Layer, StarHandle, StarKey, ToPoint, ToSurface};
+    use crate::space::log::Logger;
+    use crate::space::wave::core::ext::ExtMethod;
+    use crate::space::wave::core::ReflectedCore;
+    use crate::space::wave::{DirectedProto, Wave};
+    use async_trait::async_trait;
+    use dashmap::DashMap;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+
+    fn star(constellation: &str, name: &str, kind: StarSub) -> StarStub {
+        StarStub::new(
+            StarKey::new(&constellation.to_string(), &StarHandle::name(name)),
+            kind,
+        )
+    }
+
+    #[test]
+    pub fn test_cluster_template() {
+        let nexus = star("beta", "nexus", StarSub::Nexus);
+        let central = star("beta", "central", StarSub::Central);
+        let maelstrom = star("alpha", "maelstrom", StarSub::Maelstrom);
+
+        let alpha = MachineTemplate::connect_stars(vec![maelstrom.clone()], vec![nexus.clone()]);
+        let cons = &alpha.first().unwrap().connections;
+        assert_eq!(cons.len(), 1);
+        assert!(cons[0].is_connector());
+        assert_eq!(cons[0].key(), &nexus.key);
+
+        let beta = MachineTemplate::connect_stars(
+            vec![nexus.clone(), central.clone()],
+            vec![maelstrom.clone()],
+        );
+        let beta_nexus = beta.iter().find(|s| s.key == nexus.key).unwrap();
+        assert_eq!(beta_nexus.connections.len(), 2);
+        assert!(beta_nexus.connections.iter().all(|con| con.is_receiver()));
+        let beta_central = beta.iter().find(|s| s.key == central.key).unwrap();
+        assert_eq!(beta_central.connections.len(), 1);
+        assert!(beta_central.connections[0].is_connector());
+
+        // a standalone machine wires every star to its own nexus
+        let stars = MachineTemplate::connect_stars(MachineTemplate::default_stars(), vec![]);
+        let standalone_nexus = stars.iter().find(|s| s.kind == StarSub::Nexus).unwrap();
+        assert_eq!(standalone_nexus.connections.len(), stars.len() - 1);
+    }
+
+    /// opens a hyperway for every knock which answers each ping sent over it
+    struct PongGate {
+        knocks: mpsc::Sender<Knock>,
+    }
+
+    #[async_trait]
+    impl HyperGate for PongGate {
+        async fn knock(&self, knock: Knock) -> Result<HyperwayEndpoint, SpaceErr> {
+            self.knocks.send(knock).await.unwrap_or_default();
+            let (tx, mut from_remote) = mpsc::channel::<Wave>(1024);
+            let (to_remote, rx) = mpsc::channel(1024);
+            tokio::spawn(async move {
+                while let Some(wave) = from_remote.recv().await {
+                    if let Ok(wave) = wave.to_directed() {
+                        let from = wave.to().single_or().unwrap();
+                        let pong = wave.reflection().unwrap().make(ReflectedCore::ok(), from);
+                        to_remote.send(pong.to_wave()).await.unwrap_or_default();
+                    }
+                }
+            });
+            Ok(HyperwayEndpoint::new(tx, rx, Logger::default()))
+        }
+
+        async fn jump(
+            &self,
+            _: InterchangeKind,
+            _: HyperwayStub,
+        ) -> Result<HyperwayEndpoint, SpaceErr> {
+            Err(SpaceErr::server_error("PongGate does not jump"))
+        }
+    }
+
+    /// a cluster member hosting `stub` whose hyperlane serves a [PongGate] for it to the
+    /// clients `ca` issued.  Returns the member's [ClusterConfig], how the others reach it
+    /// and the knocks its gate receives
+    async fn member(
+        dir: &PathBuf,
+        name: &str,
+        stub: &StarStub,
+        ca: &CertGenerator,
+        ca_paths: &CertPaths,
+    ) -> (ClusterConfig, RemoteMachine, mpsc::Receiver<Knock>) {
+        let server = dir.join(name).join("server");
+        CertGenerator::gen_server(vec!["localhost".to_string()], 30, None)
+            .unwrap()
+            .write_pem(&CertPaths::in_dir(server.display()))
+            .await
+            .unwrap();
+        let client = dir.join(name).join("client");
+        CertGenerator::gen_client(name, ca)
+            .unwrap()
+            .write_pem(&CertPaths::in_dir(client.display()))
+            .await
+            .unwrap();
+
+        let (knocks_tx, knocks) = mpsc::channel(1);
+        let gates: Arc<DashMap<InterchangeKind, Arc<dyn HyperGate>>> = Arc::new(DashMap::new());
+        gates.insert(
+            InterchangeKind::Star(stub.key.clone()),
+            Arc::new(PongGate { knocks: knocks_tx }),
+        );
+        let port = port_check::free_local_ipv4_port().unwrap();
+        HyperlaneTcpServer::new_with_client_ca(
+            port,
+            CertPaths::in_dir(server.display()),
+            Some(ca_paths.cert.clone()),
+            Arc::new(HyperGateSelector::new(gates)),
+            Logger::default(),
+        )
+        .await
+        .unwrap()
+        .start()
+        .unwrap();
+
+        let cluster = ClusterConfig::new(
+            name.to_string(),
+            vec![stub.clone()],
+            client.display().to_string(),
+        );
+        let remote = RemoteMachine {
+            name: name.to_string(),
+            host: format!("localhost:{}", port),
+            cert_dir: server.display().to_string(),
+            stars: vec![stub.clone()],
+        };
+        (cluster, remote, knocks)
+    }
+
+    /// connect `from` to the star `to` of another member through `config` and ping it
+    async fn ping(
+        config: &StarlaneConfig,
+        name: &str,
+        from: &StarStub,
+        to: &StarStub,
+        knocks: &mut mpsc::Receiver<Knock>,
+    ) {
+        let factory = config
+            .remote_connection_factory_for_star(&from.key, &to.key, Logger::default())
+            .unwrap();
+        let (status_tx, _status_rx) = mpsc::channel(1024);
+        let mut endpoint = factory.create(status_tx).await.unwrap();
+
+        let knock = tokio::time::timeout(Duration::from_secs(5), knocks.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        let surface = from.key.to_point().to_surface().with_layer(Layer::Gravity);
+        assert_eq!(knock.remote, Some(surface.clone()));
+        assert_eq!(knock.cert.unwrap().subject, Some(name.to_string()));
+
+        let mut proto = DirectedProto::ping();
+        proto.from(surface);
+        proto.to(to.key.to_point().to_surface().with_layer(Layer::Gravity));
+        proto.method(ExtMethod::new("Hello").unwrap());
+        let wave = proto.build().unwrap().to_wave();
+        endpoint.tx.send(wave.clone()).await.unwrap();
+        let pong = tokio::time::timeout(Duration::from_secs(5), endpoint.rx.recv())
+            .await
+            .unwrap()
+            .unwrap()
+            .to_reflected()
+            .unwrap();
+        assert_eq!(pong.reflection_of(), &wave.id());
+        assert!(pong.core().status.is_success());
+    }
+
+    /// each machine reaches the star of the other through the [RemoteMachine] it lists,
+    /// presenting the client certificate of its [ClusterConfig]
+    #[tokio::test]
+    pub async fn test_remote_connection() {
+        rustls::crypto::aws_lc_rs::default_provider()
+            .install_default()
+            .unwrap_or_default();
+        let dir = temp_dir("cluster-connection");
+        let ca = CertGenerator::gen_ca("starlane-cluster").unwrap();
+        let ca_paths = CertPaths::in_dir(dir.join("ca").display());
+        ca.write_pem(&ca_paths).await.unwrap();
+
+        let alpha = star("alpha", "maelstrom", StarSub::Maelstrom);
+        let beta = star("beta", "nexus", StarSub::Nexus);
+        let (alpha_cluster, to_alpha, mut alpha_knocks) =
+            member(&dir, "alpha", &alpha, &ca, &ca_paths).await;
+        let (beta_cluster, to_beta, mut beta_knocks) =
+            member(&dir, "beta", &beta, &ca, &ca_paths).await;
+
+        let mut alpha_config = StarlaneConfig::default();
+        alpha_config.cluster = Some(alpha_cluster.with_machine(to_beta));
+        let mut beta_config = StarlaneConfig::default();
+        beta_config.cluster = Some(beta_cluster.with_machine(to_alpha));
+
+        ping(&alpha_config, "alpha", &alpha, &beta, &mut beta_knocks).await;
+        ping(&beta_config, "beta", &beta, &alpha, &mut alpha_knocks).await;
+
+        // a star no member hosts, and a machine that is not in a cluster
+        let central = star("alpha", "central", StarSub::Central);
+        assert!(alpha_config
+            .remote_connection_factory_for_star(&alpha.key, &central.key, Logger::default())
+            .is_err());
+        assert!(StarlaneConfig::default()
+            .remote_connection_factory_for_star(&alpha.key, &beta.key, Logger::default())
+            .is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    /// a machine reached by the other members of the cluster through its control port
+    #[cfg(feature = "postgres")]
+    fn remote(
+        name: &str,
+        config: &StarlaneConfig,
+        dir: &PathBuf,
+        stars: &Vec<StarStub>,
+    ) -> RemoteMachine {
+        RemoteMachine {
+            name: name.to_string(),
+            host: format!("localhost:{}", config.control_port),
+            cert_dir: dir.join("server").display().to_string(),
+            stars: stars.clone(),
+        }
+    }
+
+    /// make `config` the cluster member `name` hosting `stars` which presents a client
+    /// certificate issued by `ca` and admits the members that present one too
+    #[cfg(feature = "postgres")]
+    async fn join(
+        config: &mut StarlaneConfig,
+        name: &str,
+        stars: Vec<StarStub>,
+        dir: &PathBuf,
+        ca: &CertGenerator,
+        ca_dir: &PathBuf,
+        other: RemoteMachine,
+    ) {
+        let client = dir.join("client");
+        CertGenerator::gen_client(name, ca)
+            .unwrap()
+            .write_pem(&CertPaths::in_dir(client.display()))
+            .await
+            .unwrap();
+        config.cluster = Some(
+            ClusterConfig::new(name.to_string(), stars, client.display().to_string())
+                .with_machine(other),
+        );
+        config.client_auth = Some(ClientAuthConfig::new(ca_dir.display().to_string()));
+    }
+
+    #[cfg(feature = "postgres")]
+    #[tokio::test]
+    #[ignore = "needs the postgres server named by STARLANE_TEST_POSTGRES"]
+    pub async fn test_cluster() {
+        let registry = TestRegistry::new().await;
+        let dir = temp_dir("cluster");
+
+        let ca = CertGenerator::gen_ca("starlane-cluster").unwrap();
+        let ca_dir = dir.join("ca");
+        ca.write_pem(&CertPaths::in_dir(ca_dir.display()))
+            .await
+            .unwrap();
+
+        let alpha_dir = dir.join("alpha");
+        let alpha_stars = MachineTemplate::default_stars();
+        let mut alpha = config(&registry, &alpha_dir).await;
+
+        let beta_dir = dir.join("beta");
+        let beta_star = star("beta", "maelstrom", StarSub::Maelstrom);
+        let beta_stars = vec![beta_star.clone()];
+        let mut beta = config(&registry, &beta_dir).await;
+        beta.control_port =
+            port_check::free_local_ipv4_port_in_range(alpha.control_port + 1..).unwrap();
+
+        let to_beta = remote("beta", &beta, &beta_dir, &beta_stars);
+        let to_alpha = remote("alpha", &alpha, &alpha_dir, &alpha_stars);
+        join(&mut alpha, "alpha", alpha_stars.clone(), &alpha_dir, &ca, &ca_dir, to_beta).await;
+        join(&mut beta, "beta", beta_stars, &beta_dir, &ca, &ca_dir, to_alpha).await;
+
+        // beta's star connects to alpha's nexus so alpha must be listening first
+        let alpha = TestMachine::new(alpha).await;
+        let beta = TestMachine::new(beta).await;
+
+        let central = alpha.star(&StarKey::central()).await;
+        let maelstrom = beta.star(&beta_star.key).await;
+
+        // central wrangled before beta joined, so wrangle again until beta's star is found
+        tokio::time::timeout(READY_TIMEOUT, async {
+            loop {
+                if let Ok(wrangles) = central.api.wrangle().await {
+                    if wrangles.stars().await.contains(&beta_star.key) {
+                        return;
+                    }
+                }
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        })
+        .await
+        .expect("central never wrangled the star of the other machine");
+
+        // every star beta wrangles is hosted by alpha
+        let wrangled = maelstrom.api.wrangle().await.unwrap().stars().await;
+        assert!(!wrangled.is_empty());
+        assert!(wrangled
+            .iter()
+            .all(|key| alpha_stars.iter().any(|stub| stub.key == *key)));
+
+        // a search for a star on the other machine is forwarded through alpha's nexus
+        for (skel, key) in [(&central, &beta_star.key), (&maelstrom, &StarKey::central())] {
+            let discoveries = Wrangler::new(skel.clone(), Search::Star(key.clone()))
+                .wrangle(false)
+                .await
+                .unwrap();
+            assert!(discoveries
+                .iter()
+                .any(|discovery| discovery.star_key == *key));
+        }
+
+        beta.terminate();
+        alpha.terminate();
+        registry.drop().await;
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
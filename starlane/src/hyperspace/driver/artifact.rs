@@ -1,37 +1,39 @@
 use crate::hyperspace::driver::{
     Driver, DriverCtx, DriverErr, DriverHandler, DriverSkel, DriverStatus, HyperDriverFactory,
-    HyperSkel, Particle, ParticleSkel, ParticleSphere, ParticleSphereInner, StdParticleErr,
+    HyperParticleSkel, HyperSkel, Particle, ParticleSphere, StdParticleErr,
+};
+use crate::hyperspace::driver::filestore::{child_point, is_dir, reclaim};
+use crate::hyperspace::executor::cli::CliErr;
+use crate::hyperspace::executor::dialect::filestore::{FileStoreErr, FileStoreIn, FileStoreOut};
+use crate::hyperspace::reg::Registration;
+use crate::hyperspace::service::{
+    FileStoreService, Service, ServiceErr, ServiceKind, ServiceRunner, ServiceSelector,
+    ServiceTemplate,
 };
-use crate::hyperspace::executor::dialect::filestore::FileStoreIn;
-use crate::hyperspace::platform::Platform;
-use crate::hyperspace::service::{FileStoreService, Service, ServiceKind, ServiceRunner, ServiceSelector};
 use crate::hyperspace::star::HyperStarSkel;
+use crate::hyperspace::template::Templates;
 use async_trait::async_trait;
-use once_cell::sync::Lazy;
 use starlane_macros::{handler, DirectedHandler};
-use crate::space::artifact::ArtRef;
-use crate::space::command::common::{SetProperties, StateSrc};
-use crate::space::command::direct::create::{
-    Create, KindTemplate, PointSegTemplate, PointTemplate, Strategy, Template,
-};
-use crate::space::config::bind::BindConfig;
+use crate::space::artifact::asynch::{ArtErr, ArtifactFetcher};
+use crate::space::command::common::StateSrc;
+use crate::space::command::direct::create::Strategy;
 use crate::space::err::SpaceErr;
 use crate::space::hyper::HyperSubstance;
 use crate::space::kind::{ArtifactSubKind, BaseKind, Kind};
-use crate::space::loc::ToBaseKind;
-use crate::space::parse::bind_config;
-use crate::space::particle::PointKind;
+use crate::space::loc::ToPoint;
+use crate::space::particle::{Status, Stub};
 use crate::space::point::Point;
-use crate::space::selector::KindSelector;
-use crate::space::substance::Substance;
-use crate::space::util::{log, IdSelector};
+use crate::space::selector::{KindSelector, Selector};
+use crate::space::substance::{Bin, Substance};
+use crate::space::util::{IdSelector, ValuePattern};
+use crate::space::wave::core::ReflectedCore;
 use crate::space::wave::exchange::asynch::InCtx;
-use crate::space::wave::{DirectedProto, Pong, Wave};
-use std::marker::PhantomData;
-use std::str::FromStr;
+use std::collections::BTreeSet;
+use std::io::{Cursor, Read};
+use std::path::PathBuf;
 use std::sync::Arc;
-use tempdir::TempDir;
-use tracing::Instrument;
+use tokio::sync::OnceCell;
+use zip::ZipArchive;
 
 pub struct RepoDriverFactory {}
 
@@ -97,6 +99,11 @@ impl Driver for RepoDriver {
         Kind::Repo
     }
 
+    /// reclaim the root each Repo was given within the service
+    async fn on_delete(&self, point: &Point) -> Result<(), DriverErr> {
+        reclaim(&self.filestore, point).await
+    }
+
     async fn particle(&self, point: &Point) -> Result<ParticleSphere, DriverErr> {
         let filestore = self.filestore.sub_root(point.md5().into()).await?;
 
@@ -120,22 +127,6 @@ impl Particle for Repo {
     }
 }
 
-/*
-fn store() -> Result<ValueRepo<String>, UniErr> {
-    let config = acid_store::store::DirectoryConfig {
-        path: PathBuf::from("./data/artifacts"),
-    };
-
-    match OpenOptions::new()
-        .mode(acid_store::repo::OpenMode::Create)
-        .open(&config)
-    {
-        Ok(repo) => Ok(repo),
-        Err(err) => return Err(UniErr::new(500u16, err.to_string())),
-    }
-}
- */
-
 #[derive(DirectedHandler)]
 pub struct Repo {
     filestore: FileStoreService,
@@ -146,589 +137,595 @@ impl Repo {}
 #[handler]
 impl Repo {}
 
-/*
 pub struct BundleSeriesDriverFactory;
 
 impl BundleSeriesDriverFactory {
     pub fn new() -> Self {
-        Self {}
+        Self
     }
 }
 
 #[async_trait]
-impl<P,S> HyperDriverFactory for BundleSeriesDriverFactory
+impl HyperDriverFactory for BundleSeriesDriverFactory {
+    fn kind(&self) -> Kind {
+        Kind::BundleSeries
+    }
 
-{
-    fn kind(&self) -> KindSelector {
+    fn selector(&self) -> KindSelector {
         KindSelector::from_base(BaseKind::BundleSeries)
     }
 
     async fn create(
         &self,
-        skel: HyperStarSkel,
-        driver_skel: DriverSkel,
-        ctx: DriverCtx,
+        _: HyperStarSkel,
+        _: DriverSkel,
+        _: DriverCtx,
     ) -> Result<Box<dyn Driver>, DriverErr> {
-        Ok(Box::new(BundleSeriesDriver::new(ctx)))
+        Ok(Box::new(BundleSeriesDriver))
     }
 }
 
-pub struct BundleSeriesDriver  {
-    ctx: DriverCtx,
-}
-
-#[handler]
-impl  BundleSeriesDriver {
-    pub fn new(ctx: DriverCtx) -> Self {
-        Self {
-            ctx
-        }
-    }
-}
+pub struct BundleSeriesDriver;
 
 #[async_trait]
-impl Driver for BundleSeriesDriver
-
-{
+impl Driver for BundleSeriesDriver {
     fn kind(&self) -> Kind {
         Kind::BundleSeries
     }
 
-    async fn item(&self, point: &Point) -> Result<ItemSphere, DriverErr> {
-        Ok(ItemSphere::Handler(Box::new(BundleSeries::new(self.ctx.clone()))))
+    async fn particle(&self, _: &Point) -> Result<ParticleSphere, DriverErr> {
+        Ok(BundleSeries::restore((), (), ()).sphere()?)
     }
 }
 
-pub struct BundleSeries  {
-    ctx: DriverCtx
-}
-
-impl  BundleSeries {
-   pub fn new( ctx: DriverCtx) -> BundleSeries{
-       Self {
-           ctx
-       }
-   }
-}
-
-#[handler]
-impl  BundleSeries  {
+#[derive(DirectedHandler)]
+pub struct BundleSeries;
 
-}
+impl Particle for BundleSeries {
+    type Skel = ();
+    type Ctx = ();
+    type State = ();
+    type Err = StdParticleErr;
 
-#[async_trait]
-impl ItemHandler for BundleSeries
+    fn restore(_: Self::Skel, _: Self::Ctx, _: Self::State) -> Self {
+        BundleSeries
+    }
 
-{
-    async fn bind(&self) -> Result<ArtRef<BindConfig>, DriverErr> {
-        Ok(SERIES_BIND_CONFIG.clone())
+    fn sphere(self) -> Result<ParticleSphere, Self::Err> {
+        Ok(ParticleSphere::new_handler(self))
     }
 }
 
+#[handler]
+impl BundleSeries {}
+
 pub struct BundleDriverFactory;
 
 impl BundleDriverFactory {
     pub fn new() -> Self {
-        Self {}
+        Self
     }
 }
 
 #[async_trait]
-impl HyperDriverFactory for BundleDriverFactory
+impl HyperDriverFactory for BundleDriverFactory {
+    fn kind(&self) -> Kind {
+        Kind::Bundle
+    }
 
-{
-    fn kind(&self) -> KindSelector {
+    fn selector(&self) -> KindSelector {
         KindSelector::from_base(BaseKind::Bundle)
     }
 
     async fn create(
         &self,
-        skel: HyperStarSkel,
-        driver_skel: DriverSkel,
-        ctx: DriverCtx,
+        star: HyperStarSkel,
+        skel: DriverSkel,
+        _: DriverCtx,
     ) -> Result<Box<dyn Driver>, DriverErr> {
-        let skel = HyperSkel::new(skel, driver_skel);
-        Ok(Box::new(BundleDriver::new(skel, ctx)))
+        let service = skel.select_service(ServiceKind::FileStore).await?;
+
+        let filestore = service.filestore()?;
+
+        Ok(Box::new(BundleDriver::new(
+            HyperSkel::new(star, skel),
+            filestore,
+        )))
     }
 }
 
-pub struct BundleDriver
-
-{
+pub struct BundleDriver {
     skel: HyperSkel,
-    ctx: DriverCtx,
+    filestore: Arc<FileStoreService>,
 }
 
-#[handler]
-impl BundleDriver
-
-{
-    pub fn new(skel: HyperSkel, ctx: DriverCtx) -> Self {
-        Self { skel, ctx }
+impl BundleDriver {
+    pub fn new(skel: HyperSkel, filestore: FileStoreService) -> Self {
+        let filestore = Arc::new(filestore);
+        Self { skel, filestore }
     }
 }
 
 #[async_trait]
-impl Driver for BundleDriver
-
-{
+impl Driver for BundleDriver {
     fn kind(&self) -> Kind {
         Kind::Bundle
     }
 
-    async fn item(&self, point: &Point) -> Result<ItemSphere, DriverErr> {
-        Ok(ItemSphere::Handler(Box::new(Bundle)))
+    /// reclaim the root the Bundle's files were unzipped into
+    async fn on_delete(&self, point: &Point) -> Result<(), DriverErr> {
+        reclaim(&self.filestore, point).await
+    }
+
+    async fn particle(&self, _: &Point) -> Result<ParticleSphere, DriverErr> {
+        Ok(Bundle::restore((), (), ()).sphere()?)
     }
 
     async fn handler(&self) -> Box<dyn DriverHandler> {
         Box::new(BundleDriverHandler::restore(
             self.skel.clone(),
-            self.ctx.clone(),
+            self.filestore.clone(),
         ))
     }
 }
 
-pub struct BundleDriverHandler
-
-{
+#[derive(DirectedHandler)]
+pub struct BundleDriverHandler {
     skel: HyperSkel,
-    ctx: DriverCtx,
+    filestore: Arc<FileStoreService>,
 }
 
-impl BundleDriverHandler
-
-{
-    fn restore(skel: HyperSkel, ctx: DriverCtx) -> Self {
-        Self { skel, ctx }
+impl BundleDriverHandler {
+    fn restore(skel: HyperSkel, filestore: Arc<FileStoreService>) -> Self {
+        Self { skel, filestore }
     }
 }
 
 impl DriverHandler for BundleDriverHandler {}
 
 #[handler]
-impl BundleDriverHandler
-
-{
-    fn store(&self) -> Result<ValueRepo<String>, SpaceErr> {
-        let config = acid_store::store::DirectoryConfig {
-            path: PathBuf::from(format!("{}artifacts", self.skel.star.data_dir())),
-        };
-
-        match OpenOptions::new()
-            .mode(acid_store::repo::OpenMode::Create)
-            .open(&config)
-        {
-            Ok(repo) => Ok(repo),
-            Err(err) => return Err(SpaceErr::new(500u16, err.to_string())),
-        }
-    }
+impl BundleDriverHandler {
+    /// explode the published zip into the Bundle's FileStore and register an
+    /// `Artifact` particle for every file and directory within it
     #[route("Hyp<Assign>")]
-    async fn assign(&self, ctx: InCtx<'_, HyperSubstance>) -> Result<(), DriverErr> {
+    pub async fn assign(&self, ctx: InCtx<'_, HyperSubstance>) -> Result<(), DriverErr> {
         if let HyperSubstance::Assign(assign) = ctx.input {
-            let state = match &assign.state {
-                StateSrc::Substance(data) => data.clone(),
-                StateSrc::None => {
-                    return self
-                        .skel
-                        .driver
-                        .logger
-                        .result(Err("ArtifactBundle cannot be stateless".into()));
-                }
+            let bundle = &assign.details.stub.point;
+            let zip = match &assign.state {
+                StateSrc::Subst(substance) => match &**substance {
+                    Substance::Bin(zip) => zip.clone(),
+                    substance => Err(SpaceErr::bad_request(format!(
+                        "Bundle expected a zip Bin, found: {}",
+                        substance.kind().to_string()
+                    )))?,
+                },
+                StateSrc::None => Err(SpaceErr::bad_request("Bundle cannot be stateless"))?,
             };
-            if let Substance::Bin(zip) = (*state).clone() {
-                let temp_dir = TempDir::new("zipcheck")?;
-                let temp_path = temp_dir.path().clone();
-                let file_path = temp_path.with_file_name("file.zip");
-                let mut file = File::create(file_path.as_path())?;
-                file.write_all(zip.as_slice())?;
-
-                let file = File::open(file_path.as_path())?;
-                let mut archive = zip::ZipArchive::new(file)?;
-                let mut artifacts = vec![];
-                for i in 0..archive.len() {
-                    let file = archive.by_index(i).unwrap();
-                    if !file.name().ends_with("/") {
-                        artifacts.push(file.name().to_string())
-                    }
-                }
 
-                {
-                    let mut store = self.store()?;
-                    let state = *state;
-                    store.insert(assign.details.stub.point.to_string(), &state)?;
-                    store.commit()?;
-                }
+            let files = unzip(zip)?;
 
-                self.skel
-                    .star
-                    .registry
-                    .assign_star(&assign.details.stub.point, &self.skel.star.point)
-                    .await?;
+            let filestore = self.filestore.sub_root(bundle.md5().into()).await?;
+            filestore.execute(FileStoreIn::Init).await?;
 
-                let mut point_and_kind_set = HashSet::new();
-                for artifact in artifacts {
-                    let mut path = String::new();
-                    let segments = artifact.split("/");
-                    let segments: Vec<&str> = segments.collect();
-                    for (index, segment) in segments.iter().enumerate() {
-                        path.push_str(segment);
-                        if index < segments.len() - 1 {
-                            path.push_str("/");
-                        }
-                        let point = Point::from_str(
-                            format!(
-                                "{}:/{}",
-                                assign.details.stub.point.to_string(),
-                                path.as_str()
-                            )
-                            .as_str(),
-                        )?;
-                        let kind = if index < segments.len() - 1 {
-                            Kind::Artifact(ArtifactSubKind::Dir)
-                        } else {
-                            Kind::Artifact(ArtifactSubKind::Raw)
-                        };
-                        let point_and_kind = PointKind { point, kind };
-                        point_and_kind_set.insert(point_and_kind);
-                    }
+            let mut dirs = BTreeSet::new();
+            dirs.insert(PathBuf::from("/"));
+            for (path, _) in &files {
+                for dir in path.ancestors().skip(1) {
+                    dirs.insert(dir.to_path_buf());
                 }
+            }
 
-                let root_point_and_kind = PointKind {
-                    point: Point::from_str(
-                        format!("{}:/", assign.details.stub.point.to_string()).as_str(),
-                    )?,
-                    kind: Kind::Artifact(ArtifactSubKind::Dir),
-                };
+            for dir in &dirs {
+                filestore
+                    .execute(FileStoreIn::Mkdir { path: dir.clone() })
+                    .await?;
+            }
 
-                point_and_kind_set.insert(root_point_and_kind);
-
-                let mut point_and_kind_set: Vec<PointKind> =
-                    point_and_kind_set.into_iter().collect();
-
-                // shortest first will ensure that dirs are created before files
-                point_and_kind_set.sort_by(|a, b| {
-                    if a.point.to_string().len() > b.point.to_string().len() {
-                        Ordering::Greater
-                    } else if a.point.to_string().len() < b.point.to_string().len() {
-                        Ordering::Less
-                    } else {
-                        Ordering::Equal
-                    }
-                });
-
-                {
-                    let ctx = self.ctx.clone();
-                    //                    tokio::spawn(async move {
-                    for point_and_kind in point_and_kind_set {
-                        let parent = point_and_kind.point.parent().expect("expected parent");
-
-                        let state = match point_and_kind.kind {
-                            Kind::Artifact(ArtifactSubKind::Dir) => StateSrc::None,
-                            Kind::Artifact(_) => {
-                                let mut path = point_and_kind
-                                    .point
-                                    .filepath()
-                                    .expect("expecting non Dir artifact to have a filepath");
-                                // convert to relative path
-                                path.remove(0);
-                                match archive.by_name(path.as_str()) {
-                                    Ok(mut file) => {
-                                        let mut buf = vec![];
-                                        file.read_to_end(&mut buf);
-                                        let bin = Arc::new(buf);
-                                        let payload = Substance::Bin(bin);
-                                        StateSrc::Substance(Box::new(payload))
-                                    }
-                                    Err(err) => StateSrc::None,
-                                }
-                            }
-                            _ => {
-                                panic!("unexpected knd");
-                            }
-                        };
-
-                        let create = Create {
-                            template: Template {
-                                point: PointTemplate {
-                                    parent: parent.clone(),
-                                    child_segment_template: PointSegTemplate::Exact(
-                                        point_and_kind
-                                            .point
-                                            .last_segment()
-                                            .expect("expected final segment")
-                                            .to_string(),
-                                    ),
-                                },
-                                kind: KindTemplate {
-                                    base: point_and_kind.kind.to_base(),
-                                    sub: point_and_kind.kind.sub().into(),
-                                    specific: None,
-                                },
-                            },
-                            state,
-                            properties: SetProperties::new(),
-                            strategy: Strategy::Commit,
-                        };
-
-                        let wave: DirectedProto = create.into();
-                        let pong: Wave<Pong> = ctx.transmitter.direct(wave).await.unwrap();
-                    }
-                    //   });
-                }
-            } else {
-                return Err("ArtifactBundle Manager expected Bin payload".into());
+            let mut artifacts = vec![];
+            for dir in dirs {
+                artifacts.push((child_point(bundle, &dir, true)?, ArtifactSubKind::Dir));
+            }
+            for (path, state) in files {
+                artifacts.push((child_point(bundle, &path, false)?, artifact_kind(&path)));
+                filestore
+                    .execute(FileStoreIn::Write { path, state })
+                    .await?;
             }
 
-            Ok(())
-        } else {
-            Err(DriverErr::new("Bad Reqeust: expected Assign"))
+            let owner = ctx.wave().agent().clone().to_point();
+            let registry = self.skel.driver.registry();
+            for (point, kind) in artifacts {
+                let registration = Registration {
+                    point: point.clone(),
+                    kind: Kind::Artifact(kind),
+                    registry: Default::default(),
+                    properties: Default::default(),
+                    owner: owner.clone(),
+                    strategy: Strategy::Ensure,
+                    status: Status::Ready,
+                };
+                registry.register(&registration).await?;
+                registry.assign_star(&point, &self.skel.star.point).await?;
+            }
         }
+        Ok(())
     }
 }
 
+#[derive(DirectedHandler)]
 pub struct Bundle;
 
-#[handler]
-impl Bundle {}
+impl Particle for Bundle {
+    type Skel = ();
+    type Ctx = ();
+    type State = ();
+    type Err = StdParticleErr;
 
-#[async_trait]
-impl ItemHandler for Bundle
+    fn restore(_: Self::Skel, _: Self::Ctx, _: Self::State) -> Self {
+        Bundle
+    }
 
-{
-    async fn bind(&self) -> Result<ArtRef<BindConfig>, DriverErr> {
-        Ok(BUNDLE_BIND_CONFIG.clone())
+    fn sphere(self) -> Result<ParticleSphere, Self::Err> {
+        Ok(ParticleSphere::new_handler(self))
     }
 }
 
+#[handler]
+impl Bundle {}
+
 pub struct ArtifactDriverFactory;
 
 impl ArtifactDriverFactory {
     pub fn new() -> Self {
-        Self {}
+        Self
     }
 }
 
 #[async_trait]
-impl HyperDriverFactory for ArtifactDriverFactory
+impl HyperDriverFactory for ArtifactDriverFactory {
+    fn kind(&self) -> Kind {
+        Kind::Artifact(ArtifactSubKind::Raw)
+    }
 
-{
-    fn kind(&self) -> KindSelector {
+    fn selector(&self) -> KindSelector {
         KindSelector::from_base(BaseKind::Artifact)
     }
 
     async fn create(
         &self,
-        skel: HyperStarSkel,
-        driver_skel: DriverSkel,
-        ctx: DriverCtx,
+        _: HyperStarSkel,
+        skel: DriverSkel,
+        _: DriverCtx,
     ) -> Result<Box<dyn Driver>, DriverErr> {
-        Ok(Box::new(ArtifactDriver::new(driver_skel, ctx)))
+        let service = skel.select_service(ServiceKind::FileStore).await?;
+
+        let filestore = service.filestore()?;
+
+        Ok(Box::new(ArtifactDriver::new(skel, filestore)))
     }
 }
 
-pub struct ArtifactDriver
-
-{
+pub struct ArtifactDriver {
     skel: DriverSkel,
-    ctx: DriverCtx,
+    filestore: FileStoreService,
 }
 
-
-impl ArtifactDriver
-
-{
-    pub fn new(skel: DriverSkel, ctx: DriverCtx) -> Self {
-        Self { skel, ctx }
+impl ArtifactDriver {
+    pub fn new(skel: DriverSkel, filestore: FileStoreService) -> Self {
+        Self { skel, filestore }
     }
 }
 
-#[handler]
-impl ArtifactDriver
-
-{
-}
-
 #[async_trait]
-impl Driver for ArtifactDriver
-
-{
+impl Driver for ArtifactDriver {
     fn kind(&self) -> Kind {
         Kind::Artifact(ArtifactSubKind::Raw)
     }
 
-    async fn item(&self, point: &Point) -> Result<ItemSphere, DriverErr> {
+    async fn particle(&self, point: &Point) -> Result<ParticleSphere, DriverErr> {
         let record = self.skel.locate(point).await?;
+        let bundle = point.non_file_parent();
+        let filestore = self.filestore.sub_root(bundle.md5().into()).await?;
+        let skel = HyperParticleSkel {
+            skel: self.skel.clone(),
+            point: point.clone(),
+            kind: record.details.stub.kind,
+        };
 
-        let skel = ItemSkel::new(point.clone(), record.details.stub.kind, self.skel.clone());
-        Ok(ItemSphere::Handler(Box::new(Artifact::restore(
-            skel,
-            (),
-            (),
-        ))))
-    }
-
-    async fn handler(&self) -> Box<dyn DriverHandler> {
-        let skel = HyperSkel::new(self.skel.skel.clone(), self.skel.clone());
-        Box::new(ArtifactDriverHandler::restore(skel))
+        Ok(Artifact::restore(skel, (), filestore).sphere()?)
     }
 }
 
-pub struct ArtifactDriverHandler
-
-{
-    skel: HyperSkel,
+#[derive(DirectedHandler)]
+pub struct Artifact {
+    skel: HyperParticleSkel,
+    filestore: FileStoreService,
 }
 
-impl ArtifactDriverHandler
+impl Particle for Artifact {
+    type Skel = HyperParticleSkel;
+    type Ctx = ();
+    type State = FileStoreService;
+    type Err = StdParticleErr;
 
-{
-    fn restore(skel: HyperSkel) -> Self {
-        Self { skel }
+    fn restore(skel: Self::Skel, _: Self::Ctx, filestore: Self::State) -> Self {
+        Self { skel, filestore }
     }
-}
-
-impl DriverHandler for ArtifactDriverHandler  {}
-
-#[handler]
-impl ArtifactDriverHandler
 
-{
-    fn store(&self) -> Result<ValueRepo<String>, SpaceErr> {
-        let config = acid_store::store::DirectoryConfig {
-            path: PathBuf::from(format!("{}artifacts", self.skel.star.data_dir())),
-        };
+    fn sphere(self) -> Result<ParticleSphere, Self::Err> {
+        Ok(ParticleSphere::new_handler(self))
+    }
+}
 
-        match OpenOptions::new()
-            .mode(acid_store::repo::OpenMode::Create)
-            .open(&config)
-        {
-            Ok(repo) => Ok(repo),
-            Err(err) => return Err(SpaceErr::new(500u16, err.to_string())),
+impl Artifact {
+    async fn content(&self) -> Result<ReflectedCore, DriverErr> {
+        if let Kind::Artifact(ArtifactSubKind::Dir) = self.skel.kind {
+            return Ok(ReflectedCore::ok());
         }
-    }
 
-    #[route("Hyp<Assign>")]
-    async fn assign(&self, ctx: InCtx<'_, HyperSubstance>) -> Result<(), DriverErr> {
-        if let HyperSubstance::Assign(assign) = ctx.input {
-            if let Kind::Artifact(sub) = &assign.details.stub.kind {
-                match sub {
-                    ArtifactSubKind::Dir => {}
-                    _ => {
-                        let substance = assign.state.get_substance()?;
-                        let mut store = self.skel.driver.logger.result(self.store())?;
-                        store
-                            .insert(assign.details.stub.point.to_string(), &substance)
-                            .map_err(|e| SpaceErr::server_error(e.to_string()))?;
-                        self.skel.driver.logger.result(
-                            store
-                                .commit()
-                                .map_err(|e| SpaceErr::server_error(e.to_string())),
-                        )?;
-                    }
-                }
-                self.skel
-                    .star
-                    .registry
-                    .assign_star(&assign.details.stub.point, &self.skel.star.point)
-                    .await?;
-            }
-            Ok(())
-        } else {
-            Err(DriverErr::new("ArtifactDriver expected Assign"))
+        let path = artifact_path(&self.skel.point)?;
+        match self.filestore.execute(FileStoreIn::Read { path }).await? {
+            FileStoreOut::Read(bin) => Ok(ReflectedCore::ok_body(Substance::Bin(bin))),
+            _ => Err(SpaceErr::server_error(
+                "FileStore read returned unexpected output",
+            ))?,
         }
     }
 }
 
-pub struct Artifact
+#[handler]
+impl Artifact {
+    #[route("Cmd<Read>")]
+    pub async fn read(&self, _: InCtx<'_, ()>) -> Result<ReflectedCore, DriverErr> {
+        self.content().await
+    }
 
-{
-    skel: ItemSkel,
+    #[route("Http<Get>")]
+    pub async fn get(&self, _: InCtx<'_, ()>) -> Result<ReflectedCore, DriverErr> {
+        self.content().await
+    }
 }
 
-#[handler]
-impl Artifact
-
-{
-    fn store(&self) -> Result<ValueRepo<String>, SpaceErr> {
-        let config = acid_store::store::DirectoryConfig {
-            path: PathBuf::from(format!("{}artifacts", self.skel.data_dir())),
-        };
+/// fetches published artifacts straight from the Bundle's FileStore so that
+/// [Artifacts] can serve more than the builtins.  [Artifacts] are built before the
+/// machine so the FileStore is selected from the machine's service templates just as
+/// the machine selects it for the [BundleDriver]
+pub struct FileStoreArtifactFetcher {
+    services: Templates<ServiceTemplate>,
+    filestore: OnceCell<FileStoreService>,
+}
 
-        match OpenOptions::new()
-            .mode(acid_store::repo::OpenMode::Create)
-            .open(&config)
-        {
-            Ok(repo) => Ok(repo),
-            Err(err) => return Err(SpaceErr::new(500u16, err.to_string())),
+impl FileStoreArtifactFetcher {
+    pub fn new(services: Templates<ServiceTemplate>) -> Self {
+        Self {
+            services,
+            filestore: OnceCell::new(),
         }
     }
 
-    #[route("Cmd<Read>")]
-    pub async fn read(&self, _ctx: InCtx<'_, ()>) -> Result<Substance, DriverErr> {
-        if let Kind::Artifact(ArtifactSubKind::Dir) = self.skel.kind {
-            return Ok(Substance::Empty);
+    fn select_service(&self, kind: ServiceKind) -> Result<Service<ServiceRunner>, ServiceErr> {
+        let selector = ServiceSelector {
+            name: IdSelector::Always,
+            kind,
+            driver: Some(Kind::Bundle),
+        };
+        match self.services.select(&selector).first() {
+            None => Err(ServiceErr::NoTemplate {
+                candidates: self.services.near_misses(&selector),
+                selector,
+            }),
+            Some(template) => Ok(template.clone().into()),
         }
-        let store = self.store()?;
-
-        let substance: Substance = store.get(&self.skel.point.to_string()).unwrap();
-        Ok(substance)
     }
 
-    #[route("Http<Get>")]
-    pub async fn get(&self, _: InCtx<'_, ()>) -> Result<Substance, DriverErr> {
-        if let Kind::Artifact(ArtifactSubKind::Dir) = self.skel.kind {
-            return Ok(Substance::Empty);
-        }
-        let store = self.store()?;
-
-        let substance: Substance = store.get(&self.skel.point.to_string()).unwrap();
-        Ok(substance)
+    async fn filestore(&self) -> Result<&FileStoreService, ArtErr> {
+        self.filestore
+            .get_or_try_init(|| async {
+                let service = ArtErr::result(self.select_service(ServiceKind::FileStore))?;
+                ArtErr::result(service.filestore())
+            })
+            .await
     }
-}
-
-impl Item for Artifact
-
-{
-    type Skel = ItemSkel;
-    type Ctx = ();
-    type State = ();
 
-    fn restore(skel: Self::Skel, ctx: Self::Ctx, state: Self::State) -> Self {
-        Self { skel }
+    /// the root the artifact's Bundle was unzipped into and the artifact's path within it
+    async fn locate(&self, point: &Point) -> Result<(FileStoreService, PathBuf), ArtErr> {
+        let path = artifact_path(point).map_err(|_| ArtErr::not_found(point))?;
+        let bundle = point.non_file_parent();
+        let filestore = ArtErr::result(self.filestore().await?.sub_root(bundle.md5().into()).await)?;
+        Ok((filestore, path))
     }
 }
 
-/*
 #[async_trait]
-impl DirectedHandler for Artifact where P: Cosmos {
-    async fn handle(&self, ctx: RootInCtx) -> CoreBounce {
-        println!("ARTIFACT HANDLE REQUEST : {}",ctx.wave.clone().to_wave().desc());
-
-        let core = ReflectedCore {
-            headers: Default::default(),
-            status: Default::default(),
-            body: Default::default()
+impl ArtifactFetcher for FileStoreArtifactFetcher {
+    async fn stub(&self, point: &Point) -> Result<Stub, ArtErr> {
+        let (filestore, path) = self.locate(point).await?;
+        match filestore.execute(FileStoreIn::Exists { path: path.clone() }).await {
+            Ok(FileStoreOut::Exists(true)) => {}
+            Ok(_) => Err(ArtErr::not_found(point))?,
+            Err(err) => Err(ArtErr::err(err))?,
+        }
+        let kind = if is_dir(&filestore, &path).await.map_err(ArtErr::err)? {
+            ArtifactSubKind::Dir
+        } else {
+            artifact_kind(&path)
         };
-        CoreBounce::Reflected(core)
+        Ok(Stub {
+            point: point.clone(),
+            kind: Kind::Artifact(kind),
+            status: Status::Ready,
+        })
+    }
+
+    async fn fetch(&self, point: &Point) -> Result<Arc<Bin>, ArtErr> {
+        let (filestore, path) = self.locate(point).await?;
+        match filestore.execute(FileStoreIn::Read { path }).await {
+            Ok(FileStoreOut::Read(bin)) => Ok(Arc::new(bin)),
+            Ok(_) => Err(ArtErr::err("FileStore read returned unexpected output")),
+            Err(FileStoreErr::CliErr(CliErr::Exit { .. })) => Err(ArtErr::not_found(point)),
+            Err(err) => Err(ArtErr::err(err)),
+        }
     }
-}
-
- */
 
-#[async_trait]
-impl ItemHandler for Artifact
+    fn selector(&self) -> ValuePattern<Selector> {
+        ValuePattern::Always
+    }
+}
 
-{
-    async fn bind(&self) -> Result<ArtRef<BindConfig>, DriverErr> {
-        Ok(ARTIFACT_BIND_CONFIG.clone())
+/// read every file out of a zip archive, keyed by its absolute path within the bundle
+fn unzip(zip: Bin) -> Result<Vec<(PathBuf, Bin)>, DriverErr> {
+    let mut archive =
+        ZipArchive::new(Cursor::new(zip)).map_err(|e| SpaceErr::bad_request(e.to_string()))?;
+    let mut files = vec![];
+    for index in 0..archive.len() {
+        let mut file = archive
+            .by_index(index)
+            .map_err(|e| SpaceErr::bad_request(e.to_string()))?;
+        if file.is_dir() {
+            continue;
+        }
+        let path = file
+            .enclosed_name()
+            .ok_or(SpaceErr::bad_request(format!(
+                "bundle entry '{}' escapes the bundle",
+                file.name()
+            )))?
+            .to_path_buf();
+        let mut bin = vec![];
+        file.read_to_end(&mut bin).map_err(SpaceErr::from)?;
+        files.push((PathBuf::from("/").join(path), bin));
     }
+    Ok(files)
 }
 
+fn artifact_path(point: &Point) -> Result<PathBuf, DriverErr> {
+    let path = point.filepath().ok_or(SpaceErr::server_error(format!(
+        "'{}' is not an artifact point",
+        point.to_string()
+    )))?;
+    Ok(path.into())
+}
 
- */
+fn artifact_kind(path: &PathBuf) -> ArtifactSubKind {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("bind") => ArtifactSubKind::Bind,
+        Some("wasm") => ArtifactSubKind::Wasm,
+        _ => ArtifactSubKind::Raw,
+    }
+}
 
 #[cfg(test)]
 pub mod tests {
+    #[cfg(feature = "postgres")]
+    use crate::hyperspace::driver::artifact::FileStoreArtifactFetcher;
+    use crate::hyperspace::driver::artifact::{artifact_kind, unzip};
+    use crate::hyperspace::driver::filestore::child_point;
+    #[cfg(feature = "postgres")]
+    use crate::hyperspace::harness::{config, TestMachine, TestRegistry};
+    #[cfg(feature = "postgres")]
+    use crate::hyperspace::test_util::temp_dir;
+    #[cfg(feature = "postgres")]
+    use crate::hyperspace::machine::MachineTemplate;
+    #[cfg(feature = "postgres")]
+    use crate::space::artifact::asynch::{ArtErr, ArtifactFetcher};
+    #[cfg(feature = "postgres")]
+    use crate::space::kind::Kind;
+    use crate::space::kind::ArtifactSubKind;
+    #[cfg(feature = "postgres")]
+    use crate::space::loc::{StarHandle, StarKey};
+    use crate::space::point::Point;
+    use std::io::{Cursor, Write};
+    use std::path::PathBuf;
+    use std::str::FromStr;
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
     #[test]
-    pub fn test() {}
+    pub fn test_unzip_bundle() {
+        let mut zip = ZipWriter::new(Cursor::new(vec![]));
+        zip.add_directory("bind/", FileOptions::default()).unwrap();
+        zip.start_file("bind/app.bind", FileOptions::default())
+            .unwrap();
+        zip.write_all(b"Bind(version=1.0.0){}").unwrap();
+        zip.start_file("readme.md", FileOptions::default()).unwrap();
+        zip.write_all(b"hello").unwrap();
+        let zip = zip.finish().unwrap().into_inner();
+
+        let files = unzip(zip).unwrap();
+        assert_eq!(2, files.len());
+        let (path, bin) = files.get(0).unwrap();
+        assert_eq!(&PathBuf::from("/bind/app.bind"), path);
+        assert_eq!(b"Bind(version=1.0.0){}".to_vec(), *bin);
+        assert_eq!(ArtifactSubKind::Bind, artifact_kind(path));
+        assert_eq!(
+            ArtifactSubKind::Raw,
+            artifact_kind(&PathBuf::from("/readme.md"))
+        );
+
+        let bundle = Point::from_str("repo:my-series:1.0.0").unwrap();
+        let point = child_point(&bundle, path, false).unwrap();
+        assert_eq!("repo:my-series:1.0.0:/bind/app.bind", point.to_string());
+        assert_eq!(bundle, point.non_file_parent());
+        let point = child_point(&bundle, &PathBuf::from("/bind"), true).unwrap();
+        assert_eq!("repo:my-series:1.0.0:/bind/", point.to_string());
+    }
+
+    /// a published Bundle's files are served by the machine's [Artifacts] and stubbed
+    /// by a [FileStoreArtifactFetcher] selecting the same FileStore as the Bundle driver
+    #[cfg(feature = "postgres")]
+    #[tokio::test]
+    #[ignore = "needs the postgres server named by STARLANE_TEST_POSTGRES"]
+    pub async fn test_publish_fetch() {
+        let registry = TestRegistry::new().await;
+        let dir = temp_dir("artifact");
+        let machine = TestMachine::new(config(&registry, &dir).await).await;
+        let scribe = machine
+            .star(&StarKey::new(
+                &"central".to_string(),
+                &StarHandle::name("scribe"),
+            ))
+            .await;
+
+        let bundle = Point::from_str("fetch-repo:site:1.0.0").unwrap();
+        machine
+            .publish(
+                &scribe,
+                &bundle,
+                &[
+                    ("bind/site.bind", &b"Bind(version=1.0.0){}"[..]),
+                    ("hello.txt", &b"hello"[..]),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let hello = Point::from_str("fetch-repo:site:1.0.0:/hello.txt").unwrap();
+        let missing = Point::from_str("fetch-repo:site:1.0.0:/missing.txt").unwrap();
+        assert_eq!(
+            b"hello".to_vec(),
+            *machine.api.artifacts.get_raw(&hello).await.unwrap()
+        );
+        assert!(matches!(
+            machine.api.artifacts.get_raw(&missing).await,
+            Err(ArtErr::NotFound(_))
+        ));
+
+        let fetcher = FileStoreArtifactFetcher::new(MachineTemplate::default().services);
+        let stub = fetcher.stub(&hello).await.unwrap();
+        assert_eq!(hello, stub.point);
+        assert_eq!(Kind::Artifact(ArtifactSubKind::Raw), stub.kind);
+        let bind = Point::from_str("fetch-repo:site:1.0.0:/bind/").unwrap();
+        assert_eq!(
+            Kind::Artifact(ArtifactSubKind::Dir),
+            fetcher.stub(&bind).await.unwrap().kind
+        );
+        assert!(matches!(
+            fetcher.stub(&missing).await,
+            Err(ArtErr::NotFound(_))
+        ));
+
+        machine.terminate();
+        registry.drop().await;
+        std::fs::remove_dir_all(dir).unwrap();
+    }
 }
@@ -2,10 +2,12 @@ use crate::hyperspace::driver::{
     Driver, DriverAvail, DriverCtx, DriverErr, DriverSkel, DriverStatus, HyperDriverFactory,
     HyperSkel, Particle, ParticleErr, ParticleRouter, ParticleSphere, ParticleSphereInner,
 };
+use crate::hyperspace::hyperlane::token::{TokenStore, TokenStoreHyperAuthenticator};
 use crate::hyperspace::hyperlane::{
-    AnonHyperAuthenticatorAssignEndPoint, FromTransform, HopTransform, HyperClient, HyperGreeter,
-    Hyperway, HyperwayConfigurator, HyperwayEndpointFactory, HyperwayInterchange, HyperwayStub,
-    InterchangeGate, TransportTransform,
+    AnonHyperAuthenticatorAssignEndPoint, CertHyperAuthenticator, FromTransform, HopTransform,
+    HyperClient, HyperConnectionStatus, HyperGreeter, Hyperway, HyperwayConfigurator,
+    HyperwayEndpointFactory, HyperwayInterchange, HyperwayStub, InterchangeGate,
+    TransportTransform,
 };
 use crate::hyperspace::platform::Platform;
 use crate::hyperspace::star::{HyperStarSkel, LayerInjectionRouter};
@@ -38,13 +40,18 @@ use crate::space::wave::exchange::asynch::{
 };
 use crate::space::wave::exchange::SetStrategy;
 use crate::space::wave::{Agent, DirectedProto, PongCore, ToRecipients, Wave, WaveVariantDef};
+use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 use thiserror::Error;
+use tokio::sync::broadcast;
 
-pub struct ControlDriverFactory {}
+pub struct ControlDriverFactory {
+    cert_agents: Arc<HashMap<String, Point>>,
+    tokens: Option<TokenStore>,
+}
 
 #[async_trait]
 impl HyperDriverFactory for ControlDriverFactory {
@@ -72,6 +79,8 @@ impl HyperDriverFactory for ControlDriverFactory {
             external_router: None,
             control_ctxs: Arc::new(Default::default()),
             fabric_routers: Arc::new(Default::default()),
+            cert_agents: self.cert_agents.clone(),
+            tokens: self.tokens.clone(),
             ctx,
         }))
     }
@@ -79,7 +88,21 @@ impl HyperDriverFactory for ControlDriverFactory {
 
 impl ControlDriverFactory {
     pub fn new() -> Self {
-        Self {}
+        Self::new_with_cert_agents(Arc::new(HashMap::new()))
+    }
+
+    /// `cert_agents` maps client certificate names to the [Agent] a control connection acts as
+    pub fn new_with_cert_agents(cert_agents: Arc<HashMap<String, Point>>) -> Self {
+        Self {
+            cert_agents,
+            tokens: None,
+        }
+    }
+
+    /// accept tokens from `tokens` in the [Knock] of a control connection
+    pub fn with_tokens(mut self, tokens: TokenStore) -> Self {
+        self.tokens = Some(tokens);
+        self
     }
 }
 
@@ -114,6 +137,8 @@ impl HyperDriverFactory for ControlFactory {
             external_router: None,
             control_ctxs: Arc::new(Default::default()),
             fabric_routers: Arc::new(Default::default()),
+            cert_agents: Arc::new(HashMap::new()),
+            tokens: None,
             ctx,
         }))
     }
@@ -126,6 +151,8 @@ pub struct ControlDriver {
     pub external_router: Option<Arc<dyn Router>>,
     pub control_ctxs: Arc<DashMap<Point, ControlCtx>>,
     pub fabric_routers: Arc<DashMap<Point, LayerInjectionRouter>>,
+    pub cert_agents: Arc<HashMap<String, Point>>,
+    pub tokens: Option<TokenStore>,
 }
 
 #[derive(Clone)]
@@ -158,9 +185,15 @@ impl Driver for ControlDriver {
             self.fabric_routers.clone(),
             ctx,
         ));
-        let auth = AnonHyperAuthenticatorAssignEndPoint::new(
-            remote_point_factory,
-            self.skel.driver.logger.clone(),
+        let auth = CertHyperAuthenticator::new(
+            self.cert_agents.clone(),
+            TokenStoreHyperAuthenticator::new(
+                self.tokens.clone(),
+                AnonHyperAuthenticatorAssignEndPoint::new(
+                    remote_point_factory,
+                    self.skel.driver.logger.clone(),
+                ),
+            ),
         );
         let point = skel.point.clone();
         let mut interchange = HyperwayInterchange::new(point, self.skel.driver.logger.clone());
@@ -441,6 +474,15 @@ impl ControlClient {
         self.client.wait_for_greet().await
     }
 
+    pub fn status(&self) -> HyperConnectionStatus {
+        self.client.status()
+    }
+
+    /// subscribe to connection status changes i.e. to notice a dropped control connection
+    pub fn status_events(&self) -> broadcast::Receiver<HyperConnectionStatus> {
+        self.client.status_events()
+    }
+
     pub async fn transmitter_builder(&self) -> Result<ProtoTransmitterBuilder, SpaceErr> {
         self.client.transmitter_builder().await
     }
@@ -0,0 +1,445 @@
+use crate::hyperspace::database::Database as RegistryDatabase;
+use crate::hyperspace::driver::{
+    Driver, DriverCtx, DriverErr, DriverHandler, DriverSkel, HyperDriverFactory, Particle,
+    ParticleSphere, StdParticleErr,
+};
+use crate::hyperspace::registry::err::RegErr;
+use crate::hyperspace::registry::postgres::PostgresConnectInfo;
+use crate::hyperspace::star::HyperStarSkel;
+use crate::space::command::common::{PropertyMod, SetProperties};
+use crate::space::command::direct::create::KindTemplate;
+use crate::space::err::SpaceErr;
+use crate::space::hyper::HyperSubstance;
+use crate::space::kind::{BaseKind, DatabaseSubKind, DatabaseSubKindBase, Kind, Specific};
+use crate::space::point::Point;
+use crate::space::selector::KindSelector;
+use crate::space::util::ValueMatcher;
+use crate::space::wave::exchange::asynch::InCtx;
+use once_cell::sync::Lazy;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use starlane_macros::{handler, DirectedHandler};
+use std::str::FromStr;
+use url::Url;
+
+/// the only relational database on offer: a schema on the registry's own postgres
+pub static POSTGRES: Lazy<Specific> = Lazy::new(|| {
+    Specific::from_str("starlane.io:postgresql.org:postgres:registry:16.0.0").unwrap()
+});
+
+/// resolve a `Database<Relational<..>>` template against what this platform provides.
+/// a template without a specific selector gets the default
+pub fn select_kind(template: &KindTemplate) -> Result<Kind, SpaceErr> {
+    let sub = match &template.sub {
+        None => {
+            return Err(SpaceErr::expect_sub::<DatabaseSubKindBase>(
+                BaseKind::Database,
+            ))
+        }
+        Some(sub) => DatabaseSubKindBase::from_str(sub.as_str())?,
+    };
+
+    match sub {
+        DatabaseSubKindBase::Relational => {
+            if let Some(selector) = &template.specific {
+                if selector.is_match(&*POSTGRES).is_err() {
+                    return Err(SpaceErr::KindNotAvailable(template.clone()));
+                }
+            }
+            Ok(Kind::Database(DatabaseSubKind::Relational(
+                POSTGRES.clone(),
+            )))
+        }
+    }
+}
+
+/// both the schema and the role owning it are named after the particle
+pub fn sql_name(point: &Point) -> String {
+    format!("db_{}", point.md5())
+}
+
+pub struct DatabaseDriverFactory {
+    db: RegistryDatabase<PostgresConnectInfo>,
+}
+
+impl DatabaseDriverFactory {
+    pub fn new(db: RegistryDatabase<PostgresConnectInfo>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl HyperDriverFactory for DatabaseDriverFactory {
+    fn kind(&self) -> Kind {
+        Kind::Database(DatabaseSubKind::Relational(POSTGRES.clone()))
+    }
+
+    fn selector(&self) -> KindSelector {
+        KindSelector::from_base(BaseKind::Database)
+    }
+
+    async fn create(
+        &self,
+        _: HyperStarSkel,
+        skel: DriverSkel,
+        _: DriverCtx,
+    ) -> Result<Box<dyn Driver>, DriverErr> {
+        let pool = PgPoolOptions::new()
+            .max_connections(2)
+            .connect(self.db.to_uri().as_str())
+            .await
+            .map_err(RegErr::from)?;
+        Ok(Box::new(DatabaseDriver::new(skel, self.db.clone(), pool)))
+    }
+}
+
+pub struct DatabaseDriver {
+    skel: DriverSkel,
+    db: RegistryDatabase<PostgresConnectInfo>,
+    pool: PgPool,
+}
+
+impl DatabaseDriver {
+    pub fn new(skel: DriverSkel, db: RegistryDatabase<PostgresConnectInfo>, pool: PgPool) -> Self {
+        Self { skel, db, pool }
+    }
+}
+
+#[async_trait]
+impl Driver for DatabaseDriver {
+    fn kind(&self) -> Kind {
+        Kind::Database(DatabaseSubKind::Relational(POSTGRES.clone()))
+    }
+
+    async fn particle(&self, point: &Point) -> Result<ParticleSphere, DriverErr> {
+        let database = Database::restore((), (), point.clone());
+        Ok(database.sphere()?)
+    }
+
+    /// drop the schema along with everything in it and the role that owned it
+    async fn on_delete(&self, point: &Point) -> Result<(), DriverErr> {
+        let name = sql_name(point);
+
+        let mut conn = self.pool.acquire().await.map_err(RegErr::from)?;
+        let statement = format!("DROP SCHEMA IF EXISTS \"{}\" CASCADE", name);
+        sqlx::query(statement.as_str())
+            .execute(&mut *conn)
+            .await
+            .map_err(RegErr::from)?;
+        let exists = sqlx::query("SELECT 1 FROM pg_roles WHERE rolname=$1")
+            .bind(name.as_str())
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(RegErr::from)?
+            .is_some();
+        if exists {
+            for statement in [
+                format!("DROP OWNED BY \"{}\"", name),
+                format!("DROP ROLE \"{}\"", name),
+            ] {
+                sqlx::query(statement.as_str())
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(RegErr::from)?;
+            }
+        }
+        self.skel.logger.info(format!(
+            "dropped schema '{}' of '{}'",
+            name,
+            point.to_string()
+        ));
+        Ok(())
+    }
+
+    async fn handler(&self) -> Box<dyn DriverHandler> {
+        Box::new(DatabaseDriverHandler::restore(
+            self.skel.clone(),
+            self.db.clone(),
+            self.pool.clone(),
+        ))
+    }
+}
+
+#[derive(DirectedHandler)]
+pub struct DatabaseDriverHandler {
+    skel: DriverSkel,
+    db: RegistryDatabase<PostgresConnectInfo>,
+    pool: PgPool,
+}
+
+impl DatabaseDriverHandler {
+    fn restore(skel: DriverSkel, db: RegistryDatabase<PostgresConnectInfo>, pool: PgPool) -> Self {
+        Self { skel, db, pool }
+    }
+}
+
+impl DriverHandler for DatabaseDriverHandler {}
+
+#[handler]
+impl DatabaseDriverHandler {
+    /// create the role and schema then hand the credentials to the particle as
+    /// locked properties.  Re-assigning (a star restarting, say) only makes sure
+    /// the schema is there: the role keeps the password it was created with so
+    /// connections already made from the particle's properties stay valid.
+    ///
+    /// the credentials are ordinary properties, so anything that may read the
+    /// particle's `db-password` or `db-url` can log in to its schema
+    #[route("Hyp<Assign>")]
+    pub async fn assign(&self, ctx: InCtx<'_, HyperSubstance>) -> Result<(), DriverErr> {
+        if let HyperSubstance::Assign(assign) = ctx.input {
+            let point = &assign.details.stub.point;
+            let name = sql_name(point);
+
+            let mut conn = self.pool.acquire().await.map_err(RegErr::from)?;
+            let exists = sqlx::query("SELECT 1 FROM pg_roles WHERE rolname=$1")
+                .bind(name.as_str())
+                .fetch_optional(&mut *conn)
+                .await
+                .map_err(RegErr::from)?
+                .is_some();
+            let password = if exists {
+                None
+            } else {
+                let password = uuid::Uuid::new_v4().simple().to_string();
+                let statement = format!(
+                    "CREATE ROLE \"{}\" WITH LOGIN PASSWORD '{}'",
+                    name, password
+                );
+                sqlx::query(statement.as_str())
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(RegErr::from)?;
+                Some(password)
+            };
+            let statement = format!(
+                "CREATE SCHEMA IF NOT EXISTS \"{}\" AUTHORIZATION \"{}\"",
+                name, name
+            );
+            sqlx::query(statement.as_str())
+                .execute(&mut *conn)
+                .await
+                .map_err(RegErr::from)?;
+
+            if let Some(password) = password {
+                let mut url = Url::parse(self.db.to_uri().as_str())
+                    .map_err(|err| SpaceErr::server_error(err.to_string()))?;
+                url.set_username(name.as_str())
+                    .and_then(|_| url.set_password(Some(password.as_str())))
+                    .map_err(|_| {
+                        SpaceErr::server_error(format!(
+                            "registry database url cannot carry credentials: '{}'",
+                            self.db.url
+                        ))
+                    })?;
+
+                let mut properties = SetProperties::new();
+                for (key, value) in [
+                    ("db-url", url.to_string()),
+                    ("db-database", self.db.database.clone()),
+                    ("db-schema", name.clone()),
+                    ("db-user", name.clone()),
+                    ("db-password", password),
+                ] {
+                    properties.push(PropertyMod::Set {
+                        key: key.to_string(),
+                        value,
+                        lock: true,
+                    });
+                }
+                self.skel
+                    .registry()
+                    .set_properties(point, &properties)
+                    .await?;
+            }
+            self.skel.logger.info(format!(
+                "provisioned schema '{}' for '{}'",
+                name,
+                point.to_string()
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(DirectedHandler)]
+pub struct Database {
+    point: Point,
+}
+
+#[handler]
+impl Database {}
+
+impl Particle for Database {
+    type Skel = ();
+    type Ctx = ();
+    type State = Point;
+    type Err = StdParticleErr;
+
+    fn restore(_: Self::Skel, _: Self::Ctx, point: Self::State) -> Self {
+        Self { point }
+    }
+
+    fn sphere(self) -> Result<ParticleSphere, Self::Err> {
+        Ok(ParticleSphere::new_handler(self))
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::hyperspace::driver::database::{select_kind, sql_name, POSTGRES};
+    #[cfg(feature = "postgres")]
+    use crate::hyperspace::harness::{config, TestMachine, TestRegistry};
+    #[cfg(feature = "postgres")]
+    use crate::hyperspace::test_util::temp_dir;
+    #[cfg(feature = "postgres")]
+    use crate::space::command::common::SetProperties;
+    #[cfg(feature = "postgres")]
+    use crate::space::command::direct::create::KindTemplate;
+    use crate::space::err::SpaceErr;
+    #[cfg(feature = "postgres")]
+    use crate::space::kind::BaseKind;
+    use crate::space::kind::{DatabaseSubKind, Kind};
+    #[cfg(feature = "postgres")]
+    use crate::space::loc::{StarHandle, StarKey};
+    use crate::space::parse::kind_template;
+    use crate::space::parse::util::{new_span, result};
+    #[cfg(feature = "postgres")]
+    use crate::space::parse::CamelCase;
+    use crate::space::point::Point;
+    #[cfg(feature = "postgres")]
+    use sqlx::{Connection, PgConnection};
+    use std::str::FromStr;
+
+    #[test]
+    pub fn test_select_kind() {
+        let template = result(kind_template(new_span("Database<Relational>"))).unwrap();
+        let expected = Kind::Database(DatabaseSubKind::Relational(POSTGRES.clone()));
+        assert_eq!(expected, select_kind(&template).unwrap());
+
+        let template = result(kind_template(new_span(
+            "Database<Relational<*:postgresql.org:postgres:*:(^16)>>",
+        )))
+        .unwrap();
+        assert_eq!(expected, select_kind(&template).unwrap());
+
+        let template = result(kind_template(new_span(
+            "Database<Relational<*:mysql.com:mysql:*:(^8)>>",
+        )))
+        .unwrap();
+        assert!(matches!(
+            select_kind(&template),
+            Err(SpaceErr::KindNotAvailable(_))
+        ));
+
+        let template = result(kind_template(new_span("Database"))).unwrap();
+        assert!(select_kind(&template).is_err());
+    }
+
+    #[test]
+    pub fn test_sql_name() {
+        let point = Point::from_str("space:db").unwrap();
+        let name = sql_name(&point);
+        assert!(name.starts_with("db_"));
+        assert!(name.len() < 64);
+        assert_eq!(name, sql_name(&point));
+        assert_ne!(name, sql_name(&Point::from_str("space:other").unwrap()));
+    }
+
+    #[cfg(feature = "postgres")]
+    async fn exists(conn: &mut PgConnection, query: &str, name: &str) -> bool {
+        sqlx::query(query)
+            .bind(name)
+            .fetch_optional(conn)
+            .await
+            .unwrap()
+            .is_some()
+    }
+
+    /// assigning a `Database<Relational>` provisions its role and schema and hands
+    /// the particle the credentials.  Deleting it drops both again
+    #[cfg(feature = "postgres")]
+    #[tokio::test]
+    #[ignore = "needs the postgres server named by STARLANE_TEST_POSTGRES"]
+    pub async fn test_provision() {
+        let registry = TestRegistry::new().await;
+        let dir = temp_dir("database");
+        let machine = TestMachine::new(config(&registry, &dir).await).await;
+        let fold = machine
+            .star(&StarKey::new(
+                &"central".to_string(),
+                &StarHandle::name("fold"),
+            ))
+            .await;
+
+        let point = Point::from_str("test-db").unwrap();
+        let kind = KindTemplate {
+            base: BaseKind::Database,
+            sub: Some(CamelCase::from_str("Relational").unwrap()),
+            specific: None,
+        };
+        machine
+            .create(&fold, &point, kind, SetProperties::new())
+            .await
+            .unwrap();
+
+        let name = sql_name(&point);
+        let properties = machine.api.registry.get_properties(&point).await.unwrap();
+        let property = |key: &str| {
+            let property = properties
+                .get(key)
+                .unwrap_or_else(|| panic!("expected property '{}'", key));
+            assert!(property.locked, "'{}' should be locked", key);
+            property.value.clone()
+        };
+        assert_eq!(registry.database, property("db-database"));
+        assert_eq!(name, property("db-schema"));
+        assert_eq!(name, property("db-user"));
+        assert!(!property("db-password").is_empty());
+
+        // the particle's credentials log in as its role and own its schema
+        let mut conn = PgConnection::connect(property("db-url").as_str())
+            .await
+            .unwrap();
+        let owner: (String,) = sqlx::query_as(
+            "SELECT schema_owner::text FROM information_schema.schemata WHERE schema_name=$1",
+        )
+        .bind(name.as_str())
+        .fetch_one(&mut conn)
+        .await
+        .unwrap();
+        assert_eq!(name, owner.0);
+        let statement = format!("CREATE TABLE \"{}\".things (id INT)", name);
+        sqlx::query(statement.as_str())
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        conn.close().await.unwrap();
+
+        machine.delete(&point).await.ok_or().unwrap();
+
+        let mut admin = PgConnection::connect(registry.uri().as_str())
+            .await
+            .unwrap();
+        assert!(
+            !exists(
+                &mut admin,
+                "SELECT 1 FROM information_schema.schemata WHERE schema_name=$1",
+                name.as_str()
+            )
+            .await
+        );
+        assert!(
+            !exists(
+                &mut admin,
+                "SELECT 1 FROM pg_roles WHERE rolname=$1",
+                name.as_str()
+            )
+            .await
+        );
+        admin.close().await.unwrap();
+
+        machine.terminate();
+        registry.drop().await;
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
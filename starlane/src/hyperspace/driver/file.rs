@@ -0,0 +1,288 @@
+use crate::hyperspace::driver::{
+    Driver, DriverCtx, DriverErr, DriverHandler, DriverSkel, HyperDriverFactory, HyperParticleSkel,
+    Particle, ParticleSphere, StdParticleErr,
+};
+use crate::hyperspace::driver::filestore;
+use crate::hyperspace::executor::dialect::filestore::FileStoreIn;
+use crate::hyperspace::service::{FileStoreService, ServiceKind};
+use crate::hyperspace::star::HyperStarSkel;
+use async_trait::async_trait;
+use starlane_macros::{handler, DirectedHandler};
+use crate::space::command::common::StateSrc;
+use crate::space::err::SpaceErr;
+use crate::space::hyper::HyperSubstance;
+use crate::space::kind::{BaseKind, FileSubKind, Kind};
+use crate::space::point::Point;
+use crate::space::selector::KindSelector;
+use crate::space::substance::Substance;
+use crate::space::wave::core::ReflectedCore;
+use crate::space::wave::exchange::asynch::{DirectedHandler, InCtx};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+pub struct FileDriverFactory;
+
+impl FileDriverFactory {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl HyperDriverFactory for FileDriverFactory {
+    fn kind(&self) -> Kind {
+        Kind::File(FileSubKind::File)
+    }
+
+    fn selector(&self) -> KindSelector {
+        KindSelector::from_base(BaseKind::File)
+    }
+
+    async fn create(
+        &self,
+        _: HyperStarSkel,
+        skel: DriverSkel,
+        _: DriverCtx,
+    ) -> Result<Box<dyn Driver>, DriverErr> {
+        let service = skel.select_service(ServiceKind::FileStore).await?;
+
+        let filestore = service.filestore()?;
+
+        Ok(Box::new(FileDriver::new(skel, filestore)))
+    }
+}
+
+pub struct FileDriver {
+    skel: DriverSkel,
+    filestore: Arc<FileStoreService>,
+}
+
+impl FileDriver {
+    pub fn new(skel: DriverSkel, filestore: FileStoreService) -> Self {
+        let filestore = Arc::new(filestore);
+        Self { skel, filestore }
+    }
+}
+
+#[async_trait]
+impl Driver for FileDriver {
+    fn kind(&self) -> Kind {
+        Kind::File(FileSubKind::File)
+    }
+
+    /// remove the file (or directory) from the FileStore that owns `point`
+    async fn on_delete(&self, point: &Point) -> Result<(), DriverErr> {
+        let (filestore, path) = locate(&self.filestore, point).await?;
+        filestore::remove(&filestore, path).await
+    }
+
+    async fn particle(&self, point: &Point) -> Result<ParticleSphere, DriverErr> {
+        let (filestore, path) = locate(&self.filestore, point).await?;
+        let skel = HyperParticleSkel {
+            skel: self.skel.clone(),
+            point: point.clone(),
+            kind: Kind::File(sub_kind(point)),
+        };
+
+        let file = File::restore(skel, path, filestore);
+        Ok(file.sphere()?)
+    }
+
+    async fn handler(&self) -> Box<dyn DriverHandler> {
+        Box::new(FileDriverHandler::restore(self.filestore.clone()))
+    }
+}
+
+#[derive(DirectedHandler)]
+pub struct FileDriverHandler {
+    filestore: Arc<FileStoreService>,
+}
+
+impl FileDriverHandler {
+    fn restore(filestore: Arc<FileStoreService>) -> Self {
+        Self { filestore }
+    }
+}
+
+impl DriverHandler for FileDriverHandler {}
+
+#[handler]
+impl FileDriverHandler {
+    #[route("Hyp<Assign>")]
+    pub async fn assign(&self, ctx: InCtx<'_, HyperSubstance>) -> Result<(), DriverErr> {
+        if let HyperSubstance::Assign(assign) = ctx.input {
+            let stub = &assign.details.stub;
+            create(&self.filestore, &stub.point, &stub.kind, &assign.state).await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DirectedHandler)]
+pub struct File {
+    skel: HyperParticleSkel,
+    path: PathBuf,
+    filestore: FileStoreService,
+}
+
+impl Particle for File {
+    type Skel = HyperParticleSkel;
+    type Ctx = PathBuf;
+    type State = FileStoreService;
+    type Err = StdParticleErr;
+
+    fn restore(skel: Self::Skel, path: Self::Ctx, filestore: Self::State) -> Self {
+        File {
+            skel,
+            path,
+            filestore,
+        }
+    }
+
+    fn sphere(self) -> Result<ParticleSphere, Self::Err> {
+        Ok(ParticleSphere::new_handler(self))
+    }
+}
+
+#[handler]
+impl File {
+    /// a `File` returns its content and a `Dir` lists the names of its entries
+    #[route("Cmd<Read>")]
+    pub async fn read(&self, _: InCtx<'_, ()>) -> Result<ReflectedCore, DriverErr> {
+        let path = self.path.clone();
+        match &self.skel.kind {
+            Kind::File(FileSubKind::Dir) => {
+                let list = filestore::list(&self.filestore, path).await?;
+                Ok(ReflectedCore::ok_body(Substance::List(list)))
+            }
+            _ => {
+                let bin = filestore::read(&self.filestore, path).await?;
+                Ok(ReflectedCore::ok_body(Substance::Bin(bin)))
+            }
+        }
+    }
+}
+
+/// write the uploaded `state` of a newly created `File` (or make the `Dir`) in the
+/// FileStore that owns `point`
+pub(crate) async fn create(
+    filestore: &FileStoreService,
+    point: &Point,
+    kind: &Kind,
+    state: &StateSrc,
+) -> Result<(), DriverErr> {
+    let (filestore, path) = locate(filestore, point).await?;
+    match kind {
+        Kind::File(FileSubKind::Dir) => {
+            filestore.execute(FileStoreIn::Mkdir { path }).await?;
+        }
+        Kind::File(FileSubKind::File) => {
+            let state = match state {
+                StateSrc::None => vec![],
+                StateSrc::Subst(substance) => match &**substance {
+                    Substance::Bin(bin) => bin.clone(),
+                    Substance::Text(text) => text.as_bytes().to_vec(),
+                    substance => Err(SpaceErr::bad_request(format!(
+                        "File state must be Bin or Text, found: {}",
+                        substance.kind().to_string()
+                    )))?,
+                },
+            };
+            filestore::write(&filestore, path, state).await?;
+        }
+        kind => Err(SpaceErr::server_error(format!(
+            "FileDriver cannot assign kind: {}",
+            kind.to_string()
+        )))?,
+    }
+    Ok(())
+}
+
+/// resolve the FileStore that owns `point` (the nearest non file parent) and the
+/// path of `point` within it
+async fn locate(
+    filestore: &FileStoreService,
+    point: &Point,
+) -> Result<(FileStoreService, PathBuf), DriverErr> {
+    let store = point.non_file_parent();
+    let path = point.filepath().ok_or(SpaceErr::server_error(format!(
+        "'{}' is not a filesystem point",
+        point.to_string()
+    )))?;
+    let filestore = filestore.sub_root(store.md5().into()).await?;
+    Ok((filestore, path.into()))
+}
+
+fn sub_kind(point: &Point) -> FileSubKind {
+    match point.last_segment() {
+        Some(segment) if segment.is_file() => FileSubKind::File,
+        _ => FileSubKind::Dir,
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::hyperspace::driver::file::{create, locate, sub_kind};
+    use crate::hyperspace::driver::filestore;
+    use crate::hyperspace::service::tests::local_filestore;
+    use crate::hyperspace::test_util::temp_dir;
+    use crate::space::command::common::StateSrc;
+    use crate::space::kind::{FileSubKind, Kind};
+    use crate::space::point::Point;
+    use crate::space::substance::Substance;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    pub async fn test_create_read() {
+        let root = temp_dir("file-driver");
+        let service = local_filestore(root.to_str().unwrap());
+        let store = Point::from_str("space:files").unwrap();
+        filestore::create(&service, &store).await.unwrap();
+
+        let point = Point::from_str("space:files:/docs/readme.md").unwrap();
+        let state = StateSrc::Subst(Box::new(Substance::Text("# readme".to_string())));
+        create(&service, &point, &Kind::File(FileSubKind::File), &state)
+            .await
+            .unwrap();
+        let (filestore, path) = locate(&service, &point).await.unwrap();
+        assert_eq!(
+            "# readme".as_bytes(),
+            filestore::read(&filestore, path).await.unwrap().as_slice()
+        );
+
+        let dir = Point::from_str("space:files:/docs/").unwrap();
+        let (filestore, path) = locate(&service, &dir).await.unwrap();
+        let list = filestore::list(&filestore, path).await.unwrap();
+        assert_eq!(
+            Substance::Text("readme.md".to_string()),
+            **list.first().unwrap()
+        );
+
+        let state = StateSrc::Subst(Box::new(Substance::Empty));
+        assert!(
+            create(&service, &point, &Kind::File(FileSubKind::File), &state)
+                .await
+                .is_err()
+        );
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    pub fn test_file_point() {
+        let point = Point::from_str("space:files:/docs/readme.md").unwrap();
+        assert_eq!(FileSubKind::File, sub_kind(&point));
+        assert_eq!(Some("/docs/readme.md".to_string()), point.filepath());
+        assert_eq!(
+            Point::from_str("space:files").unwrap(),
+            point.non_file_parent()
+        );
+
+        let point = Point::from_str("space:files:/docs/").unwrap();
+        assert_eq!(FileSubKind::Dir, sub_kind(&point));
+
+        let point = Point::from_str("space:files:/").unwrap();
+        assert_eq!(FileSubKind::Dir, sub_kind(&point));
+        assert_eq!(Some("/".to_string()), point.filepath());
+    }
+}
@@ -1,22 +1,40 @@
 use crate::hyperspace::driver::{
-    Driver, DriverAvail, DriverCtx, DriverErr, DriverSkel, HyperDriverFactory, Particle,
-    ParticleSphere, ParticleSphereInner, StdParticleErr,
+    Driver, DriverAvail, DriverCtx, DriverErr, DriverHandler, DriverSkel, DriverStatus,
+    HyperDriverFactory, HyperParticleSkel, Particle, ParticleSphere, ParticleSphereInner,
+    StdParticleErr,
 };
 
-
+use crate::hyperspace::executor::cli::CliErr;
+use crate::hyperspace::executor::dialect::filestore::{
+    FileStoreErr, FileStoreIn, FileStoreOut, RootDir,
+};
 use crate::hyperspace::platform::Platform;
+use crate::hyperspace::reg::Registration;
+use crate::hyperspace::service::{FileStoreService, ServiceKind};
 use crate::hyperspace::star::HyperStarSkel;
 use async_trait::async_trait;
 use once_cell::sync::Lazy;
 use starlane_macros::{handler, DirectedHandler};
 use crate::space::artifact::ArtRef;
+use crate::space::command::direct::create::Strategy;
+use crate::space::command::direct::delete::Delete;
+use crate::space::command::Command;
 use crate::space::config::bind::BindConfig;
-use crate::space::kind::{BaseKind, Kind};
+use crate::space::err::SpaceErr;
+use crate::space::hyper::HyperSubstance;
+use crate::space::kind::{BaseKind, FileSubKind, Kind};
+use crate::space::loc::{ToPoint, ToSurface};
 use crate::space::parse::bind_config;
+use crate::space::particle::Status;
 use crate::space::point::Point;
-use crate::space::selector::KindSelector;
+use crate::space::selector::{KindSelector, Selector};
+use crate::space::substance::{Bin, Substance, SubstanceList, SubstanceMap};
 use crate::space::util::log;
-use crate::space::wave::exchange::asynch::DirectedHandler;
+use crate::space::wave::core::cmd::CmdMethod;
+use crate::space::wave::core::ReflectedCore;
+use crate::space::wave::exchange::asynch::{DirectedHandler, InCtx};
+use crate::space::wave::DirectedProto;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -43,20 +61,35 @@ impl HyperDriverFactory for FileStoreDriverFactory {
     async fn create(
         &self,
         _: HyperStarSkel,
-        _: DriverSkel,
+        skel: DriverSkel,
         _: DriverCtx,
     ) -> Result<Box<dyn Driver>, DriverErr> {
-        Ok(Box::new(FileStoreDriver::new(self.avail.clone())))
+        let service = skel.select_service(ServiceKind::FileStore).await?;
+
+        let filestore = service.filestore()?;
+
+        Ok(Box::new(FileStoreDriver::new(
+            skel,
+            filestore,
+            self.avail.clone(),
+        )))
     }
 }
 
 pub struct FileStoreDriver {
+    pub skel: DriverSkel,
+    pub filestore: Arc<FileStoreService>,
     pub avail: DriverAvail,
 }
 
 impl FileStoreDriver {
-    pub fn new(avail: DriverAvail) -> Self {
-        Self { avail }
+    pub fn new(skel: DriverSkel, filestore: FileStoreService, avail: DriverAvail) -> Self {
+        let filestore = Arc::new(filestore);
+        Self {
+            skel,
+            filestore,
+            avail,
+        }
     }
 }
 
@@ -66,24 +99,85 @@ impl Driver for FileStoreDriver {
         Kind::FileStore
     }
 
+    fn avail(&self) -> DriverAvail {
+        self.avail.clone()
+    }
+
+    async fn init(&mut self, skel: DriverSkel, _: DriverCtx) -> Result<(), DriverErr> {
+        skel.logger
+            .result(skel.status_tx.send(DriverStatus::Init).await)
+            .unwrap_or_default();
+
+        self.filestore.execute(FileStoreIn::Init).await?;
+
+        skel.logger
+            .result(skel.status_tx.send(DriverStatus::Ready).await)
+            .unwrap_or_default();
+        Ok(())
+    }
+
+    /// reclaim the root each FileStore was given within the service
+    async fn on_delete(&self, point: &Point) -> Result<(), DriverErr> {
+        reclaim(&self.filestore, point).await
+    }
+
     async fn particle(&self, point: &Point) -> Result<ParticleSphere, DriverErr> {
-        let base = FileStore::restore((), (), ());
+        let filestore = self.filestore.sub_root(point.md5().into()).await?;
+        let skel = HyperParticleSkel {
+            skel: self.skel.clone(),
+            point: point.clone(),
+            kind: Kind::FileStore,
+        };
+
+        let base = FileStore::restore(skel, (), filestore);
 
         Ok(base.sphere()?)
     }
+
+    async fn handler(&self) -> Box<dyn DriverHandler> {
+        Box::new(FileStoreDriverHandler::restore(self.filestore.clone()))
+    }
+}
+
+#[derive(DirectedHandler)]
+pub struct FileStoreDriverHandler {
+    filestore: Arc<FileStoreService>,
+}
+
+impl FileStoreDriverHandler {
+    fn restore(filestore: Arc<FileStoreService>) -> Self {
+        Self { filestore }
+    }
+}
+
+impl DriverHandler for FileStoreDriverHandler {}
+
+#[handler]
+impl FileStoreDriverHandler {
+    /// give each newly assigned FileStore its own root within the service
+    #[route("Hyp<Assign>")]
+    pub async fn assign(&self, ctx: InCtx<'_, HyperSubstance>) -> Result<(), DriverErr> {
+        if let HyperSubstance::Assign(assign) = ctx.input {
+            create(&self.filestore, &assign.details.stub.point).await?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(DirectedHandler)]
-pub struct FileStore;
+pub struct FileStore {
+    skel: HyperParticleSkel,
+    filestore: FileStoreService,
+}
 
 impl Particle for FileStore {
-    type Skel = ();
+    type Skel = HyperParticleSkel;
     type Ctx = ();
-    type State = ();
+    type State = FileStoreService;
     type Err = StdParticleErr;
 
-    fn restore(_: Self::Skel, _: Self::Ctx, _: Self::State) -> Self {
-        FileStore
+    fn restore(skel: Self::Skel, _: Self::Ctx, filestore: Self::State) -> Self {
+        FileStore { skel, filestore }
     }
 
     fn sphere(self) -> Result<ParticleSphere, Self::Err> {
@@ -91,5 +185,415 @@ impl Particle for FileStore {
     }
 }
 
+impl FileStore {
+    /// register `path` as a `File` particle beneath this FileStore, ensuring every
+    /// parent directory along the way is registered as a `Dir`
+    async fn register(
+        &self,
+        path: &PathBuf,
+        kind: FileSubKind,
+        owner: &Point,
+    ) -> Result<(), DriverErr> {
+        let star = self.skel.skel.locate(&self.skel.point).await?.location.star;
+
+        let mut points = vec![];
+        let mut dir = PathBuf::from("/");
+        points.push((child_point(&self.skel.point, &dir, true)?, FileSubKind::Dir));
+        if let Some(parent) = path.parent() {
+            for component in parent.iter().skip(1) {
+                dir.push(component);
+                points.push((child_point(&self.skel.point, &dir, true)?, FileSubKind::Dir));
+            }
+        }
+        if path.parent().is_some() {
+            let is_dir = kind == FileSubKind::Dir;
+            points.push((child_point(&self.skel.point, path, is_dir)?, kind));
+        }
+
+        for (point, kind) in points {
+            let registration = Registration {
+                point: point.clone(),
+                kind: Kind::File(kind),
+                registry: Default::default(),
+                properties: Default::default(),
+                owner: owner.clone(),
+                strategy: Strategy::Ensure,
+                status: Status::Ready,
+            };
+            self.skel.skel.registry().register(&registration).await?;
+            if let Some(star) = &star {
+                self.skel.skel.registry().assign_star(&point, star).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[handler]
-impl FileStore {}
+impl FileStore {
+    #[route("Cmd<Read>")]
+    pub async fn read(&self, ctx: InCtx<'_, String>) -> Result<ReflectedCore, DriverErr> {
+        let path = norm(ctx.input)?;
+        let bin = read(&self.filestore, path).await?;
+        Ok(ReflectedCore::ok_body(Substance::Bin(bin)))
+    }
+
+    /// expects a map with a `path` and the `content` to write to it
+    #[route("Cmd<Update>")]
+    pub async fn update(&self, ctx: InCtx<'_, SubstanceMap>) -> Result<ReflectedCore, DriverErr> {
+        let path = match ctx.input.get("path") {
+            Some(Substance::Text(path)) => norm(path)?,
+            _ => Err(SpaceErr::bad_request("FileStore update expects a 'path'"))?,
+        };
+        let state = match ctx.input.get("content") {
+            Some(Substance::Bin(bin)) => bin.clone(),
+            Some(Substance::Text(text)) => text.as_bytes().to_vec(),
+            None => vec![],
+            _ => Err(SpaceErr::bad_request(
+                "FileStore update 'content' must be Bin or Text",
+            ))?,
+        };
+
+        write(&self.filestore, path.clone(), state).await?;
+
+        let owner = ctx.wave().agent().clone().to_point();
+        self.register(&path, FileSubKind::File, &owner).await?;
+        Ok(ReflectedCore::ok())
+    }
+
+    #[route("Ext<Mkdir>")]
+    pub async fn mkdir(&self, ctx: InCtx<'_, String>) -> Result<ReflectedCore, DriverErr> {
+        let path = norm(ctx.input)?;
+        self.filestore
+            .execute(FileStoreIn::Mkdir { path: path.clone() })
+            .await?;
+
+        let owner = ctx.wave().agent().clone().to_point();
+        self.register(&path, FileSubKind::Dir, &owner).await?;
+        Ok(ReflectedCore::ok())
+    }
+
+    /// returns the names of the entries directly within a directory
+    #[route("Ext<List>")]
+    pub async fn list(&self, ctx: InCtx<'_, String>) -> Result<ReflectedCore, DriverErr> {
+        let path = norm(ctx.input)?;
+        let list = list(&self.filestore, path).await?;
+        Ok(ReflectedCore::ok_body(Substance::List(list)))
+    }
+
+    /// deletes the `File` at `path` (a directory with everything beneath it) the way the
+    /// `delete` command would so each one is retracted before its registry row goes
+    #[route("Ext<Remove>")]
+    pub async fn remove(&self, ctx: InCtx<'_, String>) -> Result<ReflectedCore, DriverErr> {
+        let path = norm(ctx.input)?;
+        if path.parent().is_none() {
+            Err(SpaceErr::bad_request("cannot remove the FileStore root"))?;
+        }
+
+        let dir = is_dir(&self.filestore, &path).await?;
+        let point = child_point(&self.skel.point, &path, dir)?;
+        let selector = match dir {
+            true => format!("{}+:**", point.to_string()),
+            false => point.to_string(),
+        };
+        let delete = Delete {
+            selector: Selector::from_str(selector.as_str()).map_err(SpaceErr::from)?,
+        };
+        let mut proto = DirectedProto::ping();
+        proto.method(CmdMethod::Command);
+        proto.body(Substance::Command(Box::new(Command::Delete(delete))));
+        proto.agent(ctx.wave().agent().clone());
+        proto.to(Point::global_executor().to_surface());
+        ctx.transmitter.ping(proto).await?.ok_or()?;
+
+        // whatever was written without ever being registered
+        remove(&self.filestore, path).await?;
+        Ok(ReflectedCore::ok())
+    }
+}
+
+/// give the FileStore at `point` its own root within the driver's `filestore`
+pub(crate) async fn create(
+    filestore: &FileStoreService,
+    point: &Point,
+) -> Result<FileStoreService, DriverErr> {
+    let store = filestore.sub_root(point.md5().into()).await?;
+    store.execute(FileStoreIn::Init).await?;
+    Ok(store)
+}
+
+/// remove the root [create] gave the particle at `point`
+pub(crate) async fn reclaim(filestore: &FileStoreService, point: &Point) -> Result<(), DriverErr> {
+    remove(filestore, point.md5().into()).await
+}
+
+/// remove `path` from `filestore` if it is there
+pub(crate) async fn remove(filestore: &FileStoreService, path: PathBuf) -> Result<(), DriverErr> {
+    if let FileStoreOut::Exists(true) = filestore
+        .execute(FileStoreIn::Exists { path: path.clone() })
+        .await?
+    {
+        filestore.execute(FileStoreIn::Remove { path }).await?;
+    }
+    Ok(())
+}
+
+/// write `state` to `path` making any missing parent directories first
+pub(crate) async fn write(
+    filestore: &FileStoreService,
+    path: PathBuf,
+    state: Bin,
+) -> Result<(), DriverErr> {
+    if let Some(parent) = path.parent() {
+        filestore
+            .execute(FileStoreIn::Mkdir {
+                path: parent.to_path_buf(),
+            })
+            .await?;
+    }
+    filestore.execute(FileStoreIn::Write { path, state }).await?;
+    Ok(())
+}
+
+pub(crate) async fn read(filestore: &FileStoreService, path: PathBuf) -> Result<Bin, DriverErr> {
+    match filestore.execute(FileStoreIn::Read { path }).await? {
+        FileStoreOut::Read(bin) => Ok(bin),
+        _ => Err(SpaceErr::server_error(
+            "FileStore read returned unexpected output",
+        ))?,
+    }
+}
+
+/// the names of the entries directly within the directory at `path`
+pub(crate) async fn list(
+    filestore: &FileStoreService,
+    path: PathBuf,
+) -> Result<SubstanceList, DriverErr> {
+    match filestore.execute(FileStoreIn::List { path }).await? {
+        FileStoreOut::List(paths) => {
+            let mut list = SubstanceList::new();
+            for path in paths {
+                if let Some(name) = path.file_name() {
+                    let name = name.to_string_lossy().to_string();
+                    list.push(Box::new(Substance::Text(name)));
+                }
+            }
+            Ok(list)
+        }
+        _ => Err(SpaceErr::server_error(
+            "FileStore list returned unexpected output",
+        ))?,
+    }
+}
+
+/// true when `path` is a directory.  Only a directory can be listed so a `list` the
+/// FileStore refuses means `path` is not one.  Any other error is the call failing
+pub(crate) async fn is_dir(
+    filestore: &FileStoreService,
+    path: &PathBuf,
+) -> Result<bool, DriverErr> {
+    match filestore
+        .execute(FileStoreIn::List { path: path.clone() })
+        .await
+    {
+        Ok(_) => Ok(true),
+        Err(FileStoreErr::CliErr(CliErr::Exit { .. })) => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// clean `path` and anchor it to the FileStore root so `..` can never climb out
+fn norm<S>(path: S) -> Result<PathBuf, DriverErr>
+where
+    S: AsRef<str>,
+{
+    Ok(RootDir::new("/".into())
+        .norm(&PathBuf::from(path.as_ref()))
+        .map_err(FileStoreErr::from)?)
+}
+
+/// the point of the `File` (or `Artifact`) at `path` within the filesystem of `store`
+/// i.e. `store:/dir/file.txt` or `store:/dir/` when `dir` is true
+pub(crate) fn child_point(store: &Point, path: &PathBuf, dir: bool) -> Result<Point, DriverErr> {
+    let mut path = path.to_string_lossy().to_string();
+    if dir && !path.ends_with("/") {
+        path.push('/');
+    }
+    let point = Point::from_str(format!("{}:{}", store.to_string(), path).as_str());
+    Ok(point.map_err(SpaceErr::from)?)
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::hyperspace::driver::filestore::{
+        child_point, create, is_dir, list, norm, read, write,
+    };
+    #[cfg(feature = "postgres")]
+    use crate::hyperspace::harness::{config, kind, TestMachine, TestRegistry};
+    use crate::hyperspace::service::tests::local_filestore;
+    #[cfg(feature = "postgres")]
+    use crate::hyperspace::star::HyperStarSkel;
+    use crate::hyperspace::test_util::temp_dir;
+    #[cfg(feature = "postgres")]
+    use crate::space::command::common::SetProperties;
+    #[cfg(feature = "postgres")]
+    use crate::space::kind::BaseKind;
+    #[cfg(feature = "postgres")]
+    use crate::space::loc::{StarHandle, StarKey, ToSurface};
+    use crate::space::point::Point;
+    use crate::space::substance::Substance;
+    #[cfg(feature = "postgres")]
+    use crate::space::substance::SubstanceMap;
+    #[cfg(feature = "postgres")]
+    use crate::space::wave::core::cmd::CmdMethod;
+    #[cfg(feature = "postgres")]
+    use crate::space::wave::core::ext::ExtMethod;
+    #[cfg(feature = "postgres")]
+    use crate::space::wave::core::{Method, ReflectedCore};
+    #[cfg(feature = "postgres")]
+    use crate::space::wave::DirectedProto;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    pub async fn test_create_write_read() {
+        let dir = temp_dir("filestore-driver");
+        let filestore = local_filestore(dir.to_str().unwrap());
+        let point = Point::from_str("my-domain.com:files").unwrap();
+        let store = create(&filestore, &point).await.unwrap();
+
+        let path = norm("/docs/readme.txt").unwrap();
+        write(&store, path.clone(), "hello".as_bytes().to_vec())
+            .await
+            .unwrap();
+        assert_eq!("hello".as_bytes(), read(&store, path).await.unwrap().as_slice());
+
+        let names = list(&store, norm("/docs").unwrap()).await.unwrap();
+        assert_eq!(1, names.len());
+        assert_eq!(
+            Substance::Text("readme.txt".to_string()),
+            **names.first().unwrap()
+        );
+
+        // a second FileStore does not see the first one's files
+        let other = Point::from_str("my-domain.com:other-files").unwrap();
+        let other = create(&filestore, &other).await.unwrap();
+        assert!(read(&other, norm("/docs/readme.txt").unwrap()).await.is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    pub async fn test_is_dir() {
+        let dir = temp_dir("filestore-is-dir");
+        let filestore = local_filestore(dir.to_str().unwrap());
+        let point = Point::from_str("my-domain.com:files").unwrap();
+        let store = create(&filestore, &point).await.unwrap();
+        write(&store, norm("/docs/readme.txt").unwrap(), vec![])
+            .await
+            .unwrap();
+
+        assert!(is_dir(&store, &norm("/docs").unwrap()).await.unwrap());
+        assert!(!is_dir(&store, &norm("/docs/readme.txt").unwrap())
+            .await
+            .unwrap());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(feature = "postgres")]
+    async fn send<M>(star: &HyperStarSkel, to: &Point, method: M, body: Substance) -> ReflectedCore
+    where
+        M: Into<Method>,
+    {
+        let mut proto = DirectedProto::ping();
+        proto.method(method);
+        proto.body(body);
+        proto.to(to.clone().to_surface());
+        star.gravity_transmitter
+            .ping(proto)
+            .await
+            .unwrap()
+            .variant
+            .core
+    }
+
+    /// removing a directory deletes the File particles registered beneath it
+    #[cfg(feature = "postgres")]
+    #[tokio::test]
+    #[ignore = "needs the postgres server named by STARLANE_TEST_POSTGRES"]
+    pub async fn test_remove_deletes_children() {
+        let registry = TestRegistry::new().await;
+        let dir = temp_dir("filestore");
+        let machine = TestMachine::new(config(&registry, &dir).await).await;
+        let scribe = machine
+            .star(&StarKey::new(
+                &"central".to_string(),
+                &StarHandle::name("scribe"),
+            ))
+            .await;
+        let store = Point::from_str("files").unwrap();
+        machine
+            .create(&scribe, &store, kind(BaseKind::FileStore), SetProperties::new())
+            .await
+            .unwrap();
+
+        let mut update = SubstanceMap::new();
+        update.insert(
+            "path".to_string(),
+            Substance::Text("/docs/sub/readme.txt".to_string()),
+        );
+        update.insert("content".to_string(), Substance::Text("hello".to_string()));
+        send(&scribe, &store, CmdMethod::Update, Substance::Map(update))
+            .await
+            .ok_or()
+            .unwrap();
+
+        let points: Vec<Point> = ["files:/docs/", "files:/docs/sub/", "files:/docs/sub/readme.txt"]
+            .iter()
+            .map(|point| Point::from_str(point).unwrap())
+            .collect();
+        for point in points.iter() {
+            assert!(scribe.registry.record(point).await.is_ok());
+        }
+
+        let remove = ExtMethod::new("Remove").unwrap();
+        send(&scribe, &store, remove, Substance::Text("/docs".to_string()))
+            .await
+            .ok_or()
+            .unwrap();
+        for point in points.iter() {
+            assert!(scribe.registry.record(point).await.is_err());
+        }
+        let read = Substance::Text("/docs/sub/readme.txt".to_string());
+        assert!(send(&scribe, &store, CmdMethod::Read, read)
+            .await
+            .ok_or()
+            .is_err());
+
+        machine.terminate();
+        registry.drop().await;
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    pub fn test_child_point() {
+        let store = Point::from_str("my-domain.com:files").unwrap();
+        let path = norm("docs/../readme.txt").unwrap();
+        assert_eq!(PathBuf::from("/readme.txt"), path);
+
+        let point = child_point(&store, &path, false).unwrap();
+        assert_eq!("my-domain.com:files:/readme.txt", point.to_string());
+
+        let point = child_point(&store, &norm("/docs").unwrap(), true).unwrap();
+        assert_eq!("my-domain.com:files:/docs/", point.to_string());
+
+        let point = child_point(&store, &PathBuf::from("/"), true).unwrap();
+        assert_eq!("my-domain.com:files:/", point.to_string());
+
+        assert_eq!(
+            PathBuf::from("/etc/passwd"),
+            norm("../../etc/passwd").unwrap()
+        );
+    }
+}
@@ -0,0 +1,620 @@
+use crate::hyperspace::driver::{
+    Driver, DriverCtx, DriverErr, DriverHandler, DriverSkel, HyperDriverFactory, HyperParticleSkel,
+    Particle, ParticleSphere, StdParticleErr,
+};
+use crate::hyperspace::executor::cli::os::CliOsExecutor;
+use crate::hyperspace::executor::cli::{CliErr, HostEnv};
+use crate::hyperspace::host::ExeStub;
+use crate::hyperspace::star::HyperStarSkel;
+use crate::space::err::SpaceErr;
+use crate::space::hyper::HyperSubstance;
+use crate::space::kind::{BaseKind, Kind};
+use crate::space::log::Logger;
+use crate::space::particle::Properties;
+use crate::space::point::Point;
+use crate::space::selector::KindSelector;
+use crate::space::substance::{Substance, SubstanceList};
+use crate::space::wave::core::cmd::CmdMethod;
+use crate::space::wave::core::http2::StatusCode;
+use crate::space::wave::core::{CoreBounce, DirectedCore, Method, ReflectedCore};
+use crate::space::wave::exchange::asynch::{DirectedHandler, InCtx, RootInCtx};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use starlane_macros::{handler, DirectedHandler};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::ChildStdin;
+use tokio::sync::{oneshot, watch, Mutex};
+
+/// environment variable telling the executable which host particle it is running as
+pub const HOST_POINT_ENV: &'static str = "HOST_POINT";
+
+/// how many lines of stdout/stderr output a host keeps for its log aspect
+const LOG_TAIL: usize = 256;
+
+/// how long a wave waits on the executable's reply before failing
+const RELAY_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub struct HostDriverFactory;
+
+impl HostDriverFactory {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl HyperDriverFactory for HostDriverFactory {
+    fn kind(&self) -> Kind {
+        Kind::Host
+    }
+
+    fn selector(&self) -> KindSelector {
+        KindSelector::from_base(BaseKind::Host)
+    }
+
+    async fn create(
+        &self,
+        _: HyperStarSkel,
+        skel: DriverSkel,
+        _: DriverCtx,
+    ) -> Result<Box<dyn Driver>, DriverErr> {
+        Ok(Box::new(HostDriver::new(skel)))
+    }
+}
+
+pub struct HostDriver {
+    skel: DriverSkel,
+    procs: HostProcs,
+}
+
+impl HostDriver {
+    pub fn new(skel: DriverSkel) -> Self {
+        let procs = HostProcs::new();
+        Self { skel, procs }
+    }
+}
+
+#[async_trait]
+impl Driver for HostDriver {
+    fn kind(&self) -> Kind {
+        Kind::Host
+    }
+
+    async fn particle(&self, point: &Point) -> Result<ParticleSphere, DriverErr> {
+        let skel = HyperParticleSkel {
+            skel: self.skel.clone(),
+            point: point.clone(),
+            kind: Kind::Host,
+        };
+        let proc = match self.procs.get(point) {
+            Some(proc) => proc,
+            None => {
+                let proc = self.procs.supervise(&self.skel, point, None).await?;
+                // a restarted host picks up the log it had before the restart
+                match skel.state::<Vec<String>>().await {
+                    Ok(Some(tail)) => proc.resume(tail).await,
+                    Ok(None) => {}
+                    Err(err) => self.skel.logger.warn(format!(
+                        "host '{}' could not restore its log: {}",
+                        point.to_string(),
+                        err.to_string()
+                    )),
+                }
+                proc
+            }
+        };
+        let host = Host::restore(skel, (), proc);
+        Ok(host.sphere()?)
+    }
+
+    /// a host's log aspect outlives a restart of its star
+    fn stateful(&self) -> bool {
+        true
+    }
+
+    async fn handler(&self) -> Box<dyn DriverHandler> {
+        Box::new(HostDriverHandler::restore(
+            self.skel.clone(),
+            self.procs.clone(),
+        ))
+    }
+
+    async fn on_delete(&self, point: &Point) -> Result<(), DriverErr> {
+        self.procs.remove(point);
+        Ok(())
+    }
+}
+
+#[derive(DirectedHandler)]
+pub struct HostDriverHandler {
+    skel: DriverSkel,
+    procs: HostProcs,
+}
+
+impl HostDriverHandler {
+    fn restore(skel: DriverSkel, procs: HostProcs) -> Self {
+        Self { skel, procs }
+    }
+}
+
+impl DriverHandler for HostDriverHandler {}
+
+#[handler]
+impl HostDriverHandler {
+    /// start the executable as soon as the host is created
+    #[route("Hyp<Assign>")]
+    pub async fn assign(&self, ctx: InCtx<'_, HyperSubstance>) -> Result<(), DriverErr> {
+        if let HyperSubstance::Assign(assign) = ctx.input {
+            let details = &assign.details;
+            self.procs
+                .supervise(&self.skel, &details.stub.point, Some(&details.properties))
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Host {
+    skel: HyperParticleSkel,
+    proc: Arc<HostProc>,
+}
+
+impl Particle for Host {
+    type Skel = HyperParticleSkel;
+    type Ctx = ();
+    type State = Arc<HostProc>;
+    type Err = StdParticleErr;
+
+    fn restore(skel: Self::Skel, _: Self::Ctx, proc: Self::State) -> Self {
+        Host { skel, proc }
+    }
+
+    fn sphere(self) -> Result<ParticleSphere, Self::Err> {
+        Ok(ParticleSphere::new_handler(self))
+    }
+}
+
+#[async_trait]
+impl DirectedHandler for Host {
+    async fn handle(&self, ctx: RootInCtx) -> CoreBounce {
+        let core = ctx.wave.core();
+        // `Cmd<Log>` reads the host's log aspect, everything else goes to the process
+        let result = if core.method == Method::Cmd(CmdMethod::Log) {
+            Ok(self.proc.log().await)
+        } else {
+            self.proc.relay(core).await
+        };
+
+        // nothing is written unless the log changed since the last snapshot
+        if let Err(err) = self.skel.snapshot(&self.proc.tail().await).await {
+            self.skel.skel.logger.warn(format!(
+                "host '{}' could not snapshot its log: {}",
+                self.skel.point.to_string(),
+                err.to_string()
+            ));
+        }
+
+        match result {
+            Ok(core) => CoreBounce::Reflected(core),
+            Err(err) => {
+                self.skel.skel.logger.error(format!(
+                    "host '{}' failed: {}",
+                    self.skel.point.to_string(),
+                    err.to_string()
+                ));
+                CoreBounce::Reflected(ReflectedCore::server_error())
+            }
+        }
+    }
+}
+
+/// one line written to the executable's stdin per directed wave
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostRequest {
+    pub id: u64,
+    pub method: String,
+    pub uri: String,
+    pub body: Substance,
+}
+
+/// one line the executable writes to stdout in reply to the [HostRequest] with
+/// the same `id`.  Any other stdout line is treated as log output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostReply {
+    pub id: u64,
+    pub status: u16,
+    pub body: Substance,
+}
+
+/// the one live executable of each host point.  Starting a process is serialized
+/// so a point is never spawned twice
+#[derive(Clone)]
+pub struct HostProcs {
+    procs: Arc<DashMap<Point, Arc<HostProc>>>,
+    lock: Arc<Mutex<()>>,
+}
+
+impl HostProcs {
+    pub fn new() -> Self {
+        Self {
+            procs: Arc::new(DashMap::new()),
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// the process of `point` if it is still running
+    pub fn get(&self, point: &Point) -> Option<Arc<HostProc>> {
+        self.procs
+            .get(point)
+            .map(|proc| proc.clone())
+            .filter(|proc| proc.is_alive())
+    }
+
+    /// return the running process of `point` or start it.  `properties` are read
+    /// from the registry when not given
+    pub async fn supervise(
+        &self,
+        skel: &DriverSkel,
+        point: &Point,
+        properties: Option<&Properties>,
+    ) -> Result<Arc<HostProc>, DriverErr> {
+        let _lock = self.lock.lock().await;
+        if let Some(proc) = self.get(point) {
+            return Ok(proc);
+        }
+        let proc = match properties {
+            Some(properties) => supervise(skel, point, properties).await?,
+            None => supervise(skel, point, &skel.registry().get_properties(point).await?).await?,
+        };
+        self.procs.insert(point.clone(), proc.clone());
+        Ok(proc)
+    }
+
+    /// forget the process of `point` and kill it
+    pub fn remove(&self, point: &Point) {
+        if let Some((_, proc)) = self.procs.remove(point) {
+            proc.kill();
+        }
+    }
+}
+
+/// a running executable and the waves waiting on its replies
+pub struct HostProc {
+    stdin: Mutex<ChildStdin>,
+    seq: AtomicU64,
+    pending: Arc<DashMap<u64, oneshot::Sender<HostReply>>>,
+    tail: Arc<Mutex<VecDeque<String>>>,
+    logged: watch::Sender<usize>,
+    alive: Arc<AtomicBool>,
+    kill: std::sync::Mutex<Option<oneshot::Sender<()>>>,
+}
+
+impl HostProc {
+    /// spawn the executable and watch its output and exit
+    pub fn spawn(executor: &CliOsExecutor, logger: Logger) -> Result<Self, CliErr> {
+        let mut process = executor.spawn(&vec![])?;
+        let stdin = process.stdin.take().ok_or(CliErr::TakeStdIn)?;
+        let stdout = process.stdout.take().ok_or(CliErr::TakeStdOut)?;
+        let stderr = process.stderr.take().ok_or(CliErr::TakeStdErr)?;
+
+        let pending: Arc<DashMap<u64, oneshot::Sender<HostReply>>> = Arc::new(DashMap::new());
+        let tail = Arc::new(Mutex::new(VecDeque::new()));
+        let logged = watch::Sender::new(0);
+        let alive = Arc::new(AtomicBool::new(true));
+        let (kill_tx, kill_rx) = oneshot::channel::<()>();
+
+        {
+            let pending = pending.clone();
+            let tail = tail.clone();
+            let logged = logged.clone();
+            let logger = logger.clone();
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stdout).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    match serde_json::from_str::<HostReply>(line.as_str()) {
+                        Ok(reply) => {
+                            if let Some((_, tx)) = pending.remove(&reply.id) {
+                                tx.send(reply).unwrap_or_default();
+                            }
+                        }
+                        Err(_) => {
+                            logger.info(line.as_str());
+                            push(&tail, &logged, line).await;
+                        }
+                    }
+                }
+            });
+        }
+
+        {
+            let tail = tail.clone();
+            let logged = logged.clone();
+            let logger = logger.clone();
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    logger.warn(line.as_str());
+                    push(&tail, &logged, line).await;
+                }
+            });
+        }
+
+        {
+            let pending = pending.clone();
+            let alive = alive.clone();
+            tokio::spawn(async move {
+                tokio::select! {
+                    status = process.wait() => match status {
+                        Ok(status) => logger.warn(format!("host exited: {}", status)),
+                        Err(err) => logger.error(format!("host wait failed: {}", err)),
+                    },
+                    // fires on `kill` or when the proc is dropped
+                    _ = kill_rx => {
+                        if let Err(err) = process.kill().await {
+                            logger.error(format!("host kill failed: {}", err));
+                        }
+                    }
+                }
+                alive.store(false, Ordering::Relaxed);
+                // dropping the senders fails every wave still waiting on a reply
+                pending.clear();
+            });
+        }
+
+        Ok(Self {
+            stdin: Mutex::new(stdin),
+            seq: AtomicU64::new(0),
+            pending,
+            tail,
+            logged,
+            alive,
+            kill: std::sync::Mutex::new(Some(kill_tx)),
+        })
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.alive.load(Ordering::Relaxed)
+    }
+
+    /// stop the executable, failing every wave still waiting on it
+    pub fn kill(&self) {
+        if let Some(kill) = self.kill.lock().unwrap().take() {
+            kill.send(()).unwrap_or_default();
+        }
+    }
+
+    /// wait until the process has written at least `lines` lines to its log aspect
+    pub async fn logged(&self, lines: usize) {
+        self.logged
+            .subscribe()
+            .wait_for(|logged| *logged >= lines)
+            .await
+            .ok();
+    }
+
+    /// write the wave to the process and wait for its reply
+    pub async fn relay(&self, core: &DirectedCore) -> Result<ReflectedCore, DriverErr> {
+        let id = self.seq.fetch_add(1, Ordering::Relaxed);
+        let request = HostRequest {
+            id,
+            method: core.method.to_string(),
+            uri: core.uri.to_string(),
+            body: core.body.clone(),
+        };
+        let mut line =
+            serde_json::to_string(&request).map_err(|e| SpaceErr::server_error(e.to_string()))?;
+        line.push('\n');
+
+        let (tx, rx) = oneshot::channel();
+        self.pending.insert(id, tx);
+        {
+            let mut stdin = self.stdin.lock().await;
+            let written = match stdin.write_all(line.as_bytes()).await {
+                Ok(_) => stdin.flush().await,
+                Err(err) => Err(err),
+            };
+            if let Err(err) = written {
+                self.pending.remove(&id);
+                Err(SpaceErr::server_error(err.to_string()))?;
+            }
+        }
+
+        let reply = match tokio::time::timeout(RELAY_TIMEOUT, rx).await {
+            Ok(reply) => reply?,
+            Err(_) => {
+                self.pending.remove(&id);
+                Err(SpaceErr::timeout(format!(
+                    "host did not reply within {}s",
+                    RELAY_TIMEOUT.as_secs()
+                )))?
+            }
+        };
+        let mut reflected = ReflectedCore::ok_body(reply.body);
+        reflected.status = StatusCode::from_u16(reply.status)?;
+        Ok(reflected)
+    }
+
+    /// the most recent lines the process wrote that were not replies
+    pub async fn log(&self) -> ReflectedCore {
+        let mut list = SubstanceList::new();
+        for line in self.tail.lock().await.iter() {
+            list.push(Box::new(Substance::Text(line.clone())));
+        }
+        ReflectedCore::ok_body(Substance::List(list))
+    }
+
+    /// the lines of the log aspect, oldest first
+    pub async fn tail(&self) -> Vec<String> {
+        self.tail.lock().await.iter().cloned().collect()
+    }
+
+    /// put the log of a previous process ahead of whatever this one has written
+    pub async fn resume(&self, lines: Vec<String>) {
+        let mut tail = self.tail.lock().await;
+        for line in lines.into_iter().rev() {
+            if tail.len() >= LOG_TAIL {
+                break;
+            }
+            tail.push_front(line);
+        }
+    }
+}
+
+impl Drop for HostProc {
+    fn drop(&mut self) {
+        self.kill();
+    }
+}
+
+async fn push(tail: &Mutex<VecDeque<String>>, logged: &watch::Sender<usize>, line: String) {
+    let mut tail = tail.lock().await;
+    if tail.len() >= LOG_TAIL {
+        tail.pop_front();
+    }
+    tail.push_back(line);
+    logged.send_modify(|logged| *logged += 1);
+}
+
+/// fetch the host's `bin` artifact into its own directory and start it
+async fn supervise(
+    skel: &DriverSkel,
+    point: &Point,
+    properties: &Properties,
+) -> Result<Arc<HostProc>, DriverErr> {
+    let bin = properties.get("bin").ok_or(SpaceErr::bad_request(format!(
+        "Host '{}' is missing required property 'bin'",
+        point.to_string()
+    )))?;
+    let bin = Point::from_str(bin.value.as_str()).map_err(SpaceErr::from)?;
+    let exe = skel.artifacts().get_raw(&bin).await?;
+
+    let pwd = PathBuf::from(skel.data_dir())
+        .join("hosts")
+        .join(point.md5());
+    tokio::fs::create_dir_all(&pwd)
+        .await
+        .map_err(SpaceErr::from)?;
+    let loc = pwd.join("bin");
+    tokio::fs::write(&loc, exe.as_slice())
+        .await
+        .map_err(SpaceErr::from)?;
+    executable(&loc).await.map_err(SpaceErr::from)?;
+
+    let mut env = HostEnv::builder();
+    env.pwd(pwd.display());
+    env.env(HOST_POINT_ENV, point.to_string());
+    let stub = ExeStub::new(loc.display().to_string(), env.build());
+
+    let logger = skel.logger.push(point.clone());
+    let proc = HostProc::spawn(&CliOsExecutor::new(stub), logger)
+        .map_err(|e| SpaceErr::server_error(e.to_string()))?;
+    Ok(Arc::new(proc))
+}
+
+#[cfg(unix)]
+pub(crate) async fn executable(path: &PathBuf) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    tokio::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).await
+}
+
+#[cfg(not(unix))]
+pub(crate) async fn executable(_: &PathBuf) -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::hyperspace::driver::host::HostProc;
+    use crate::hyperspace::driver::state::ParticleStateStore;
+    use crate::hyperspace::executor::cli::os::CliOsExecutor;
+    use crate::hyperspace::executor::cli::HostEnv;
+    use crate::hyperspace::host::ExeStub;
+    use crate::hyperspace::service::tests::local_filestore;
+    use crate::hyperspace::test_util::temp_dir;
+    use crate::space::kind::Kind;
+    use crate::space::log::Logger;
+    use crate::space::point::Point;
+    use crate::space::substance::Substance;
+    use crate::space::wave::core::cmd::CmdMethod;
+    use crate::space::wave::core::DirectedCore;
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    fn echo() -> HostProc {
+        let loc = std::fs::canonicalize("fixtures/host/echo.sh").unwrap();
+        let stub = ExeStub::new(loc.display().to_string(), HostEnv::builder().build());
+        HostProc::spawn(&CliOsExecutor::new(stub), Logger::default()).unwrap()
+    }
+
+    #[tokio::test]
+    pub async fn test_host_relay() {
+        let proc = echo();
+
+        let body = Substance::Text("hello".to_string());
+        let core = DirectedCore::cmd(CmdMethod::Read).with_body(body.clone());
+        let reflected = proc.relay(&core).await.unwrap();
+        assert!(reflected.status.is_success());
+        assert_eq!(body, reflected.body);
+
+        // whatever the executable writes to stderr lands in its log aspect
+        tokio::time::timeout(Duration::from_secs(5), proc.logged(1))
+            .await
+            .unwrap();
+        let log = proc.log().await;
+        match log.body {
+            Substance::List(list) => assert!(!list.is_empty()),
+            _ => panic!("expected log to be a List"),
+        }
+
+        proc.kill();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while proc.is_alive() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert!(proc.relay(&core).await.is_err());
+    }
+
+    #[tokio::test]
+    pub async fn test_host_log_snapshot() {
+        let dir = temp_dir("host");
+        let filestore = local_filestore(dir.to_str().unwrap());
+        let driver = Point::from_str("GLOBAL::star:drivers:host").unwrap();
+        let point = Point::from_str("my-domain.com:host").unwrap();
+        let store = ParticleStateStore::with_filestore(Kind::Host, &driver, &filestore)
+            .await
+            .unwrap();
+
+        let proc = echo();
+        let core =
+            DirectedCore::cmd(CmdMethod::Read).with_body(Substance::Text("hello".to_string()));
+        proc.relay(&core).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(5), proc.logged(1))
+            .await
+            .unwrap();
+        let tail = proc.tail().await;
+        store.put(&point, &tail).await.unwrap();
+        proc.kill();
+
+        // the star restarts: a new store restores the snapshot for a new process
+        let restarted = ParticleStateStore::with_filestore(Kind::Host, &driver, &filestore)
+            .await
+            .unwrap();
+        restarted.restore(&point).await.unwrap();
+        let proc = echo();
+        proc.resume(restarted.get(&point).await.unwrap().unwrap())
+            .await;
+        assert!(proc.tail().await.starts_with(tail.as_slice()));
+        proc.kill();
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,509 @@
+use crate::hyperspace::driver::host::executable;
+use crate::hyperspace::driver::mechtron::deliver;
+use crate::hyperspace::driver::process::{ProcessDriverFactory, WaveCodec};
+use crate::hyperspace::driver::{
+    Driver, DriverCtx, DriverErr, DriverSkel, HyperDriverFactory, HyperParticleSkel, Particle,
+    ParticleSphere, StdParticleErr,
+};
+use crate::hyperspace::executor::cli::HostEnv;
+use crate::hyperspace::host::wasm::cache::WasmModuleMemCache;
+use crate::hyperspace::host::wasm::source::ArtifactSrc;
+use crate::hyperspace::host::wasm::{WasmHost, WasmHostConfig, WasmService};
+use crate::hyperspace::host::ExeStub;
+use crate::hyperspace::star::HyperStarSkel;
+use crate::space::command::direct::select::{Select, SelectIntoSubstance, SelectKind};
+use crate::space::err::SpaceErr;
+use crate::space::hyper::ParticleRecord;
+use crate::space::kind::{BaseKind, Kind, KindParts};
+use crate::space::loc::ToBaseKind;
+use crate::space::particle::Properties;
+use crate::space::point::Point;
+use crate::space::security::Access;
+use crate::space::selector::{KindSelector, Selector};
+use crate::space::substance::Substance;
+use crate::space::wave::core::{CoreBounce, ReflectedCore};
+use crate::space::wave::exchange::asynch::{DirectedHandler, RootInCtx};
+use crate::space::HYPERUSER;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// the property of a `Driver` particle naming the kind its driver serves
+pub const DRIVER_KIND: &'static str = "kind";
+
+/// the property of a `Driver` particle naming the wasm or executable artifact to load
+pub const DRIVER_BIN: &'static str = "bin";
+
+/// the optional property of a `Driver` particle choosing the [WaveCodec] an executable speaks
+pub const DRIVER_CODEC: &'static str = "codec";
+
+/// environment variable telling a loaded guest which particle it is running as
+pub const PARTICLE_POINT_ENV: &'static str = "PARTICLE_POINT";
+
+/// `Driver` and `Host` particles make their star fetch and run their `bin` artifact
+pub fn runs_bin(kind: &Kind) -> bool {
+    match kind.to_base() {
+        BaseKind::Driver | BaseKind::Host => true,
+        _ => false,
+    }
+}
+
+/// only the HyperUser or an agent with super access where a particle that [runs_bin] lives may
+/// create it or change its `bin`
+pub fn authorize(
+    access: &Access,
+    agent: &Point,
+    kind: &Kind,
+    parent: &Point,
+) -> Result<(), SpaceErr> {
+    if *agent == *HYPERUSER || access.has_super() {
+        Ok(())
+    } else {
+        Err(SpaceErr::new(
+            403,
+            format!(
+                "'{}' may not run the bin of a {} in '{}'",
+                agent.to_string(),
+                kind.to_base().to_string(),
+                parent.to_string()
+            ),
+        ))
+    }
+}
+
+/// what a `Driver` particle asks to be loaded
+#[derive(Clone, Debug)]
+pub struct DriverDef {
+    pub kind: Kind,
+    pub selector: KindSelector,
+    pub bin: Point,
+    pub codec: WaveCodec,
+}
+
+impl DriverDef {
+    /// read the `kind`, `bin` and optional `codec` properties of the `Driver` particle at `point`
+    pub fn parse(point: &Point, properties: &Properties) -> Result<Self, SpaceErr> {
+        let required = |key: &str| {
+            properties
+                .get(key)
+                .map(|property| property.value.clone())
+                .ok_or(SpaceErr::bad_request(format!(
+                    "Driver '{}' is missing required property '{}'",
+                    point.to_string(),
+                    key
+                )))
+        };
+        let kind = required(DRIVER_KIND)?;
+        let bin = required(DRIVER_BIN)?;
+        let codec = match properties.get(DRIVER_CODEC) {
+            None => WaveCodec::default(),
+            Some(codec) => WaveCodec::from_str(codec.value.as_str()).map_err(|_| {
+                SpaceErr::bad_request(format!(
+                    "Driver '{}' has an unknown codec: '{}'",
+                    point.to_string(),
+                    codec.value
+                ))
+            })?,
+        };
+        Ok(Self {
+            selector: KindSelector::from_str(kind.as_str())?,
+            kind: Kind::try_from(KindParts::from_str(kind.as_str())?)?,
+            bin: Point::from_str(bin.as_str())?,
+            codec,
+        })
+    }
+
+    /// a wasm `bin` is executed once per wave, anything else is run as a [ProcessDriverFactory]
+    pub fn is_wasm(&self) -> bool {
+        self.bin.to_string().ends_with(".wasm")
+    }
+}
+
+/// the factory for the driver the `Driver` particle at `point` asks for.  An
+/// executable is first fetched into the driver's own directory
+pub async fn factory(
+    skel: &DriverSkel,
+    point: &Point,
+    properties: &Properties,
+) -> Result<Arc<dyn HyperDriverFactory>, DriverErr> {
+    let def = DriverDef::parse(point, properties)?;
+    if def.is_wasm() {
+        Ok(Arc::new(LoadedDriverFactory::new(def)))
+    } else {
+        let stub = install(skel, point, &def.bin).await?;
+        Ok(Arc::new(ProcessDriverFactory::new(
+            def.kind,
+            def.selector,
+            stub,
+            def.codec,
+        )))
+    }
+}
+
+/// fetch the executable artifact into the directory of the `Driver` particle at `point`
+async fn install(skel: &DriverSkel, point: &Point, bin: &Point) -> Result<ExeStub, DriverErr> {
+    let exe = skel.artifacts().get_raw(bin).await?;
+    let pwd = PathBuf::from(skel.data_dir())
+        .join("drivers")
+        .join(point.md5());
+    tokio::fs::create_dir_all(&pwd)
+        .await
+        .map_err(SpaceErr::from)?;
+    let loc = pwd.join("bin");
+    tokio::fs::write(&loc, exe.as_slice())
+        .await
+        .map_err(SpaceErr::from)?;
+    executable(&loc).await.map_err(SpaceErr::from)?;
+
+    let mut env = HostEnv::builder();
+    env.pwd(pwd.display());
+    Ok(ExeStub::new(loc.display().to_string(), env.build()))
+}
+
+/// true if `record` is a `Driver` particle assigned to `star` that names a driver
+/// to load.  The drivers a star builds itself are registered without a `kind`
+pub fn restorable(record: &ParticleRecord, star: &Point) -> bool {
+    record.details.stub.kind == Kind::Driver
+        && record.location.star.as_ref() == Some(star)
+        && record.details.properties.contains_key(DRIVER_KIND)
+}
+
+/// load again every driver that a `Driver` particle assigned to this star asked
+/// for before the star was restarted
+pub async fn restore(skel: &DriverSkel) -> Result<(), DriverErr> {
+    let mut select = Select {
+        pattern: Selector::from_str("**<Driver>").map_err(SpaceErr::from)?,
+        properties: Default::default(),
+        into_substance: SelectIntoSubstance::Points,
+        kind: SelectKind::Initial,
+    };
+    let points = skel.registry().select(&mut select).await?;
+    for point in points.list.into_iter() {
+        if let Substance::Point(point) = *point {
+            let record = skel.registry().record(&point).await?;
+            if !restorable(&record, &skel.star.point) {
+                continue;
+            }
+            let loaded = match factory(skel, &point, &record.details.properties).await {
+                Ok(factory) => skel
+                    .drivers()
+                    .load(point.clone(), factory)
+                    .await
+                    .map_err(DriverErr::from),
+                Err(err) => Err(err),
+            };
+            if let Err(err) = loaded {
+                skel.logger.error(format!(
+                    "could not restore Driver '{}': {}",
+                    point.to_string(),
+                    err.to_string()
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// hosts particles of a kind with a wasm guest
+pub struct LoadedDriverFactory {
+    def: DriverDef,
+}
+
+impl LoadedDriverFactory {
+    pub fn new(def: DriverDef) -> Self {
+        Self { def }
+    }
+}
+
+#[async_trait]
+impl HyperDriverFactory for LoadedDriverFactory {
+    fn kind(&self) -> Kind {
+        self.def.kind.clone()
+    }
+
+    fn selector(&self) -> KindSelector {
+        self.def.selector.clone()
+    }
+
+    async fn create(
+        &self,
+        _: HyperStarSkel,
+        skel: DriverSkel,
+        _: DriverCtx,
+    ) -> Result<Box<dyn Driver>, DriverErr> {
+        Ok(Box::new(LoadedDriver::new(
+            skel,
+            self.def.kind.clone(),
+            self.def.bin.clone(),
+        )))
+    }
+}
+
+/// hosts particles with a wasm guest that is executed once per wave
+pub struct LoadedDriver {
+    skel: DriverSkel,
+    kind: Kind,
+    bin: Point,
+    wasm: Arc<Mutex<WasmService>>,
+}
+
+impl LoadedDriver {
+    pub fn new(skel: DriverSkel, kind: Kind, bin: Point) -> Self {
+        let source = Box::new(ArtifactSrc::new(skel.artifacts().clone()));
+        let cache = Box::new(WasmModuleMemCache::new(source));
+        let wasm = Arc::new(Mutex::new(WasmService::new(cache)));
+        Self {
+            skel,
+            kind,
+            bin,
+            wasm,
+        }
+    }
+}
+
+#[async_trait]
+impl Driver for LoadedDriver {
+    fn kind(&self) -> Kind {
+        self.kind.clone()
+    }
+
+    async fn particle(&self, point: &Point) -> Result<ParticleSphere, DriverErr> {
+        let mut config = WasmHostConfig::builder();
+        config.env(PARTICLE_POINT_ENV, point.to_string());
+        let host = self
+            .wasm
+            .lock()
+            .await
+            .provision(self.bin.to_string(), config.build())
+            .await?;
+        let skel = HyperParticleSkel {
+            skel: self.skel.clone(),
+            point: point.clone(),
+            kind: self.kind.clone(),
+        };
+        let particle = Loaded::restore(skel, (), host);
+        Ok(particle.sphere()?)
+    }
+}
+
+/// a particle whose every wave is handed to the driver's wasm guest
+pub struct Loaded {
+    skel: HyperParticleSkel,
+    host: WasmHost,
+}
+
+impl Particle for Loaded {
+    type Skel = HyperParticleSkel;
+    type Ctx = ();
+    type State = WasmHost;
+    type Err = StdParticleErr;
+
+    fn restore(skel: Self::Skel, _: Self::Ctx, host: Self::State) -> Self {
+        Loaded { skel, host }
+    }
+
+    fn sphere(self) -> Result<ParticleSphere, Self::Err> {
+        Ok(ParticleSphere::new_handler(self))
+    }
+}
+
+#[async_trait]
+impl DirectedHandler for Loaded {
+    async fn handle(&self, ctx: RootInCtx) -> CoreBounce {
+        match deliver(&self.host, ctx.wave.core()).await {
+            Ok(core) => CoreBounce::Reflected(core),
+            Err(err) => {
+                self.skel.skel.logger.error(format!(
+                    "{} '{}' failed: {}",
+                    self.skel.kind.to_string(),
+                    self.skel.point.to_string(),
+                    err.to_string()
+                ));
+                CoreBounce::Reflected(ReflectedCore::server_error())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::hyperspace::driver::loader::{
+        authorize, restorable, runs_bin, DriverDef, DRIVER_BIN, DRIVER_CODEC, DRIVER_KIND,
+    };
+    use crate::hyperspace::driver::process::WaveCodec;
+    #[cfg(feature = "postgres")]
+    use crate::hyperspace::harness::{self, config, kind, TestMachine, TestRegistry};
+    #[cfg(feature = "postgres")]
+    use crate::hyperspace::test_util::temp_dir;
+    use crate::space::hyper::{ParticleLocation, ParticleRecord};
+    use crate::space::kind::{BaseKind, Kind, StarSub};
+    use crate::space::loc::{StarHandle, StarKey, ToPoint};
+    use crate::space::particle::{Details, Properties, Property, Status, Stub};
+    use crate::space::point::Point;
+    use crate::space::security::Access;
+    use crate::space::HYPERUSER;
+    use std::str::FromStr;
+
+    fn properties(props: &[(&str, &str)]) -> Properties {
+        let mut properties = Properties::new();
+        for (key, value) in props {
+            properties.insert(
+                key.to_string(),
+                Property {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                    locked: false,
+                },
+            );
+        }
+        properties
+    }
+
+    #[test]
+    pub fn test_driver_def() {
+        let point = Point::from_str("my-drivers:mechtron").unwrap();
+        let def = DriverDef::parse(
+            &point,
+            &properties(&[
+                (DRIVER_KIND, "Mechtron"),
+                (DRIVER_BIN, "repo:drivers:1.0.0:/bin/thing.wasm"),
+            ]),
+        )
+        .unwrap();
+        assert!(def.is_wasm());
+        assert_eq!(Kind::Mechtron, def.kind);
+        assert_eq!(WaveCodec::Json, def.codec);
+        assert!(def.selector.is_match(&Kind::Mechtron).is_ok());
+        assert!(def.selector.is_match(&Kind::Star(StarSub::Fold)).is_err());
+
+        let def = DriverDef::parse(
+            &point,
+            &properties(&[
+                (DRIVER_KIND, "Mechtron"),
+                (DRIVER_BIN, "repo:drivers:1.0.0:/bin/thing"),
+                (DRIVER_CODEC, "bincode"),
+            ]),
+        )
+        .unwrap();
+        assert!(!def.is_wasm());
+        assert_eq!(WaveCodec::Bincode, def.codec);
+
+        assert!(DriverDef::parse(&point, &properties(&[(DRIVER_KIND, "Mechtron")])).is_err());
+        assert!(DriverDef::parse(
+            &point,
+            &properties(&[
+                (DRIVER_KIND, "Nope"),
+                (DRIVER_BIN, "repo:drivers:1.0.0:/bin/thing"),
+            ])
+        )
+        .is_err());
+        assert!(DriverDef::parse(
+            &point,
+            &properties(&[
+                (DRIVER_KIND, "Mechtron"),
+                (DRIVER_BIN, "repo:drivers:1.0.0:/bin/thing"),
+                (DRIVER_CODEC, "xml"),
+            ])
+        )
+        .is_err());
+    }
+
+    #[test]
+    pub fn test_restorable() {
+        let star = StarKey::central().to_point();
+        let other = StarKey::new(&"central".to_string(), &StarHandle::new("fold", 0)).to_point();
+        let record = |kind: Kind, star: &Point, props: &[(&str, &str)]| ParticleRecord {
+            details: Details {
+                stub: Stub {
+                    point: Point::from_str("my-drivers:mechtron").unwrap(),
+                    kind,
+                    status: Status::Ready,
+                },
+                properties: properties(props),
+            },
+            location: ParticleLocation::new(Some(star.clone()), None),
+        };
+        let loaded = [
+            (DRIVER_KIND, "Mechtron"),
+            (DRIVER_BIN, "repo:drivers:1.0.0:/bin/thing.wasm"),
+        ];
+
+        assert!(restorable(&record(Kind::Driver, &star, &loaded), &star));
+        // assigned to another star
+        assert!(!restorable(&record(Kind::Driver, &other, &loaded), &star));
+        // a driver the star builds itself
+        assert!(!restorable(&record(Kind::Driver, &star, &[]), &star));
+        assert!(!restorable(&record(Kind::Mechtron, &star, &loaded), &star));
+    }
+
+    /// a `Driver` created through the global create path loads its driver on the star
+    /// it is assigned to and deleting it unloads the driver again
+    #[cfg(feature = "postgres")]
+    #[tokio::test]
+    #[ignore = "needs the postgres server named by STARLANE_TEST_POSTGRES"]
+    pub async fn test_load_and_unload() {
+        let registry = TestRegistry::new().await;
+        let dir = temp_dir("loader");
+        let machine = TestMachine::new(config(&registry, &dir).await).await;
+
+        // a Nexus hosts no Mechtrons of its own
+        let nexus = machine
+            .star(&StarKey::new(
+                &"central".to_string(),
+                &StarHandle::name("nexus"),
+            ))
+            .await;
+        let point = nexus.point.push("mechtron-driver").unwrap();
+
+        // `bin` is required
+        assert!(machine
+            .create(
+                &nexus,
+                &point,
+                kind(BaseKind::Driver),
+                harness::properties(&[(DRIVER_KIND, "Mechtron")]),
+            )
+            .await
+            .is_err());
+
+        machine
+            .create(
+                &nexus,
+                &point,
+                kind(BaseKind::Driver),
+                harness::properties(&[
+                    (DRIVER_KIND, "Mechtron"),
+                    (DRIVER_BIN, "repo:drivers:1.0.0:/bin/echo.wasm"),
+                    (DRIVER_CODEC, "json"),
+                ]),
+            )
+            .await
+            .unwrap();
+        assert!(nexus.drivers.find_by_point(&point).await.unwrap().is_some());
+        assert!(nexus.drivers.find(Kind::Mechtron).await.unwrap().is_some());
+
+        assert!(machine.delete(&point).await.status.is_success());
+        assert!(nexus.drivers.find_by_point(&point).await.unwrap().is_none());
+        assert!(nexus.drivers.find(Kind::Mechtron).await.unwrap().is_none());
+
+        machine.terminate();
+        registry.drop().await;
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    pub fn test_authorize() {
+        let parent = Point::from_str("localhost:drivers").unwrap();
+        let agent = Point::from_str("localhost:users:scott").unwrap();
+        for kind in [Kind::Driver, Kind::Host] {
+            assert!(runs_bin(&kind));
+            assert!(authorize(&Access::none(), &HYPERUSER, &kind, &parent).is_ok());
+            assert!(authorize(&Access::Super, &agent, &kind, &parent).is_ok());
+            // owning the parent is not enough to run an arbitrary bin on a star
+            assert!(authorize(&Access::Owner, &agent, &kind, &parent).is_err());
+            assert!(authorize(&Access::none(), &agent, &kind, &parent).is_err());
+        }
+        assert!(!runs_bin(&Kind::Base));
+    }
+}
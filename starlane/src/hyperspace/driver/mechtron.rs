@@ -0,0 +1,332 @@
+use crate::hyperspace::driver::{
+    Driver, DriverCtx, DriverErr, DriverHandler, DriverSkel, HyperDriverFactory, HyperParticleSkel,
+    Particle, ParticleSphere, StdParticleErr,
+};
+use crate::hyperspace::host::wasm::cache::WasmModuleMemCache;
+use crate::hyperspace::host::wasm::source::ArtifactSrc;
+use crate::hyperspace::host::wasm::{WasmHost, WasmHostConfig, WasmService};
+use crate::hyperspace::star::HyperStarSkel;
+use crate::space::config::mechtron::MechtronConfig;
+use crate::space::err::SpaceErr;
+use crate::space::hyper::HyperSubstance;
+use crate::space::kind::{BaseKind, Kind};
+use crate::space::particle::Properties;
+use crate::space::point::Point;
+use crate::space::selector::KindSelector;
+use crate::space::substance::Substance;
+use crate::space::wave::core::{CoreBounce, DirectedCore, ReflectedCore};
+use crate::space::wave::exchange::asynch::{DirectedHandler, InCtx, RootInCtx};
+use dashmap::DashMap;
+use starlane_macros::{handler, DirectedHandler};
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// environment variable telling a guest which mechtron it is running as
+pub const MECHTRON_POINT_ENV: &'static str = "MECHTRON_POINT";
+
+pub struct MechtronDriverFactory;
+
+impl MechtronDriverFactory {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl HyperDriverFactory for MechtronDriverFactory {
+    fn kind(&self) -> Kind {
+        Kind::Mechtron
+    }
+
+    fn selector(&self) -> KindSelector {
+        KindSelector::from_base(BaseKind::Mechtron)
+    }
+
+    async fn create(
+        &self,
+        _: HyperStarSkel,
+        skel: DriverSkel,
+        _: DriverCtx,
+    ) -> Result<Box<dyn Driver>, DriverErr> {
+        Ok(Box::new(MechtronDriver::new(skel)))
+    }
+}
+
+pub struct MechtronDriver {
+    skel: DriverSkel,
+    hosts: MechtronHosts,
+}
+
+impl MechtronDriver {
+    pub fn new(skel: DriverSkel) -> Self {
+        let source = Box::new(ArtifactSrc::new(skel.artifacts().clone()));
+        let cache = Box::new(WasmModuleMemCache::new(source));
+        let hosts = MechtronHosts::new(WasmService::new(cache));
+        Self { skel, hosts }
+    }
+}
+
+#[async_trait]
+impl Driver for MechtronDriver {
+    fn kind(&self) -> Kind {
+        Kind::Mechtron
+    }
+
+    async fn particle(&self, point: &Point) -> Result<ParticleSphere, DriverErr> {
+        let host = match self.hosts.get(point) {
+            Some(host) => host,
+            None => {
+                let properties = self.skel.registry().get_properties(point).await?;
+                provision(&self.skel, &self.hosts, point, &properties).await?
+            }
+        };
+        let skel = HyperParticleSkel {
+            skel: self.skel.clone(),
+            point: point.clone(),
+            kind: Kind::Mechtron,
+        };
+        let mechtron = Mechtron::restore(skel, (), host);
+        Ok(mechtron.sphere()?)
+    }
+
+    async fn on_delete(&self, point: &Point) -> Result<(), DriverErr> {
+        self.hosts.remove(point);
+        Ok(())
+    }
+
+    async fn handler(&self) -> Box<dyn DriverHandler> {
+        Box::new(MechtronDriverHandler::restore(
+            self.skel.clone(),
+            self.hosts.clone(),
+        ))
+    }
+}
+
+#[derive(DirectedHandler)]
+pub struct MechtronDriverHandler {
+    skel: DriverSkel,
+    hosts: MechtronHosts,
+}
+
+impl MechtronDriverHandler {
+    fn restore(skel: DriverSkel, hosts: MechtronHosts) -> Self {
+        Self { skel, hosts }
+    }
+}
+
+impl DriverHandler for MechtronDriverHandler {}
+
+#[handler]
+impl MechtronDriverHandler {
+    /// compile the guest up front so a bad `config` or `bin` fails the create
+    /// instead of the first wave
+    #[route("Hyp<Assign>")]
+    pub async fn assign(&self, ctx: InCtx<'_, HyperSubstance>) -> Result<(), DriverErr> {
+        if let HyperSubstance::Assign(assign) = ctx.input {
+            let details = &assign.details;
+            provision(
+                &self.skel,
+                &self.hosts,
+                &details.stub.point,
+                &details.properties,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+/// the host of each mechtron is provisioned once, when it is assigned or when it
+/// first receives a wave after a restart, and reused for every wave after that
+#[derive(Clone)]
+pub struct MechtronHosts {
+    wasm: Arc<Mutex<WasmService>>,
+    hosts: Arc<DashMap<Point, Arc<WasmHost>>>,
+}
+
+impl MechtronHosts {
+    pub fn new(wasm: WasmService) -> Self {
+        Self {
+            wasm: Arc::new(Mutex::new(wasm)),
+            hosts: Arc::new(DashMap::new()),
+        }
+    }
+
+    pub fn get(&self, point: &Point) -> Option<Arc<WasmHost>> {
+        self.hosts.get(point).map(|host| host.value().clone())
+    }
+
+    /// ready a host for `point` running the guest at `wasm`
+    pub async fn provision<S>(&self, point: &Point, wasm: S) -> Result<Arc<WasmHost>, DriverErr>
+    where
+        S: ToString,
+    {
+        let mut config = WasmHostConfig::builder();
+        config.env(MECHTRON_POINT_ENV, point.to_string());
+        let host = self
+            .wasm
+            .lock()
+            .await
+            .provision(wasm, config.build())
+            .await?;
+        let host = Arc::new(host);
+        self.hosts.insert(point.clone(), host.clone());
+        Ok(host)
+    }
+
+    pub fn remove(&self, point: &Point) {
+        self.hosts.remove(point);
+    }
+}
+
+pub struct Mechtron {
+    skel: HyperParticleSkel,
+    host: Arc<WasmHost>,
+}
+
+impl Particle for Mechtron {
+    type Skel = HyperParticleSkel;
+    type Ctx = ();
+    type State = Arc<WasmHost>;
+    type Err = StdParticleErr;
+
+    fn restore(skel: Self::Skel, _: Self::Ctx, host: Self::State) -> Self {
+        Mechtron { skel, host }
+    }
+
+    fn sphere(self) -> Result<ParticleSphere, Self::Err> {
+        Ok(ParticleSphere::new_handler(self))
+    }
+}
+
+#[async_trait]
+impl DirectedHandler for Mechtron {
+    async fn handle(&self, ctx: RootInCtx) -> CoreBounce {
+        match deliver(&self.host, ctx.wave.core()).await {
+            Ok(core) => CoreBounce::Reflected(core),
+            Err(err) => {
+                self.skel.skel.logger.error(format!(
+                    "mechtron '{}' failed: {}",
+                    self.skel.point.to_string(),
+                    err.to_string()
+                ));
+                CoreBounce::Reflected(ReflectedCore::server_error())
+            }
+        }
+    }
+}
+
+/// run the guest once per wave: the method and uri path are passed as
+/// arguments, the bincode serialized body is written to stdin and whatever
+/// the guest writes to stdout is deserialized as the reflected body
+pub(crate) async fn deliver(
+    host: &WasmHost,
+    core: &DirectedCore,
+) -> Result<ReflectedCore, DriverErr> {
+    let args = vec![core.method.to_string(), core.uri.path().to_string()];
+    let body = bincode::serialize(&core.body).map_err(SpaceErr::from)?;
+    let out = host.execute_with_data(args, body.as_slice()).await?;
+    let body = if out.stdout.is_empty() {
+        Substance::Empty
+    } else {
+        bincode::deserialize(out.stdout.as_slice()).map_err(SpaceErr::from)?
+    };
+    Ok(ReflectedCore::ok_body(body))
+}
+
+/// resolve the mechtron's `config` property to its `MechtronConfig` and ready
+/// a host for the `bin` it names
+async fn provision(
+    skel: &DriverSkel,
+    hosts: &MechtronHosts,
+    point: &Point,
+    properties: &Properties,
+) -> Result<Arc<WasmHost>, DriverErr> {
+    let config = config_point(point, properties)?;
+    let config: MechtronConfig = match skel.artifacts().get_mechtron(&config).await {
+        Some(config) => (*config?).clone(),
+        None => Err(SpaceErr::bad_request(format!(
+            "could not find MechtronConfig: '{}'",
+            config.to_string()
+        )))?,
+    };
+    hosts.provision(point, config.wasm.to_string()).await
+}
+
+/// the point of the `MechtronConfig` named by the required `config` property
+fn config_point(point: &Point, properties: &Properties) -> Result<Point, DriverErr> {
+    let config = properties
+        .get("config")
+        .ok_or(SpaceErr::bad_request(format!(
+            "Mechtron '{}' is missing required property 'config'",
+            point.to_string()
+        )))?;
+    Ok(Point::from_str(config.value.as_str()).map_err(SpaceErr::from)?)
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::hyperspace::driver::mechtron::{config_point, deliver, MechtronHosts};
+    use crate::hyperspace::host::wasm::cache::WasmModuleMemCache;
+    use crate::hyperspace::host::wasm::source::FileSystemSrc;
+    use crate::hyperspace::host::wasm::{WasmHostConfig, WasmService};
+    use crate::space::particle::{Properties, Property};
+    use crate::space::point::Point;
+    use crate::space::substance::Substance;
+    use crate::space::wave::core::cmd::CmdMethod;
+    use crate::space::wave::core::DirectedCore;
+    use std::str::FromStr;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    pub async fn test_provision() {
+        let point = Point::from_str("space:mechtron").unwrap();
+        let mut properties = Properties::new();
+        assert!(config_point(&point, &properties).is_err());
+        properties.insert(
+            "config".to_string(),
+            Property {
+                key: "config".to_string(),
+                value: "repo:app:1.0.0:/mechtron.yaml".to_string(),
+                locked: false,
+            },
+        );
+        assert_eq!(
+            "repo:app:1.0.0:/mechtron.yaml",
+            config_point(&point, &properties).unwrap().to_string()
+        );
+
+        let source = Box::new(FileSystemSrc::new("fixtures/wasm"));
+        let hosts = MechtronHosts::new(WasmService::new(Box::new(WasmModuleMemCache::new(source))));
+        assert!(hosts.get(&point).is_none());
+        let host = hosts.provision(&point, "echo.wasm").await.unwrap();
+        // every wave after the assign reuses the provisioned host
+        assert!(Arc::ptr_eq(&host, &hosts.get(&point).unwrap()));
+        let core = DirectedCore::cmd(CmdMethod::Read).with_body(Substance::Text("hi".to_string()));
+        assert!(deliver(&host, &core).await.unwrap().status.is_success());
+
+        hosts.remove(&point);
+        assert!(hosts.get(&point).is_none());
+    }
+
+    #[tokio::test]
+    pub async fn test_echo_mechtron() {
+        let source = Box::new(FileSystemSrc::new("fixtures/wasm"));
+        let mut service = WasmService::new(Box::new(WasmModuleMemCache::new(source)));
+        let host = service
+            .provision("echo.wasm", WasmHostConfig::default())
+            .await
+            .unwrap();
+
+        let body = Substance::Text("hello".to_string());
+        let core = DirectedCore::cmd(CmdMethod::Read).with_body(body.clone());
+        let reflected = deliver(&host, &core).await.unwrap();
+        assert!(reflected.status.is_success());
+        assert_eq!(body, reflected.body);
+
+        let core = DirectedCore::cmd(CmdMethod::Read);
+        let reflected = deliver(&host, &core).await.unwrap();
+        assert_eq!(Substance::Empty, reflected.body);
+    }
+}
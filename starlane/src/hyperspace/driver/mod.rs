@@ -1,4 +1,5 @@
 //pub mod artifact;
+pub mod database;
 //pub mod artifact;
 pub mod base;
 //pub mod cli;
@@ -6,14 +7,25 @@ pub mod control;
 pub mod root;
 pub mod space;
 pub mod star;
+pub mod userbase;
 
 pub mod artifact;
 
+pub mod file;
 pub mod filestore;
+pub mod host;
+pub mod loader;
+pub mod mechtron;
+pub mod portal;
+pub mod process;
+pub mod state;
+pub mod web;
 
 use crate::hyperspace::driver::control::ControlErr;
 use crate::hyperspace::driver::star::StarDriverFactory;
+use crate::hyperspace::driver::state::ParticleStateStore;
 use crate::hyperspace::executor::dialect::filestore::FileStoreErr;
+use crate::hyperspace::host::err::HostErr;
 use crate::hyperspace::machine::MachineErr;
 use crate::hyperspace::platform::Platform;
 use crate::hyperspace::reg::{Registration, Registry};
@@ -30,6 +42,8 @@ use futures::future::select_all;
 use futures::task::Spawn;
 use futures::{FutureExt, TryFutureExt};
 use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use starlane_macros::{handler, route, DirectedHandler, ToSpaceErr};
 use starlane_primitive_macros::push_loc;
 use crate::space::artifact::asynch::{ArtErr, Artifacts};
@@ -57,6 +71,7 @@ use crate::space::substance::{Substance, SubstanceErr};
 use crate::space::util::{log, IdSelector, ValueMatcher};
 use crate::space::wave::core::cmd::CmdMethod;
 use crate::space::wave::core::http2::StatusCode;
+use crate::space::wave::core::hyper::HypMethod;
 use crate::space::wave::core::{CoreBounce, Method, ReflectedCore};
 use crate::space::wave::exchange::asynch::{
     DirectedHandler, Exchanger, InCtx, ProtoTransmitter, ProtoTransmitterBuilder, RootInCtx,
@@ -65,11 +80,13 @@ use crate::space::wave::exchange::asynch::{
 use crate::space::wave::exchange::SetStrategy;
 use crate::space::wave::{Agent, DirectedWave, ReflectedWave, Wave};
 use crate::space::HYPERUSER;
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::marker::PhantomData;
 use std::ops::Deref;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 use tokio::sync::mpsc::error::SendError;
 use tokio::sync::{mpsc, oneshot, watch, RwLock};
@@ -119,6 +136,24 @@ impl DriversBuilder {
         self.factories.push(factory);
     }
 
+    /// drop every factory whose kind matches `selector`
+    pub fn remove(&mut self, selector: &KindSelector) {
+        let (removed, factories): (Vec<_>, Vec<_>) = self
+            .factories
+            .drain(..)
+            .partition(|factory| selector.is_match(&factory.kind()).is_ok());
+        self.factories = factories;
+        for factory in removed {
+            let selector = factory.selector();
+            self.kinds.retain(|kind| *kind != selector);
+            self.external_kinds.retain(|kind| *kind != selector);
+        }
+    }
+
+    pub fn factories(&self) -> Vec<Arc<dyn HyperDriverFactory>> {
+        self.factories.clone()
+    }
+
     pub fn add_pre(&mut self, factory: Arc<dyn HyperDriverFactory>) {
         self.kinds.insert(0, factory.selector());
         if factory.avail() == DriverAvail::External {
@@ -157,6 +192,15 @@ pub enum DriversCall {
         driver: DriverApi,
         rtn: oneshot::Sender<()>,
     },
+    LoadDriver {
+        point: Point,
+        factory: Arc<dyn HyperDriverFactory>,
+        rtn: oneshot::Sender<Result<(), SpaceErr>>,
+    },
+    UnloadDriver {
+        point: Point,
+        rtn: oneshot::Sender<()>,
+    },
     Visit(Traversal<Wave>),
     InternalKinds(oneshot::Sender<Vec<KindSelector>>),
     ExternalKinds(oneshot::Sender<Vec<KindSelector>>),
@@ -186,6 +230,7 @@ pub enum DriversCall {
         rtn: oneshot::Sender<Result<DriverStatus, SpaceErr>>,
     },
     StatusRx(oneshot::Sender<watch::Receiver<DriverStatus>>),
+    History(oneshot::Sender<HashMap<Point, Vec<DriverStatusEvent>>>),
     ByPoint {
         point: Point,
         rtn: oneshot::Sender<Option<DriverApi>>,
@@ -278,6 +323,13 @@ impl DriversApi {
         rtn_rx.await?
     }
 
+    /// the most recent status changes of every driver on this star, oldest first
+    pub async fn history(&self) -> Result<HashMap<Point, Vec<DriverStatusEvent>>, SpaceErr> {
+        let (rtn, mut rtn_rx) = oneshot::channel();
+        self.call_tx.send(DriversCall::History(rtn)).await?;
+        Ok(rtn_rx.await?)
+    }
+
     pub async fn find_by_point(&self, point: &Point) -> Result<Option<DriverApi>, SpaceErr> {
         let (rtn, mut rtn_rx) = oneshot::channel();
         self.call_tx
@@ -293,6 +345,37 @@ impl DriversApi {
         self.call_tx.send(DriversCall::Init0).await;
     }
 
+    /// start a driver from `factory` while the star is running.  The driver lives
+    /// at `point` (the `Driver` particle that asked for it) and serves the factory's
+    /// [KindSelector] until it is unloaded
+    pub async fn load(
+        &self,
+        point: Point,
+        factory: Arc<dyn HyperDriverFactory>,
+    ) -> Result<(), SpaceErr> {
+        let (rtn, mut rtn_rx) = oneshot::channel();
+        self.call_tx
+            .send(DriversCall::LoadDriver {
+                point,
+                factory,
+                rtn,
+            })
+            .await?;
+        rtn_rx.await?
+    }
+
+    /// stop and forget a driver previously started with [DriversApi::load]
+    pub async fn unload(&self, point: &Point) -> Result<(), SpaceErr> {
+        let (rtn, mut rtn_rx) = oneshot::channel();
+        self.call_tx
+            .send(DriversCall::UnloadDriver {
+                point: point.clone(),
+                rtn,
+            })
+            .await?;
+        Ok(rtn_rx.await?)
+    }
+
     /*
     pub async fn route(&self, wave: UltraWave) {
         self.call_tx.send( DriversCall::Route(wave)).await;
@@ -311,10 +394,12 @@ pub struct Drivers {
     call_rx: mpsc::Receiver<DriversCall>,
     call_tx: mpsc::Sender<DriversCall>,
     statuses_rx: Arc<DashMap<KindSelector, watch::Receiver<DriverStatus>>>,
+    history: Arc<DashMap<Point, VecDeque<DriverStatusEvent>>>,
     status_tx: mpsc::Sender<DriverStatus>,
     status_rx: watch::Receiver<DriverStatus>,
     kinds: Vec<KindSelector>,
     external_kinds: Vec<KindSelector>,
+    loaded: HashSet<Point>,
     init: bool,
 }
 
@@ -355,12 +440,14 @@ impl Drivers {
             call_rx,
             call_tx: call_tx.clone(),
             statuses_rx,
+            history: Arc::new(DashMap::new()),
             factories,
             status_tx: mpsc_status_tx,
             status_rx: watch_status_rx.clone(),
             init: false,
             kinds,
             external_kinds,
+            loaded: HashSet::new(),
         };
 
         drivers.start();
@@ -383,10 +470,21 @@ impl Drivers {
                             .insert(driver.get_point(), driver.clone());
                         self.kind_to_driver
                             .insert(driver.kind.clone(), driver.clone());
-                        if !driver.kind.is_match(&Kind::Driver).is_ok() {
-                            let driver_driver = self.find(&Kind::Driver).unwrap();
-                            driver_driver.add_driver(driver.clone()).await;
-                        }
+                        // the DriverDriver hosts its own particle too so `Driver`
+                        // particles can be assigned to it
+                        let driver_driver = self.find(&Kind::Driver).unwrap();
+                        driver_driver.add_driver(driver.clone()).await;
+                        rtn.send(());
+                    }
+                    DriversCall::LoadDriver {
+                        point,
+                        factory,
+                        rtn,
+                    } => {
+                        rtn.send(self.load(point, factory).await);
+                    }
+                    DriversCall::UnloadDriver { point, rtn } => {
+                        self.unload(&point).await;
                         rtn.send(());
                     }
                     DriversCall::Visit(traversal) => {
@@ -409,6 +507,16 @@ impl Drivers {
                     DriversCall::StatusRx(rtn) => {
                         rtn.send(self.status_rx.clone());
                     }
+                    DriversCall::History(rtn) => {
+                        let history = self
+                            .history
+                            .iter()
+                            .map(|multi| {
+                                (multi.key().clone(), multi.value().iter().cloned().collect())
+                            })
+                            .collect();
+                        rtn.send(history).unwrap_or_default();
+                    }
                     DriversCall::Get { kind, rtn } => {
                         rtn.send(
                             self.find(&kind).cloned().ok_or(
@@ -487,6 +595,7 @@ impl Drivers {
             self.create(
                 factory.kind(),
                 factory.selector(),
+                self.driver_point(&factory.selector()),
                 factory.clone(),
                 status_tx,
             )
@@ -518,13 +627,69 @@ impl Drivers {
 
         for factory in self.factories.clone() {
             let status_tx = statuses_tx.remove(&factory.selector()).unwrap();
-            self.create(factory.kind(), factory.selector(), factory, status_tx)
+            let point = self.driver_point(&factory.selector());
+            self.create(factory.kind(), factory.selector(), point, factory, status_tx)
                 .await;
         }
 
         self.status_listen(None).await;
     }
 
+    /// where a built in driver for `selector` is registered: `<star>:drivers:<kind>`
+    fn driver_point(&self, selector: &KindSelector) -> Point {
+        self.skel
+            .point
+            .push("drivers")
+            .unwrap()
+            .push(selector.as_point_segments().unwrap())
+            .unwrap()
+    }
+
+    async fn load(
+        &mut self,
+        point: Point,
+        factory: Arc<dyn HyperDriverFactory>,
+    ) -> Result<(), SpaceErr> {
+        if self.loaded.contains(&point) {
+            return Ok(());
+        }
+        let kind = factory.kind();
+        if self.find(&kind).is_some() {
+            return Err(SpaceErr::bad_request(format!(
+                "star already has a driver for kind: {}",
+                kind.to_string()
+            )));
+        }
+
+        let selector = factory.selector();
+        self.kinds.push(selector.clone());
+        if factory.avail() == DriverAvail::External {
+            self.external_kinds.push(selector.clone());
+        }
+        self.loaded.insert(point.clone());
+
+        // loaded drivers stay out of `statuses_rx` so a driver that fails to load
+        // can't take down the Drivers status of the whole star
+        let (status_tx, _) = watch::channel(DriverStatus::Pending);
+        self.create(kind, selector, point, factory, status_tx).await;
+        Ok(())
+    }
+
+    async fn unload(&mut self, point: &Point) {
+        if !self.loaded.remove(point) {
+            return;
+        }
+        if let Some(driver) = self.point_to_driver.remove(point) {
+            self.kind_to_driver.remove(&driver.kind);
+            self.kinds.retain(|kind| *kind != driver.kind);
+            self.external_kinds.retain(|kind| *kind != driver.kind);
+            if let Some(driver_driver) = self.find(&Kind::Driver) {
+                driver_driver.remove_driver(point).await;
+            }
+            driver.stop().await;
+        }
+    }
+
     async fn status_listen(&self, on_complete: Option<oneshot::Sender<Result<(), ()>>>) {
         let logger = self.skel.logger.clone();
         let status_tx = self.status_tx.clone();
@@ -581,7 +746,7 @@ impl Drivers {
                     break;
                 } else if retries > 0 {
                     status_tx
-                        .send(DriverStatus::Fatal(
+                        .send(DriverStatus::Retrying(
                             "One or more Drivers is Retrying initialization".to_string(),
                         ))
                         .await;
@@ -608,13 +773,13 @@ impl Drivers {
         &self,
         kind: Kind,
         selector: KindSelector,
+        point: Point,
         factory: Arc<dyn HyperDriverFactory>,
         status_tx: watch::Sender<DriverStatus>,
     ) {
         {
             let star = self.skel.clone();
             let call_tx = self.call_tx.clone();
-            let drivers_point = self.skel.point.push("drivers").unwrap();
 
             async fn register(
                 skel: &HyperStarSkel,
@@ -636,18 +801,24 @@ impl Drivers {
                 skel.registry.assign_star(&point, &skel.point).await?;
                 Ok(())
             }
-            let point = drivers_point
-                .push(selector.as_point_segments().unwrap())
-                .unwrap();
             let status_rx = status_tx.subscribe();
 
             {
                 let logger = push_loc!((self.skel.logger, &point));
                 let kind = selector.clone();
                 let mut status_rx = status_rx.clone();
+                let history = self.history.clone();
+                let point = point.clone();
                 tokio::spawn(async move {
                     loop {
                         let status = status_rx.borrow().clone();
+                        {
+                            let mut events = history.entry(point.clone()).or_default();
+                            if events.len() >= STATUS_HISTORY {
+                                events.pop_front();
+                            }
+                            events.push_back(DriverStatusEvent::new(point.clone(), status.clone()));
+                        }
                         match status {
                             DriverStatus::Unknown => {
                                 //                                logger.info(format!("{} {}", kind.to_string(), status.to_string()));
@@ -756,8 +927,7 @@ impl Drivers {
                 let ctx = DriverCtx::new(transmitter.clone());
 
                 tokio::spawn(async move {
-                    let driver =
-                        logger.result(factory.create(skel.clone(), driver_skel.clone(), ctx).await);
+                    let driver = create_driver(&factory, &skel, &driver_skel, &ctx, &logger).await;
                     match driver {
                         Ok(driver) => {
                             let layer = driver.layer();
@@ -783,19 +953,10 @@ impl Drivers {
                                 })
                                 .await
                                 .unwrap_or_default();
-                            if driver.kind.is_match(&Kind::Driver).is_ok() {
-                                driver.on_added();
-                            }
                             rtn_rx.await;
                         }
                         Err(err) => {
-                            logger.error(err.to_string());
-                            driver_skel
-                                .status_tx
-                                .send(DriverStatus::Fatal(
-                                    "Driver Factory creation error".to_string(),
-                                ))
-                                .await;
+                            driver_skel.status_tx.send(DriverStatus::Fatal(err)).await;
                         }
                     }
                 });
@@ -952,6 +1113,18 @@ impl DriverApi {
         self.call_tx.send(DriverRunnerCall::AddDriver(api)).await;
     }
 
+    /// This method call will only work for DriverDriver
+    pub async fn remove_driver(&self, point: &Point) {
+        self.call_tx
+            .send(DriverRunnerCall::RemoveDriver(point.clone()))
+            .await;
+    }
+
+    /// ends the runner which drops the driver along with everything it holds
+    pub async fn stop(&self) {
+        self.call_tx.send(DriverRunnerCall::Stop).await;
+    }
+
     pub async fn init_item(&self, point: Point) -> Result<Status, DriverErr> {
         let (rtn, mut rtn_rx) = oneshot::channel();
         self.call_tx
@@ -1008,6 +1181,9 @@ impl DriverApi {
 #[derive(strum_macros::Display)]
 pub enum DriverRunnerCall {
     AddDriver(DriverApi),
+    RetryInit(u32),
+    RemoveDriver(Point),
+    Stop,
     GetPoint(oneshot::Sender<Point>),
     Traverse(Traversal<Wave>),
     Handle(Traversal<Wave>),
@@ -1130,7 +1306,7 @@ pub struct DriverRunner {
     star_skel: HyperStarSkel,
     call_tx: mpsc::Sender<DriverRunnerCall>,
     call_rx: mpsc::Receiver<DriverRunnerCall>,
-    driver: Box<dyn Driver>,
+    driver: Arc<RwLock<Box<dyn Driver>>>,
     router: LayerInjectionRouter,
     logger: Logger,
     status_rx: watch::Receiver<DriverStatus>,
@@ -1158,7 +1334,7 @@ impl DriverRunner {
             star_skel: star_skel,
             call_tx: call_tx.clone(),
             call_rx: call_rx,
-            driver,
+            driver: Arc::new(RwLock::new(driver)),
             router,
             logger,
             status_rx,
@@ -1177,33 +1353,18 @@ impl DriverRunner {
             while let Some(call) = self.call_rx.recv().await {
                 match call {
                     DriverRunnerCall::OnAdded => {
-                        let mut router = LayerInjectionRouter::new(
-                            self.star_skel.clone(),
-                            self.skel.point.clone().to_surface().with_layer(Layer::Core),
-                        );
-                        router.direction = Some(TraversalDirection::Fabric);
-
-                        let mut transmitter = ProtoTransmitter::new(
-                            Arc::new(router),
-                            self.star_skel.exchanger.clone(),
-                        );
-                        let ctx = DriverCtx::new(transmitter);
-                        match self
-                            .skel
-                            .logger
-                            .result(self.driver.init(self.skel.clone(), ctx).await)
-                        {
-                            Ok(_) => {}
-                            Err(err) => {
-                                self.skel
-                                    .status_tx
-                                    .send(DriverStatus::Fatal(err.to_string()))
-                                    .await;
-                            }
-                        }
+                        self.init(1).await;
+                    }
+                    DriverRunnerCall::RetryInit(attempt) => {
+                        self.init(attempt).await;
                     }
                     DriverRunnerCall::Traverse(traversal) => {
-                        self.traverse(traversal).await.unwrap();
+                        let ready = *self.status_rx.borrow() == DriverStatus::Ready;
+                        if traversal.is_directed() && !ready {
+                            self.unavailable(traversal).await;
+                        } else {
+                            self.traverse(traversal).await.unwrap();
+                        }
                     }
                     DriverRunnerCall::Handle(traversal) => {
                         if traversal.is_directed() {
@@ -1211,6 +1372,7 @@ impl DriverRunner {
                             self.logger
                                 .track(&wave, || Tracker::new("driver:shell", "Route"));
                             let reflection = wave.reflection();
+                            let retracted = retracted(&wave);
                             let port = wave.to().clone().unwrap_single();
                             let logger = push_loc!((self.star_skel.logger, &port));
                             let router = Arc::new(self.router.clone());
@@ -1219,9 +1381,21 @@ impl DriverRunner {
                             let ctx =
                                 RootInCtx::new(wave, port.clone(), logger, transmitter.clone());
                             let handler = self.handler().await;
+                            let driver = self.driver.clone();
                             let skel = self.skel.clone();
                             tokio::spawn(async move {
-                                match handler.handle(ctx).await {
+                                let bounce = match retracted {
+                                    None => handler.handle(ctx).await,
+                                    Some(point) => CoreBounce::Reflected(
+                                        match delete(&driver, &skel, &point).await {
+                                            Ok(_) => ReflectedCore::ok(),
+                                            Err(err) => {
+                                                SpaceErr::to_space_err(err).as_reflected_core()
+                                            }
+                                        },
+                                    ),
+                                };
+                                match bounce {
                                     CoreBounce::Absorbed => {
                                         // do nothing
                                     }
@@ -1241,7 +1415,9 @@ impl DriverRunner {
                         }
                     }
                     DriverRunnerCall::Particle { point, tx } => {
-                        let result = self.logger.result(self.driver.particle(&point).await);
+                        let result = self
+                            .logger
+                            .result(self.driver.read().await.particle(&point).await);
                         tx.send(result);
                     }
                     DriverRunnerCall::DriverRunnerRequest(request) => match request {
@@ -1253,13 +1429,21 @@ impl DriverRunner {
                         let bind = self
                             .skel
                             .artifacts()
-                            .get_bind(&self.driver.kind().to_base().bind())
+                            .get_bind(&self.driver.read().await.kind().to_base().bind())
                             .await
                             .map_err(|e| e.into());
                         rtn.send(bind);
                     }
                     DriverRunnerCall::InitParticle { point, rtn } => {
-                        let particle = self.driver.particle(&point).await.unwrap();
+                        // the particle finds its last snapshot already in memory when restored
+                        let driver = self.driver.read().await;
+                        if driver.stateful() {
+                            self.skel
+                                .logger
+                                .result(self.skel.states().restore(&point).await)
+                                .unwrap_or_default();
+                        }
+                        let particle = driver.particle(&point).await.unwrap();
                         rtn.send(particle.init().await);
                     }
                     DriverRunnerCall::GetPoint(rtn) => {
@@ -1275,14 +1459,100 @@ impl DriverRunner {
                         rtn.send(bind);
                     }
                     DriverRunnerCall::AddDriver(api) => {
-                        self.driver.add_driver(api.clone()).await;
+                        self.driver.read().await.add_driver(api.clone()).await;
                         api.on_added();
                     }
+                    DriverRunnerCall::RemoveDriver(point) => {
+                        self.driver.read().await.remove_driver(&point).await;
+                    }
+                    DriverRunnerCall::Stop => {
+                        break;
+                    }
                 }
             }
         });
     }
 
+    /// `attempt` starts at 1.  A failed init is tried again after the driver's
+    /// [RetryPolicy] backoff until it runs out of attempts and becomes `Fatal`
+    async fn init(&mut self, attempt: u32) {
+        let mut router = LayerInjectionRouter::new(
+            self.star_skel.clone(),
+            self.skel.point.clone().to_surface().with_layer(Layer::Core),
+        );
+        router.direction = Some(TraversalDirection::Fabric);
+
+        let transmitter = ProtoTransmitter::new(Arc::new(router), self.star_skel.exchanger.clone());
+        let ctx = DriverCtx::new(transmitter);
+        let err = match self
+            .skel
+            .logger
+            .result(self.driver.write().await.init(self.skel.clone(), ctx).await)
+        {
+            Ok(_) => return,
+            Err(err) => err,
+        };
+
+        let policy = self.driver.read().await.retry();
+        if attempt >= policy.max_attempts {
+            self.skel
+                .status_tx
+                .send(DriverStatus::Fatal(format!(
+                    "init failed after {} attempt(s): {}",
+                    attempt,
+                    err.to_string()
+                )))
+                .await;
+            return;
+        }
+
+        let delay = policy.backoff(attempt);
+        self.skel
+            .status_tx
+            .send(DriverStatus::Retrying(format!(
+                "init attempt {} of {} failed: {} (next attempt in {}ms)",
+                attempt,
+                policy.max_attempts,
+                err.to_string(),
+                delay.as_millis()
+            )))
+            .await;
+        let call_tx = self.call_tx.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            call_tx
+                .send(DriverRunnerCall::RetryInit(attempt + 1))
+                .await
+                .unwrap_or_default();
+        });
+    }
+
+    /// reflect a 503 for a directed wave that arrives before the driver is `Ready`
+    async fn unavailable(&self, traversal: Traversal<Wave>) {
+        let status = self.status_rx.borrow().clone();
+        let wave = traversal.payload.to_directed().unwrap();
+        let reflection = match wave.reflection() {
+            Ok(reflection) => reflection,
+            // a signal has nobody waiting on it
+            Err(_) => return,
+        };
+        let core = SpaceErr::new(
+            503,
+            format!(
+                "driver for {} is not ready: {}",
+                self.skel.kind.to_string(),
+                status.describe()
+            ),
+        )
+        .as_reflected_core();
+        let reflect = reflection.make(core, traversal.to.clone());
+        let transmitter = ProtoTransmitter::new(
+            Arc::new(self.router.clone()),
+            self.star_skel.exchanger.clone(),
+        );
+        transmitter.route(reflect.to_wave()).await;
+    }
+
     async fn traverse(&self, traversal: Traversal<Wave>) -> Result<(), DriverErr> {
         self.skel.logger.track(&traversal, || {
             Tracker::new(
@@ -1324,14 +1594,42 @@ impl DriverRunner {
         Ok(ParticleOuter {
             surface: port.clone(),
             skel: self.star_skel.clone(),
-            particle: self.skel.logger.result(self.driver.particle(point).await)?,
+            particle: self
+                .skel
+                .logger
+                .result(self.driver.read().await.particle(point).await)?,
             router: Arc::new(self.router.clone().with(port)),
         })
     }
 
     async fn handler(&self) -> Box<dyn DriverHandler> {
-        self.driver.handler().await
+        self.driver.read().await.handler().await
+    }
+}
+
+/// the particle a `Hyp<Retract>` says is being deleted
+fn retracted(wave: &DirectedWave) -> Option<Point> {
+    if let Method::Hyp(HypMethod::Retract) = wave.core().method {
+        if let Substance::Hyper(HyperSubstance::Retract(retract)) = &wave.core().body {
+            return Some(retract.details.stub.point.clone());
+        }
+    }
+    None
+}
+
+/// clean up after a particle of `driver` that is being deleted.  A `Hyp<Retract>` is
+/// answered here and never reaches the driver's handler
+async fn delete(
+    driver: &RwLock<Box<dyn Driver>>,
+    skel: &DriverSkel,
+    point: &Point,
+) -> Result<(), DriverErr> {
+    let driver = driver.read().await;
+    driver.on_delete(point).await?;
+    if driver.stateful() {
+        skel.states().remove(point).await?;
     }
+    Ok(())
 }
 
 #[derive(Clone)]
@@ -1353,8 +1651,9 @@ pub struct DriverSkel {
     pub point: Point,
     pub logger: Logger,
     pub status_rx: watch::Receiver<DriverStatus>,
-    pub status_tx: mpsc::Sender<DriverStatus>,
+    pub status_tx: DriverStatusTx,
     pub request_tx: mpsc::Sender<DriverRunnerRequest>,
+    states: ParticleStateStore,
 }
 
 impl DriverSkel {
@@ -1392,6 +1691,11 @@ impl DriverSkel {
         &self.star.machine_api.registry
     }
 
+    /// snapshots of the state of this driver's particles
+    pub fn states(&self) -> &ParticleStateStore {
+        &self.states
+    }
+
     pub fn new(
         star: HyperStarSkel,
         kind: Kind,
@@ -1402,20 +1706,8 @@ impl DriverSkel {
         status_tx: watch::Sender<DriverStatus>,
         request_tx: mpsc::Sender<DriverRunnerRequest>,
     ) -> Self {
-        let (mpsc_status_tx, mut mpsc_status_rx): (
-            tokio::sync::mpsc::Sender<DriverStatus>,
-            tokio::sync::mpsc::Receiver<DriverStatus>,
-        ) = mpsc::channel(128);
-
-        let watch_status_rx = status_tx.subscribe();
-        tokio::spawn(async move {
-            while let Some(status) = mpsc_status_rx.recv().await {
-                status_tx.send(status.clone());
-                if let DriverStatus::Fatal(_) = status {
-                    break;
-                }
-            }
-        });
+        let status_rx = status_tx.subscribe();
+        let states = ParticleStateStore::new(star.machine_api.clone(), kind.clone(), &point);
 
         Self {
             star,
@@ -1423,9 +1715,10 @@ impl DriverSkel {
             selector,
             point,
             logger,
-            status_tx: mpsc_status_tx,
-            status_rx: watch_status_rx,
+            status_tx: DriverStatusTx::new(status_tx),
+            status_rx,
             request_tx,
+            states,
         }
     }
 
@@ -1543,6 +1836,50 @@ pub trait HyperDriverFactory: Send + Sync {
     fn properties(&self) -> SetProperties {
         SetProperties::default()
     }
+
+    /// how a failed [HyperDriverFactory::create] is retried
+    fn retry(&self) -> RetryPolicy {
+        RetryPolicy::default()
+    }
+}
+
+/// create the driver with `factory`, retrying after the factory's [RetryPolicy] backoff.
+/// Returns the reason for going `Fatal` once the attempts run out
+async fn create_driver(
+    factory: &Arc<dyn HyperDriverFactory>,
+    star: &HyperStarSkel,
+    skel: &DriverSkel,
+    ctx: &DriverCtx,
+    logger: &Logger,
+) -> Result<Box<dyn Driver>, String> {
+    let policy = factory.retry();
+    let mut attempt = 1;
+    loop {
+        let driver = factory.create(star.clone(), skel.clone(), ctx.clone()).await;
+        let err = match logger.result(driver) {
+            Ok(driver) => return Ok(driver),
+            Err(err) => err,
+        };
+        if attempt >= policy.max_attempts {
+            return Err(format!(
+                "driver factory create failed after {} attempt(s): {}",
+                attempt,
+                err.to_string()
+            ));
+        }
+        let delay = policy.backoff(attempt);
+        skel.status_tx
+            .send(DriverStatus::Retrying(format!(
+                "driver factory create attempt {} of {} failed: {} (next attempt in {}ms)",
+                attempt,
+                policy.max_attempts,
+                err.to_string(),
+                delay.as_millis()
+            )))
+            .await;
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
 }
 
 #[derive(Clone)]
@@ -1576,14 +1913,35 @@ pub trait Driver: Send + Sync {
         Ok(())
     }
 
+    /// how a failed [Driver::init] is retried
+    fn retry(&self) -> RetryPolicy {
+        RetryPolicy::default()
+    }
+
+    /// a stateful driver's particles keep their state with [HyperParticleSkel::snapshot].
+    /// It is restored when a particle is initialized and removed when it is deleted,
+    /// which needs a FileStore service for the driver
+    fn stateful(&self) -> bool {
+        false
+    }
+
     async fn particle(&self, point: &Point) -> Result<ParticleSphere, DriverErr>;
 
     async fn handler(&self) -> Box<dyn DriverHandler> {
         Box::new(DefaultDriverHandler::restore())
     }
 
+    /// called when a particle this driver hosts is deleted and before its registry
+    /// row is removed.  Release anything kept for `point` outside of the registry
+    async fn on_delete(&self, _point: &Point) -> Result<(), DriverErr> {
+        Ok(())
+    }
+
     /// This is sorta a hack, it only works for DriverDriver
     async fn add_driver(&self, _driver: DriverApi) {}
+
+    /// This is sorta a hack, it only works for DriverDriver
+    async fn remove_driver(&self, _point: &Point) {}
 }
 
 #[async_trait]
@@ -1630,6 +1988,43 @@ pub enum DriverStatus {
     Fatal(String),
 }
 
+impl DriverStatus {
+    /// the status along with its reason if it has one
+    pub fn describe(&self) -> String {
+        match self {
+            DriverStatus::Retrying(reason) | DriverStatus::Fatal(reason) => {
+                format!("{}({})", self.to_string(), reason)
+            }
+            _ => self.to_string(),
+        }
+    }
+}
+
+/// sets a driver's status.  The status is visible to the driver's runner as soon as
+/// `send` returns so a driver that reports `Ready` never has its next wave refused.
+/// Once `Fatal` the status no longer changes
+#[derive(Clone)]
+pub struct DriverStatusTx {
+    tx: Arc<watch::Sender<DriverStatus>>,
+}
+
+impl DriverStatusTx {
+    pub fn new(tx: watch::Sender<DriverStatus>) -> Self {
+        Self { tx: Arc::new(tx) }
+    }
+
+    pub async fn send(&self, status: DriverStatus) -> Result<(), SpaceErr> {
+        self.tx.send_if_modified(|current| match current {
+            DriverStatus::Fatal(_) => false,
+            current => {
+                *current = status;
+                true
+            }
+        });
+        Ok(())
+    }
+}
+
 impl<E> From<Result<DriverStatus, E>> for DriverStatus
 where
     E: ToString,
@@ -1646,6 +2041,81 @@ where
 pub struct DriverStatusEvent {
     pub driver: Point,
     pub status: DriverStatus,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl DriverStatusEvent {
+    pub fn new(driver: Point, status: DriverStatus) -> Self {
+        Self {
+            driver,
+            status,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+/// one line per driver with its current status, each followed by an indented line
+/// per remembered status change.  Drivers are ordered by point
+pub fn describe_history(history: HashMap<Point, Vec<DriverStatusEvent>>) -> Vec<String> {
+    let mut history: Vec<_> = history.into_iter().collect();
+    history.sort_by(|(a, _), (b, _)| a.to_string().cmp(&b.to_string()));
+    let mut lines = vec![];
+    for (driver, events) in history {
+        let current = events
+            .last()
+            .map(|event| event.status.describe())
+            .unwrap_or("Unknown".to_string());
+        lines.push(format!("{}\t{}", driver.to_string(), current));
+        for event in events {
+            lines.push(format!(
+                "    {}\t{}",
+                event.timestamp.to_rfc3339(),
+                event.status.describe()
+            ));
+        }
+    }
+    lines
+}
+
+/// how many status changes [Drivers] remembers for each driver
+const STATUS_HISTORY: usize = 32;
+
+/// exponential backoff between attempts to init a driver.  The delay doubles
+/// after every failed attempt up to `max_delay`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 8,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// a failed init is immediately `Fatal`
+    pub fn never() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// the delay after failed attempt number `attempt` (starting at 1)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_delay
+            .checked_mul(factor)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay)
+    }
 }
 
 pub trait ParticleState: Send + Sync {}
@@ -1737,6 +2207,24 @@ pub struct HyperParticleSkel {
     pub kind: Kind,
 }
 
+impl HyperParticleSkel {
+    /// the last state this particle stored with [HyperParticleSkel::snapshot].  Only
+    /// restored after a restart if the driver is [Driver::stateful]
+    pub async fn state<S>(&self) -> Result<Option<S>, DriverErr>
+    where
+        S: DeserializeOwned,
+    {
+        self.skel.states().get(&self.point).await
+    }
+
+    pub async fn snapshot<S>(&self, state: &S) -> Result<(), DriverErr>
+    where
+        S: Serialize,
+    {
+        self.skel.states().put(&self.point, state).await
+    }
+}
+
 #[derive(Clone)]
 pub struct ParticleSkel {
     skel: DriverSkel,
@@ -1849,6 +2337,30 @@ impl Driver for DriverDriver {
         self.map.insert(point, api);
     }
 
+    async fn remove_driver(&self, point: &Point) {
+        self.map.remove(point);
+    }
+
+    /// once the star's own drivers are `Ready` the drivers loaded from `Driver`
+    /// particles before a restart are loaded again
+    async fn init(&mut self, skel: DriverSkel, _: DriverCtx) -> Result<(), DriverErr> {
+        skel.logger
+            .result(skel.status_tx.send(DriverStatus::Ready).await)
+            .unwrap_or_default();
+        tokio::spawn(async move {
+            let mut drivers = skel.drivers().clone();
+            while drivers.status() != DriverStatus::Ready {
+                if drivers.status_changed().await.is_err() {
+                    return;
+                }
+            }
+            skel.logger
+                .result(loader::restore(&skel).await)
+                .unwrap_or_default();
+        });
+        Ok(())
+    }
+
     async fn particle(&self, point: &Point) -> Result<ParticleSphere, DriverErr> {
         let api = self
             .get_driver(point)
@@ -1858,6 +2370,48 @@ impl Driver for DriverDriver {
 
         Ok(any_result(particle.sphere())?)
     }
+
+    /// deleting the `Driver` particle unloads its driver
+    async fn on_delete(&self, point: &Point) -> Result<(), DriverErr> {
+        self.skel.drivers().unload(point).await?;
+        Ok(())
+    }
+
+    async fn handler(&self) -> Box<dyn DriverHandler> {
+        Box::new(DriverDriverHandler::restore(self.skel.clone()))
+    }
+}
+
+#[derive(DirectedHandler)]
+pub struct DriverDriverHandler {
+    skel: DriverSkel,
+}
+
+impl DriverDriverHandler {
+    fn restore(skel: DriverSkel) -> Self {
+        Self { skel }
+    }
+}
+
+impl DriverHandler for DriverDriverHandler {}
+
+#[handler]
+impl DriverDriverHandler {
+    /// a `Driver` particle created with a `kind` and a `bin` property loads the
+    /// `bin` artifact as the driver for `kind` on this star
+    #[route("Hyp<Assign>")]
+    pub async fn assign(&self, ctx: InCtx<'_, HyperSubstance>) -> Result<(), DriverErr> {
+        if let HyperSubstance::Assign(assign) = ctx.input {
+            let details = &assign.details;
+            let factory =
+                loader::factory(&self.skel, &details.stub.point, &details.properties).await?;
+            self.skel
+                .drivers()
+                .load(details.stub.point.clone(), factory)
+                .await?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(DirectedHandler)]
@@ -1986,6 +2540,8 @@ pub enum DriverErr {
     FileStoreErr(#[from] FileStoreErr),
     #[error(transparent)]
     ServiceErr(#[from] ServiceErr),
+    #[error(transparent)]
+    HostErr(#[from] HostErr),
     #[error("particle router for '{point}<{kind}>' is not set")]
     ParticleRouterNotSet { point: Point, kind: Kind },
     #[error("tokio recv error")]
@@ -2023,6 +2579,16 @@ impl DriverErr {
     }
 }
 
+impl CoreReflector for DriverErr {
+    fn as_reflected_core(self) -> ReflectedCore {
+        ReflectedCore {
+            headers: Default::default(),
+            status: StatusCode::fail(),
+            body: Substance::Err(SubstanceErr(self.to_string())),
+        }
+    }
+}
+
 pub trait ParticleErr: std::error::Error + Send + Sync + 'static + CoreReflector + Sized {}
 
 pub type StdParticleErr = Box<StdParticleErrInner>;
@@ -2050,3 +2616,78 @@ impl<T> From<tokio::sync::mpsc::error::SendError<T>> for DriverErr {
         DriverErr::TokioMpscSendErr
     }
 }
+
+#[cfg(test)]
+pub mod test {
+    use crate::hyperspace::driver::{
+        describe_history, DriverStatus, DriverStatusEvent, DriverStatusTx, RetryPolicy,
+    };
+    use crate::space::point::Point;
+    use std::collections::HashMap;
+    use std::str::FromStr;
+    use std::time::Duration;
+    use tokio::sync::watch;
+
+    #[test]
+    pub fn test_retry_backoff() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+        };
+        assert_eq!(Duration::from_millis(100), policy.backoff(1));
+        assert_eq!(Duration::from_millis(200), policy.backoff(2));
+        assert_eq!(Duration::from_millis(800), policy.backoff(4));
+        assert_eq!(Duration::from_secs(1), policy.backoff(5));
+        assert_eq!(Duration::from_secs(1), policy.backoff(64));
+    }
+
+    #[test]
+    pub fn test_status_describe() {
+        assert_eq!("Ready", DriverStatus::Ready.describe());
+        assert_eq!(
+            "Retrying(missing binary)",
+            DriverStatus::Retrying("missing binary".to_string()).describe()
+        );
+    }
+
+    #[tokio::test]
+    pub async fn test_status_tx() {
+        let (tx, rx) = watch::channel(DriverStatus::Pending);
+        let tx = DriverStatusTx::new(tx);
+        // the runner refuses waves until it sees `Ready` so it must not lag behind the driver
+        tx.send(DriverStatus::Ready).await.unwrap();
+        assert!(DriverStatus::Ready == *rx.borrow());
+
+        tx.send(DriverStatus::Fatal("broken".to_string()))
+            .await
+            .unwrap();
+        tx.send(DriverStatus::Ready).await.unwrap();
+        assert_eq!("Fatal(broken)", rx.borrow().describe());
+    }
+
+    #[test]
+    pub fn test_describe_history() {
+        let web = Point::from_str("GLOBAL::star:drivers:web-server").unwrap();
+        let base = Point::from_str("GLOBAL::star:drivers:base").unwrap();
+        let mut history = HashMap::new();
+        history.insert(
+            web.clone(),
+            vec![
+                DriverStatusEvent::new(web.clone(), DriverStatus::Init),
+                DriverStatusEvent::new(web.clone(), DriverStatus::Retrying("port".to_string())),
+            ],
+        );
+        history.insert(
+            base.clone(),
+            vec![DriverStatusEvent::new(base.clone(), DriverStatus::Ready)],
+        );
+
+        let lines = describe_history(history);
+        assert_eq!(5, lines.len());
+        assert_eq!(format!("{}\tReady", base.to_string()), lines[0]);
+        assert!(lines[1].starts_with("    ") && lines[1].ends_with("\tReady"));
+        assert_eq!(format!("{}\tRetrying(port)", web.to_string()), lines[2]);
+        assert!(lines[3].ends_with("\tInit"));
+    }
+}
@@ -0,0 +1,932 @@
+use crate::hyperspace::driver::{
+    Driver, DriverCtx, DriverErr, DriverHandler, DriverSkel, DriverStatus, HyperDriverFactory,
+    HyperSkel, Particle, ParticleSphere, StdParticleErr,
+};
+use crate::hyperspace::hyperlane::token::{TokenStore, TokenStoreHyperAuthenticator};
+use crate::hyperspace::hyperlane::{
+    AnonHyperAuthenticator, CertHyperAuthenticator, FromTransform, HopTransform,
+    HyperAuthenticator, HyperGate, HyperGreeter, Hyperway, HyperwayConfigurator, HyperwayEndpoint,
+    HyperwayInterchange, HyperwayStub, TransportTransform,
+};
+use crate::hyperspace::star::{HyperStarSkel, LayerInjectionRouter};
+use crate::space::command::common::StateSrc;
+use crate::space::command::direct::create::{
+    Create, KindTemplate, PointSegTemplate, PointTemplate, Strategy, Template,
+};
+use crate::space::command::direct::delete::Delete;
+use crate::space::command::direct::select::{Select, SelectIntoSubstance, SelectKind};
+use crate::space::err::SpaceErr;
+use crate::space::hyper::{ControlPattern, Greet, HyperSubstance, InterchangeKind, Knock};
+use crate::space::kind::{BaseKind, Kind};
+use crate::space::loc::{Layer, Surface, ToSurface};
+use crate::space::log::{Logger, Tracker};
+use crate::space::particle::traversal::Traversal;
+use crate::space::point::Point;
+use crate::space::selector::{KindSelector, Selector};
+use crate::space::substance::Substance;
+use crate::space::wave::exchange::asynch::{InCtx, Router, TraversalRouter};
+use crate::space::wave::{Agent, Wave};
+use anyhow::anyhow;
+use dashmap::DashMap;
+use starlane_macros::{handler, DirectedHandler};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// the gate an external process knocks on to enter the portal at `point`
+pub fn interchange_kind(point: &Point) -> InterchangeKind {
+    InterchangeKind::Portal(ControlPattern::Star(point.clone()))
+}
+
+/// authenticates the processes knocking on a Portal's gate.  Anonymous knocks are refused
+pub type PortalAuth = CertHyperAuthenticator<TokenStoreHyperAuthenticator<AnonHyperAuthenticator>>;
+
+pub struct PortalDriverFactory {
+    gates: Arc<DashMap<Point, PortalGate>>,
+    cert_agents: Arc<HashMap<String, Point>>,
+    tokens: Option<TokenStore>,
+}
+
+impl PortalDriverFactory {
+    pub fn new() -> Self {
+        Self::new_with_cert_agents(Arc::new(HashMap::new()))
+    }
+
+    /// `cert_agents` maps client certificate names to the [Agent] a remote acts for
+    pub fn new_with_cert_agents(cert_agents: Arc<HashMap<String, Point>>) -> Self {
+        Self {
+            gates: Arc::new(DashMap::new()),
+            cert_agents,
+            tokens: None,
+        }
+    }
+
+    /// accept tokens from `tokens` in the [Knock] of a remote
+    pub fn with_tokens(mut self, tokens: TokenStore) -> Self {
+        self.tokens = Some(tokens);
+        self
+    }
+
+    fn auth(&self) -> PortalAuth {
+        CertHyperAuthenticator::new(
+            self.cert_agents.clone(),
+            TokenStoreHyperAuthenticator::new(self.tokens.clone(), AnonHyperAuthenticator::new()),
+        )
+    }
+
+    /// the factory of the remotes beneath this factory's Portals.  Both drivers share
+    /// the open gates
+    pub fn remotes(&self) -> RemoteDriverFactory {
+        RemoteDriverFactory::new(self.gates.clone())
+    }
+}
+
+#[async_trait]
+impl HyperDriverFactory for PortalDriverFactory {
+    fn kind(&self) -> Kind {
+        Kind::Portal
+    }
+
+    fn selector(&self) -> KindSelector {
+        KindSelector::from_base(BaseKind::Portal)
+    }
+
+    async fn create(
+        &self,
+        star: HyperStarSkel,
+        driver: DriverSkel,
+        _: DriverCtx,
+    ) -> Result<Box<dyn Driver>, DriverErr> {
+        let skel = HyperSkel::new(star, driver);
+        Ok(Box::new(PortalDriver::new(
+            skel,
+            self.gates.clone(),
+            self.auth(),
+        )))
+    }
+}
+
+pub struct PortalDriver {
+    skel: HyperSkel,
+    gates: Arc<DashMap<Point, PortalGate>>,
+    auth: PortalAuth,
+}
+
+impl PortalDriver {
+    pub fn new(skel: HyperSkel, gates: Arc<DashMap<Point, PortalGate>>, auth: PortalAuth) -> Self {
+        Self { skel, gates, auth }
+    }
+}
+
+#[async_trait]
+impl Driver for PortalDriver {
+    fn kind(&self) -> Kind {
+        Kind::Portal
+    }
+
+    fn layer(&self) -> Layer {
+        Layer::Portal
+    }
+
+    /// open the gate of every Portal assigned to this star before it was restarted so
+    /// remotes can knock again without waiting for a wave to reach the Portal.
+    /// A gate that cannot be opened is logged rather than failing the driver
+    async fn init(&mut self, skel: DriverSkel, _: DriverCtx) -> Result<(), DriverErr> {
+        skel.logger
+            .result(skel.status_tx.send(DriverStatus::Init).await)
+            .unwrap_or_default();
+
+        let mut select = Select {
+            pattern: Selector::from_str("**<Portal>").map_err(SpaceErr::from)?,
+            properties: Default::default(),
+            into_substance: SelectIntoSubstance::Points,
+            kind: SelectKind::Initial,
+        };
+        let points = skel.registry().select(&mut select).await?;
+        for point in points.list.into_iter() {
+            if let Substance::Point(point) = *point {
+                let record = skel.registry().record(&point).await?;
+                if record.location.star.as_ref() != Some(&skel.star.point) {
+                    continue;
+                }
+                if let Err(err) = open(&self.skel, &self.gates, &self.auth, &point).await {
+                    skel.logger.error(format!(
+                        "could not open Portal '{}': {}",
+                        point.to_string(),
+                        err.to_string()
+                    ));
+                }
+            }
+        }
+
+        skel.logger
+            .result(skel.status_tx.send(DriverStatus::Ready).await)
+            .unwrap_or_default();
+        Ok(())
+    }
+
+    async fn particle(&self, point: &Point) -> Result<ParticleSphere, DriverErr> {
+        open(&self.skel, &self.gates, &self.auth, point).await?;
+        let portal = Portal::restore((), (), point.clone());
+        Ok(portal.sphere()?)
+    }
+
+    async fn handler(&self) -> Box<dyn DriverHandler> {
+        Box::new(PortalDriverHandler::restore(
+            self.skel.clone(),
+            self.gates.clone(),
+            self.auth.clone(),
+        ))
+    }
+
+    /// close the gate and forget the remotes that entered through it
+    async fn on_delete(&self, point: &Point) -> Result<(), DriverErr> {
+        if self.gates.remove(point).is_some() {
+            self.skel
+                .star
+                .machine_api
+                .remove_interchange(interchange_kind(point))
+                .await?;
+        }
+        let remotes = Selector::from_str(format!("{}:*<Remote>", point.to_string()).as_str())
+            .map_err(SpaceErr::from)?;
+        self.skel
+            .driver
+            .registry()
+            .delete(&Delete { selector: remotes })
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DirectedHandler)]
+pub struct PortalDriverHandler {
+    skel: HyperSkel,
+    gates: Arc<DashMap<Point, PortalGate>>,
+    auth: PortalAuth,
+}
+
+impl PortalDriverHandler {
+    fn restore(skel: HyperSkel, gates: Arc<DashMap<Point, PortalGate>>, auth: PortalAuth) -> Self {
+        Self { skel, gates, auth }
+    }
+}
+
+impl DriverHandler for PortalDriverHandler {}
+
+#[handler]
+impl PortalDriverHandler {
+    #[route("Hyp<Assign>")]
+    pub async fn assign(&self, ctx: InCtx<'_, HyperSubstance>) -> Result<(), DriverErr> {
+        if let HyperSubstance::Assign(assign) = ctx.input {
+            open(
+                &self.skel,
+                &self.gates,
+                &self.auth,
+                &assign.details.stub.point,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+pub struct RemoteDriverFactory {
+    gates: Arc<DashMap<Point, PortalGate>>,
+}
+
+impl RemoteDriverFactory {
+    fn new(gates: Arc<DashMap<Point, PortalGate>>) -> Self {
+        Self { gates }
+    }
+}
+
+#[async_trait]
+impl HyperDriverFactory for RemoteDriverFactory {
+    fn kind(&self) -> Kind {
+        Kind::Remote
+    }
+
+    fn selector(&self) -> KindSelector {
+        KindSelector::from_base(BaseKind::Remote)
+    }
+
+    async fn create(
+        &self,
+        star: HyperStarSkel,
+        driver: DriverSkel,
+        _: DriverCtx,
+    ) -> Result<Box<dyn Driver>, DriverErr> {
+        let skel = HyperSkel::new(star, driver);
+        Ok(Box::new(RemoteDriver::new(skel, self.gates.clone())))
+    }
+}
+
+/// hosts the particles external processes were assigned when they knocked on a
+/// Portal's gate.  A remote is always a child of its Portal
+pub struct RemoteDriver {
+    skel: HyperSkel,
+    gates: Arc<DashMap<Point, PortalGate>>,
+}
+
+impl RemoteDriver {
+    pub fn new(skel: HyperSkel, gates: Arc<DashMap<Point, PortalGate>>) -> Self {
+        Self { skel, gates }
+    }
+}
+
+#[async_trait]
+impl Driver for RemoteDriver {
+    fn kind(&self) -> Kind {
+        Kind::Remote
+    }
+
+    fn layer(&self) -> Layer {
+        Layer::Portal
+    }
+
+    async fn particle(&self, point: &Point) -> Result<ParticleSphere, DriverErr> {
+        let portal = point.parent().ok_or(SpaceErr::server_error(format!(
+            "Remote '{}' has no Portal",
+            point.to_string()
+        )))?;
+        let gate = self.gates.get(&portal).ok_or(SpaceErr::not_found(format!(
+            "Portal '{}' is not open",
+            portal.to_string()
+        )))?;
+        let remote = Remote::restore(self.skel.clone(), gate.router.clone(), ());
+        Ok(remote.sphere()?)
+    }
+
+    /// drop the hyperway of the remote
+    async fn on_delete(&self, point: &Point) -> Result<(), DriverErr> {
+        if let Some(gate) = point.parent().and_then(|portal| self.gates.get(&portal)) {
+            gate.interchange
+                .remove(point.clone().to_surface().with_layer(Layer::Core));
+        }
+        Ok(())
+    }
+}
+
+/// an open gate; dropping it stops relaying waves from its remotes
+pub struct PortalGate {
+    interchange: Arc<HyperwayInterchange>,
+    router: Arc<dyn Router>,
+    relay: JoinHandle<()>,
+}
+
+impl PortalGate {
+    /// the gate of the Portal at `portal`.  A knock `auth` admits is assigned a point by
+    /// `remotes`, which forget it again once its hyperway closes, and every wave its
+    /// process sends is routed by the router `inject` returns for the remote's surface
+    pub async fn new<A, F>(
+        portal: &Point,
+        auth: A,
+        remotes: Arc<dyn Remotes>,
+        inject: F,
+        logger: Logger,
+    ) -> (Arc<dyn HyperGate>, Self)
+    where
+        A: HyperAuthenticator + 'static,
+        F: Fn(Surface) -> Arc<dyn Router> + Send + Sync + 'static,
+    {
+        let mut interchange = HyperwayInterchange::new(portal.clone(), logger.clone());
+        let hyperway = Hyperway::new(
+            Point::remote_endpoint().to_surface(),
+            Agent::HyperUser,
+            logger.clone(),
+        );
+        let mut hyperway_endpoint = hyperway.hyperway_endpoint_far(None).await;
+        interchange.add(hyperway).await;
+        interchange.singular_to(Point::remote_endpoint().to_surface());
+        let interchange = Arc::new(interchange);
+        let router: Arc<dyn Router> = interchange.router().into();
+
+        let gate = Arc::new(RemoteGate {
+            auth,
+            remotes,
+            greeter: PortalGreeter::new(portal.clone()),
+            interchange: interchange.clone(),
+            logger: logger.clone(),
+        });
+
+        let relay = {
+            let portal = portal.clone();
+            tokio::spawn(async move {
+                while let Some(hop) = hyperway_endpoint.rx.recv().await {
+                    let remote = hop.from().clone().with_layer(Layer::Portal);
+                    if remote.point.parent().as_ref() != Some(&portal) {
+                        logger.warn(format!(
+                            "'{}' is not a remote of this portal",
+                            remote.point.to_string()
+                        ));
+                        continue;
+                    }
+                    let transport = match hop.unwrap_from_hop() {
+                        Ok(transport) => transport,
+                        Err(err) => {
+                            logger.warn(format!("could not unwrap from Hop: {}", err.to_string()));
+                            continue;
+                        }
+                    };
+                    if transport.to.point != remote.point {
+                        logger.warn("a remote cannot transport to any other point than itself");
+                        continue;
+                    }
+                    match transport.unwrap_from_transport() {
+                        Ok(wave) => inject(remote.with_layer(Layer::Shell)).route(wave).await,
+                        Err(err) => {
+                            logger.warn(format!(
+                                "could not unwrap from Transport: {}",
+                                err.to_string()
+                            ));
+                        }
+                    }
+                }
+            })
+        };
+
+        (
+            gate,
+            Self {
+                interchange,
+                router,
+                relay,
+            },
+        )
+    }
+}
+
+impl Drop for PortalGate {
+    fn drop(&mut self) {
+        self.relay.abort();
+    }
+}
+
+/// the gate a Portal registers with the machine.  Unlike an [InterchangeGate] it only
+/// creates a remote once the knock is authenticated and removes the remote again when
+/// the process's hyperway closes
+///
+/// [InterchangeGate]: crate::hyperspace::hyperlane::InterchangeGate
+struct RemoteGate<A>
+where
+    A: HyperAuthenticator,
+{
+    auth: A,
+    remotes: Arc<dyn Remotes>,
+    greeter: PortalGreeter,
+    interchange: Arc<HyperwayInterchange>,
+    logger: Logger,
+}
+
+impl<A> RemoteGate<A>
+where
+    A: HyperAuthenticator,
+{
+    /// mount a hyperway for `stub`.  When the returned endpoint is dropped the hyperway
+    /// is removed and so is `remote` if the gate created it
+    async fn enter(
+        &self,
+        stub: HyperwayStub,
+        remote: Option<Point>,
+    ) -> Result<HyperwayEndpoint, SpaceErr> {
+        let greet = self.greeter.greet(stub).await?;
+        let mut hyperway = Hyperway::new(
+            greet.surface.clone(),
+            greet.agent.clone(),
+            self.logger.clone(),
+        );
+        PortalHyperwayConfigurator.config(&greet, &mut hyperway);
+        self.interchange.add(hyperway).await;
+
+        let port = greet.surface.clone();
+        let stub = HyperwayStub {
+            agent: greet.agent.clone(),
+            remote: greet.surface.clone(),
+        };
+        let mut ext = self.logger.result_ctx(
+            "RemoteGate.enter",
+            self.interchange.mount(stub, Some(greet.into())).await,
+        )?;
+
+        let (drop_tx, drop_rx) = oneshot::channel();
+        ext.add_drop_tx(drop_tx);
+
+        let interchange = self.interchange.clone();
+        let remotes = self.remotes.clone();
+        let logger = self.logger.clone();
+        tokio::spawn(async move {
+            drop_rx.await.unwrap_or_default();
+            interchange.remove(port);
+            if let Some(remote) = remote {
+                logger
+                    .result_ctx("RemoteGate.exit", remotes.delete(&remote).await)
+                    .unwrap_or_default();
+            }
+        });
+
+        Ok(ext)
+    }
+}
+
+#[async_trait]
+impl<A> HyperGate for RemoteGate<A>
+where
+    A: HyperAuthenticator + 'static,
+{
+    async fn knock(&self, knock: Knock) -> Result<HyperwayEndpoint, SpaceErr> {
+        let stub = self.auth.auth(knock).await?;
+        if let Agent::Anonymous = stub.agent {
+            return Err(SpaceErr::new(
+                401,
+                "a Portal does not admit anonymous remotes",
+            ));
+        }
+
+        let remote = self.remotes.create().await?;
+        let stub = HyperwayStub {
+            agent: stub.agent,
+            remote: remote.clone().to_surface(),
+        };
+        match self.enter(stub, Some(remote.clone())).await {
+            Ok(endpoint) => Ok(endpoint),
+            Err(err) => {
+                self.logger
+                    .result_ctx("RemoteGate.knock", self.remotes.delete(&remote).await)
+                    .unwrap_or_default();
+                Err(err)
+            }
+        }
+    }
+
+    async fn jump(
+        &self,
+        _kind: InterchangeKind,
+        stub: HyperwayStub,
+    ) -> Result<HyperwayEndpoint, SpaceErr> {
+        self.enter(stub, None).await
+    }
+}
+
+/// register a gate for the Portal at `point` with the machine unless it already has one
+async fn open(
+    skel: &HyperSkel,
+    gates: &DashMap<Point, PortalGate>,
+    auth: &PortalAuth,
+    point: &Point,
+) -> Result<(), DriverErr> {
+    if gates.contains_key(point) {
+        return Ok(());
+    }
+
+    let logger = skel.driver.logger.push(point.clone());
+    let remotes = Arc::new(RemoteCreator::new(skel.clone(), point.clone()));
+    let star = skel.star.clone();
+    let inject = move |remote: Surface| -> Arc<dyn Router> {
+        Arc::new(LayerInjectionRouter::new(star.clone(), remote))
+    };
+    let (gate, portal) =
+        PortalGate::new(point, auth.clone(), remotes, inject, logger.clone()).await;
+
+    skel.star
+        .machine_api
+        .add_interchange(interchange_kind(point), gate)
+        .await?;
+    gates.insert(point.clone(), portal);
+    logger.info(format!("portal '{}' is open", point.to_string()));
+    Ok(())
+}
+
+pub struct PortalHyperwayConfigurator;
+
+impl HyperwayConfigurator for PortalHyperwayConfigurator {
+    fn config(&self, greet: &Greet, hyperway: &mut Hyperway) {
+        hyperway.transform_inbound(Box::new(FromTransform::new(greet.surface.clone())));
+        hyperway.transform_inbound(Box::new(TransportTransform::new(greet.transport.clone())));
+        hyperway.transform_inbound(Box::new(HopTransform::new(greet.hop.clone())));
+    }
+}
+
+/// the remotes of one Portal
+#[async_trait]
+pub trait Remotes: Send + Sync {
+    /// the point an admitted process acts as
+    async fn create(&self) -> Result<Point, SpaceErr>;
+
+    /// forget `remote` once its process has left
+    async fn delete(&self, remote: &Point) -> Result<(), SpaceErr>;
+}
+
+/// creates the particle a knocking process acts as, beneath the portal, and deletes it
+/// when the process leaves
+pub struct RemoteCreator {
+    skel: HyperSkel,
+    portal: Point,
+}
+
+impl RemoteCreator {
+    pub fn new(skel: HyperSkel, portal: Point) -> Self {
+        Self { skel, portal }
+    }
+}
+
+#[async_trait]
+impl Remotes for RemoteCreator {
+    async fn create(&self) -> Result<Point, SpaceErr> {
+        let create = Create {
+            template: Template::new(
+                PointTemplate {
+                    parent: self.portal.clone(),
+                    child_segment_template: PointSegTemplate::Pattern("remote-%".to_string()),
+                },
+                KindTemplate {
+                    base: BaseKind::Remote,
+                    sub: None,
+                    specific: None,
+                },
+            ),
+            properties: Default::default(),
+            strategy: Strategy::Commit,
+            state: StateSrc::None,
+        };
+
+        match self.skel.driver.logger.result_ctx(
+            "create-remote",
+            self.skel.star.create_and_assign(create).await,
+        ) {
+            Ok(details) => Ok(details.stub.point),
+            Err(err) => Err(anyhow!(err))?,
+        }
+    }
+
+    async fn delete(&self, remote: &Point) -> Result<(), SpaceErr> {
+        let selector = Selector::from_str(remote.to_string().as_str())?;
+        self.skel
+            .driver
+            .registry()
+            .delete(&Delete { selector })
+            .await
+            .map_err(|err| SpaceErr::server_error(err.to_string()))?;
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct PortalGreeter {
+    pub portal: Point,
+}
+
+impl PortalGreeter {
+    pub fn new(portal: Point) -> Self {
+        Self { portal }
+    }
+}
+
+#[async_trait]
+impl HyperGreeter for PortalGreeter {
+    async fn greet(&self, stub: HyperwayStub) -> Result<Greet, SpaceErr> {
+        Ok(Greet {
+            surface: stub.remote.clone().with_layer(Layer::Core),
+            agent: stub.agent.clone(),
+            hop: self.portal.clone().to_surface(),
+            transport: stub.remote.clone().with_layer(Layer::Portal),
+        })
+    }
+}
+
+#[derive(DirectedHandler)]
+pub struct Portal {
+    point: Point,
+}
+
+#[handler]
+impl Portal {}
+
+impl Particle for Portal {
+    type Skel = ();
+    type Ctx = ();
+    type State = Point;
+    type Err = StdParticleErr;
+
+    fn restore(_: Self::Skel, _: Self::Ctx, point: Self::State) -> Self {
+        Self { point }
+    }
+
+    fn sphere(self) -> Result<ParticleSphere, Self::Err> {
+        Ok(ParticleSphere::new_handler(self))
+    }
+}
+
+/// waves reaching a remote's Portal layer leave through its portal's interchange
+pub struct Remote {
+    skel: HyperSkel,
+    router: Arc<dyn Router>,
+}
+
+impl Particle for Remote {
+    type Skel = HyperSkel;
+    type Ctx = Arc<dyn Router>;
+    type State = ();
+    type Err = StdParticleErr;
+
+    fn restore(skel: Self::Skel, router: Self::Ctx, _: Self::State) -> Self {
+        Self { skel, router }
+    }
+
+    fn sphere(self) -> Result<ParticleSphere, Self::Err> {
+        Ok(ParticleSphere::new_router(self))
+    }
+}
+
+#[async_trait]
+impl TraversalRouter for Remote {
+    async fn traverse(&self, traversal: Traversal<Wave>) -> Result<(), SpaceErr> {
+        self.skel.driver.logger.track(&traversal, || {
+            Tracker::new(
+                format!("portal -> {}", traversal.dir.to_string()),
+                "Traverse",
+            )
+        });
+
+        self.router.route(traversal.payload).await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::hyperspace::driver::portal::{interchange_kind, PortalGate, Remotes};
+    #[cfg(feature = "postgres")]
+    use crate::hyperspace::harness::{config, kind, TestMachine, TestRegistry};
+    #[cfg(feature = "postgres")]
+    use crate::hyperspace::hyperlane::tcp::{CertGenerator, CertPaths};
+    use crate::hyperspace::hyperlane::token::{TokenStore, TokenStoreHyperAuthenticator};
+    use crate::hyperspace::hyperlane::AnonHyperAuthenticator;
+    #[cfg(feature = "postgres")]
+    use crate::hyperspace::star::HyperStarSkel;
+    use crate::hyperspace::test_util::temp_dir;
+    #[cfg(feature = "postgres")]
+    use crate::server::ClientAuthConfig;
+    #[cfg(feature = "postgres")]
+    use crate::space::command::common::SetProperties;
+    #[cfg(feature = "postgres")]
+    use crate::space::command::direct::select::{Select, SelectIntoSubstance, SelectKind};
+    use crate::space::err::{SpaceErr, StatusErr};
+    #[cfg(feature = "postgres")]
+    use crate::space::hyper::ClientCert;
+    use crate::space::hyper::Knock;
+    #[cfg(feature = "postgres")]
+    use crate::space::kind::BaseKind;
+    use crate::space::loc::{Layer, Surface, ToSurface};
+    #[cfg(feature = "postgres")]
+    use crate::space::loc::{StarHandle, StarKey};
+    use crate::space::log::Logger;
+    use crate::space::point::Point;
+    #[cfg(feature = "postgres")]
+    use crate::space::selector::Selector;
+    use crate::space::substance::Substance;
+    use crate::space::wave::core::cmd::CmdMethod;
+    use crate::space::wave::exchange::asynch::Router;
+    use crate::space::wave::{DirectedProto, Wave};
+    use std::str::FromStr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+
+    /// hands out `remote` and reports each remote it is told to delete
+    pub struct TestRemotes {
+        remote: Point,
+        created: AtomicUsize,
+        deleted: mpsc::Sender<Point>,
+    }
+
+    #[async_trait]
+    impl Remotes for TestRemotes {
+        async fn create(&self) -> Result<Point, SpaceErr> {
+            self.created.fetch_add(1, Ordering::SeqCst);
+            Ok(self.remote.clone())
+        }
+
+        async fn delete(&self, remote: &Point) -> Result<(), SpaceErr> {
+            self.deleted.send(remote.clone()).await.unwrap();
+            Ok(())
+        }
+    }
+
+    /// stands in for the star: notes where each wave was injected
+    pub struct TestInjector {
+        injector: Surface,
+        tx: mpsc::Sender<(Surface, Wave)>,
+    }
+
+    #[async_trait]
+    impl Router for TestInjector {
+        async fn route(&self, wave: Wave) {
+            self.tx.send((self.injector.clone(), wave)).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    pub async fn test_knock() {
+        let portal = Point::from_str("localhost:portal").unwrap();
+        let remote = portal.push("remote-0").unwrap();
+        let other = Point::from_str("localhost:other").unwrap();
+        let (tx, mut rx) = mpsc::channel(32);
+        let inject = move |injector: Surface| -> Arc<dyn Router> {
+            Arc::new(TestInjector {
+                injector,
+                tx: tx.clone(),
+            })
+        };
+        let (deleted, mut deleted_rx) = mpsc::channel(1);
+        let remotes = Arc::new(TestRemotes {
+            remote: remote.clone(),
+            created: AtomicUsize::new(0),
+            deleted,
+        });
+
+        let dir = temp_dir("portal");
+        let store = TokenStore::new(dir.join("tokens.yaml").to_str().unwrap().to_string());
+        let record = store
+            .create(Point::from_str("users:scott").unwrap(), None)
+            .unwrap();
+        let auth = TokenStoreHyperAuthenticator::new(Some(store), AnonHyperAuthenticator::new());
+
+        let (gate, portal_gate) =
+            PortalGate::new(&portal, auth, remotes.clone(), inject, Logger::default()).await;
+
+        // an anonymous knock is refused before any remote is created
+        let knock = Knock::new(
+            interchange_kind(&portal),
+            other.to_surface(),
+            Substance::Empty,
+        );
+        match gate.knock(knock).await {
+            Ok(_) => panic!("a Portal must not admit an anonymous knock"),
+            Err(err) => assert_eq!(err.status(), 401),
+        }
+        assert_eq!(remotes.created.load(Ordering::SeqCst), 0);
+
+        // an authenticated knock creates the remote and the process enters as it
+        let knock = Knock::new(
+            interchange_kind(&portal),
+            other.to_surface(),
+            Substance::Token(record.token.clone()),
+        );
+        let mut endpoint = gate.knock(knock).await.unwrap();
+        assert_eq!(remotes.created.load(Ordering::SeqCst), 1);
+
+        // waves from the process are injected at the remote's Shell layer
+        let mut proto = DirectedProto::cmd(other.to_surface(), CmdMethod::Bounce);
+        proto.fill_from(remote.to_surface());
+        endpoint
+            .tx
+            .send(proto.build().unwrap().to_wave())
+            .await
+            .unwrap();
+        let (injector, wave) = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(remote.to_surface().with_layer(Layer::Shell), injector);
+        assert_eq!(other.to_surface(), wave.to().single_or().unwrap());
+
+        // waves reaching the remote's Portal layer leave through the gate
+        let mut proto = DirectedProto::cmd(remote.to_surface(), CmdMethod::Bounce);
+        proto.fill_from(other.to_surface());
+        portal_gate
+            .router
+            .route(proto.build().unwrap().to_wave())
+            .await;
+        let wave = tokio::time::timeout(Duration::from_secs(5), endpoint.rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(other.to_surface(), *wave.from());
+
+        // the remote is deleted once the process leaves
+        drop(endpoint);
+        let gone = tokio::time::timeout(Duration::from_secs(5), deleted_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(remote, gone);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(feature = "postgres")]
+    async fn remotes(star: &HyperStarSkel, portal: &Point) -> usize {
+        let mut select = Select {
+            pattern: Selector::from_str(format!("{}:*<Remote>", portal.to_string()).as_str())
+                .unwrap(),
+            properties: Default::default(),
+            into_substance: SelectIntoSubstance::Points,
+            kind: SelectKind::Initial,
+        };
+        star.registry.select(&mut select).await.unwrap().list.len()
+    }
+
+    /// a process knocking on a Portal of a running machine is only given a Remote once
+    /// its client certificate names an agent, and the Remote is gone after it leaves
+    #[cfg(feature = "postgres")]
+    #[tokio::test]
+    #[ignore = "needs the postgres server named by STARLANE_TEST_POSTGRES"]
+    pub async fn test_remote_lifecycle() {
+        let registry = TestRegistry::new().await;
+        let dir = temp_dir("portal");
+
+        let ca = CertGenerator::gen_ca("starlane-portal").unwrap();
+        let ca_dir = dir.join("ca");
+        ca.write_pem(&CertPaths::in_dir(ca_dir.display()))
+            .await
+            .unwrap();
+        let mut client_auth = ClientAuthConfig::new(ca_dir.display().to_string());
+        client_auth.agents.insert(
+            "portal-process".to_string(),
+            Point::from_str("portal-process").unwrap(),
+        );
+        let mut config = config(&registry, &dir).await;
+        config.client_auth = Some(client_auth);
+        let machine = TestMachine::new(config).await;
+
+        let jump = machine
+            .star(&StarKey::new(
+                &"central".to_string(),
+                &StarHandle::name("jump"),
+            ))
+            .await;
+        let portal = Point::from_str("portal").unwrap();
+        machine
+            .create(&jump, &portal, kind(BaseKind::Portal), SetProperties::new())
+            .await
+            .unwrap();
+
+        let process = Point::from_str("process").unwrap().to_surface();
+        let knock = Knock::new(interchange_kind(&portal), process.clone(), Substance::Empty);
+        assert!(machine.api.knock(knock).await.is_err());
+        assert_eq!(remotes(&jump, &portal).await, 0);
+
+        // the server sets the certificate of a knock that arrived over mutual TLS
+        let mut knock = Knock::new(interchange_kind(&portal), process, Substance::Empty);
+        knock.cert = Some(ClientCert::new(Some("portal-process".to_string()), vec![]));
+        let endpoint = machine.api.knock(knock).await.unwrap();
+        assert_eq!(remotes(&jump, &portal).await, 1);
+
+        drop(endpoint);
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while remotes(&jump, &portal).await > 0 {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        })
+        .await
+        .expect("the Remote outlived its hyperway");
+
+        machine.terminate();
+        registry.drop().await;
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
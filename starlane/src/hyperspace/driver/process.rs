@@ -0,0 +1,1187 @@
+use crate::hyperspace::driver::{
+    Driver, DriverCtx, DriverErr, DriverHandler, DriverSkel, DriverStatus, DriverStatusTx,
+    HyperDriverFactory, HyperParticleSkel, Particle, ParticleSphere, RetryPolicy, StdParticleErr,
+};
+use crate::hyperspace::executor::cli::os::CliOsExecutor;
+use crate::hyperspace::executor::cli::CliErr;
+use crate::hyperspace::host::ExeStub;
+use crate::hyperspace::star::HyperStarSkel;
+use crate::space::err::{CoreReflector, SpaceErr, StatusErr};
+use crate::space::hyper::HyperSubstance;
+use crate::space::kind::Kind;
+use crate::space::loc::ToSurface;
+use crate::space::log::Logger;
+use crate::space::point::Point;
+use crate::space::selector::KindSelector;
+use crate::space::substance::Substance;
+use crate::space::wave::core::hyper::HypMethod;
+use crate::space::wave::core::{CoreBounce, ReflectedCore};
+use crate::space::wave::exchange::asynch::{DirectedHandler, InCtx, ProtoTransmitter, RootInCtx};
+use crate::space::wave::{Agent, DirectedProto, DirectedWave, ReflectedWave, Wave, WaveId};
+use dashmap::DashMap;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use starlane_macros::{handler, DirectedHandler};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::process::ChildStdin;
+use tokio::sync::{oneshot, watch, Mutex};
+
+/// environment variable telling the process which driver it is running as
+pub const DRIVER_POINT_ENV: &'static str = "DRIVER_POINT";
+
+/// environment variable telling the process how its frames are encoded
+pub const DRIVER_CODEC_ENV: &'static str = "DRIVER_CODEC";
+
+/// a frame larger than this is treated as a broken process rather than allocated
+const MAX_FRAME: u32 = 64 * 1024 * 1024;
+
+/// how long the process has to reflect its `Hyp<Init>` before it is killed
+const INIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// how long a particle wave waits on the process's reflection before failing with a 504
+const EXCHANGE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// how a frame's payload is encoded.  Every frame is a big endian `u32` length
+/// followed by that many bytes of payload, which is a [Wave] in both directions
+#[derive(Clone, Debug, Eq, PartialEq, strum_macros::Display, strum_macros::EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum WaveCodec {
+    Json,
+    Bincode,
+}
+
+impl Default for WaveCodec {
+    fn default() -> Self {
+        WaveCodec::Json
+    }
+}
+
+impl WaveCodec {
+    pub fn encode<T>(&self, value: &T) -> Result<Vec<u8>, SpaceErr>
+    where
+        T: Serialize,
+    {
+        match self {
+            WaveCodec::Json => {
+                serde_json::to_vec(value).map_err(|e| SpaceErr::server_error(e.to_string()))
+            }
+            WaveCodec::Bincode => Ok(bincode::serialize(value)?),
+        }
+    }
+
+    pub fn decode<T>(&self, payload: &[u8]) -> Result<T, SpaceErr>
+    where
+        T: DeserializeOwned,
+    {
+        match self {
+            WaveCodec::Json => {
+                serde_json::from_slice(payload).map_err(|e| SpaceErr::server_error(e.to_string()))
+            }
+            WaveCodec::Bincode => Ok(bincode::deserialize(payload)?),
+        }
+    }
+}
+
+pub async fn write_frame<W>(writer: &mut W, payload: &[u8]) -> Result<(), SpaceErr>
+where
+    W: AsyncWrite + Unpin,
+{
+    if payload.len() > MAX_FRAME as usize {
+        return Err(SpaceErr::server_error(format!(
+            "frame of {} bytes exceeds the {} byte limit",
+            payload.len(),
+            MAX_FRAME
+        )));
+    }
+    writer.write_u32(payload.len() as u32).await?;
+    writer.write_all(payload).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// returns `None` once the process has closed its end
+pub async fn read_frame<R>(reader: &mut R) -> Result<Option<Vec<u8>>, SpaceErr>
+where
+    R: AsyncRead + Unpin,
+{
+    let len = match reader.read_u32().await {
+        Ok(len) => len,
+        Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => Err(err)?,
+    };
+    if len > MAX_FRAME {
+        return Err(SpaceErr::server_error(format!(
+            "frame of {} bytes exceeds the {} byte limit",
+            len, MAX_FRAME
+        )));
+    }
+    let mut payload = vec![0u8; len as usize];
+    reader.read_exact(payload.as_mut_slice()).await?;
+    Ok(Some(payload))
+}
+
+/// the process side of the protocol for drivers written in rust.  Every
+/// [DirectedWave] read from `reader` is handed to `handle` and, unless it is a
+/// signal, answered with its [ReflectedWave] on `writer`.  See `examples/echo_driver.rs`
+///
+/// Waves flow the other way too: a process may write a [DirectedWave] of its own,
+/// which the driver sends from the driver's point, and the reflections come back on
+/// `reader`.  `serve` never directs a wave so it skips any reflection it reads
+pub async fn serve<R, W, F>(
+    mut reader: R,
+    mut writer: W,
+    codec: WaveCodec,
+    mut handle: F,
+) -> Result<(), SpaceErr>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+    F: FnMut(&DirectedWave) -> ReflectedCore,
+{
+    while let Some(payload) = read_frame(&mut reader).await? {
+        let wave: Wave = codec.decode(payload.as_slice())?;
+        if wave.is_reflected() {
+            continue;
+        }
+        let wave = wave.to_directed()?;
+        let core = handle(&wave);
+        if let DirectedWave::Signal(_) = wave {
+            continue;
+        }
+        let from = wave.to().single_or()?;
+        let reflected = wave.reflection()?.make(core, from);
+        write_frame(&mut writer, codec.encode(&reflected.to_wave())?.as_slice()).await?;
+    }
+    Ok(())
+}
+
+/// adapts a long lived child process to a [Driver].  The process reads
+/// [DirectedWave] frames from stdin and answers each with a [ReflectedWave]
+/// frame on stdout whose `reflection_of` is the id of the directed wave.
+/// Replies may come in any order.  Whatever the process writes to stderr is logged.
+///
+/// The process may also write [DirectedWave] frames of its own to stdout.  They are
+/// sent through the driver's transmitter from the driver's point, see [Outbound],
+/// and their reflections are written back to stdin.
+///
+/// The process is started with [DRIVER_POINT_ENV] and [DRIVER_CODEC_ENV] set.
+/// Before any particle waves it receives a `Hyp<Init>` addressed to the driver
+/// itself whose body is the driver's kind, and the driver only becomes `Ready`
+/// once that is reflected ok within [INIT_TIMEOUT]. Every particle created
+/// afterwards is announced with the star's `Hyp<Assign>`.  A process that exits
+/// after that is respawned by its [SupervisedProc]
+pub struct ProcessDriverFactory {
+    kind: Kind,
+    selector: KindSelector,
+    stub: ExeStub,
+    codec: WaveCodec,
+}
+
+impl ProcessDriverFactory {
+    pub fn new(kind: Kind, selector: KindSelector, stub: ExeStub, codec: WaveCodec) -> Self {
+        Self {
+            kind,
+            selector,
+            stub,
+            codec,
+        }
+    }
+}
+
+#[async_trait]
+impl HyperDriverFactory for ProcessDriverFactory {
+    fn kind(&self) -> Kind {
+        self.kind.clone()
+    }
+
+    fn selector(&self) -> KindSelector {
+        self.selector.clone()
+    }
+
+    async fn create(
+        &self,
+        _: HyperStarSkel,
+        skel: DriverSkel,
+        ctx: DriverCtx,
+    ) -> Result<Box<dyn Driver>, DriverErr> {
+        let outbound = Outbound::new(ctx.transmitter, skel.point.clone());
+        Ok(Box::new(ProcessDriver::spawn(
+            skel,
+            self.kind.clone(),
+            self.stub.clone(),
+            self.codec.clone(),
+            Some(outbound),
+        )?))
+    }
+}
+
+pub struct ProcessDriver {
+    skel: DriverSkel,
+    kind: Kind,
+    proc: Arc<SupervisedProc>,
+}
+
+impl ProcessDriver {
+    pub fn spawn(
+        skel: DriverSkel,
+        kind: Kind,
+        mut stub: ExeStub,
+        codec: WaveCodec,
+        outbound: Option<Outbound>,
+    ) -> Result<Self, DriverErr> {
+        stub.env
+            .env
+            .insert(DRIVER_POINT_ENV.to_string(), skel.point.to_string());
+        stub.env
+            .env
+            .insert(DRIVER_CODEC_ENV.to_string(), codec.to_string());
+        let proc = SupervisedProc::spawn(
+            CliOsExecutor::new(stub),
+            codec,
+            skel.point.clone(),
+            kind.clone(),
+            skel.status_tx.clone(),
+            skel.logger.clone(),
+            outbound,
+        )?;
+        Ok(Self {
+            skel,
+            kind,
+            proc: Arc::new(proc),
+        })
+    }
+}
+
+#[async_trait]
+impl Driver for ProcessDriver {
+    fn kind(&self) -> Kind {
+        self.kind.clone()
+    }
+
+    async fn init(&mut self, skel: DriverSkel, _: DriverCtx) -> Result<(), DriverErr> {
+        skel.logger
+            .result(skel.status_tx.send(DriverStatus::Init).await)
+            .unwrap_or_default();
+
+        self.proc.init().await?;
+
+        skel.logger
+            .result(skel.status_tx.send(DriverStatus::Ready).await)
+            .unwrap_or_default();
+        self.proc.supervise(self.retry());
+        Ok(())
+    }
+
+    /// the respawned process isn't told about a deleted particle
+    async fn on_delete(&self, point: &Point) -> Result<(), DriverErr> {
+        self.proc.forget(point);
+        Ok(())
+    }
+
+    async fn particle(&self, point: &Point) -> Result<ParticleSphere, DriverErr> {
+        let skel = HyperParticleSkel {
+            skel: self.skel.clone(),
+            point: point.clone(),
+            kind: self.kind.clone(),
+        };
+        let particle = ProcessParticle::restore(skel, (), self.proc.clone());
+        Ok(particle.sphere()?)
+    }
+
+    async fn handler(&self) -> Box<dyn DriverHandler> {
+        Box::new(ProcessDriverHandler::restore(self.proc.clone()))
+    }
+}
+
+#[derive(DirectedHandler)]
+pub struct ProcessDriverHandler {
+    proc: Arc<SupervisedProc>,
+}
+
+impl ProcessDriverHandler {
+    fn restore(proc: Arc<SupervisedProc>) -> Self {
+        Self { proc }
+    }
+}
+
+impl DriverHandler for ProcessDriverHandler {}
+
+#[handler]
+impl ProcessDriverHandler {
+    /// hand the assign to the process so it can ready the particle before the
+    /// create completes
+    #[route("Hyp<Assign>")]
+    pub async fn assign(&self, ctx: InCtx<'_, HyperSubstance>) -> Result<ReflectedCore, DriverErr> {
+        match ctx.input {
+            HyperSubstance::Assign(assign) => {
+                let point = assign.details.stub.point.clone();
+                Ok(self.proc.assign(point, ctx.wave().clone()).await?)
+            }
+            _ => Ok(self.proc.exchange(ctx.wave().clone()).await?),
+        }
+    }
+}
+
+pub struct ProcessParticle {
+    skel: HyperParticleSkel,
+    proc: Arc<SupervisedProc>,
+}
+
+impl Particle for ProcessParticle {
+    type Skel = HyperParticleSkel;
+    type Ctx = ();
+    type State = Arc<SupervisedProc>;
+    type Err = StdParticleErr;
+
+    fn restore(skel: Self::Skel, _: Self::Ctx, proc: Self::State) -> Self {
+        ProcessParticle { skel, proc }
+    }
+
+    fn sphere(self) -> Result<ParticleSphere, Self::Err> {
+        Ok(ParticleSphere::new_handler(self))
+    }
+}
+
+#[async_trait]
+impl DirectedHandler for ProcessParticle {
+    async fn handle(&self, ctx: RootInCtx) -> CoreBounce {
+        // a signal is never reflected so there is nothing to wait on
+        let result = if let DirectedWave::Signal(_) = ctx.wave {
+            self.proc.signal(ctx.wave.clone()).await.map(|_| None)
+        } else {
+            self.proc.exchange(ctx.wave.clone()).await.map(Some)
+        };
+        match result {
+            Ok(Some(core)) => CoreBounce::Reflected(core),
+            Ok(None) => CoreBounce::Absorbed,
+            Err(err) => {
+                self.skel.skel.logger.error(format!(
+                    "driver process for '{}' failed: {}",
+                    self.skel.point.to_string(),
+                    err.to_string()
+                ));
+                match err {
+                    DriverErr::SpaceErr(err) if err.status() == 504 => {
+                        CoreBounce::Reflected(ReflectedCore::err(err))
+                    }
+                    _ => CoreBounce::Reflected(ReflectedCore::server_error()),
+                }
+            }
+        }
+    }
+}
+
+/// the [DriverProc] of a driver.  Once [SupervisedProc::supervise]d a process that
+/// exits is respawned after the driver's [RetryPolicy] backoff, handed a fresh
+/// `Hyp<Init>` and then every `Hyp<Assign>` of the particles it hosted.  The driver
+/// is `Retrying` until the new process reflects them and `Fatal` if the attempts
+/// run out
+pub struct SupervisedProc {
+    executor: CliOsExecutor,
+    codec: WaveCodec,
+    point: Point,
+    kind: Kind,
+    status_tx: DriverStatusTx,
+    logger: Logger,
+    outbound: Option<Outbound>,
+    init_timeout: Duration,
+    proc: std::sync::RwLock<Arc<DriverProc>>,
+    assigns: DashMap<Point, DirectedWave>,
+    supervised: AtomicBool,
+}
+
+impl SupervisedProc {
+    pub fn spawn(
+        executor: CliOsExecutor,
+        codec: WaveCodec,
+        point: Point,
+        kind: Kind,
+        status_tx: DriverStatusTx,
+        logger: Logger,
+        outbound: Option<Outbound>,
+    ) -> Result<Self, DriverErr> {
+        let proc = DriverProc::spawn(&executor, codec.clone(), logger.clone(), outbound.clone())
+            .map_err(|e| SpaceErr::server_error(e.to_string()))?;
+        Ok(Self {
+            executor,
+            codec,
+            point,
+            kind,
+            status_tx,
+            logger,
+            outbound,
+            init_timeout: INIT_TIMEOUT,
+            proc: std::sync::RwLock::new(Arc::new(proc)),
+            assigns: DashMap::new(),
+            supervised: AtomicBool::new(false),
+        })
+    }
+
+    /// how long each process has to reflect its `Hyp<Init>`, [INIT_TIMEOUT] by default
+    pub fn with_init_timeout(mut self, timeout: Duration) -> Self {
+        self.init_timeout = timeout;
+        self
+    }
+
+    /// the process currently running
+    pub fn current(&self) -> Arc<DriverProc> {
+        self.proc.read().unwrap().clone()
+    }
+
+    fn init_wave(&self) -> Result<DirectedWave, SpaceErr> {
+        let mut init = DirectedProto::ping();
+        init.method(HypMethod::Init);
+        init.agent(Agent::HyperUser);
+        init.from(self.point.to_surface());
+        init.to(self.point.to_surface());
+        init.body(Substance::Text(self.kind.to_string()));
+        init.build()
+    }
+
+    /// handshake the running process.  A process that was killed by an earlier
+    /// failed init is replaced first
+    pub async fn init(&self) -> Result<(), DriverErr> {
+        let proc = self.current();
+        if proc.is_alive() {
+            proc.handshake(self.init_wave()?, self.init_timeout).await
+        } else {
+            self.restart().await
+        }
+    }
+
+    /// spawn a new process, init it, replay the assigns and make it current
+    async fn restart(&self) -> Result<(), DriverErr> {
+        let proc = DriverProc::spawn(
+            &self.executor,
+            self.codec.clone(),
+            self.logger.clone(),
+            self.outbound.clone(),
+        )
+        .map_err(|e| SpaceErr::server_error(e.to_string()))?;
+        proc.handshake(self.init_wave()?, self.init_timeout).await?;
+        let assigns: Vec<DirectedWave> = self
+            .assigns
+            .iter()
+            .map(|assign| assign.value().clone())
+            .collect();
+        for assign in assigns {
+            proc.exchange(assign).await?.ok_or()?;
+        }
+        *self.proc.write().unwrap() = Arc::new(proc);
+        Ok(())
+    }
+
+    /// respawn the process whenever it exits until this is dropped or the driver
+    /// becomes `Fatal`.  Only the first call has any effect
+    pub fn supervise(self: &Arc<Self>, policy: RetryPolicy) {
+        if self.supervised.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        let supervised = Arc::downgrade(self);
+        tokio::spawn(async move {
+            loop {
+                let exited = match supervised.upgrade() {
+                    Some(supervised) => supervised.current().exited(),
+                    None => return,
+                };
+                exited.await;
+                // the process is killed when the driver drops it
+                let supervised = match supervised.upgrade() {
+                    Some(supervised) => supervised,
+                    None => return,
+                };
+                if !supervised.respawn(&policy).await {
+                    return;
+                }
+            }
+        });
+    }
+
+    /// returns `false` once the attempts have run out and the driver is `Fatal`
+    async fn respawn(&self, policy: &RetryPolicy) -> bool {
+        for attempt in 1..=policy.max_attempts {
+            let delay = policy.backoff(attempt);
+            self.status_tx
+                .send(DriverStatus::Retrying(format!(
+                    "driver process exited, respawn attempt {} of {} in {}ms",
+                    attempt,
+                    policy.max_attempts,
+                    delay.as_millis()
+                )))
+                .await
+                .unwrap_or_default();
+            tokio::time::sleep(delay).await;
+            match self.restart().await {
+                Ok(_) => {
+                    self.status_tx
+                        .send(DriverStatus::Ready)
+                        .await
+                        .unwrap_or_default();
+                    return true;
+                }
+                Err(err) => self.logger.error(format!(
+                    "driver process respawn attempt {} failed: {}",
+                    attempt,
+                    err.to_string()
+                )),
+            }
+        }
+        self.status_tx
+            .send(DriverStatus::Fatal(format!(
+                "driver process could not be respawned after {} attempt(s)",
+                policy.max_attempts
+            )))
+            .await
+            .unwrap_or_default();
+        false
+    }
+
+    /// exchange the `Hyp<Assign>` of the particle at `point`, remembering it for a
+    /// respawned process if the process accepts it
+    pub async fn assign(
+        &self,
+        point: Point,
+        wave: DirectedWave,
+    ) -> Result<ReflectedCore, DriverErr> {
+        let core = self.current().exchange(wave.clone()).await?;
+        if core.status.is_success() {
+            self.assigns.insert(point, wave);
+        }
+        Ok(core)
+    }
+
+    pub fn forget(&self, point: &Point) {
+        self.assigns.remove(point);
+    }
+
+    pub async fn exchange(&self, wave: DirectedWave) -> Result<ReflectedCore, DriverErr> {
+        self.current().exchange(wave).await
+    }
+
+    pub async fn signal(&self, wave: DirectedWave) -> Result<(), DriverErr> {
+        self.current().signal(wave).await
+    }
+}
+
+/// sends the [DirectedWave]s a driver process writes out through the driver's
+/// transmitter.  They go from the driver's point as the driver's agent whatever the
+/// process put in them, and their reflections are made to answer the process's wave
+#[derive(Clone)]
+pub struct Outbound {
+    transmitter: ProtoTransmitter,
+    point: Point,
+}
+
+impl Outbound {
+    pub fn new(transmitter: ProtoTransmitter, point: Point) -> Self {
+        Self { transmitter, point }
+    }
+
+    /// direct `wave` and return the reflections to hand back to the process, none for
+    /// a signal.  A wave that can't be sent is reflected with the error
+    pub async fn direct(&self, wave: DirectedWave) -> Result<Vec<ReflectedWave>, SpaceErr> {
+        let from = self.point.to_surface();
+        let mut proto = DirectedProto::kind(&wave.directed_kind());
+        proto.core(wave.core().clone())?;
+        proto.to(wave.to());
+        proto.from(from.clone());
+        proto.agent(Agent::Point(self.point.clone()));
+        proto.handling(wave.handling().clone());
+        proto.scope(wave.scope().clone());
+
+        let cores = match &wave {
+            DirectedWave::Signal(_) => {
+                self.transmitter.signal(proto).await?;
+                return Ok(vec![]);
+            }
+            DirectedWave::Ping(_) => match self.transmitter.ping(proto).await {
+                Ok(pong) => vec![pong.variant.core],
+                Err(err) => vec![err.as_reflected_core()],
+            },
+            DirectedWave::Ripple(_) => match self.transmitter.ripple(proto).await {
+                Ok(echoes) => echoes.into_iter().map(|echo| echo.variant.core).collect(),
+                Err(err) => vec![err.as_reflected_core()],
+            },
+        };
+        let reflection = wave.reflection()?;
+        Ok(cores
+            .into_iter()
+            .map(|core| reflection.clone().make(core, from.clone()))
+            .collect())
+    }
+}
+
+/// a running driver process and the waves waiting on its reflections
+pub struct DriverProc {
+    stdin: Arc<Mutex<ChildStdin>>,
+    codec: WaveCodec,
+    pending: Arc<DashMap<WaveId, oneshot::Sender<ReflectedWave>>>,
+    alive: watch::Receiver<bool>,
+    kill: std::sync::Mutex<Option<oneshot::Sender<()>>>,
+}
+
+impl DriverProc {
+    /// spawn the executable and route the reflections it writes to their waves.  The
+    /// waves it directs are sent by `outbound`, without one they are dropped
+    pub fn spawn(
+        executor: &CliOsExecutor,
+        codec: WaveCodec,
+        logger: Logger,
+        outbound: Option<Outbound>,
+    ) -> Result<Self, CliErr> {
+        let mut process = executor.spawn(&vec![])?;
+        let stdin = Arc::new(Mutex::new(process.stdin.take().ok_or(CliErr::TakeStdIn)?));
+        let stdout = process.stdout.take().ok_or(CliErr::TakeStdOut)?;
+        let stderr = process.stderr.take().ok_or(CliErr::TakeStdErr)?;
+
+        let pending: Arc<DashMap<WaveId, oneshot::Sender<ReflectedWave>>> =
+            Arc::new(DashMap::new());
+        let (alive_tx, alive) = watch::channel(true);
+        let (kill_tx, kill_rx) = oneshot::channel::<()>();
+
+        {
+            let pending = pending.clone();
+            let stdin = stdin.clone();
+            let codec = codec.clone();
+            let logger = logger.clone();
+            tokio::spawn(async move {
+                let mut stdout = stdout;
+                loop {
+                    let payload = match read_frame(&mut stdout).await {
+                        Ok(Some(payload)) => payload,
+                        Ok(None) => break,
+                        Err(err) => {
+                            logger.error(format!("driver process frame: {}", err.to_string()));
+                            break;
+                        }
+                    };
+                    let wave = match codec.decode::<Wave>(payload.as_slice()) {
+                        Ok(wave) => wave,
+                        Err(err) => {
+                            logger.warn(format!(
+                                "driver process wrote a frame that is not a Wave: {}",
+                                err.to_string()
+                            ));
+                            continue;
+                        }
+                    };
+                    if wave.is_reflected() {
+                        let reflected = match wave.to_reflected() {
+                            Ok(reflected) => reflected,
+                            Err(_) => continue,
+                        };
+                        if let Some((_, tx)) = pending.remove(reflected.reflection_of()) {
+                            tx.send(reflected).unwrap_or_default();
+                        }
+                        continue;
+                    }
+                    let directed = match (wave.to_directed(), &outbound) {
+                        (Ok(directed), Some(_)) => directed,
+                        _ => {
+                            logger.warn("driver process directed a wave but has no way out");
+                            continue;
+                        }
+                    };
+                    // don't hold up the reflections the process writes meanwhile
+                    let outbound = outbound.clone().unwrap();
+                    let stdin = stdin.clone();
+                    let codec = codec.clone();
+                    let logger = logger.clone();
+                    tokio::spawn(async move {
+                        let reflected = match outbound.direct(directed).await {
+                            Ok(reflected) => reflected,
+                            Err(err) => {
+                                logger.warn(format!(
+                                    "driver process wave could not be sent: {}",
+                                    err.to_string()
+                                ));
+                                return;
+                            }
+                        };
+                        for reflected in reflected {
+                            let payload = match codec.encode(&reflected.to_wave()) {
+                                Ok(payload) => payload,
+                                Err(err) => {
+                                    logger.error(err.to_string());
+                                    continue;
+                                }
+                            };
+                            let mut stdin = stdin.lock().await;
+                            if let Err(err) = write_frame(&mut *stdin, payload.as_slice()).await {
+                                logger.warn(format!(
+                                    "driver process reflection could not be written: {}",
+                                    err.to_string()
+                                ));
+                            }
+                        }
+                    });
+                }
+            });
+        }
+
+        {
+            let logger = logger.clone();
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    logger.info(line.as_str());
+                }
+            });
+        }
+
+        {
+            let pending = pending.clone();
+            tokio::spawn(async move {
+                tokio::select! {
+                    status = process.wait() => match status {
+                        Ok(status) => logger.warn(format!("driver process exited: {}", status)),
+                        Err(err) => logger.error(format!("driver process wait failed: {}", err)),
+                    },
+                    // fires on `kill` or when the proc is dropped
+                    _ = kill_rx => {
+                        if let Err(err) = process.kill().await {
+                            logger.error(format!("driver process kill failed: {}", err));
+                        }
+                    }
+                }
+                alive_tx.send(false).unwrap_or_default();
+                // dropping the senders fails every wave still waiting on a reflection
+                pending.clear();
+            });
+        }
+
+        Ok(Self {
+            stdin,
+            codec,
+            pending,
+            alive,
+            kill: std::sync::Mutex::new(Some(kill_tx)),
+        })
+    }
+
+    pub fn is_alive(&self) -> bool {
+        *self.alive.borrow()
+    }
+
+    /// resolves once the process has exited or been killed.  The future doesn't
+    /// keep the process alive
+    pub fn exited(&self) -> impl Future<Output = ()> + Send + 'static {
+        let mut alive = self.alive.clone();
+        async move {
+            alive.wait_for(|alive| !*alive).await.ok();
+        }
+    }
+
+    /// stop the process, failing every wave still waiting on it
+    pub fn kill(&self) {
+        if let Some(kill) = self.kill.lock().unwrap().take() {
+            kill.send(()).unwrap_or_default();
+        }
+    }
+
+    /// exchange `wave` expecting an ok reflection within `timeout`.  A process
+    /// that doesn't answer in time is killed
+    pub async fn handshake(&self, wave: DirectedWave, timeout: Duration) -> Result<(), DriverErr> {
+        match tokio::time::timeout(timeout, self.exchange(wave)).await {
+            Ok(reflected) => {
+                reflected?.ok_or()?;
+                Ok(())
+            }
+            Err(_) => {
+                self.kill();
+                Err(SpaceErr::timeout(format!(
+                    "driver process did not reflect within {}s",
+                    timeout.as_secs()
+                )))?
+            }
+        }
+    }
+
+    /// write the wave to the process and wait for its reflection
+    pub async fn exchange(&self, wave: DirectedWave) -> Result<ReflectedCore, DriverErr> {
+        self.exchange_within(wave, EXCHANGE_TIMEOUT).await
+    }
+
+    /// write the wave to the process and wait up to `timeout` for its reflection.
+    /// A wave that isn't reflected in time fails with a 504 and stops waiting
+    pub async fn exchange_within(
+        &self,
+        wave: DirectedWave,
+        timeout: Duration,
+    ) -> Result<ReflectedCore, DriverErr> {
+        if !self.is_alive() {
+            Err(SpaceErr::server_error("driver process is not running"))?;
+        }
+        let id = wave.id().clone();
+        let payload = self.codec.encode(&wave.to_wave())?;
+
+        let (tx, rx) = oneshot::channel();
+        self.pending.insert(id.clone(), tx);
+        {
+            let mut stdin = self.stdin.lock().await;
+            if let Err(err) = write_frame(&mut *stdin, payload.as_slice()).await {
+                self.pending.remove(&id);
+                Err(err)?;
+            }
+        }
+
+        let reflected = match tokio::time::timeout(timeout, rx).await {
+            Ok(reflected) => reflected?,
+            Err(_) => {
+                self.pending.remove(&id);
+                Err(SpaceErr::new(
+                    504,
+                    format!(
+                        "driver process did not reflect within {}s",
+                        timeout.as_secs()
+                    ),
+                ))?
+            }
+        };
+        Ok(reflected.core().clone())
+    }
+
+    /// write the signal to the process without waiting on a reflection
+    pub async fn signal(&self, wave: DirectedWave) -> Result<(), DriverErr> {
+        if !self.is_alive() {
+            Err(SpaceErr::server_error("driver process is not running"))?;
+        }
+        let payload = self.codec.encode(&wave.to_wave())?;
+        let mut stdin = self.stdin.lock().await;
+        write_frame(&mut *stdin, payload.as_slice()).await?;
+        Ok(())
+    }
+}
+
+impl Drop for DriverProc {
+    fn drop(&mut self) {
+        self.kill();
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::hyperspace::driver::process::{
+        read_frame, write_frame, DriverProc, Outbound, SupervisedProc, WaveCodec, DRIVER_CODEC_ENV,
+        MAX_FRAME,
+    };
+    use crate::hyperspace::driver::{DriverErr, DriverStatus, DriverStatusTx, RetryPolicy};
+    use crate::hyperspace::executor::cli::os::CliOsExecutor;
+    use crate::hyperspace::executor::cli::HostEnv;
+    use crate::hyperspace::host::ExeStub;
+    use crate::space::err::StatusErr;
+    use crate::space::kind::Kind;
+    use crate::space::loc::ToSurface;
+    use crate::space::log::Logger;
+    use crate::space::point::Point;
+    use crate::space::settings::Timeouts;
+    use crate::space::substance::Substance;
+    use crate::space::wave::core::cmd::CmdMethod;
+    use crate::space::wave::core::hyper::HypMethod;
+    use crate::space::wave::core::ReflectedCore;
+    use crate::space::wave::exchange::asynch::{Exchanger, ProtoTransmitterBuilder, TxRouter};
+    use crate::space::wave::{Agent, DirectedProto, DirectedWave};
+    use std::str::FromStr;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::sync::{mpsc, watch};
+
+    /// `examples/echo_driver.rs` is built beside the test binary by `cargo test`
+    fn echo_driver(codec: &WaveCodec) -> ExeStub {
+        let exe = std::env::current_exe().unwrap();
+        let loc = exe
+            .parent()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .join("examples")
+            .join("echo_driver");
+        let mut env = HostEnv::builder();
+        env.env(DRIVER_CODEC_ENV, codec.to_string());
+        ExeStub::new(loc.display().to_string(), env.build())
+    }
+
+    fn init() -> DirectedWave {
+        let point = Point::from_str("localhost:drivers:thing").unwrap();
+        let mut init = DirectedProto::ping();
+        init.method(HypMethod::Init);
+        init.agent(Agent::HyperUser);
+        init.from(point.to_surface());
+        init.to(point.to_surface());
+        init.body(Substance::Text("Mechtron".to_string()));
+        init.build().unwrap()
+    }
+
+    #[tokio::test]
+    pub async fn test_echo_driver() {
+        let point = Point::from_str("localhost:thing").unwrap();
+        for codec in vec![WaveCodec::Json, WaveCodec::Bincode] {
+            let proc = DriverProc::spawn(
+                &CliOsExecutor::new(echo_driver(&codec)),
+                codec.clone(),
+                Logger::default(),
+                None,
+            )
+            .unwrap();
+            proc.handshake(init(), Duration::from_secs(5))
+                .await
+                .unwrap();
+
+            let body = Substance::Text("hello".to_string());
+            let mut proto = DirectedProto::ping();
+            proto.method(CmdMethod::Read);
+            proto.from(point.to_surface());
+            proto.to(point.to_surface());
+            proto.body(body.clone());
+            let reflected = proc.exchange(proto.build().unwrap()).await.unwrap();
+            assert!(reflected.status.is_success());
+            assert_eq!(body, reflected.body);
+        }
+    }
+
+    fn read() -> DirectedWave {
+        let point = Point::from_str("localhost:thing").unwrap();
+        let mut proto = DirectedProto::ping();
+        proto.method(CmdMethod::Read);
+        proto.from(point.to_surface());
+        proto.to(point.to_surface());
+        proto.body(Substance::Text("hello".to_string()));
+        proto.build().unwrap()
+    }
+
+    fn supervised(stub: ExeStub) -> (Arc<SupervisedProc>, watch::Receiver<DriverStatus>) {
+        let (tx, rx) = watch::channel(DriverStatus::Ready);
+        let proc = SupervisedProc::spawn(
+            CliOsExecutor::new(stub),
+            WaveCodec::Json,
+            Point::from_str("localhost:drivers:thing").unwrap(),
+            Kind::Mechtron,
+            DriverStatusTx::new(tx),
+            Logger::default(),
+            None,
+        )
+        .unwrap()
+        .with_init_timeout(Duration::from_millis(200));
+        (Arc::new(proc), rx)
+    }
+
+    #[tokio::test]
+    pub async fn test_respawn_killed_process() {
+        let (proc, mut status) = supervised(echo_driver(&WaveCodec::Json));
+        proc.init().await.unwrap();
+        // replayed to the respawned process
+        let point = Point::from_str("localhost:thing").unwrap();
+        proc.assign(point, read()).await.unwrap();
+        proc.supervise(RetryPolicy {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+        });
+
+        let killed = proc.current();
+        killed.kill();
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            status.wait_for(|status| matches!(status, DriverStatus::Retrying(_))),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            status.wait_for(|status| *status == DriverStatus::Ready),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert!(!killed.is_alive());
+        assert!(!Arc::ptr_eq(&killed, &proc.current()));
+        let reflected = proc.exchange(read()).await.unwrap();
+        assert!(reflected.status.is_success());
+    }
+
+    #[tokio::test]
+    pub async fn test_respawn_fatal() {
+        // `cat` never reflects the `Hyp<Init>` so every respawn fails
+        let (proc, mut status) =
+            supervised(ExeStub::new("cat".to_string(), HostEnv::builder().build()));
+        proc.supervise(RetryPolicy {
+            max_attempts: 2,
+            initial_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(10),
+        });
+
+        proc.current().kill();
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            status.wait_for(|status| matches!(status, DriverStatus::Fatal(_))),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(!proc.current().is_alive());
+        assert!(proc.exchange(read()).await.is_err());
+    }
+
+    #[tokio::test]
+    pub async fn test_init_timeout() {
+        // `cat` writes every frame straight back, which is never a reflection
+        let stub = ExeStub::new("cat".to_string(), HostEnv::builder().build());
+        let proc = DriverProc::spawn(
+            &CliOsExecutor::new(stub),
+            WaveCodec::Json,
+            Logger::default(),
+            None,
+        )
+        .unwrap();
+        assert!(proc
+            .handshake(init(), Duration::from_millis(200))
+            .await
+            .is_err());
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while proc.is_alive() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert!(proc.exchange(init()).await.is_err());
+    }
+
+    #[tokio::test]
+    pub async fn test_exchange_timeout() {
+        let point = Point::from_str("localhost:thing").unwrap();
+        let stub = ExeStub::new("cat".to_string(), HostEnv::builder().build());
+        let proc = DriverProc::spawn(
+            &CliOsExecutor::new(stub),
+            WaveCodec::Json,
+            Logger::default(),
+            None,
+        )
+        .unwrap();
+
+        let mut proto = DirectedProto::ping();
+        proto.method(CmdMethod::Read);
+        proto.from(point.to_surface());
+        proto.to(point.to_surface());
+        let err = proc
+            .exchange_within(proto.build().unwrap(), Duration::from_millis(200))
+            .await
+            .unwrap_err();
+        match err {
+            DriverErr::SpaceErr(err) => assert_eq!(504, err.status()),
+            err => panic!("expected a 504 but got: {}", err.to_string()),
+        }
+        // the unanswered wave no longer waits on a reflection
+        assert!(proc.pending.is_empty());
+
+        let mut proto = DirectedProto::signal();
+        proto.method(CmdMethod::Read);
+        proto.from(point.to_surface());
+        proto.to(point.to_surface());
+        proc.signal(proto.build().unwrap()).await.unwrap();
+        assert!(proc.pending.is_empty());
+        assert!(proc.is_alive());
+    }
+
+    #[tokio::test]
+    pub async fn test_outbound() {
+        let driver = Point::from_str("localhost:drivers:thing").unwrap();
+        let other = Point::from_str("localhost:other").unwrap();
+        let (tx, mut rx) = mpsc::channel(32);
+        let (sent_tx, mut sent) = mpsc::channel(32);
+        let exchanger = Exchanger::new(driver.to_surface(), Timeouts::default(), Logger::default());
+
+        // stands in for the particle the process directs its waves at
+        {
+            let other = other.clone();
+            let exchanger = exchanger.clone();
+            tokio::spawn(async move {
+                while let Some(wave) = rx.recv().await {
+                    let directed = wave.to_directed().unwrap();
+                    let reflected = directed.reflection().unwrap().make(
+                        ReflectedCore::ok_body(Substance::Text("pong".to_string())),
+                        other.to_surface(),
+                    );
+                    sent_tx.send(directed).await.unwrap();
+                    exchanger.reflected(reflected).await.unwrap();
+                }
+            });
+        }
+        let transmitter = ProtoTransmitterBuilder::new(Arc::new(TxRouter::new(tx)), exchanger);
+        let outbound = Outbound::new(transmitter.build(), driver.clone());
+
+        // whoever the process claims to be the wave goes from the driver
+        let mut proto = DirectedProto::ping();
+        proto.method(CmdMethod::Read);
+        proto.agent(Agent::HyperUser);
+        proto.from(Point::from_str("localhost:impostor").unwrap().to_surface());
+        proto.to(other.to_surface());
+        let wave = proto.build().unwrap();
+        let reflected = outbound.direct(wave.clone()).await.unwrap();
+        assert_eq!(1, reflected.len());
+        assert_eq!(wave.id(), reflected[0].reflection_of());
+        assert_eq!(
+            Substance::Text("pong".to_string()),
+            reflected[0].core().body
+        );
+        let directed = sent.recv().await.unwrap();
+        assert_eq!(driver.to_surface(), *directed.from());
+        assert_eq!(Agent::Point(driver.clone()), *directed.agent());
+
+        // `cat` writes the wave straight back to stdout as if the process directed it
+        let stub = ExeStub::new("cat".to_string(), HostEnv::builder().build());
+        let proc = DriverProc::spawn(
+            &CliOsExecutor::new(stub),
+            WaveCodec::Json,
+            Logger::default(),
+            Some(outbound),
+        )
+        .unwrap();
+        proc.signal(wave).await.unwrap();
+        let directed = tokio::time::timeout(Duration::from_secs(5), sent.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(driver.to_surface(), *directed.from());
+    }
+
+    #[tokio::test]
+    pub async fn test_oversized_frame() {
+        let (mut writer, _reader) = tokio::io::duplex(1024);
+        let payload = vec![0u8; MAX_FRAME as usize + 1];
+        assert!(write_frame(&mut writer, payload.as_slice()).await.is_err());
+    }
+
+    #[tokio::test]
+    pub async fn test_frames() {
+        let point = Point::from_str("localhost:thing").unwrap();
+        let mut proto = DirectedProto::ping();
+        proto.method(CmdMethod::Read);
+        proto.from(point.to_surface());
+        proto.to(point.to_surface());
+        proto.body(Substance::Text("hello".to_string()));
+        let wave = proto.build().unwrap();
+
+        for codec in vec![WaveCodec::Json, WaveCodec::Bincode] {
+            let (mut writer, mut reader) = tokio::io::duplex(1024);
+            let payload = codec.encode(&wave).unwrap();
+            write_frame(&mut writer, payload.as_slice()).await.unwrap();
+            drop(writer);
+
+            let payload = read_frame(&mut reader).await.unwrap().unwrap();
+            let decoded: DirectedWave = codec.decode(payload.as_slice()).unwrap();
+            assert_eq!(wave, decoded);
+            // the process closing its end is not an error
+            assert!(read_frame(&mut reader).await.unwrap().is_none());
+        }
+
+        assert_eq!(WaveCodec::Bincode, WaveCodec::from_str("bincode").unwrap());
+    }
+}
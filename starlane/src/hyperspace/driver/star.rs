@@ -1,6 +1,6 @@
 use crate::hyperspace::driver::{
-    Driver, DriverAvail, DriverCtx, DriverErr, DriverSkel, DriverStatus, HyperDriverFactory,
-    Particle, ParticleSphere, ParticleSphereInner, ParticleStarErr,
+    describe_history, Driver, DriverAvail, DriverCtx, DriverErr, DriverSkel, DriverStatus,
+    HyperDriverFactory, Particle, ParticleSphere, ParticleSphereInner, ParticleStarErr,
 };
 use crate::hyperspace::platform::Platform;
 use crate::hyperspace::reg::Registration;
@@ -12,6 +12,7 @@ use starlane_macros::{handler, route, DirectedHandler};
 use starlane_primitive_macros::push_mark;
 use crate::space::artifact::ArtRef;
 use crate::space::command::common::StateSrc;
+use crate::space::command::Command;
 use crate::space::command::direct::create::Strategy;
 use crate::space::config::bind::BindConfig;
 use crate::space::err::{CoreReflector, SpaceErr};
@@ -28,7 +29,7 @@ use crate::space::particle::traversal::TraversalInjection;
 use crate::space::particle::Status;
 use crate::space::point::Point;
 use crate::space::selector::{KindBaseSelector, KindSelector, Pattern, SubKindSelector};
-use crate::space::substance::{Substance, SubstanceKind};
+use crate::space::substance::{Substance, SubstanceKind, SubstanceList};
 use crate::space::util::{log, ValueMatcher, ValuePattern};
 use crate::space::wave::core::http2::StatusCode;
 use crate::space::wave::core::hyper::HypMethod;
@@ -315,17 +316,20 @@ impl Star {
     ) -> Result<ParticleLocation, <Self as Particle>::Err> {
         if let HyperSubstance::Provision(provision) = ctx.input {
             let record = self.skel.registry.record(&provision.point).await?;
+            let kind = record.details.stub.kind.clone();
+            let local = self.skel.drivers.find_external(kind.clone()).await?;
+
+            let mut selector = self.skel.wrangles.find(&kind);
+            if selector.is_none() && local.is_none() {
+                // a driver for this kind may have been loaded from a `Driver` particle
+                // since this star last wrangled
+                self.skel.api.wrangle().await?;
+                selector = self.skel.wrangles.find(&kind);
+            }
 
-            match self.skel.wrangles.find(&record.details.stub.kind) {
+            match selector {
                 None => {
-                    let kind = record.details.stub.kind.clone();
-                    if self
-                        .skel
-                        .drivers
-                        .find_external(record.details.stub.kind.clone())
-                        .await?
-                        .is_some()
-                    {
+                    if local.is_some() {
                         let assign = HyperSubstance::Assign(Assign::new(
                             AssignmentKind::Create,
                             record.details,
@@ -428,6 +432,57 @@ impl Star {
         }
     }
 
+    #[route("Hyp<Retract>")]
+    pub async fn retract(
+        &self,
+        ctx: InCtx<'_, HyperSubstance>,
+    ) -> Result<ReflectedCore, <Self as Particle>::Err> {
+        if let HyperSubstance::Retract(retract) = ctx.input {
+            if let Some(driver) = self
+                .skel
+                .drivers
+                .local_driver_lookup(retract.kind().clone())
+                .await?
+            {
+                let mut directed = DirectedProto::ping();
+                directed.method(HypMethod::Retract);
+                directed.from(self.skel.point.to_surface());
+                directed.to(driver.to_surface());
+                directed.body(HyperSubstance::Retract(retract.clone()).into());
+                directed.track = ctx.wave().track();
+                // the driver's runner answers once `Driver::on_delete` is done
+                let pong: WaveVariantDef<PongCore> = ctx.transmitter.direct(directed).await?;
+                self.skel.logger.result(pong.ok_or())?;
+            }
+            Ok(ReflectedCore::ok())
+        } else {
+            Err(SpaceErr::expected_substance(
+                SubstanceKind::Hyper(HyperSubstanceKind::Retract),
+                ctx.input.kind().into(),
+            ))?
+        }
+    }
+
+    /// the star's part of the `drivers` command: the status history of its own drivers
+    #[route("Cmd<Command>")]
+    pub async fn command(
+        &self,
+        ctx: InCtx<'_, Command>,
+    ) -> Result<ReflectedCore, <Self as Particle>::Err> {
+        match ctx.input {
+            Command::Drivers => {
+                let mut list = SubstanceList::new();
+                for line in describe_history(self.skel.drivers.history().await?) {
+                    list.push(Box::new(Substance::Text(line)));
+                }
+                Ok(ReflectedCore::ok_body(Substance::List(list)))
+            }
+            _ => Err(SpaceErr::bad_request(
+                "a star only executes the drivers command",
+            ))?,
+        }
+    }
+
     #[route("Hyp<Transport>")]
     pub async fn transport(&self, ctx: InCtx<'_, Wave>) {
         self.skel.logger.track(ctx.wave(), || {
@@ -572,6 +627,24 @@ impl StarWrangles {
         return None;
     }
 
+    /// every star that was discovered to serve a kind
+    pub async fn stars(&self) -> Vec<StarKey> {
+        let selectors: Vec<_> = self
+            .wrangles
+            .iter()
+            .map(|multi| multi.value().clone())
+            .collect();
+        let mut stars = vec![];
+        for selector in selectors {
+            for star in selector.read().await.stars.iter() {
+                if !stars.contains(&star.discovery.star_key) {
+                    stars.push(star.discovery.star_key.clone());
+                }
+            }
+        }
+        stars
+    }
+
     pub async fn add(&self, discoveries: Vec<StarDiscovery>) {
         for discovery in discoveries {
             for kind in discovery.kinds.clone() {
@@ -586,6 +659,11 @@ impl StarWrangles {
                     Some(mut wrangler) => {
                         let mut wrangler = wrangler.value_mut();
                         let mut wrangler = wrangler.write().await;
+                        // wrangling again (i.e. after a remote machine joins) replaces what
+                        // was previously discovered about the same star
+                        wrangler.stars.retain(|star| {
+                            star.discovery.star_key != discovery.discovery.star_key
+                        });
                         wrangler.stars.push(discovery.clone());
                         wrangler.sort();
                     }
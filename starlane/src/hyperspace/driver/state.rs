@@ -0,0 +1,248 @@
+use crate::hyperspace::driver::filestore::remove;
+use crate::hyperspace::driver::DriverErr;
+use crate::hyperspace::executor::dialect::filestore::{FileStoreIn, FileStoreOut};
+use crate::hyperspace::machine::MachineApi;
+use crate::hyperspace::service::{
+    FileStoreService, Service, ServiceErr, ServiceKind, ServiceSelector,
+};
+use crate::space::err::SpaceErr;
+use crate::space::kind::Kind;
+use crate::space::point::Point;
+use crate::space::substance::Bin;
+use crate::space::util::IdSelector;
+use dashmap::DashMap;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+/// the directory (relative to the FileStore root) beneath which drivers snapshot particle state
+pub const STATES_DIR: &'static str = "states";
+
+/// persists the serde state of each particle a driver hosts so it survives a restart.
+/// snapshots are written to the FileStore service only when the state actually changes and
+/// are read back into memory when the particle of a [crate::hyperspace::driver::Driver::stateful]
+/// driver is initialized
+#[derive(Clone)]
+pub struct ParticleStateStore {
+    machine: Option<MachineApi>,
+    kind: Kind,
+    root: PathBuf,
+    filestore: Arc<OnceCell<FileStoreService>>,
+    snapshots: Arc<DashMap<Point, Bin>>,
+}
+
+impl ParticleStateStore {
+    /// `driver` is the point of the driver whose particles are stored
+    pub fn new(machine: MachineApi, kind: Kind, driver: &Point) -> Self {
+        Self {
+            machine: Some(machine),
+            kind,
+            root: PathBuf::from(STATES_DIR).join(driver.md5()),
+            filestore: Arc::new(OnceCell::new()),
+            snapshots: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// store the states of `driver`'s particles in `filestore` instead of the FileStore
+    /// service the machine selects for the driver
+    pub async fn with_filestore(
+        kind: Kind,
+        driver: &Point,
+        filestore: &FileStoreService,
+    ) -> Result<Self, DriverErr> {
+        let root = PathBuf::from(STATES_DIR).join(driver.md5());
+        let filestore = filestore.sub_root(root.clone()).await?;
+        filestore.execute(FileStoreIn::Init).await?;
+        Ok(Self {
+            machine: None,
+            kind,
+            root,
+            filestore: Arc::new(OnceCell::new_with(Some(filestore))),
+            snapshots: Arc::new(DashMap::new()),
+        })
+    }
+
+    async fn filestore(&self) -> Result<&FileStoreService, DriverErr> {
+        self.filestore
+            .get_or_try_init(|| async {
+                let machine = self.machine.as_ref().ok_or(SpaceErr::server_error(
+                    "particle state store has no FileStore",
+                ))?;
+                let selector = ServiceSelector {
+                    name: IdSelector::Always,
+                    kind: ServiceKind::FileStore,
+                    driver: Some(self.kind.clone()),
+                };
+                let service: Service<_> = machine.select_service(selector).await?.into();
+                let filestore = service.filestore()?.sub_root(self.root.clone()).await?;
+                filestore.execute(FileStoreIn::Init).await?;
+                Ok::<FileStoreService, DriverErr>(filestore)
+            })
+            .await
+    }
+
+    fn path(point: &Point) -> PathBuf {
+        point.md5().into()
+    }
+
+    /// the last snapshot of `point` or `None` if it never stored any state
+    pub async fn get<S>(&self, point: &Point) -> Result<Option<S>, DriverErr>
+    where
+        S: DeserializeOwned,
+    {
+        if !self.snapshots.contains_key(point) {
+            self.restore(point).await?;
+        }
+        match self.snapshots.get(point) {
+            None => Ok(None),
+            Some(snapshot) => Ok(Some(decode(snapshot.value())?)),
+        }
+    }
+
+    /// snapshot `state` for `point`.  Nothing is written if it is unchanged since the
+    /// last snapshot
+    pub async fn put<S>(&self, point: &Point, state: &S) -> Result<(), DriverErr>
+    where
+        S: Serialize,
+    {
+        let snapshot = encode(state)?;
+        if !changed(self.snapshots.get(point).as_deref(), &snapshot) {
+            return Ok(());
+        }
+        self.filestore()
+            .await?
+            .execute(FileStoreIn::Write {
+                path: Self::path(point),
+                state: snapshot.clone(),
+            })
+            .await?;
+        self.snapshots.insert(point.clone(), snapshot);
+        Ok(())
+    }
+
+    /// forget the state of `point` both in memory and in the FileStore
+    pub async fn remove(&self, point: &Point) -> Result<(), DriverErr> {
+        self.snapshots.remove(point);
+        let filestore = match self.filestore().await {
+            Ok(filestore) => filestore,
+            // without a FileStore service no state was ever stored
+            Err(DriverErr::ServiceErr(ServiceErr::NoTemplate { .. })) => return Ok(()),
+            Err(err) => return Err(err),
+        };
+        remove(filestore, Self::path(point)).await
+    }
+
+    /// load the stored snapshot of `point` (if there is one) into memory.  Called when
+    /// the particle is initialized by [crate::hyperspace::driver::DriverApi::init_item]
+    pub async fn restore(&self, point: &Point) -> Result<(), DriverErr> {
+        let filestore = self.filestore().await?;
+        let path = Self::path(point);
+        if let FileStoreOut::Exists(false) = filestore
+            .execute(FileStoreIn::Exists { path: path.clone() })
+            .await?
+        {
+            return Ok(());
+        }
+        match filestore.execute(FileStoreIn::Read { path }).await? {
+            FileStoreOut::Read(snapshot) => {
+                self.snapshots.insert(point.clone(), snapshot);
+                Ok(())
+            }
+            _ => Err(SpaceErr::server_error(
+                "FileStore read returned unexpected output",
+            ))?,
+        }
+    }
+}
+
+fn encode<S>(state: &S) -> Result<Bin, SpaceErr>
+where
+    S: Serialize,
+{
+    Ok(bincode::serialize(state)?)
+}
+
+fn decode<S>(snapshot: &Bin) -> Result<S, SpaceErr>
+where
+    S: DeserializeOwned,
+{
+    Ok(bincode::deserialize(snapshot.as_slice())?)
+}
+
+fn changed(last: Option<&Bin>, snapshot: &Bin) -> bool {
+    match last {
+        None => true,
+        Some(last) => last != snapshot,
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::hyperspace::driver::state::{changed, decode, encode, ParticleStateStore};
+    use crate::hyperspace::service::tests::local_filestore;
+    use crate::hyperspace::test_util::temp_dir;
+    use crate::space::kind::Kind;
+    use crate::space::point::Point;
+    use serde::{Deserialize, Serialize};
+    use std::str::FromStr;
+
+    #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+    struct Counter {
+        name: String,
+        count: u64,
+    }
+
+    #[test]
+    pub fn test_snapshot() {
+        let counter = Counter {
+            name: "hits".to_string(),
+            count: 3,
+        };
+        let snapshot = encode(&counter).unwrap();
+        assert_eq!(counter, decode(&snapshot).unwrap());
+
+        assert!(changed(None, &snapshot));
+        assert!(!changed(Some(&snapshot), &encode(&counter).unwrap()));
+        let counter = Counter {
+            count: 4,
+            ..counter
+        };
+        assert!(changed(Some(&snapshot), &encode(&counter).unwrap()));
+    }
+
+    #[tokio::test]
+    pub async fn test_store() {
+        let dir = temp_dir("particle-states");
+        let filestore = local_filestore(dir.to_str().unwrap());
+        let driver = Point::from_str("GLOBAL::star:drivers:base").unwrap();
+        let point = Point::from_str("my-domain.com:counter").unwrap();
+        let store = ParticleStateStore::with_filestore(Kind::Base, &driver, &filestore)
+            .await
+            .unwrap();
+
+        assert_eq!(None, store.get::<Counter>(&point).await.unwrap());
+        let counter = Counter {
+            name: "hits".to_string(),
+            count: 3,
+        };
+        store.put(&point, &counter).await.unwrap();
+        assert_eq!(Some(counter.clone()), store.get(&point).await.unwrap());
+
+        // a restarted driver reads the snapshot back from the FileStore
+        let restarted = ParticleStateStore::with_filestore(Kind::Base, &driver, &filestore)
+            .await
+            .unwrap();
+        restarted.restore(&point).await.unwrap();
+        assert_eq!(Some(counter), restarted.get(&point).await.unwrap());
+
+        restarted.remove(&point).await.unwrap();
+        let restarted = ParticleStateStore::with_filestore(Kind::Base, &driver, &filestore)
+            .await
+            .unwrap();
+        assert_eq!(None, restarted.get::<Counter>(&point).await.unwrap());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,535 @@
+use crate::hyperspace::driver::{
+    Driver, DriverCtx, DriverErr, DriverSkel, HyperDriverFactory, HyperParticleSkel, Particle,
+    ParticleSphere, StdParticleErr,
+};
+use crate::hyperspace::hyperlane::token::{TokenRecord, TokenStore};
+use crate::hyperspace::reg::{Registration, Registry};
+use crate::hyperspace::star::HyperStarSkel;
+use crate::space::command::common::{PropertyMod, SetProperties};
+use crate::space::command::direct::create::{KindTemplate, Strategy};
+use crate::space::err::SpaceErr;
+use crate::space::kind::{BaseKind, Kind, Specific, UserBaseSubKind, UserBaseSubKindBase};
+use crate::space::loc::ToPoint;
+use crate::space::particle::Status;
+use crate::space::point::Point;
+use crate::space::security::Access;
+use crate::space::selector::KindSelector;
+use crate::space::substance::{Substance, SubstanceMap};
+use crate::space::util::ValueMatcher;
+use crate::space::wave::core::ReflectedCore;
+use crate::space::wave::exchange::asynch::InCtx;
+use once_cell::sync::Lazy;
+use sha2::Sha256;
+use starlane_macros::{handler, DirectedHandler};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// the identity provider an `OAuth` UserBase is backed by when the template doesn't say
+pub static KEYCLOAK: Lazy<Specific> =
+    Lazy::new(|| Specific::from_str("old.io:redhat.com:keycloak:community:18.0.0").unwrap());
+
+/// the locked property of a `User` holding its salted password hash.  The lock only keeps
+/// it from being overwritten, an agent that may read the `User` may read the hash
+pub const PASSWORD_HASH: &'static str = "password-hash";
+
+/// how long a token issued by `Ext<Login>` stays valid
+pub const TOKEN_TTL: Duration = Duration::from_secs(60 * 60 * 12);
+
+const ROUNDS: u32 = 100_000;
+
+/// verified against when a login names a user that doesn't exist so that it takes as
+/// long as one with a wrong password.  No password hashes to all zeros
+static DUMMY_HASH: Lazy<String> =
+    Lazy::new(|| format!("pbkdf2-sha256${}$dummy${}", ROUNDS, "0".repeat(64)));
+
+pub fn select_kind(template: &KindTemplate) -> Result<Kind, SpaceErr> {
+    let sub = match &template.sub {
+        None => {
+            return Err(SpaceErr::expect_sub::<UserBaseSubKindBase>(
+                BaseKind::UserBase,
+            ))
+        }
+        Some(sub) => UserBaseSubKindBase::from_str(sub.as_str())?,
+    };
+
+    match sub {
+        UserBaseSubKindBase::Local => Ok(Kind::UserBase(UserBaseSubKind::Local)),
+        UserBaseSubKindBase::OAuth => {
+            if let Some(selector) = &template.specific {
+                if selector.is_match(&*KEYCLOAK).is_err() {
+                    return Err(SpaceErr::KindNotAvailable(template.clone()));
+                }
+            }
+            Ok(Kind::UserBase(UserBaseSubKind::OAuth(KEYCLOAK.clone())))
+        }
+    }
+}
+
+/// `pbkdf2-sha256$<rounds>$<salt>$<hash>` where the salt is fresh for every call
+pub fn hash_password(password: &str) -> String {
+    let salt = uuid::Uuid::new_v4().simple().to_string();
+    hash_with(password, salt.as_str(), ROUNDS)
+}
+
+pub fn verify_password(password: &str, hash: &str) -> bool {
+    let parts: Vec<&str> = hash.split('$').collect();
+    if parts.len() != 4 || parts[0] != "pbkdf2-sha256" {
+        return false;
+    }
+    let rounds = match u32::from_str(parts[1]) {
+        Ok(rounds) => rounds,
+        Err(_) => return false,
+    };
+    let expected = hash_with(password, parts[2], rounds);
+    // compare every byte so the time taken doesn't reveal how much of the hash matched
+    expected.len() == hash.len()
+        && expected
+            .bytes()
+            .zip(hash.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// [hash_password] on a blocking thread since [ROUNDS] of pbkdf2 would stall the runtime
+pub(crate) async fn hash(password: String) -> Result<String, SpaceErr> {
+    tokio::task::spawn_blocking(move || hash_password(password.as_str()))
+        .await
+        .map_err(|err| SpaceErr::server_error(err.to_string()))
+}
+
+/// [verify_password] on a blocking thread
+pub(crate) async fn verify(password: String, hash: String) -> Result<bool, SpaceErr> {
+    tokio::task::spawn_blocking(move || verify_password(password.as_str(), hash.as_str()))
+        .await
+        .map_err(|err| SpaceErr::server_error(err.to_string()))
+}
+
+fn hash_with(password: &str, salt: &str, rounds: u32) -> String {
+    let mut out = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt.as_bytes(), rounds, &mut out);
+    let hex: String = out.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("pbkdf2-sha256${}${}${}", rounds, salt, hex)
+}
+
+fn text(map: &SubstanceMap, key: &str) -> Result<String, SpaceErr> {
+    match map.get(key) {
+        Some(Substance::Text(value)) => Ok(value.clone()),
+        _ => Err(SpaceErr::bad_request(format!(
+            "expected text field '{}'",
+            key
+        ))),
+    }
+}
+
+pub struct UserBaseDriverFactory {
+    tokens: TokenStore,
+}
+
+impl UserBaseDriverFactory {
+    /// tokens are issued into `tokens` so the control hyperlane accepts them
+    pub fn new(tokens: TokenStore) -> Self {
+        Self { tokens }
+    }
+}
+
+#[async_trait]
+impl HyperDriverFactory for UserBaseDriverFactory {
+    fn kind(&self) -> Kind {
+        Kind::UserBase(UserBaseSubKind::Local)
+    }
+
+    fn selector(&self) -> KindSelector {
+        KindSelector::from_str("UserBase<Local>").unwrap()
+    }
+
+    async fn create(
+        &self,
+        _: HyperStarSkel,
+        skel: DriverSkel,
+        _: DriverCtx,
+    ) -> Result<Box<dyn Driver>, DriverErr> {
+        Ok(Box::new(UserBaseDriver::new(skel, self.tokens.clone())))
+    }
+}
+
+pub struct UserBaseDriver {
+    skel: DriverSkel,
+    tokens: TokenStore,
+}
+
+impl UserBaseDriver {
+    pub fn new(skel: DriverSkel, tokens: TokenStore) -> Self {
+        Self { skel, tokens }
+    }
+}
+
+#[async_trait]
+impl Driver for UserBaseDriver {
+    fn kind(&self) -> Kind {
+        Kind::UserBase(UserBaseSubKind::Local)
+    }
+
+    /// the tokens a deleted UserBase issued no longer authenticate anyone
+    async fn on_delete(&self, point: &Point) -> Result<(), DriverErr> {
+        let tokens = self.tokens.scoped(point.clone());
+        tokio::task::spawn_blocking(move || tokens.revoke_all())
+            .await
+            .map_err(|err| SpaceErr::server_error(err.to_string()))??;
+        Ok(())
+    }
+
+    async fn particle(&self, point: &Point) -> Result<ParticleSphere, DriverErr> {
+        let skel = HyperParticleSkel {
+            skel: self.skel.clone(),
+            point: point.clone(),
+            kind: self.kind(),
+        };
+        let tokens = self.tokens.scoped(point.clone());
+        let userbase = UserBase::restore(skel, (), tokens);
+        Ok(userbase.sphere()?)
+    }
+}
+
+#[derive(DirectedHandler)]
+pub struct UserBase {
+    skel: HyperParticleSkel,
+    tokens: TokenStore,
+}
+
+impl Particle for UserBase {
+    type Skel = HyperParticleSkel;
+    type Ctx = ();
+    type State = TokenStore;
+    type Err = StdParticleErr;
+
+    fn restore(skel: Self::Skel, _: Self::Ctx, tokens: Self::State) -> Self {
+        Self { skel, tokens }
+    }
+
+    fn sphere(self) -> Result<ParticleSphere, Self::Err> {
+        Ok(ParticleSphere::new_handler(self))
+    }
+}
+
+#[handler]
+impl UserBase {
+    /// expects a map with a `username` and `password`. the `User` is registered
+    /// beneath this UserBase with only the hash of its password
+    #[route("Ext<AddUser>")]
+    pub async fn add_user(&self, ctx: InCtx<'_, SubstanceMap>) -> Result<ReflectedCore, DriverErr> {
+        let registry = self.skel.skel.registry();
+        let agent = ctx.wave().agent().to_point();
+        let user = add_user(registry, &self.skel.point, &agent, ctx.input).await?;
+        if let Some(star) = self.skel.skel.locate(&self.skel.point).await?.location.star {
+            registry.assign_star(&user, &star).await?;
+        }
+        Ok(ReflectedCore::ok_body(Substance::Point(user)))
+    }
+
+    /// expects a map with a `username` and `password` and reflects a [Substance::Token]
+    /// issued by this UserBase that authenticates a control connection as that `User`
+    #[route("Ext<Login>")]
+    pub async fn login(&self, ctx: InCtx<'_, SubstanceMap>) -> Result<ReflectedCore, DriverErr> {
+        let registry = self.skel.skel.registry();
+        let record = login(registry, &self.tokens, &self.skel.point, ctx.input).await?;
+        Ok(ReflectedCore::ok_body(Substance::Token(record.token)))
+    }
+}
+
+/// only an agent that may create children of the UserBase may add users to it
+fn authorize(access: &Access, agent: &Point, userbase: &Point) -> Result<(), SpaceErr> {
+    if access.permissions().child.create {
+        Ok(())
+    } else {
+        Err(SpaceErr::new(
+            403,
+            format!(
+                "'{}' may not add users to '{}'",
+                agent.to_string(),
+                userbase.to_string()
+            ),
+        ))
+    }
+}
+
+/// register the `User` named in `input` beneath `userbase` on behalf of `agent` with
+/// the hash of its password in its locked [PASSWORD_HASH] property
+pub(crate) async fn add_user(
+    registry: &Registry,
+    userbase: &Point,
+    agent: &Point,
+    input: &SubstanceMap,
+) -> Result<Point, DriverErr> {
+    authorize(&registry.access(agent, userbase).await?, agent, userbase)?;
+    let username = text(input, "username")?;
+    let password = text(input, "password")?;
+    let user = userbase.push(username.as_str()).map_err(SpaceErr::from)?;
+    if registry.record(&user).await.is_ok() {
+        Err(SpaceErr::new(
+            409,
+            format!("'{}' already exists", user.to_string()),
+        ))?;
+    }
+
+    let mut properties = SetProperties::new();
+    properties.push(PropertyMod::Set {
+        key: PASSWORD_HASH.to_string(),
+        value: hash(password).await?,
+        lock: true,
+    });
+    let registration = Registration {
+        point: user.clone(),
+        kind: Kind::User,
+        registry: Default::default(),
+        properties,
+        owner: agent.clone(),
+        strategy: Strategy::Commit,
+        status: Status::Ready,
+    };
+    registry.register(&registration).await?;
+    Ok(user)
+}
+
+/// check the `username` and `password` in `input` against the users of `userbase`
+/// and issue a token for the `User` they name
+pub(crate) async fn login(
+    registry: &Registry,
+    tokens: &TokenStore,
+    userbase: &Point,
+    input: &SubstanceMap,
+) -> Result<TokenRecord, DriverErr> {
+    let username = text(input, "username")?;
+    let password = text(input, "password")?;
+    let denied = || SpaceErr::new(401, "invalid username or password");
+
+    let user = userbase.push(username.as_str()).map_err(|_| denied())?;
+    // a deleted `User` has no record left to log in with
+    let hash = registry.record(&user).await.ok().and_then(|record| {
+        record
+            .details
+            .properties
+            .get(PASSWORD_HASH)
+            .map(|hash| hash.value.clone())
+    });
+    // verify even when there is no such user so the time taken doesn't reveal which exist
+    let verified = verify(password, hash.clone().unwrap_or_else(|| DUMMY_HASH.clone())).await?;
+    if hash.is_none() || !verified {
+        Err(denied())?;
+    }
+
+    Ok(tokens.issue(user, Some(TOKEN_TTL)).await?)
+}
+
+/// hosts the `User` particles a local UserBase registers
+pub struct UserDriverFactory;
+
+impl UserDriverFactory {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl HyperDriverFactory for UserDriverFactory {
+    fn kind(&self) -> Kind {
+        Kind::User
+    }
+
+    fn selector(&self) -> KindSelector {
+        KindSelector::from_base(BaseKind::User)
+    }
+
+    async fn create(
+        &self,
+        _: HyperStarSkel,
+        _: DriverSkel,
+        _: DriverCtx,
+    ) -> Result<Box<dyn Driver>, DriverErr> {
+        Ok(Box::new(UserDriver))
+    }
+}
+
+pub struct UserDriver;
+
+#[async_trait]
+impl Driver for UserDriver {
+    fn kind(&self) -> Kind {
+        Kind::User
+    }
+
+    async fn particle(&self, point: &Point) -> Result<ParticleSphere, DriverErr> {
+        let user = User::restore((), (), point.clone());
+        Ok(user.sphere()?)
+    }
+}
+
+#[derive(DirectedHandler)]
+pub struct User {
+    point: Point,
+}
+
+#[handler]
+impl User {}
+
+impl Particle for User {
+    type Skel = ();
+    type Ctx = ();
+    type State = Point;
+    type Err = StdParticleErr;
+
+    fn restore(_: Self::Skel, _: Self::Ctx, point: Self::State) -> Self {
+        Self { point }
+    }
+
+    fn sphere(self) -> Result<ParticleSphere, Self::Err> {
+        Ok(ParticleSphere::new_handler(self))
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::hyperspace::driver::userbase::{
+        add_user, authorize, hash, hash_password, login, select_kind, verify, verify_password,
+        DUMMY_HASH, PASSWORD_HASH,
+    };
+    use crate::hyperspace::hyperlane::token::TokenStore;
+    use crate::hyperspace::reg::Registry;
+    use crate::hyperspace::registry::mem::registry::MemoryRegistry;
+    use crate::hyperspace::test_util::temp_dir;
+    use crate::space::kind::{Kind, KindParts, UserBaseSubKind};
+    use crate::space::parse::kind_template;
+    use crate::space::parse::util::{new_span, result};
+    use crate::space::point::Point;
+    use crate::space::security::Access;
+    use crate::space::substance::{Substance, SubstanceMap};
+    use std::str::FromStr;
+    use std::sync::Arc;
+
+    fn credentials(username: &str, password: &str) -> SubstanceMap {
+        let mut map = SubstanceMap::default();
+        map.insert(
+            "username".to_string(),
+            Substance::Text(username.to_string()),
+        );
+        map.insert(
+            "password".to_string(),
+            Substance::Text(password.to_string()),
+        );
+        map
+    }
+
+    #[test]
+    pub fn test_password_hash() {
+        let hash = hash_password("secret");
+        assert!(!hash.contains("secret"));
+        assert!(verify_password("secret", hash.as_str()));
+        assert!(!verify_password("Secret", hash.as_str()));
+        assert!(!verify_password("secret", "secret"));
+        assert!(!verify_password("", DUMMY_HASH.as_str()));
+        // every hash gets its own salt
+        assert_ne!(hash, hash_password("secret"));
+    }
+
+    #[tokio::test]
+    pub async fn test_hash_off_runtime() {
+        let hash = hash("secret".to_string()).await.unwrap();
+        assert!(verify("secret".to_string(), hash.clone()).await.unwrap());
+        assert!(!verify("Secret".to_string(), hash).await.unwrap());
+    }
+
+    #[test]
+    pub fn test_select_kind() {
+        let template = result(kind_template(new_span("UserBase<Local>"))).unwrap();
+        assert_eq!(
+            Kind::UserBase(UserBaseSubKind::Local),
+            select_kind(&template).unwrap()
+        );
+        assert_eq!(
+            Kind::UserBase(UserBaseSubKind::Local),
+            Kind::try_from(KindParts::from_str("UserBase<Local>").unwrap()).unwrap()
+        );
+
+        let template = result(kind_template(new_span("UserBase"))).unwrap();
+        assert!(select_kind(&template).is_err());
+        let template = result(kind_template(new_span("UserBase<Ldap>"))).unwrap();
+        assert!(select_kind(&template).is_err());
+    }
+
+    #[test]
+    pub fn test_authorize() {
+        let agent = Point::from_str("localhost:users:scott").unwrap();
+        let userbase = Point::from_str("localhost:users").unwrap();
+        assert!(authorize(&Access::Owner, &agent, &userbase).is_ok());
+        assert!(authorize(&Access::Super, &agent, &userbase).is_ok());
+        assert!(authorize(&Access::none(), &agent, &userbase).is_err());
+    }
+
+    #[tokio::test]
+    pub async fn test_add_user_and_login() {
+        let registry: Registry = Arc::new(MemoryRegistry::new());
+        let dir = temp_dir("userbase");
+        let store = TokenStore::new(dir.join("tokens.yaml").to_str().unwrap().to_string());
+        let userbase = Point::from_str("localhost:users").unwrap();
+        let tokens = store.scoped(userbase.clone());
+        let owner = Point::from_str("localhost:owner").unwrap();
+
+        let user = add_user(
+            &registry,
+            &userbase,
+            &owner,
+            &credentials("scott", "secret"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(userbase.push("scott").unwrap(), user);
+        // only the hash of the password is kept, in a locked property
+        let properties = registry.get_properties(&user).await.unwrap();
+        let hash = properties.get(PASSWORD_HASH).unwrap();
+        assert!(hash.locked);
+        let hash = &hash.value;
+        assert!(!hash.contains("secret"));
+        assert!(verify_password("secret", hash.as_str()));
+        assert!(
+            add_user(&registry, &userbase, &owner, &credentials("scott", "other"))
+                .await
+                .is_err()
+        );
+
+        let record = login(
+            &registry,
+            &tokens,
+            &userbase,
+            &credentials("scott", "secret"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(user, record.agent);
+        assert_eq!(Some(userbase.clone()), record.scope);
+        assert!(record.expires.is_some());
+        // the control hyperlane's unscoped store accepts the token
+        assert_eq!(
+            Some(record.clone()),
+            store.lookup(&record.token).await.unwrap()
+        );
+
+        assert!(login(
+            &registry,
+            &tokens,
+            &userbase,
+            &credentials("scott", "Secret")
+        )
+        .await
+        .is_err());
+        assert!(login(
+            &registry,
+            &tokens,
+            &userbase,
+            &credentials("nobody", "secret")
+        )
+        .await
+        .is_err());
+        assert_eq!(1, tokens.list().unwrap().len());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
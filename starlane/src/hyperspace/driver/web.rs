@@ -0,0 +1,839 @@
+use crate::hyperspace::driver::{
+    Driver, DriverCtx, DriverErr, DriverHandler, DriverSkel, DriverStatus, HyperDriverFactory,
+    Particle, ParticleSphere, StdParticleErr,
+};
+use crate::hyperspace::star::HyperStarSkel;
+use crate::space::command::direct::select::{Select, SelectIntoSubstance, SelectKind};
+use crate::space::err::{CoreReflector, SpaceErr};
+use crate::space::hyper::HyperSubstance;
+use crate::space::kind::{BaseKind, Kind};
+use crate::space::loc::{Layer, ToSurface};
+use crate::space::log::Logger;
+use crate::space::particle::Properties;
+use crate::space::point::Point;
+use crate::space::selector::{KindSelector, Selector};
+use crate::space::substance::Substance;
+use crate::space::wave::core::http2::{HttpMethod, HttpRequest};
+use crate::space::wave::core::{DirectedCore, HeaderMap, ReflectedCore};
+use crate::space::wave::exchange::asynch::{InCtx, ProtoTransmitter};
+use crate::space::wave::{DirectedProto, PongCore, WaveVariantDef};
+use dashmap::DashMap;
+use starlane_macros::{handler, DirectedHandler};
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader,
+};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use url::Url;
+
+/// the longest request line or header line a client may send
+const MAX_LINE: usize = 8 * 1024;
+
+/// the most headers a request may carry
+const MAX_HEADERS: usize = 100;
+
+/// the largest request body that will be read
+const MAX_BODY: usize = 16 * 1024 * 1024;
+
+/// how long a rejected request's leftover input is drained before hanging up
+const LINGER: Duration = Duration::from_secs(1);
+
+/// how long a connection may take to send its next request before it is closed
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub struct WebServerDriverFactory {
+    addr: SocketAddr,
+}
+
+impl WebServerDriverFactory {
+    /// `addr` is where a WebServer listens unless its `host` or `port` properties
+    /// say otherwise
+    pub fn new(addr: SocketAddr) -> Self {
+        Self { addr }
+    }
+}
+
+#[async_trait]
+impl HyperDriverFactory for WebServerDriverFactory {
+    fn kind(&self) -> Kind {
+        Kind::WebServer
+    }
+
+    fn selector(&self) -> KindSelector {
+        KindSelector::from_base(BaseKind::WebServer)
+    }
+
+    async fn create(
+        &self,
+        _: HyperStarSkel,
+        skel: DriverSkel,
+        _: DriverCtx,
+    ) -> Result<Box<dyn Driver>, DriverErr> {
+        Ok(Box::new(WebServerDriver::new(skel, self.addr)))
+    }
+}
+
+pub struct WebServerDriver {
+    skel: DriverSkel,
+    addr: SocketAddr,
+    listeners: Arc<DashMap<Point, WebListener>>,
+}
+
+impl WebServerDriver {
+    pub fn new(skel: DriverSkel, addr: SocketAddr) -> Self {
+        Self {
+            skel,
+            addr,
+            listeners: Arc::new(DashMap::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl Driver for WebServerDriver {
+    fn kind(&self) -> Kind {
+        Kind::WebServer
+    }
+
+    /// listen again for every WebServer assigned to this star before it was restarted.
+    /// A WebServer that cannot be bound is logged rather than failing the driver
+    async fn init(&mut self, skel: DriverSkel, _: DriverCtx) -> Result<(), DriverErr> {
+        skel.logger
+            .result(skel.status_tx.send(DriverStatus::Init).await)
+            .unwrap_or_default();
+
+        let mut select = Select {
+            pattern: Selector::from_str("**<WebServer>").map_err(SpaceErr::from)?,
+            properties: Default::default(),
+            into_substance: SelectIntoSubstance::Points,
+            kind: SelectKind::Initial,
+        };
+        let points = skel.registry().select(&mut select).await?;
+        for point in points.list.into_iter() {
+            if let Substance::Point(point) = *point {
+                let record = skel.registry().record(&point).await?;
+                if record.location.star.as_ref() != Some(&skel.star.point) {
+                    continue;
+                }
+                let properties = &record.details.properties;
+                if let Err(err) =
+                    listen(&skel, &self.listeners, &point, properties, self.addr).await
+                {
+                    skel.logger.error(format!(
+                        "could not bind WebServer '{}': {}",
+                        point.to_string(),
+                        err.to_string()
+                    ));
+                }
+            }
+        }
+
+        skel.logger
+            .result(skel.status_tx.send(DriverStatus::Ready).await)
+            .unwrap_or_default();
+        Ok(())
+    }
+
+    async fn particle(&self, point: &Point) -> Result<ParticleSphere, DriverErr> {
+        let properties = self.skel.registry().get_properties(point).await?;
+        listen(&self.skel, &self.listeners, point, &properties, self.addr).await?;
+        let server = WebServer::restore((), (), point.clone());
+        Ok(server.sphere()?)
+    }
+
+    /// stop listening so the port is free again
+    async fn on_delete(&self, point: &Point) -> Result<(), DriverErr> {
+        self.listeners.remove(point);
+        Ok(())
+    }
+
+    async fn handler(&self) -> Box<dyn DriverHandler> {
+        Box::new(WebServerDriverHandler::restore(
+            self.skel.clone(),
+            self.listeners.clone(),
+            self.addr,
+        ))
+    }
+}
+
+#[derive(DirectedHandler)]
+pub struct WebServerDriverHandler {
+    skel: DriverSkel,
+    listeners: Arc<DashMap<Point, WebListener>>,
+    addr: SocketAddr,
+}
+
+impl WebServerDriverHandler {
+    fn restore(
+        skel: DriverSkel,
+        listeners: Arc<DashMap<Point, WebListener>>,
+        addr: SocketAddr,
+    ) -> Self {
+        Self {
+            skel,
+            listeners,
+            addr,
+        }
+    }
+}
+
+impl DriverHandler for WebServerDriverHandler {}
+
+#[handler]
+impl WebServerDriverHandler {
+    #[route("Hyp<Assign>")]
+    pub async fn assign(&self, ctx: InCtx<'_, HyperSubstance>) -> Result<(), DriverErr> {
+        if let HyperSubstance::Assign(assign) = ctx.input {
+            let details = &assign.details;
+            listen(
+                &self.skel,
+                &self.listeners,
+                &details.stub.point,
+                &details.properties,
+                self.addr,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+/// a WebServer has nothing to say for itself: every request it receives is
+/// routed onward by its bind config before it gets here
+#[derive(DirectedHandler)]
+pub struct WebServer {
+    point: Point,
+}
+
+#[handler]
+impl WebServer {}
+
+impl Particle for WebServer {
+    type Skel = ();
+    type Ctx = ();
+    type State = Point;
+    type Err = StdParticleErr;
+
+    fn restore(_: Self::Skel, _: Self::Ctx, point: Self::State) -> Self {
+        Self { point }
+    }
+
+    fn sphere(self) -> Result<ParticleSphere, Self::Err> {
+        Ok(ParticleSphere::new_handler(self))
+    }
+}
+
+/// aborts the accept loop when the WebServer is forgotten
+pub struct WebListener {
+    pub addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl Drop for WebListener {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// bind the WebServer's port and relay every request to the WebServer itself
+/// where its bind config routes it on to the target point
+async fn listen(
+    skel: &DriverSkel,
+    listeners: &DashMap<Point, WebListener>,
+    point: &Point,
+    properties: &Properties,
+    default: SocketAddr,
+) -> Result<(), DriverErr> {
+    if listeners.contains_key(point) {
+        return Ok(());
+    }
+    let invalid = |name: &str, value: &String| {
+        SpaceErr::bad_request(format!(
+            "WebServer '{}' has an invalid {}: '{}'",
+            point.to_string(),
+            name,
+            value
+        ))
+    };
+    let host = match properties.get("host") {
+        None => default.ip(),
+        Some(host) => {
+            IpAddr::from_str(host.value.as_str()).map_err(|_| invalid("host", &host.value))?
+        }
+    };
+    let port = match properties.get("port") {
+        None => default.port(),
+        Some(port) => {
+            u16::from_str(port.value.as_str()).map_err(|_| invalid("port", &port.value))?
+        }
+    };
+    let addr = SocketAddr::new(host, port);
+    let listener = TcpListener::bind(addr).await.map_err(SpaceErr::from)?;
+    let dispatcher = Arc::new(WaveDispatcher::new(
+        point.clone(),
+        skel.item_ctx(point, Layer::Core)?.transmitter,
+    ));
+    let logger = skel.logger.push(point.clone());
+    logger.info(format!("listening for http on {}", addr));
+    let task = tokio::spawn(serve(listener, dispatcher, logger));
+    listeners.insert(point.clone(), WebListener { addr, task });
+    Ok(())
+}
+
+/// turns an http request into a directed wave and the reflection back into a response
+#[async_trait]
+pub trait HttpDispatcher: Send + Sync {
+    async fn dispatch(&self, request: HttpRequest) -> ReflectedCore;
+}
+
+pub struct WaveDispatcher {
+    point: Point,
+    transmitter: ProtoTransmitter,
+}
+
+impl WaveDispatcher {
+    pub fn new(point: Point, transmitter: ProtoTransmitter) -> Self {
+        Self { point, transmitter }
+    }
+}
+
+#[async_trait]
+impl HttpDispatcher for WaveDispatcher {
+    async fn dispatch(&self, request: HttpRequest) -> ReflectedCore {
+        let core: DirectedCore = request.into();
+        let mut proto = DirectedProto::ping();
+        if let Err(err) = proto.core(core) {
+            return err.as_reflected_core();
+        }
+        proto.to(self.point.to_surface());
+        let pong: Result<WaveVariantDef<PongCore>, SpaceErr> = self.transmitter.direct(proto).await;
+        match pong {
+            Ok(pong) => pong.variant.core,
+            Err(err) => err.as_reflected_core(),
+        }
+    }
+}
+
+pub async fn serve(listener: TcpListener, dispatcher: Arc<dyn HttpDispatcher>, logger: Logger) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                let dispatcher = dispatcher.clone();
+                let logger = logger.clone();
+                tokio::spawn(async move {
+                    let (read, mut write) = stream.into_split();
+                    let mut read = BufReader::new(read);
+                    if let Err(err) = session(&mut read, &mut write, &*dispatcher).await {
+                        logger.warn(format!("http connection failed: {}", err.to_string()));
+                    }
+                });
+            }
+            Err(err) => {
+                logger.error(format!("http accept failed: {}", err.to_string()));
+                continue;
+            }
+        }
+    }
+}
+
+/// serve requests off one connection until the client hangs up or asks to close
+async fn session<R, W>(
+    read: &mut R,
+    write: &mut W,
+    dispatcher: &dyn HttpDispatcher,
+) -> Result<(), SpaceErr>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    loop {
+        let request = match tokio::time::timeout(READ_TIMEOUT, read_request(read))
+            .await
+            .unwrap_or_else(|_| {
+                Err(SpaceErr::new(
+                    408,
+                    format!("no request within {}s", READ_TIMEOUT.as_secs()),
+                ))
+            }) {
+            Ok(Some(request)) => request,
+            Ok(None) => return Ok(()),
+            Err(err) => {
+                write_response(write, &err.as_reflected_core(), true).await?;
+                // hang up our side and drain what the client already sent, closing
+                // with unread input would reset the connection under the response
+                write.shutdown().await?;
+                let mut rest = read.take(MAX_BODY as u64);
+                let mut sink = tokio::io::sink();
+                let drain = tokio::io::copy(&mut rest, &mut sink);
+                tokio::time::timeout(LINGER, drain).await.ok();
+                return Ok(());
+            }
+        };
+        let close = request
+            .headers
+            .get("connection")
+            .map(|value| value.eq_ignore_ascii_case("close"))
+            .unwrap_or(false);
+        let reflected = dispatcher.dispatch(request).await;
+        write_response(write, &reflected, close).await?;
+        if close {
+            return Ok(());
+        }
+    }
+}
+
+/// read one HTTP/1.1 request. header names are lower cased and only bodies with a
+/// `content-length` are supported, any `transfer-encoding` is rejected rather than
+/// risk disagreeing with a proxy about where the request ends
+async fn read_request<R>(read: &mut R) -> Result<Option<HttpRequest>, SpaceErr>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut line = String::new();
+    if read_line(read, &mut line, 414).await? == 0 {
+        return Ok(None);
+    }
+    let mut parts = line.split_whitespace();
+    let (method, target) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(method), Some(target), Some(version)) if version.starts_with("HTTP/1.") => {
+            (method.to_string(), target.to_string())
+        }
+        _ => {
+            return Err(SpaceErr::bad_request(format!(
+                "malformed request line: '{}'",
+                line.trim_end()
+            )))
+        }
+    };
+
+    let mut headers = HeaderMap::new();
+    let mut count = 0usize;
+    loop {
+        if read_line(read, &mut line, 431).await? == 0 {
+            return Err(SpaceErr::bad_request("connection closed inside headers"));
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        count += 1;
+        if count > MAX_HEADERS {
+            return Err(SpaceErr::new(
+                431,
+                format!("more than {} headers", MAX_HEADERS),
+            ));
+        }
+        let (name, value) = header.split_once(':').ok_or(SpaceErr::bad_request(format!(
+            "malformed header: '{}'",
+            header
+        )))?;
+        headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+    }
+
+    let method = http_method(method.as_str())?;
+    let host = headers
+        .get("host")
+        .cloned()
+        .unwrap_or("localhost".to_string());
+    let uri = Url::parse(format!("http://{}{}", host, target).as_str())
+        .map_err(|err| SpaceErr::bad_request(err.to_string()))?;
+
+    if let Some(encoding) = headers.get("transfer-encoding") {
+        if headers.get("content-length").is_some() {
+            return Err(SpaceErr::bad_request(
+                "request has both content-length and transfer-encoding",
+            ));
+        }
+        return Err(SpaceErr::new(
+            501,
+            format!("transfer-encoding '{}' is not supported", encoding),
+        ));
+    }
+    let length = match headers.get("content-length") {
+        None => 0usize,
+        Some(length) => usize::from_str(length)
+            .map_err(|_| SpaceErr::bad_request(format!("invalid content-length: '{}'", length)))?,
+    };
+    if length > MAX_BODY {
+        return Err(SpaceErr::new(
+            413,
+            format!("content-length {} exceeds {} bytes", length, MAX_BODY),
+        ));
+    }
+    let mut body = vec![0u8; length];
+    read.read_exact(body.as_mut_slice()).await?;
+    let body = to_substance(headers.get("content-type"), body);
+
+    Ok(Some(HttpRequest {
+        method,
+        headers,
+        uri,
+        body,
+    }))
+}
+
+/// read one line of at most [MAX_LINE] bytes into `line`, failing with `status`
+/// when the client sends more
+async fn read_line<R>(read: &mut R, line: &mut String, status: u16) -> Result<usize, SpaceErr>
+where
+    R: AsyncBufRead + Unpin,
+{
+    line.clear();
+    let len = (&mut *read)
+        .take(MAX_LINE as u64 + 1)
+        .read_line(line)
+        .await?;
+    if len > MAX_LINE {
+        return Err(SpaceErr::new(
+            status,
+            format!("line exceeds {} bytes", MAX_LINE),
+        ));
+    }
+    Ok(len)
+}
+
+/// `GET` to `HttpMethod::Get`
+fn http_method(method: &str) -> Result<HttpMethod, SpaceErr> {
+    let mut chars = method.chars();
+    let camel = match chars.next() {
+        Some(first) => format!(
+            "{}{}",
+            first.to_ascii_uppercase(),
+            chars.as_str().to_lowercase()
+        ),
+        None => String::new(),
+    };
+    HttpMethod::from_str(camel.as_str())
+        .map_err(|_| SpaceErr::new(501, format!("unsupported http method: '{}'", method)))
+}
+
+fn to_substance(content_type: Option<&String>, body: Vec<u8>) -> Substance {
+    if body.is_empty() {
+        return Substance::Empty;
+    }
+    let content_type = content_type.map(|c| c.as_str()).unwrap_or_default();
+    if content_type.starts_with("application/json") {
+        if let Ok(json) = serde_json::from_slice(body.as_slice()) {
+            return Substance::Json(json);
+        }
+    }
+    if content_type.starts_with("text/") {
+        match String::from_utf8(body) {
+            Ok(text) => Substance::Text(text),
+            Err(err) => Substance::Bin(err.into_bytes()),
+        }
+    } else {
+        Substance::Bin(body)
+    }
+}
+
+fn from_substance(body: &Substance) -> Result<Option<(&'static str, Vec<u8>)>, SpaceErr> {
+    Ok(match body {
+        Substance::Empty => None,
+        Substance::Text(text) => Some(("text/plain; charset=utf-8", text.clone().into_bytes())),
+        Substance::Bin(bin) => Some(("application/octet-stream", bin.clone())),
+        Substance::Json(json) => Some((
+            "application/json",
+            serde_json::to_vec(json).map_err(|err| SpaceErr::server_error(err.to_string()))?,
+        )),
+        other => Some((
+            "application/json",
+            serde_json::to_vec(other).map_err(|err| SpaceErr::server_error(err.to_string()))?,
+        )),
+    })
+}
+
+async fn write_response<W>(
+    write: &mut W,
+    reflected: &ReflectedCore,
+    close: bool,
+) -> Result<(), SpaceErr>
+where
+    W: AsyncWrite + Unpin,
+{
+    let status = reflected.status.as_u16();
+    let mut head = format!("HTTP/1.1 {} {}\r\n", status, reason(status));
+    for (name, value) in &reflected.headers {
+        head.push_str(format!("{}: {}\r\n", name, value).as_str());
+    }
+    let body = match from_substance(&reflected.body)? {
+        None => vec![],
+        Some((content_type, body)) => {
+            head.push_str(format!("content-type: {}\r\n", content_type).as_str());
+            body
+        }
+    };
+    head.push_str(format!("content-length: {}\r\n", body.len()).as_str());
+    if close {
+        head.push_str("connection: close\r\n");
+    }
+    head.push_str("\r\n");
+
+    write.write_all(head.as_bytes()).await?;
+    write.write_all(body.as_slice()).await?;
+    write.flush().await?;
+    Ok(())
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        408 => "Request Timeout",
+        413 => "Payload Too Large",
+        414 => "URI Too Long",
+        431 => "Request Header Fields Too Large",
+        500 => "Internal Server Error",
+        501 => "Not Implemented",
+        503 => "Service Unavailable",
+        _ => "",
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::hyperspace::driver::web::{serve, HttpDispatcher, WaveDispatcher, MAX_BODY};
+    #[cfg(feature = "postgres")]
+    use crate::hyperspace::harness::{self, config, kind, TestMachine, TestRegistry};
+    #[cfg(feature = "postgres")]
+    use crate::hyperspace::test_util::temp_dir;
+    #[cfg(feature = "postgres")]
+    use crate::space::kind::BaseKind;
+    use crate::space::loc::ToSurface;
+    #[cfg(feature = "postgres")]
+    use crate::space::loc::{StarHandle, StarKey};
+    use crate::space::log::Logger;
+    use crate::space::point::Point;
+    use crate::space::settings::Timeouts;
+    use crate::space::substance::Substance;
+    use crate::space::wave::core::http2::{HttpMethod, HttpRequest};
+    use crate::space::wave::core::ReflectedCore;
+    use crate::space::wave::exchange::asynch::{Exchanger, ProtoTransmitterBuilder, TxRouter};
+    use crate::space::wave::exchange::SetStrategy;
+    use crate::space::wave::Agent;
+    use std::str::FromStr;
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio::sync::mpsc;
+
+    async fn request(addr: std::net::SocketAddr, request: &[u8]) -> String {
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        client.write_all(request).await.unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    /// answers `/echo` with the request body and everything else with a 404
+    pub struct EchoDispatcher;
+
+    #[async_trait]
+    impl HttpDispatcher for EchoDispatcher {
+        async fn dispatch(&self, request: HttpRequest) -> ReflectedCore {
+            match (&request.method, request.uri.path()) {
+                (HttpMethod::Post, "/echo") => ReflectedCore::ok_body(request.body),
+                (HttpMethod::Get, "/echo") => {
+                    ReflectedCore::ok_body(Substance::Text("echo".to_string()))
+                }
+                _ => ReflectedCore::not_found(),
+            }
+        }
+    }
+
+    #[tokio::test]
+    pub async fn test_web_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener, Arc::new(EchoDispatcher), Logger::default()));
+
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        client
+            .write_all(b"GET /echo HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        client
+            .write_all(
+                b"POST /echo HTTP/1.1\r\nHost: localhost\r\ncontent-type: text/plain\r\ncontent-length: 5\r\nConnection: close\r\n\r\nhello",
+            )
+            .await
+            .unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).await.unwrap();
+
+        let mut responses = response.split("HTTP/1.1 ").skip(1);
+        let first = responses.next().unwrap();
+        assert!(first.starts_with("200 OK"));
+        assert!(first.ends_with("\r\n\r\necho"));
+        let second = responses.next().unwrap();
+        assert!(second.starts_with("200 OK"));
+        assert!(second.contains("connection: close"));
+        assert!(second.ends_with("\r\n\r\nhello"));
+
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        client
+            .write_all(b"DELETE /nowhere HTTP/1.1\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 404 Not Found"));
+
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        client
+            .write_all(b"BREW /pot HTTP/1.1\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 501 Not Implemented"));
+    }
+
+    #[tokio::test]
+    pub async fn test_web_server_limits() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener, Arc::new(EchoDispatcher), Logger::default()));
+
+        let post = format!(
+            "POST /echo HTTP/1.1\r\ncontent-length: {}\r\n\r\n",
+            MAX_BODY + 1
+        );
+        let response = request(addr, post.as_bytes()).await;
+        assert!(response.starts_with("HTTP/1.1 413 Payload Too Large"));
+
+        let get = format!(
+            "GET /echo HTTP/1.1\r\nx-big: {}\r\n\r\n",
+            "a".repeat(10_000)
+        );
+        let response = request(addr, get.as_bytes()).await;
+        assert!(response.starts_with("HTTP/1.1 431 Request Header Fields Too Large"));
+
+        let get = format!("GET /echo HTTP/1.1\r\n{}\r\n", "x-many: a\r\n".repeat(101));
+        let response = request(addr, get.as_bytes()).await;
+        assert!(response.starts_with("HTTP/1.1 431 Request Header Fields Too Large"));
+
+        let get = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(10_000));
+        let response = request(addr, get.as_bytes()).await;
+        assert!(response.starts_with("HTTP/1.1 414 URI Too Long"));
+
+        // a chunked body must not be taken for an empty one
+        let post =
+            "POST /echo HTTP/1.1\r\ntransfer-encoding: chunked\r\n\r\n5\r\nhello\r\n0\r\n\r\n";
+        let response = request(addr, post.as_bytes()).await;
+        assert!(response.starts_with("HTTP/1.1 501 Not Implemented"));
+
+        let post =
+            "POST /echo HTTP/1.1\r\ncontent-length: 5\r\ntransfer-encoding: chunked\r\n\r\nhello";
+        let response = request(addr, post.as_bytes()).await;
+        assert!(response.starts_with("HTTP/1.1 400 Bad Request"));
+    }
+
+    /// requests become waves directed at the WebServer's point and the reflection
+    /// becomes the response
+    #[tokio::test]
+    pub async fn test_wave_dispatcher() {
+        let server = Point::from_str("localhost:web").unwrap();
+        let client = Point::from_str("localhost:web:client").unwrap();
+        let (tx, mut rx) = mpsc::channel(32);
+        let exchanger = Exchanger::new(client.to_surface(), Timeouts::default(), Logger::default());
+
+        // stands in for the bound particle: answers with the path it was sent
+        {
+            let server = server.clone();
+            let exchanger = exchanger.clone();
+            tokio::spawn(async move {
+                while let Some(wave) = rx.recv().await {
+                    let directed = wave.to_directed().unwrap();
+                    assert_eq!(server.to_surface(), directed.to().single_or().unwrap());
+                    let path = directed.core().uri.path().to_string();
+                    let reflection = directed.reflection().unwrap();
+                    let reflected = reflection.make(
+                        ReflectedCore::ok_body(Substance::Text(path)),
+                        server.to_surface(),
+                    );
+                    exchanger.reflected(reflected).await.unwrap();
+                }
+            });
+        }
+
+        let mut transmitter = ProtoTransmitterBuilder::new(Arc::new(TxRouter::new(tx)), exchanger);
+        transmitter.from = SetStrategy::Override(client.to_surface());
+        transmitter.agent = SetStrategy::Override(Agent::Anonymous);
+        let dispatcher = WaveDispatcher::new(server, transmitter.build());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener, Arc::new(dispatcher), Logger::default()));
+
+        let response = request(
+            addr,
+            b"GET /index.html HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with("\r\n\r\n/index.html"));
+    }
+
+    /// a request to the WebServer's port is routed by its bind config to the target
+    /// point, here an Artifact that answers `Http<Get>` with its content
+    #[cfg(feature = "postgres")]
+    #[tokio::test]
+    #[ignore = "needs the postgres server named by STARLANE_TEST_POSTGRES"]
+    pub async fn test_bind_routes_request() {
+        let registry = TestRegistry::new().await;
+        let dir = temp_dir("web");
+        let machine = TestMachine::new(config(&registry, &dir).await).await;
+        let star = |name: &str| StarKey::new(&"central".to_string(), &StarHandle::name(name));
+
+        let scribe = machine.star(&star("scribe")).await;
+        let bundle = Point::from_str("web-repo:site:1.0.0").unwrap();
+        let bind = b"Bind(version=1.0.0) { Route<Http<Get>>/hello -> web-repo:site:1.0.0:/hello.txt => &; }";
+        machine
+            .publish(
+                &scribe,
+                &bundle,
+                &[("bind/site.bind", &bind[..]), ("hello.txt", &b"hello"[..])],
+            )
+            .await
+            .unwrap();
+
+        let jump = machine.star(&star("jump")).await;
+        let port = port_check::free_local_ipv4_port().unwrap();
+        machine
+            .create(
+                &jump,
+                &Point::from_str("site").unwrap(),
+                kind(BaseKind::WebServer),
+                harness::properties(&[
+                    ("bind", "web-repo:site:1.0.0:/bind/site.bind"),
+                    ("host", "127.0.0.1"),
+                    ("port", port.to_string().as_str()),
+                ]),
+            )
+            .await
+            .unwrap();
+
+        let addr = std::net::SocketAddr::from(([127, 0, 0, 1], port));
+        let response = request(
+            addr,
+            b"GET /hello HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+        assert!(response.ends_with("\r\n\r\nhello"));
+
+        machine.terminate();
+        registry.drop().await;
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,6 +1,8 @@
 pub mod os;
+pub mod wasm;
 
 use crate::hyperspace::executor::Executor;
+use crate::hyperspace::host::err::HostErr;
 use itertools::Itertools;
 use nom::AsBytes;
 use os::OsProcess;
@@ -13,6 +15,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use thiserror::Error;
 use tokio::io::AsyncWriteExt;
+use wasm::WasmProcess;
 
 pub type CliIn = CliInDef<Option<Vec<u8>>>;
 #[derive(Clone, Eq, PartialEq)]
@@ -122,6 +125,7 @@ impl CliIn {
 
 pub enum CliOut {
     Os(OsProcess),
+    Wasm(WasmProcess),
 }
 
 impl CliOut {
@@ -132,12 +136,15 @@ impl CliOut {
                 stdin.write_all(&input[..]).await?;
                 stdin.flush().await?;
             }
+            // a wasm guest has already consumed the stdin it was executed with
+            CliOut::Wasm(_) => Err(CliErr::TakeStdIn)?,
         }
         Ok(())
     }
     pub fn close_stdin(&mut self) -> Result<(), CliErr> {
         match self {
             CliOut::Os(proc) => proc.close_stdin()?,
+            CliOut::Wasm(_) => {}
         }
         Ok(())
     }
@@ -148,6 +155,10 @@ impl CliOut {
                 let mut stdout = proc.stdout.take().ok_or(CliErr::TakeStdOut)?;
                 tokio::io::copy(&mut stdout, out).await?;
             }
+            CliOut::Wasm(proc) => {
+                let stdout = proc.stdout.take().ok_or(CliErr::TakeStdOut)?;
+                out.extend(stdout);
+            }
         }
         Ok(())
     }
@@ -162,6 +173,39 @@ impl CliOut {
                 tokio::io::copy(&mut stdout, &mut out).await?;
                 Ok(out)
             }
+            CliOut::Wasm(proc) => Ok(proc.stdout.take().ok_or(CliErr::TakeStdOut)?),
+        }
+    }
+
+    /// wait for the process to exit.  A non zero exit status is returned as
+    /// [CliErr::Exit] along with whatever the process wrote to stderr
+    pub async fn wait(&mut self) -> Result<(), CliErr> {
+        match self {
+            CliOut::Os(proc) => {
+                let mut err = vec![];
+                if let Some(mut stderr) = proc.stderr.take() {
+                    tokio::io::copy(&mut stderr, &mut err).await?;
+                }
+                let status = proc.wait().await?;
+                if status.success() {
+                    Ok(())
+                } else {
+                    Err(CliErr::Exit {
+                        code: status.code(),
+                        stderr: String::from_utf8_lossy(&err).trim().to_string(),
+                    })
+                }
+            }
+            CliOut::Wasm(proc) => {
+                if proc.success() {
+                    Ok(())
+                } else {
+                    Err(CliErr::Exit {
+                        code: Some(proc.code),
+                        stderr: String::from_utf8_lossy(&proc.stderr).trim().to_string(),
+                    })
+                }
+            }
         }
     }
 }
@@ -183,6 +227,16 @@ pub enum CliErr {
     TokioIoErr(String),
     #[error("file not found: '{0}'")]
     FileNotFound(String),
+    #[error("process exited with status {code:?}: '{stderr}'")]
+    Exit { code: Option<i32>, stderr: String },
+    #[error("wasm host error: '{0}'")]
+    Wasm(String),
+}
+
+impl From<HostErr> for CliErr {
+    fn from(err: HostErr) -> Self {
+        Self::Wasm(err.to_string())
+    }
 }
 
 impl From<tokio::io::Error> for CliErr {
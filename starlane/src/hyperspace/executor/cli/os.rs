@@ -18,15 +18,10 @@ impl CliOsExecutor {
     pub fn new(stub: ExeStub) -> Self {
         Self { stub }
     }
-}
 
-#[async_trait]
-impl Executor for CliOsExecutor {
-    type In = CliIn;
-    type Out = CliOut;
-    type Err = CliErr;
-
-    async fn execute(&self, mut input: Self::In) -> Result<Self::Out, Self::Err> {
+    /// start the executable with piped stdio.  Unlike `execute` stdin is left
+    /// open so a long running process can keep being fed input
+    pub fn spawn(&self, args: &Vec<String>) -> Result<OsProcess, CliErr> {
         let path: PathBuf = self.stub.loc.clone().into();
         if !path.exists() {
             Result::Err(CliErr::FileNotFound(self.stub.loc.clone()))?;
@@ -35,19 +30,28 @@ impl Executor for CliOsExecutor {
         let mut command = Command::new(self.stub.loc.clone());
 
         command.envs(self.stub.env.env.clone());
-        command.args(&input.args);
+        command.args(args);
         command.current_dir(self.stub.env.pwd.clone());
         command.env_clear();
         command.envs(&self.stub.env.env);
-        //command.stdin(Stdio::piped()).output().await?;
-        command.stdin(Stdio::piped()).output().await?;
-        //command.stdout(Stdio::piped()).output().await?;
-        command.stdout(Stdio::piped()).output().await?;
-        //command.stderr(Stdio::piped()).output().await?;
-        command.stderr(Stdio::piped()).output().await?;
+        command.stdin(Stdio::piped());
+        command.stdout(Stdio::piped());
+        command.stderr(Stdio::piped());
+        // a long running process must not outlive whatever is holding it
+        command.kill_on_drop(true);
         let child = command.spawn()?;
-        //        Ok(OsProcess::new(child))
-        let mut process = OsProcess::new(child);
+        Ok(OsProcess::new(child))
+    }
+}
+
+#[async_trait]
+impl Executor for CliOsExecutor {
+    type In = CliIn;
+    type Out = CliOut;
+    type Err = CliErr;
+
+    async fn execute(&self, mut input: Self::In) -> Result<Self::Out, Self::Err> {
+        let mut process = self.spawn(&input.args)?;
 
         if let Option::Some(mut data) = input.stdin.take() {
             let mut stdin = process.stdin.take().ok_or(CliErr::TakeStdIn)?;
@@ -0,0 +1,204 @@
+use crate::hyperspace::executor::cli::{CliErr, CliIn, CliOut};
+use crate::hyperspace::executor::{ExeConf, Executor};
+use crate::hyperspace::host::err::HostErr;
+use crate::hyperspace::host::wasm::cache::WasmModuleMemCache;
+use crate::hyperspace::host::wasm::source::FileSystemSrc;
+use crate::hyperspace::host::wasm::{HostFs, WasmHost, WasmHostConfig, WasmOut, WasmService};
+use crate::hyperspace::host::{Host, HostCli, WasmStub};
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// modules are compiled once per process no matter how many executors run them.
+/// `loc` is used as the key so it is read relative to the working directory
+static WASM: Lazy<Mutex<WasmService>> = Lazy::new(|| {
+    let source = Box::new(FileSystemSrc::new("."));
+    let cache = Box::new(WasmModuleMemCache::new(source));
+    Mutex::new(WasmService::new(cache))
+});
+
+/// runs a wasi module through wasmer-wasix as if it were a native executable.  The
+/// guest sees the stub's environment and, of the host's filesystem, only the
+/// directories its `preopen` variables name.  The guest starts in the first of them
+#[derive(Clone)]
+pub struct CliWasmExecutor {
+    pub stub: WasmStub,
+}
+
+impl CliWasmExecutor {
+    pub fn new(stub: WasmStub) -> Self {
+        Self { stub }
+    }
+
+    async fn host(&self) -> Result<WasmHost, HostErr> {
+        let mut config = WasmHostConfig::builder();
+        for (key, value) in self.stub.stub.env.env.iter() {
+            config.env(key, value);
+        }
+        let mut dirs = vec![];
+        for key in self.stub.preopen.iter() {
+            if let Some(dir) = self.stub.stub.env.env.get(key) {
+                // a directory must exist before it can be preopened
+                tokio::fs::create_dir_all(dir).await?;
+                let dir = tokio::fs::canonicalize(dir).await?;
+                // the guest is told the path it is able to see
+                config.env(key, dir.display());
+                dirs.push(dir);
+            }
+        }
+        if let Some(pwd) = dirs.first().cloned() {
+            config.fs(Arc::new(HostFs::new(dirs.clone())), |fs| {
+                for dir in dirs.iter() {
+                    fs.preopen(dir.display());
+                }
+                fs.pwd(pwd.display());
+            });
+        }
+        WASM.lock()
+            .await
+            .provision(self.stub.stub.loc.as_str(), config.build())
+            .await
+    }
+}
+
+#[async_trait]
+impl Executor for CliWasmExecutor {
+    type In = CliIn;
+    type Out = CliOut;
+    type Err = CliErr;
+
+    async fn execute(&self, mut input: Self::In) -> Result<Self::Out, Self::Err> {
+        let host = self.host().await?;
+        let stdin = input.stdin.take().unwrap_or_default();
+        let out = host.run_with_data(&input.args, stdin.as_slice()).await?;
+        Ok(CliOut::Wasm(WasmProcess::new(out)))
+    }
+
+    fn conf(&self) -> ExeConf {
+        ExeConf::Host(Host::Cli(HostCli::Wasm(self.stub.clone())))
+    }
+}
+
+/// a guest that has already run to completion.  Its stdin was the input it was
+/// executed with and its output is buffered
+pub struct WasmProcess {
+    pub stdout: Option<Vec<u8>>,
+    pub stderr: Vec<u8>,
+    pub code: i32,
+}
+
+impl WasmProcess {
+    pub fn new(out: WasmOut) -> Self {
+        Self {
+            stdout: Some(out.stdout),
+            stderr: out.stderr,
+            code: out.code,
+        }
+    }
+
+    pub fn success(&self) -> bool {
+        self.code == 0
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::hyperspace::executor::cli::wasm::CliWasmExecutor;
+    use crate::hyperspace::executor::cli::{CliIn, HostEnv};
+    use crate::hyperspace::executor::dialect::filestore::{
+        FileStore, FileStoreIn, FileStoreOut, FILE_STORE_ROOT,
+    };
+    use crate::hyperspace::executor::Executor;
+    use crate::hyperspace::host::{ExeStub, WasmStub};
+    use crate::hyperspace::service::FILESTORE_SERVICE_WASM;
+    use crate::hyperspace::test_util::temp_dir;
+
+    /// the filestore service built for `wasm32-wasip1` by `make filestore-wasm`
+    fn filestore_wasm() -> String {
+        let target = std::env::current_exe()
+            .unwrap()
+            .ancestors()
+            .nth(3)
+            .unwrap()
+            .to_path_buf();
+        let wasm = target
+            .join("wasm32-wasip1")
+            .join("release")
+            .join(FILESTORE_SERVICE_WASM);
+        if !wasm.exists() {
+            panic!(
+                "'{}' not found: run `make filestore-wasm` to build the filestore service for wasm",
+                wasm.display()
+            );
+        }
+        wasm.display().to_string()
+    }
+
+    #[tokio::test]
+    pub async fn test_wasm_executor() {
+        let stub = ExeStub::new("fixtures/wasm/echo.wasm".to_string(), HostEnv::default());
+        let executor = CliWasmExecutor::new(WasmStub::new(stub, vec![]));
+        let mut out = executor
+            .execute(CliIn::stdin(vec!["echo"], "hello".as_bytes().to_vec()))
+            .await
+            .unwrap();
+        out.close_stdin().unwrap();
+        assert_eq!("hello".as_bytes(), out.stdout().await.unwrap().as_slice());
+        out.wait().await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "needs the filestore service built for wasm by `make filestore-wasm`"]
+    pub async fn test_wasm_filestore() {
+        let wasm = filestore_wasm();
+        let root = temp_dir("wasm-filestore");
+        let mut env = HostEnv::builder();
+        env.env(FILE_STORE_ROOT, root.display());
+        let stub = ExeStub::new(wasm, env.build());
+        let executor = CliWasmExecutor::new(WasmStub::new(stub, vec![FILE_STORE_ROOT.to_string()]));
+        let filestore = FileStore::try_from(executor).unwrap();
+
+        filestore.execute(FileStoreIn::Init).await.unwrap();
+        filestore
+            .execute(FileStoreIn::Mkdir { path: "dir".into() })
+            .await
+            .unwrap();
+        filestore
+            .execute(FileStoreIn::Write {
+                path: "dir/hello.txt".into(),
+                state: "hello".as_bytes().to_vec(),
+            })
+            .await
+            .unwrap();
+
+        // the guest wrote within the preopened root on the host
+        let root = root.canonicalize().unwrap();
+        assert_eq!(
+            "hello",
+            std::fs::read_to_string(root.join("dir").join("hello.txt")).unwrap()
+        );
+
+        let FileStoreOut::Read(bin) = filestore
+            .execute(FileStoreIn::Read {
+                path: "dir/hello.txt".into(),
+            })
+            .await
+            .unwrap()
+        else {
+            panic!("expected FileStoreOut::Read");
+        };
+        assert_eq!("hello".as_bytes(), &bin[..]);
+
+        let FileStoreOut::List(paths) = filestore
+            .execute(FileStoreIn::List { path: "dir".into() })
+            .await
+            .unwrap()
+        else {
+            panic!("expected FileStoreOut::List");
+        };
+        assert_eq!(vec![root.join("dir").join("hello.txt")], paths);
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
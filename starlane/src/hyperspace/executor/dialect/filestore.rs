@@ -1,19 +1,16 @@
 use crate::hyperspace::executor::cli::os::CliOsExecutor;
+use crate::hyperspace::executor::cli::wasm::CliWasmExecutor;
 use crate::hyperspace::executor::cli::{CliErr, CliExecutor, CliIn, CliOut};
 use crate::hyperspace::executor::Executor;
 use crate::hyperspace::host::err::HostErr;
-use clap::{Parser, Subcommand};
 use itertools::Itertools;
-use path_clean::PathClean;
 use crate::space::loc::ToPoint;
 use crate::space::path::Path;
 use crate::space::point::Point;
 use crate::space::substance::Bin;
 use std::io::{BufRead, Error};
 use std::path::{PathBuf, StripPrefixError};
-use std::str::FromStr;
 use std::sync::Arc;
-use strum_macros::EnumString;
 use thiserror::Error;
 /*
 impl <E> From<Box<E>> for FileStore
@@ -24,7 +21,7 @@ where E: Executor<In=CliIn,Out=CliOut> {
 }
  */
 
-pub const FILE_STORE_ROOT: &'static str = "FILE_STORE_ROOT";
+pub use starlane_filestore_protocol::*;
 
 #[derive(Clone)]
 pub struct FileStoreApi {
@@ -116,7 +113,14 @@ impl FileStore {
 
                         FileStoreOut::List(paths)
                     }
-                    FileStoreInKind::Exists { .. } => FileStoreOut::Exists(true),
+                    FileStoreInKind::Exists { .. } => {
+                        // exists reports its answer through the exit status
+                        return match out.wait().await {
+                            Ok(()) => Ok(FileStoreOut::Exists(true)),
+                            Err(CliErr::Exit { .. }) => Ok(FileStoreOut::Exists(false)),
+                            Err(err) => Err(err.into()),
+                        };
+                    }
                     FileStoreInKind::Pwd => {
                         let stdout = out.stdout().await?;
                         let line = stdout
@@ -128,7 +132,8 @@ impl FileStore {
                         FileStoreOut::Pwd(line)
                     }
                 };
-                out.close_stdin();
+                out.close_stdin()?;
+                out.wait().await?;
                 Ok(rtn)
             }
         }
@@ -201,64 +206,6 @@ pub enum FileStoreOut {
     Pwd(PathBuf),
 }
 
-#[derive(Clone, Debug, Parser)]
-#[command(version, about, long_about = None)]
-pub struct FileStoreCli {
-    #[command(subcommand)]
-    pub command: FileStoreCommand,
-}
-
-#[derive(Clone, Debug, Subcommand, EnumString, strum_macros::Display)]
-pub enum FileStoreCommand {
-    Init,
-    Write { path: PathBuf },
-    Read { path: PathBuf },
-    Mkdir { path: PathBuf },
-    Remove { path: PathBuf },
-    List { path: PathBuf },
-    Exists { path: PathBuf },
-    Pwd,
-}
-
-impl FileStoreCli {
-    pub fn new(command: FileStoreCommand) -> Self {
-        FileStoreCli { command }
-    }
-}
-
-impl Into<Vec<String>> for FileStoreCli {
-    fn into(self) -> Vec<String> {
-        match &self.command {
-            FileStoreCommand::Init => vec!["init".to_string()],
-            FileStoreCommand::Write { path } => {
-                vec!["write".to_string(), to_str(path)]
-            }
-            FileStoreCommand::Read { path } => {
-                vec!["read".to_string(), to_str(path)]
-            }
-            FileStoreCommand::Mkdir { path } => {
-                vec!["mkdir".to_string(), to_str(path)]
-            }
-            FileStoreCommand::Remove { path } => {
-                vec!["remove".to_string(), to_str(path)]
-            }
-            FileStoreCommand::List { path } => {
-                vec!["list".to_string(), to_str(path)]
-            }
-            FileStoreCommand::Exists { path } => {
-                vec!["exists".to_string(), to_str(path)]
-            }
-            FileStoreCommand::Pwd => {
-                vec!["pwd".to_string()]
-            }
-        }
-    }
-}
-
-pub fn to_str(path: &PathBuf) -> String {
-    path.to_str().unwrap().to_string()
-}
-
 pub fn stringify(vec: Vec<&'static str>) -> Vec<String> {
     let mut rtn = vec![];
     for v in vec {
@@ -267,12 +214,6 @@ pub fn stringify(vec: Vec<&'static str>) -> Vec<String> {
     rtn
 }
 
-impl ToString for FileStoreCli {
-    fn to_string(&self) -> String {
-        self.command.to_string()
-    }
-}
-
 impl TryFrom<CliOsExecutor> for FileStore {
     type Error = HostErr;
 
@@ -281,36 +222,11 @@ impl TryFrom<CliOsExecutor> for FileStore {
     }
 }
 
-pub struct RootDir {
-    root: PathBuf,
-}
-
-impl RootDir {
-    pub fn new(root: PathBuf) -> Self {
-        let root = root.clean();
-        Self { root }
-    }
-}
-
-impl RootDir {
-    pub fn norm(&self, sub_path: &PathBuf) -> Result<PathBuf, FileStoreErr> {
-        let sub_path = sub_path.clean();
-
-        let path: PathBuf = match sub_path.starts_with("/") {
-            true => sub_path.strip_prefix("/")?.into(),
-            false => sub_path.clone(),
-        };
-        let normed: PathBuf = self.root.join(path).clean();
-        let parent = match normed.parent() {
-            None => PathBuf::from_str("/")?,
-            Some(parent) => parent.clone().into(),
-        };
-
-        if !parent.starts_with(&self.root) {
-            return Err(FileStoreErr::PathEscapesFileStoreBoundary(sub_path))?;
-        }
+impl TryFrom<CliWasmExecutor> for FileStore {
+    type Error = HostErr;
 
-        Ok(normed)
+    fn try_from(cli: CliWasmExecutor) -> Result<Self, Self::Error> {
+        Ok(FileStore::Cli(Box::new(cli)))
     }
 }
 
@@ -318,8 +234,8 @@ impl RootDir {
 pub enum FileStoreErr {
     #[error("HostErr: '{0}'")]
     HostErr(#[from] HostErr),
-    #[error("path '{0}' escapes FileStore boundaries")]
-    PathEscapesFileStoreBoundary(PathBuf),
+    #[error(transparent)]
+    RootDirErr(#[from] RootDirErr),
     #[error("could not strip prefix of path '{0}'")]
     StripPrefixError(#[from] StripPrefixError),
     #[error("expected environment variable to be set: {0}")]
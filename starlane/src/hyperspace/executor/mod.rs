@@ -2,6 +2,7 @@ pub mod cli;
 pub mod dialect;
 
 use crate::hyperspace::executor::cli::os::CliOsExecutor;
+use crate::hyperspace::executor::cli::wasm::CliWasmExecutor;
 use crate::hyperspace::host::err::HostErr;
 use crate::hyperspace::host::Host;
 use crate::hyperspace::service::ServiceErr;
@@ -43,7 +44,7 @@ impl ExeConf {
 
     pub fn create<D>(&self) -> Result<D, HostErr>
     where
-        D: TryFrom<CliOsExecutor, Error = HostErr>,
+        D: TryFrom<CliOsExecutor, Error = HostErr> + TryFrom<CliWasmExecutor, Error = HostErr>,
     {
         match self {
             ExeConf::Host(host) => Ok(host.create::<D>()?.try_into()?),
@@ -1,3 +1,5 @@
+use crate::hyperspace::driver::describe_history;
+use crate::hyperspace::driver::loader;
 use crate::hyperspace::reg::Registration;
 use crate::hyperspace::registry::err::RegErr;
 use crate::hyperspace::star::{HyperStarSkel, SmartLocator, StarErr};
@@ -6,28 +8,68 @@ use starlane_macros::{handler, route, DirectedHandler};
 use starlane_primitive_macros::push_mark;
 use crate::space::artifact::ArtRef;
 use crate::space::command::direct::create::{Create, PointSegTemplate};
+use crate::space::command::direct::delete::Delete;
 use crate::space::command::Command;
 use crate::space::command::RawCommand;
 use crate::space::config::bind::BindConfig;
 use crate::space::err::{CoreReflector, SpaceErr};
-use crate::space::loc::{ToPoint, ToSurface};
+use crate::space::hyper::Retract;
+use crate::space::loc::{StarKey, ToPoint, ToSurface};
 use crate::space::log::Logger;
 use crate::space::parse::util::new_span;
 use crate::space::parse::util::result;
 use crate::space::parse::{bind_config, command_line};
 use crate::space::particle::{Details, Status};
 use crate::space::point::Point;
-use crate::space::substance::Substance;
+use crate::space::substance::{Substance, SubstanceList};
 use crate::space::util::{log, ToResolved};
 use crate::space::wave::core::cmd::CmdMethod;
 use crate::space::wave::core::http2::StatusCode;
-use crate::space::wave::core::ReflectedCore;
-use crate::space::wave::exchange::asynch::{DirectedHandler, InCtx};
+use crate::space::wave::core::{DirectedCore, ReflectedCore};
+use crate::space::wave::exchange::asynch::{DirectedHandler, InCtx, ProtoTransmitter};
 use crate::space::wave::{Agent, DirectedProto};
+use futures::future::join_all;
+use std::collections::BTreeMap;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 use thiserror_context::impl_context;
+
+/// how long a delete waits for the hosting driver to acknowledge its `Hyp<Retract>`
+pub const RETRACT_TIMEOUT: Duration = Duration::from_secs(30);
+
+fn unacknowledged(point: &Point) -> SpaceErr {
+    SpaceErr::new(
+        504,
+        format!(
+            "driver did not acknowledge deletion of '{}' within {}s",
+            point.to_string(),
+            RETRACT_TIMEOUT.as_secs()
+        ),
+    )
+}
+
+/// why a parent is kept when one of the particles beneath it could not be deleted
+fn undeleted_child() -> SpaceErr {
+    SpaceErr::server_error("a particle beneath it could not be deleted")
+}
+
+/// the points of `list` grouped by depth, deepest first, so a parent (such as a
+/// FileStore) outlives its children while they are deleted
+fn deepest_first(list: &SubstanceList) -> Vec<Vec<Point>> {
+    let mut depths: BTreeMap<usize, Vec<Point>> = BTreeMap::new();
+    for point in list.iter() {
+        if let Substance::Point(point) = &**point {
+            depths
+                .entry(point.segments.len())
+                .or_default()
+                .push(point.clone());
+        }
+    }
+    depths.into_values().rev().collect()
+}
+
 /*
 #[derive(DirectedHandler,Clone)]
 pub struct Global where P: Platform {
@@ -46,6 +88,65 @@ impl GlobalCommandExecutionHandler {
     pub fn new(skel: HyperStarSkel) -> Self {
         Self { skel }
     }
+
+    /// ask the driver hosting `point` to clean up and wait for it to acknowledge
+    async fn retract(&self, transmitter: &ProtoTransmitter, point: &Point) -> Result<(), StarErr> {
+        let record = self.skel.registry.record(point).await?;
+        if let Some(star) = record.location.star {
+            let retract: DirectedCore = Retract::new(record.details).into();
+            let mut proto = DirectedProto::ping();
+            proto.core(retract);
+            proto.agent(Agent::HyperUser);
+            proto.to(star.to_surface());
+            let ping = transmitter.ping(proto);
+            let pong = tokio::time::timeout(RETRACT_TIMEOUT, ping)
+                .await
+                .map_err(|_| unacknowledged(point))??;
+            pong.ok_or()?;
+        }
+        Ok(())
+    }
+
+    /// retract `point` then remove its registry row.  The row stays until the
+    /// hosting driver has cleaned up
+    async fn delete(&self, transmitter: &ProtoTransmitter, point: &Point) -> Result<(), StarErr> {
+        self.retract(transmitter, point).await?;
+        let delete = Delete {
+            selector: point.clone().into(),
+        };
+        self.skel.registry.delete(&delete).await?;
+        Ok(())
+    }
+
+    /// the `drivers` command's lines for the drivers on `star`
+    async fn drivers(
+        &self,
+        transmitter: &ProtoTransmitter,
+        star: &StarKey,
+    ) -> Result<Vec<String>, SpaceErr> {
+        if *star == self.skel.key {
+            return Ok(describe_history(self.skel.drivers.history().await?));
+        }
+        let mut proto = DirectedProto::ping();
+        proto.core(Command::Drivers.into());
+        proto.agent(Agent::HyperUser);
+        proto.to(star.to_surface());
+        let pong = transmitter.ping(proto).await?;
+        pong.ok_or()?;
+        match pong.variant.core.body {
+            Substance::List(list) => Ok(list
+                .list
+                .into_iter()
+                .filter_map(|line| match *line {
+                    Substance::Text(line) => Some(line),
+                    _ => None,
+                })
+                .collect()),
+            _ => Err(SpaceErr::server_error(
+                "expected the star's drivers as a List",
+            )),
+        }
+    }
 }
 
 #[handler]
@@ -78,10 +179,54 @@ impl GlobalCommandExecutionHandler {
                 Ok(ReflectedCore::ok_body(substance))
             }
             Command::Delete(delete) => {
-                let substance: Substance = self.skel.registry.delete(delete).await?.into();
-                Ok(ReflectedCore::ok_body(substance))
+                let mut select = delete.clone().into();
+                let list = self.skel.registry.select(&mut select).await?;
+                let mut deleted = SubstanceList::new();
+                let mut failed: Vec<Point> = vec![];
+                let mut errs = vec![];
+                let transmitter = &ctx.transmitter;
+                // the points of one depth are retracted together
+                for points in deepest_first(&list) {
+                    let kept = &failed;
+                    let results = join_all(points.into_iter().map(|point| async move {
+                        let result = if kept.iter().any(|child| point.is_parent_of(child)) {
+                            Err(undeleted_child().into())
+                        } else {
+                            self.delete(transmitter, &point).await
+                        };
+                        (point, result)
+                    }))
+                    .await;
+                    for (point, result) in results {
+                        match result {
+                            Ok(_) => deleted.push(Box::new(Substance::Point(point))),
+                            Err(err) => {
+                                errs.push(format!("'{}': {}", point.to_string(), err));
+                                failed.push(point);
+                            }
+                        }
+                    }
+                }
+                if !errs.is_empty() {
+                    Err(SpaceErr::server_error(format!(
+                        "deleted {} of {} particles; failed {}",
+                        deleted.len(),
+                        list.len(),
+                        errs.join(", ")
+                    )))?;
+                }
+                Ok(ReflectedCore::ok_body(Substance::List(deleted)))
             }
             Command::Set(set) => {
+                // changing `bin` swaps what the star runs, so it needs the same rights as creating
+                if set.properties.map.contains_key("bin") {
+                    let kind = self.skel.registry.record(&set.point).await?.details.stub.kind;
+                    if loader::runs_bin(&kind) {
+                        let agent = ctx.wave().agent().clone().to_point();
+                        let access = self.skel.registry.access(&agent, &set.point).await?;
+                        loader::authorize(&access, &agent, &kind, &set.point)?;
+                    }
+                }
                 self.skel
                     .registry
                     .set_properties(&set.point, &set.properties)
@@ -97,6 +242,30 @@ impl GlobalCommandExecutionHandler {
                 let pong = ctx.transmitter.ping(proto).await?;
                 Ok(pong.variant.core)
             }
+            Command::Drivers => {
+                // this star and every star it has wrangled
+                let mut stars = self.skel.wrangles.stars().await;
+                stars.push(self.skel.key.clone());
+                stars.sort();
+                stars.dedup();
+
+                let mut list = SubstanceList::new();
+                for star in stars {
+                    match self.drivers(&ctx.transmitter, &star).await {
+                        Ok(lines) => {
+                            for line in lines {
+                                list.push(Box::new(Substance::Text(line)));
+                            }
+                        }
+                        Err(err) => list.push(Box::new(Substance::Text(format!(
+                            "{}\tUnreachable({})",
+                            star.to_point().to_string(),
+                            err.to_string()
+                        )))),
+                    }
+                }
+                Ok(ReflectedCore::ok_body(Substance::List(list)))
+            }
             c => Err(SpaceErr::unimplemented(format!("command not recognized")))?,
         }
     }
@@ -120,6 +289,12 @@ impl GlobalExecutionChamber {
             .machine_api
             .select_kind(&create.template.kind)
             .await?;
+        if loader::runs_bin(&child_kind) {
+            let agent = agent.clone().to_point();
+            let parent = &create.template.point.parent;
+            let access = self.skel.registry.access(&agent, parent).await?;
+            loader::authorize(&access, &agent, &child_kind, parent)?;
+        }
         let point = match &create.template.point.child_segment_template {
             PointSegTemplate::Exact(child_segment) => {
                 let point = create.template.point.parent.push(child_segment.clone())?;
@@ -190,3 +365,33 @@ impl GlobalExecutionChamber {
         Ok(record.details)
     }
 }
+
+#[cfg(test)]
+pub mod test {
+    use crate::hyperspace::global::deepest_first;
+    use crate::space::point::Point;
+    use crate::space::substance::{Substance, SubstanceList};
+    use std::str::FromStr;
+
+    #[test]
+    pub fn test_deepest_first() {
+        let point = |point: &str| Point::from_str(point).unwrap();
+        let mut list = SubstanceList::new();
+        for p in [
+            "localhost:files",
+            "localhost:files:/dir/",
+            "localhost:other",
+            "localhost:files:/dir/file.txt",
+        ] {
+            list.push(Box::new(Substance::Point(point(p))));
+        }
+        assert_eq!(
+            vec![
+                vec![point("localhost:files:/dir/file.txt")],
+                vec![point("localhost:files:/dir/")],
+                vec![point("localhost:files"), point("localhost:other")],
+            ],
+            deepest_first(&list)
+        );
+    }
+}
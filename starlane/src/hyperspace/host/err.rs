@@ -1,6 +1,10 @@
 use std::convert::Infallible;
 use std::fmt::{Display, Formatter};
+use std::io;
 use thiserror::Error;
+use tokio::task::JoinError;
+use wasmer::{CompileError, DeserializeError, SerializeError};
+use wasmer_wasix::{WasiRuntimeError, WasiStateCreationError};
 
 #[derive(Clone, Debug, Error)]
 pub struct HostErr {
@@ -19,6 +23,54 @@ impl From<Infallible> for HostErr {
     }
 }
 
+impl From<String> for HostErr {
+    fn from(message: String) -> Self {
+        HostErr::new(message)
+    }
+}
+
+impl From<io::Error> for HostErr {
+    fn from(err: io::Error) -> Self {
+        HostErr::new(err.to_string())
+    }
+}
+
+impl From<JoinError> for HostErr {
+    fn from(err: JoinError) -> Self {
+        HostErr::new(err.to_string())
+    }
+}
+
+impl From<CompileError> for HostErr {
+    fn from(err: CompileError) -> Self {
+        HostErr::new(err.to_string())
+    }
+}
+
+impl From<SerializeError> for HostErr {
+    fn from(err: SerializeError) -> Self {
+        HostErr::new(err.to_string())
+    }
+}
+
+impl From<DeserializeError> for HostErr {
+    fn from(err: DeserializeError) -> Self {
+        HostErr::new(err.to_string())
+    }
+}
+
+impl From<WasiStateCreationError> for HostErr {
+    fn from(err: WasiStateCreationError) -> Self {
+        HostErr::new(err.to_string())
+    }
+}
+
+impl From<WasiRuntimeError> for HostErr {
+    fn from(err: WasiRuntimeError) -> Self {
+        HostErr::new(err.to_string())
+    }
+}
+
 /*
 impl ToString for Err {
     fn to_string(&self) -> String {
@@ -1,4 +1,5 @@
 use crate::hyperspace::executor::cli::os::CliOsExecutor;
+use crate::hyperspace::executor::cli::wasm::CliWasmExecutor;
 use crate::hyperspace::executor::cli::{CliIn, CliOut, HostEnv};
 use crate::hyperspace::executor::{ExeConf, Executor};
 use crate::hyperspace::host::err::HostErr;
@@ -12,8 +13,11 @@ use std::hash::{Hash, Hasher};
 use std::io::Read;
 use std::ops::{Deref, DerefMut};
 use tokio::io::AsyncWriteExt;
+use tokio::runtime::Handle;
+use wasmer_wasix::virtual_fs::FileSystem;
 
 pub mod err;
+pub mod wasm;
 
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub struct ExtKey<B>
@@ -61,6 +65,34 @@ impl ExeStub {
     }
 }
 
+/// supplies the virtual filesystem a wasm guest sees
+pub trait FileSystemFactory: Send + Sync {
+    fn create(&self, handle: Handle) -> Result<Box<dyn FileSystem + Send + Sync>, HostErr>;
+}
+
+/// a wasi module run in place of an executable.  `preopen` names the environment
+/// variables of the stub whose values are host directories the guest may access
+#[derive(Clone, Hash, Eq, PartialEq)]
+pub struct WasmStub {
+    pub stub: ExeStub,
+    pub preopen: Vec<String>,
+}
+
+impl WasmStub {
+    pub fn new(stub: ExeStub, preopen: Vec<String>) -> Self {
+        Self { stub, preopen }
+    }
+
+    /// the host directories named by `preopen` which are set in the environment
+    pub fn dirs(&self) -> Vec<String> {
+        self.preopen
+            .iter()
+            .filter_map(|key| self.stub.env.env.get(key))
+            .cloned()
+            .collect()
+    }
+}
+
 pub fn stringify_args(args: Vec<&str>) -> Vec<String> {
     args.iter().map(|arg| arg.to_string()).collect()
 }
@@ -78,7 +110,7 @@ impl Host {
     }
     pub fn create<D>(&self) -> Result<D, HostErr>
     where
-        D: TryFrom<CliOsExecutor, Error = HostErr>,
+        D: TryFrom<CliOsExecutor, Error = HostErr> + TryFrom<CliWasmExecutor, Error = HostErr>,
     {
         match self {
             Host::Cli(host) => host.create(),
@@ -95,6 +127,7 @@ impl Host {
 #[derive(Clone, Hash, Eq, PartialEq)]
 pub enum HostCli {
     Os(ExeStub),
+    Wasm(WasmStub),
 }
 
 impl HostCli {
@@ -102,15 +135,17 @@ impl HostCli {
         let key = key.to_string();
         match self {
             HostCli::Os(stub) => stub.env.env.get(&key),
+            HostCli::Wasm(wasm) => wasm.stub.env.env.get(&key),
         }
     }
 
     pub fn create<D>(&self) -> Result<D, HostErr>
     where
-        D: TryFrom<CliOsExecutor, Error = HostErr>,
+        D: TryFrom<CliOsExecutor, Error = HostErr> + TryFrom<CliWasmExecutor, Error = HostErr>,
     {
         match self {
             HostCli::Os(stub) => Ok(D::try_from(CliOsExecutor::new(stub.clone()))?),
+            HostCli::Wasm(wasm) => Ok(D::try_from(CliWasmExecutor::new(wasm.clone()))?),
         }
     }
 
@@ -121,6 +156,11 @@ impl HostCli {
                 stub.env.env.insert(key.to_string(), value.to_string());
                 HostCli::Os(stub)
             }
+            HostCli::Wasm(wasm) => {
+                let mut wasm = wasm.clone();
+                wasm.stub.env.env.insert(key.to_string(), value.to_string());
+                HostCli::Wasm(wasm)
+            }
         }
     }
 }
@@ -5,7 +5,7 @@ use std::path::{Path, PathBuf};
 use wasmer::{Module, Store};
 
 #[async_trait]
-pub trait WasmModuleCache {
+pub trait WasmModuleCache: Send + Sync {
     async fn get(&mut self, key: &str, store: &Store) -> Result<Module, err::HostErr>;
 }
 
@@ -1,19 +1,18 @@
-mod cache;
-mod source;
+pub mod cache;
+pub mod source;
 
 use crate::hyperspace::host::err::HostErr;
 use crate::hyperspace::host::wasm::cache::WasmModuleCache;
 use crate::hyperspace::host::FileSystemFactory;
-use crate::hyperspace::hyperspace::service::OsProcess;
-use std::io::Write;
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::runtime::Handle;
-use virtual_fs::Pipe;
-use wasmer::{Module, Store};
+use wasmer::{Engine, Module, Store};
 use wasmer_compiler_singlepass::Singlepass;
 use wasmer_wasix::runtime::task_manager::tokio::TokioTaskManager;
-use wasmer_wasix::{PluggableRuntime, WasiEnv};
+use wasmer_wasix::virtual_fs::{host_fs, FileSystem, FsError, Pipe, TmpFileSystem};
+use wasmer_wasix::{PluggableRuntime, WasiEnv, WasiRuntimeError};
 
 pub struct WasmService {
     store: Store,
@@ -36,37 +35,42 @@ impl WasmService {
     where
         S: ToString,
     {
-        let store = Store::new(Singlepass::default());
-        //let module = self.cache.get(wasm.to_string().as_str()).await?;
-
-        let module = self.cache.get("filestore.wasm", &store).await?;
-        //let wasm_bytes = fs::read("filestore.wasm").await?;
-        //let module = Module::new(& store,wasm_bytes).unwrap();
-
-        Result::Ok(WasmHost::new(module, host_config, store))
+        let module = self
+            .cache
+            .get(wasm.to_string().as_str(), &self.store)
+            .await?;
+        let engine = self.store.engine().clone();
+        Result::Ok(WasmHost::new(module, host_config, engine))
     }
 }
 
+/// what a guest wrote before it exited and the code it exited with
+pub struct WasmOut {
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    pub code: i32,
+}
+
 pub struct WasmHost {
-    store: Store,
+    engine: Engine,
     module: Module,
     config: WasmHostConfig,
     runtime: Arc<PluggableRuntime>,
 }
 
 impl WasmHost {
-    fn new(module: Module, config: WasmHostConfig, store: Store) -> Self {
+    fn new(module: Module, config: WasmHostConfig, engine: Engine) -> Self {
         let runtime = Arc::new(PluggableRuntime::new(Arc::new(TokioTaskManager::new(
             Handle::current(),
         ))));
         Self {
-            store,
+            engine,
             module,
             config,
             runtime,
         }
     }
-    pub async fn execute<I, Arg>(&mut self, args: I) -> Result<OsProcess, HostErr>
+    pub async fn execute<I, Arg>(&self, args: I) -> Result<WasmOut, HostErr>
     where
         I: IntoIterator<Item = Arg>,
         Arg: AsRef<[u8]>,
@@ -74,93 +78,84 @@ impl WasmHost {
         self.execute_with_data(args, &[]).await
     }
 
-    pub async fn execute_with_data<I, Arg>(
-        &mut self,
-        args: I,
-        stdin: &[u8],
-    ) -> Result<OsProcess, HostErr>
+    pub async fn execute_with_data<I, Arg>(&self, args: I, stdin: &[u8]) -> Result<WasmOut, HostErr>
     where
         I: IntoIterator<Item = Arg>,
         Arg: AsRef<[u8]>,
     {
-        let mut builder = WasiEnv::builder("wasm program").args(args);
-
         let (mut stdin_tx, stdin_rx) = Pipe::channel();
-        let (stdout_tx, stdout_rx) = Pipe::channel();
-        let (stderr_tx, stderr_rx) = Pipe::channel();
 
-        std::io::Write::write_all(&mut stdin_tx, stdin).unwrap();
-        //        stdin_tx.flush().unwrap();
+        std::io::Write::write_all(&mut stdin_tx, stdin)?;
         stdin_tx.close();
 
-        builder = builder
-            .stdin(Box::new(stdin_rx))
-            .stdout(Box::new(stdout_tx))
-            .stderr(Box::new(stderr_tx));
+        self.execute_with_stdin(args, stdin_rx).await
+    }
 
-        if let Option::Some(ref fs_config) = self.config.fs {
-            for d in &fs_config.pre_opened_dirs {
-                builder = builder.preopen_dir(Path::new(d))?;
-            }
-            builder = builder.fs(Box::new(
-                fs_config
-                    .fs_factory
-                    .create(Handle::current().clone())
-                    .unwrap(),
-            ));
+    /// like [WasmHost::execute_with_data] except a guest that exits with a non zero
+    /// code is not an error.  The code is returned in [WasmOut::code]
+    pub async fn run_with_data<I, Arg>(&self, args: I, stdin: &[u8]) -> Result<WasmOut, HostErr>
+    where
+        I: IntoIterator<Item = Arg>,
+        Arg: AsRef<[u8]>,
+    {
+        let (mut stdin_tx, stdin_rx) = Pipe::channel();
 
-            builder = builder.env("PWD", fs_config.pwd.clone());
-        };
+        std::io::Write::write_all(&mut stdin_tx, stdin)?;
+        stdin_tx.close();
 
-        if self.config.runtime {
-            builder = builder.runtime(self.runtime.clone());
+        let (mut out, result) = self.run(args, stdin_rx).await?;
+        if let Err(err) = result {
+            out.code = err.as_exit_code().ok_or(err)?.raw();
         }
+        Ok(out)
+    }
 
-        builder = builder.current_dir("/");
-        //builder = builder.stdout(Box::new(io::stdout()));
-
-        //builder.run(self.module.clone())?;
-        builder.run_with_store(self.module.clone(), &mut self.store)?;
-
-        /*
-        Ok(OsProcess {
-            stdout: stdout_rx,
-            stderr: stderr_rx,
-        })
-
-         */
-        todo!()
+    pub async fn execute_with_stdin<I, Arg>(&self, args: I, stdin: Pipe) -> Result<WasmOut, HostErr>
+    where
+        I: IntoIterator<Item = Arg>,
+        Arg: AsRef<[u8]>,
+    {
+        let (out, result) = self.run(args, stdin).await?;
+        match result {
+            Ok(_) => Ok(out),
+            Err(err) => Err(HostErr::new(format!(
+                "{} {}",
+                err.to_string(),
+                String::from_utf8_lossy(out.stderr.as_slice())
+            ))),
+        }
     }
 
-    pub async fn execute_with_stdin<I, Arg>(
-        &mut self,
+    /// run the guest to completion. wasi blocks the calling thread so the
+    /// guest is run on tokio's blocking pool
+    async fn run<I, Arg>(
+        &self,
         args: I,
         stdin: Pipe,
-    ) -> Result<OsProcess, HostErr>
+    ) -> Result<(WasmOut, Result<(), WasiRuntimeError>), HostErr>
     where
         I: IntoIterator<Item = Arg>,
         Arg: AsRef<[u8]>,
     {
         let mut builder = WasiEnv::builder("wasm program").args(args);
 
-        let (stdout_tx, stdout_rx) = Pipe::channel();
-        let (stderr_tx, stderr_rx) = Pipe::channel();
+        let (stdout_tx, mut stdout_rx) = Pipe::channel();
+        let (stderr_tx, mut stderr_rx) = Pipe::channel();
 
         builder = builder
             .stdin(Box::new(stdin))
             .stdout(Box::new(stdout_tx))
             .stderr(Box::new(stderr_tx));
 
+        for (key, value) in &self.config.env {
+            builder = builder.env(key, value);
+        }
+
         if let Option::Some(ref fs_config) = self.config.fs {
             for d in &fs_config.pre_opened_dirs {
                 builder = builder.preopen_dir(Path::new(d))?;
             }
-            builder = builder.fs(Box::new(
-                fs_config
-                    .fs_factory
-                    .create(Handle::current().clone())
-                    .unwrap(),
-            ));
+            builder = builder.fs(fs_config.fs_factory.create(Handle::current().clone())?);
 
             builder = builder.env("PWD", fs_config.pwd.clone());
         };
@@ -170,19 +165,36 @@ impl WasmHost {
         }
 
         builder = builder.current_dir("/");
-        //builder = builder.stdout(Box::new(io::stdout()));
 
-        //builder.run(self.module.clone())?;
-        builder.run_with_store(self.module.clone(), &mut self.store)?;
-
-        /*
-        Ok(OsProcess {
-            stdout: stdout_rx,
-            stderr: stderr_rx,
+        let engine = self.engine.clone();
+        let module = self.module.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let mut store = Store::new(engine);
+            builder.run_with_store(module, &mut store)
         })
-         */
-        todo!()
+        .await?;
+
+        // the guest has exited so everything it wrote is already buffered
+        let out = WasmOut {
+            stdout: drain(&mut stdout_rx),
+            stderr: drain(&mut stderr_rx),
+            code: 0,
+        };
+
+        Ok((out, result))
+    }
+}
+
+fn drain(pipe: &mut Pipe) -> Vec<u8> {
+    let mut rtn = vec![];
+    let mut buf = [0u8; 4096];
+    while let Some(len) = pipe.try_read(&mut buf) {
+        if len == 0 {
+            break;
+        }
+        rtn.extend_from_slice(&buf[..len]);
     }
+    rtn
 }
 
 pub struct WasmHostBuilder {}
@@ -200,6 +212,7 @@ impl WasmInterfaceKind {}
 pub struct WasmHostConfig {
     pub fs: Option<FsConfig>,
     pub runtime: bool,
+    pub env: HashMap<String, String>,
 }
 
 impl Default for WasmHostConfig {
@@ -207,6 +220,7 @@ impl Default for WasmHostConfig {
         Self {
             runtime: false,
             fs: Option::None,
+            env: HashMap::new(),
         }
     }
 }
@@ -221,6 +235,7 @@ pub struct WasmHostConfigBuilder {
     pub runtime: bool,
     pub pwd: String,
     pub fs: Option<FsConfigBuilder>,
+    pub env: HashMap<String, String>,
 }
 
 impl WasmHostConfigBuilder {
@@ -244,13 +259,23 @@ impl WasmHostConfigBuilder {
         self
     }
 
+    pub fn env<K, V>(&mut self, key: K, value: V) -> &mut Self
+    where
+        K: ToString,
+        V: ToString,
+    {
+        self.env.insert(key.to_string(), value.to_string());
+        self
+    }
+
     pub fn build(self) -> WasmHostConfig {
         WasmHostConfig {
             fs: match self.fs {
                 None => None,
                 Some(builder) => Some(builder.build()),
             },
-            runtime: false,
+            runtime: self.runtime,
+            env: self.env,
         }
     }
 }
@@ -261,10 +286,54 @@ impl Default for WasmHostConfigBuilder {
             pwd: ".".to_string(),
             runtime: false,
             fs: None,
+            env: HashMap::new(),
         }
     }
 }
 
+/// only the host directories `dirs` which the guest sees at the same paths.  Every
+/// dir must be absolute and canonical
+pub struct HostFs {
+    dirs: Vec<PathBuf>,
+}
+
+impl HostFs {
+    pub fn new<I, D>(dirs: I) -> Self
+    where
+        I: IntoIterator<Item = D>,
+        D: Into<PathBuf>,
+    {
+        Self {
+            dirs: dirs.into_iter().map(|dir| dir.into()).collect(),
+        }
+    }
+}
+
+impl FileSystemFactory for HostFs {
+    fn create(&self, handle: Handle) -> Result<Box<dyn FileSystem + Send + Sync>, HostErr> {
+        let err = |err: FsError, dir: &Path| {
+            HostErr::new(format!("{} '{}'", err.to_string(), dir.display()))
+        };
+        let fs = TmpFileSystem::new();
+        for dir in self.dirs.iter() {
+            let host: Arc<dyn FileSystem + Send + Sync> =
+                Arc::new(host_fs::FileSystem::new(handle.clone(), dir).map_err(|e| err(e, dir))?);
+            // a dir can only be mounted where its parent already exists
+            let mut ancestors: Vec<&Path> = dir.ancestors().skip(1).collect();
+            ancestors.reverse();
+            for ancestor in ancestors.into_iter().skip(1) {
+                match fs.create_dir(ancestor) {
+                    Ok(_) | Err(FsError::AlreadyExists) => {}
+                    Err(e) => return Err(err(e, ancestor)),
+                }
+            }
+            fs.mount(dir.clone(), &host, PathBuf::from("/"))
+                .map_err(|e| err(e, dir))?;
+        }
+        Ok(Box::new(fs))
+    }
+}
+
 #[derive(Clone)]
 pub struct FsConfig {
     pub fs_factory: Arc<dyn FileSystemFactory>,
@@ -329,82 +398,26 @@ pub mod test {
     use crate::hyperspace::host::wasm::cache::WasmModuleMemCache;
     use crate::hyperspace::host::wasm::source::FileSystemSrc;
     use crate::hyperspace::host::wasm::{WasmHostConfig, WasmService};
-    use crate::hyperspace::host::{FileSystemFactory, RootFileSystemFactory};
-    use tokio::io::AsyncReadExt;
-    use tokio::runtime::Handle;
-    use virtual_fs::{FileSystem, Pipe};
 
     #[tokio::test]
-    pub async fn test_fs() {
-        // tokio::fs::remove_dir_all("./test-dir").await;
-        //tokio::fs::create_dir_all("./test-dir").await;
-        let fs_factory = Arc::new(RootFileSystemFactory::new("./test-dir".into()));
-        let fs = fs_factory.create(Handle::current()).unwrap();
-        let paths = fs.read_dir(Path::new("/test-dir")).unwrap();
-        for path in paths {
-            let path = path.unwrap().path();
-            println!("{}", path.display());
-        }
-        let mut file = fs.new_open_options().open("blah.txt").unwrap();
-
-        //virtual_fs::AsyncWriteExt::write_all(& mut file, "hello".as_bytes()).await.unwrap();
-    }
-
-    #[tokio::test]
-    pub async fn test() {
-        tokio::fs::remove_dir_all("./test-dir").await;
-        tokio::fs::create_dir_all("./test-dir").await;
-
-        /*
-        tokio::fs::create_dir_all("./test-dir/subdir").await;
-        tokio::fs::write("./test-dir/test1.txt", "test1").await;
-        tokio::fs::write("./test-dir/test2.txt", "test2").await;
-        tokio::fs::write("./test-dir/subdir/sub1.txt", "test1").await;
-        tokio::fs::write("./test-dir/subdir/sub2.txt", "test2").await;
-
-         */
-        println!("starting test");
-        let source = Box::new(FileSystemSrc::new("."));
-        let cache = Box::new(WasmModuleMemCache::new_with_ser(source, ".".into()));
+    pub async fn test_echo() {
+        let source = Box::new(FileSystemSrc::new("fixtures/wasm"));
+        let cache = Box::new(WasmModuleMemCache::new(source));
         let mut service = WasmService::new(cache);
-        let mut builder = WasmHostConfig::builder();
-        let fs_factory = Arc::new(RootFileSystemFactory::new("./test-dir".into()));
-        builder.fs(fs_factory, |fs_builder| {
-            fs_builder.preopen("/");
-            fs_builder.pwd("/");
-        });
-        builder.runtime(false);
-        let config = builder.build();
-        let mut host = service.provision("filestore.wasm", config).await.unwrap();
-
-        //        let mut process = host.execute(&["test"]).await.unwrap();
-        let mut process = host.execute(&["mkdir", "subdir"]).await.unwrap();
-        let mut process = host.execute(&["exists", "subdir"]).await.unwrap();
-        assert!(host.execute(&["exists", "badfile"]).await.is_err());
-
-        let (mut stdin_tx, stdin_rx) = Pipe::channel();
-
-        std::io::Write::write_all(&mut stdin_tx, "hello you happy people".as_bytes()).unwrap();
-        stdin_tx.close();
-        host.execute_with_stdin(&["write", "/file1.txt"], stdin_rx)
+        let host = service
+            .provision("echo.wasm", WasmHostConfig::default())
             .await
             .unwrap();
 
-        //        let mut process = host.execute(&["list"]).await.unwrap();
-
-        // process.stdin.close();
-        println!("it worked i guess?");
-
-        let mut both = AsyncReadExt::chain(process.stdout, process.stderr);
-
-        let mut out = String::new();
-        both.read_to_string(&mut out).await.unwrap();
-
-        println!("{}", out);
-
-        let mut err = String::new();
-        both.read_to_string(&mut err).await.unwrap();
+        let out = host
+            .execute_with_data(&["echo"], "hello you happy people".as_bytes())
+            .await
+            .unwrap();
+        assert_eq!("hello you happy people".as_bytes(), out.stdout.as_slice());
+        assert!(out.stderr.is_empty());
 
-        println!("{}", err);
+        // the module is compiled once and can be run again
+        let out = host.execute(&["echo"]).await.unwrap();
+        assert!(out.stdout.is_empty());
     }
 }
@@ -1,4 +1,8 @@
 use crate::hyperspace::host::err;
+use crate::space::artifact::asynch::Artifacts;
+use crate::space::point::Point;
+use std::path::Path;
+use std::str::FromStr;
 use tokio::fs;
 
 #[async_trait]
@@ -29,3 +33,27 @@ impl FileSystemSrc {
         }
     }
 }
+
+/// loads wasm from the artifact at the point named by `key`
+pub struct ArtifactSrc {
+    artifacts: Artifacts,
+}
+
+impl ArtifactSrc {
+    pub fn new(artifacts: Artifacts) -> Self {
+        Self { artifacts }
+    }
+}
+
+#[async_trait]
+impl Source for ArtifactSrc {
+    async fn get(&self, key: &str) -> Result<Vec<u8>, err::HostErr> {
+        let point = Point::from_str(key).map_err(|e| err::HostErr::new(e.to_string()))?;
+        let bin = self
+            .artifacts
+            .get_raw(&point)
+            .await
+            .map_err(|e| err::HostErr::new(e.to_string()))?;
+        Ok((*bin).clone())
+    }
+}
@@ -0,0 +1,532 @@
+use crate::hyperspace::hyperlane::{
+    HyperAuthenticator, HyperGate, HyperwayEndpoint, HyperwayEndpointFactory, HyperwayStub,
+};
+use crate::hyperspace::reg::Registry;
+use crate::space::err::SpaceErr;
+use crate::space::hyper::{InterchangeKind, Knock};
+use crate::space::loc::ToPoint;
+use crate::space::log::Logger;
+use crate::space::point::Point;
+use crate::space::selector::Selector;
+use crate::space::wave::{Agent, Recipients, Wave, WaveId};
+use crate::space::HYPERUSER;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use starlane_primitive_macros::logger;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader, BufWriter};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, mpsc};
+
+/// identifies a capture file and the version of its record format
+const MAGIC: &[u8; 8] = b"STRLCAP1";
+
+/// a [Wave] as it was routed through a star's interchange and when it was seen
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapturedWave {
+    pub timestamp: DateTime<Utc>,
+    pub wave: Wave,
+}
+
+impl CapturedWave {
+    pub fn new(wave: Wave) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            wave,
+        }
+    }
+
+    /// waves travel between stars wrapped in hops and transports.  returns the
+    /// wave that was originally sent
+    pub fn inner(&self) -> &Wave {
+        let mut wave = &self.wave;
+        while let Some(payload) = wave.payload() {
+            wave = payload;
+        }
+        wave
+    }
+
+    /// true if the inner wave is from or addressed to a point matched by `selector`
+    pub fn is_match(&self, selector: &Selector) -> bool {
+        let wave = self.inner();
+        if selector.matches_found(&wave.from().point) {
+            return true;
+        }
+        match wave.to() {
+            Recipients::Single(to) => selector.matches_found(&to.point),
+            Recipients::Multi(to) => to.iter().any(|to| selector.matches_found(&to.point)),
+            Recipients::Watchers(_) | Recipients::Stars => false,
+        }
+    }
+}
+
+/// appends [CapturedWave] records to a capture file
+pub struct CaptureWriter<W>
+where
+    W: AsyncWrite + Unpin,
+{
+    write: W,
+}
+
+impl CaptureWriter<BufWriter<tokio::fs::File>> {
+    pub async fn create(path: &str) -> Result<Self, SpaceErr> {
+        let file = tokio::fs::File::create(path).await?;
+        Self::new(BufWriter::new(file)).await
+    }
+}
+
+impl<W> CaptureWriter<W>
+where
+    W: AsyncWrite + Unpin,
+{
+    pub async fn new(mut write: W) -> Result<Self, SpaceErr> {
+        write.write_all(MAGIC).await?;
+        Ok(Self { write })
+    }
+
+    pub async fn write(&mut self, captured: &CapturedWave) -> Result<(), SpaceErr> {
+        let data = bincode::serialize(captured)?;
+        self.write.write_u32(data.len() as u32).await?;
+        self.write.write_all(data.as_slice()).await?;
+        Ok(())
+    }
+
+    pub async fn flush(&mut self) -> Result<(), SpaceErr> {
+        Ok(self.write.flush().await?)
+    }
+}
+
+/// reads the [CapturedWave] records written by a [CaptureWriter]
+pub struct CaptureReader<R>
+where
+    R: AsyncRead + Unpin,
+{
+    read: R,
+}
+
+impl CaptureReader<BufReader<tokio::fs::File>> {
+    pub async fn open(path: &str) -> Result<Self, SpaceErr> {
+        let file = tokio::fs::File::open(path).await?;
+        Self::new(BufReader::new(file)).await
+    }
+}
+
+impl<R> CaptureReader<R>
+where
+    R: AsyncRead + Unpin,
+{
+    pub async fn new(mut read: R) -> Result<Self, SpaceErr> {
+        let mut magic = [0u8; 8];
+        read.read_exact(&mut magic).await?;
+        if magic != *MAGIC {
+            return Err(SpaceErr::bad_request("not a starlane capture file"));
+        }
+        Ok(Self { read })
+    }
+
+    /// returns `None` at the end of the capture
+    pub async fn read(&mut self) -> Result<Option<CapturedWave>, SpaceErr> {
+        let len = match self.read.read_u32().await {
+            Ok(len) => len as usize,
+            Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let mut data = vec![0u8; len];
+        self.read.read_exact(&mut data).await?;
+        Ok(Some(bincode::deserialize(data.as_slice())?))
+    }
+
+    pub async fn read_all(&mut self) -> Result<Vec<CapturedWave>, SpaceErr> {
+        let mut rtn = vec![];
+        while let Some(captured) = self.read().await? {
+            rtn.push(captured);
+        }
+        Ok(rtn)
+    }
+}
+
+/// a directed wave and the waves that reflected it.  `directed` is `None` when the
+/// capture started after the directed wave was sent
+pub struct CapturePair {
+    pub directed: Option<CapturedWave>,
+    pub reflected: Vec<CapturedWave>,
+}
+
+impl CapturePair {
+    /// groups the captures by the [WaveId] of their inner wave.  A wave that passed
+    /// through several stars is captured once per star; only the first sighting is kept
+    pub fn pairs(captures: &[CapturedWave]) -> Vec<CapturePair> {
+        let mut seen: HashSet<WaveId> = HashSet::new();
+        let mut pairs: Vec<CapturePair> = vec![];
+        for captured in captures {
+            let wave = captured.inner();
+            if !seen.insert(wave.id()) {
+                continue;
+            }
+            if wave.is_directed() {
+                pairs.push(CapturePair {
+                    directed: Some(captured.clone()),
+                    reflected: vec![],
+                });
+            } else {
+                // only directed and reflected waves are routed so this is never expected
+                let reflection_of = match wave.clone().to_reflected() {
+                    Ok(reflected) => reflected.reflection_of().clone(),
+                    Err(err) => {
+                        logger!().warn(format!(
+                            "skipping captured wave '{}' that is neither directed nor reflected: {}",
+                            wave.id().to_short_string(),
+                            err.to_string()
+                        ));
+                        continue;
+                    }
+                };
+                let pair = pairs.iter_mut().find(|pair| match &pair.directed {
+                    Some(directed) => directed.inner().id() == reflection_of,
+                    None => false,
+                });
+                match pair {
+                    Some(pair) => pair.reflected.push(captured.clone()),
+                    None => pairs.push(CapturePair {
+                        directed: None,
+                        reflected: vec![captured.clone()],
+                    }),
+                }
+            }
+        }
+        pairs
+    }
+}
+
+/// re-injects the directed waves of a capture through an endpoint created by `factory`.
+/// Reflected waves are skipped since the machine answers the directed waves itself.  When
+/// `paced` the original delay between waves is preserved.  returns the number of waves sent
+pub async fn replay(
+    captures: Vec<CapturedWave>,
+    factory: &dyn HyperwayEndpointFactory,
+    paced: bool,
+) -> Result<usize, SpaceErr> {
+    let (status_tx, mut status_rx) = mpsc::channel(32);
+    tokio::spawn(async move { while let Some(_) = status_rx.recv().await {} });
+    let endpoint = factory.create(status_tx).await?;
+
+    let mut seen: HashSet<WaveId> = HashSet::new();
+    let mut last: Option<DateTime<Utc>> = None;
+    let mut sent = 0;
+    for captured in captures {
+        let wave = captured.inner();
+        if !wave.is_directed() || !seen.insert(wave.id()) {
+            continue;
+        }
+        if paced {
+            if let Some(last) = last {
+                if let Ok(delay) = (captured.timestamp - last).to_std() {
+                    tokio::time::sleep(delay).await;
+                }
+            }
+            last = Some(captured.timestamp);
+        }
+        endpoint.tx.send(wave.clone()).await?;
+        sent += 1;
+    }
+    Ok(sent)
+}
+
+/// streams every wave routed through the machine's star interchanges to the knocking
+/// client.  Only the HyperUser, an agent with super access or one of `agents` may capture
+pub struct CaptureGate<A>
+where
+    A: HyperAuthenticator,
+{
+    auth: A,
+    registry: Registry,
+    agents: Arc<HashSet<Point>>,
+    eavesdrop_tx: broadcast::Sender<Wave>,
+    logger: Logger,
+}
+
+impl<A> CaptureGate<A>
+where
+    A: HyperAuthenticator,
+{
+    pub fn new(
+        auth: A,
+        registry: Registry,
+        agents: Arc<HashSet<Point>>,
+        eavesdrop_tx: broadcast::Sender<Wave>,
+        logger: Logger,
+    ) -> Self {
+        Self {
+            auth,
+            registry,
+            agents,
+            eavesdrop_tx,
+            logger,
+        }
+    }
+
+    /// the traffic of every particle on the machine is exposed by a capture
+    async fn authorize(&self, agent: &Agent) -> Result<(), SpaceErr> {
+        let point = match agent {
+            Agent::HyperUser => return Ok(()),
+            Agent::Anonymous => {
+                return Err(SpaceErr::new(403, "anonymous agents cannot capture traffic"))
+            }
+            Agent::Point(point) => point,
+        };
+        if *point == *HYPERUSER || self.agents.contains(point) {
+            return Ok(());
+        }
+        let access = self
+            .registry
+            .access(point, &Point::root())
+            .await
+            .map_err(|err| SpaceErr::server_error(err.to_string()))?;
+        if access.has_super() {
+            Ok(())
+        } else {
+            Err(SpaceErr::new(
+                403,
+                format!("'{}' may not capture traffic", point.to_string()),
+            ))
+        }
+    }
+}
+
+#[async_trait]
+impl<A> HyperGate for CaptureGate<A>
+where
+    A: HyperAuthenticator,
+{
+    async fn knock(&self, knock: Knock) -> Result<HyperwayEndpoint, SpaceErr> {
+        let stub = self.auth.auth(knock).await?;
+        self.authorize(&stub.agent).await?;
+
+        let (tx, rx) = mpsc::channel(1024);
+        let (discard_tx, mut discard_rx) = mpsc::channel(1);
+        tokio::spawn(async move { while let Some(_) = discard_rx.recv().await {} });
+
+        let mut eavesdrop_rx = self.eavesdrop_tx.subscribe();
+        let logger = self.logger.clone();
+        tokio::spawn(async move {
+            loop {
+                match eavesdrop_rx.recv().await {
+                    Ok(wave) => {
+                        if tx.send(wave).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        logger.warn(format!(
+                            "capture for {} fell behind and skipped {} waves",
+                            stub.agent.to_point().to_string(),
+                            skipped
+                        ));
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+
+        Ok(HyperwayEndpoint::new(discard_tx, rx, self.logger.clone()))
+    }
+
+    async fn jump(
+        &self,
+        _kind: InterchangeKind,
+        _stub: HyperwayStub,
+    ) -> Result<HyperwayEndpoint, SpaceErr> {
+        Err(SpaceErr::new(400, "cannot jump into a capture"))
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::hyperspace::hyperlane::capture::{
+        replay, CaptureGate, CapturePair, CaptureReader, CaptureWriter, CapturedWave,
+    };
+    use crate::hyperspace::hyperlane::test_util::{FAE, LESS};
+    use crate::hyperspace::hyperlane::{
+        AnonHyperAuthenticator, HyperConnectionDetails, HyperGate, HyperwayEndpoint,
+        HyperwayEndpointFactory, TokenAuthenticatorWithRemoteWhitelist,
+    };
+    use crate::hyperspace::reg::Registry;
+    use crate::hyperspace::registry::mem::registry::MemoryRegistry;
+    use crate::space::err::{SpaceErr, StatusErr};
+    use crate::space::hyper::{InterchangeKind, Knock};
+    use crate::space::loc::ToSurface;
+    use crate::space::log::Logger;
+    use crate::space::security::Access;
+    use crate::space::selector::Selector;
+    use crate::space::substance::{Substance, Token};
+    use crate::space::wave::core::ext::ExtMethod;
+    use crate::space::wave::core::ReflectedCore;
+    use crate::space::wave::{Agent, DirectedProto, Wave};
+    use async_trait::async_trait;
+    use std::collections::HashSet;
+    use std::str::FromStr;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::sync::{broadcast, mpsc};
+
+    fn ping(name: &str) -> Wave {
+        let mut proto = DirectedProto::ping();
+        proto.from(LESS.to_surface());
+        proto.to(FAE.to_surface());
+        proto.method(ExtMethod::new(name).unwrap());
+        proto.body(Substance::Empty);
+        proto.build().unwrap().to_wave()
+    }
+
+    fn capture() -> Vec<CapturedWave> {
+        let hello = ping("Hello");
+        let pong = hello
+            .clone()
+            .to_directed()
+            .unwrap()
+            .reflection()
+            .unwrap()
+            .make(ReflectedCore::ok(), FAE.to_surface())
+            .to_wave();
+        // the same wave seen again as it passed through a second star
+        let transported = hello
+            .clone()
+            .wrap_in_transport(LESS.to_surface(), FAE.to_surface())
+            .build()
+            .unwrap()
+            .to_wave();
+        vec![
+            CapturedWave::new(hello),
+            CapturedWave::new(transported),
+            CapturedWave::new(pong),
+            CapturedWave::new(ping("Goodbye")),
+        ]
+    }
+
+    #[tokio::test]
+    pub async fn test_capture_file() {
+        let captures = capture();
+        let mut writer = CaptureWriter::new(vec![]).await.unwrap();
+        for captured in captures.iter() {
+            writer.write(captured).await.unwrap();
+        }
+        let data = writer.write;
+
+        let mut reader = CaptureReader::new(data.as_slice()).await.unwrap();
+        let read = reader.read_all().await.unwrap();
+        assert_eq!(read.len(), captures.len());
+        assert_eq!(read[1].inner().id(), captures[0].wave.id());
+
+        assert!(CaptureReader::new(&b"not a capture"[..]).await.is_err());
+
+        let pairs = CapturePair::pairs(&read);
+        assert_eq!(pairs.len(), 2);
+        assert_eq!(pairs[0].reflected.len(), 1);
+        assert!(pairs[1].reflected.is_empty());
+
+        let selector = Selector::from_str(FAE.to_string().as_str()).unwrap();
+        assert!(read.iter().all(|captured| captured.is_match(&selector)));
+        let selector = Selector::from_str("nobody").unwrap();
+        assert!(!read[0].is_match(&selector));
+    }
+
+    struct ReplayFactory {
+        tx: Mutex<Option<mpsc::Sender<Wave>>>,
+    }
+
+    #[async_trait]
+    impl HyperwayEndpointFactory for ReplayFactory {
+        async fn create(
+            &self,
+            _status_tx: mpsc::Sender<HyperConnectionDetails>,
+        ) -> Result<HyperwayEndpoint, SpaceErr> {
+            let tx = self.tx.lock().unwrap().take().unwrap();
+            let (_, rx) = mpsc::channel(1);
+            Ok(HyperwayEndpoint::new(tx, rx, Logger::default()))
+        }
+    }
+
+    #[tokio::test]
+    pub async fn test_replay() {
+        let (tx, mut rx) = mpsc::channel(16);
+        let factory = ReplayFactory {
+            tx: Mutex::new(Some(tx)),
+        };
+        let captures = capture();
+        let sent = replay(captures.clone(), &factory, false).await.unwrap();
+        assert_eq!(sent, 2);
+        assert_eq!(rx.recv().await.unwrap().id(), captures[0].wave.id());
+        assert_eq!(rx.recv().await.unwrap().id(), captures[3].wave.id());
+    }
+
+    #[tokio::test]
+    pub async fn test_capture_gate() {
+        // nobody but the HyperUser and the configured agents has super access
+        let registry: Registry = Arc::new(MemoryRegistry::with_access(Access::none()));
+        let token = Token::new_uuid();
+        let auth = TokenAuthenticatorWithRemoteWhitelist::new(
+            Agent::HyperUser,
+            token.clone(),
+            HashSet::from([LESS.clone()]),
+        );
+        let (eavesdrop_tx, _) = broadcast::channel(16);
+        let gate = CaptureGate::new(
+            auth,
+            registry.clone(),
+            Arc::new(HashSet::new()),
+            eavesdrop_tx.clone(),
+            Logger::default(),
+        );
+
+        let mut knock = Knock::new(
+            InterchangeKind::Capture,
+            LESS.to_surface(),
+            Substance::Token(token.clone()),
+        );
+        let mut endpoint = gate.knock(knock.clone()).await.unwrap();
+        let wave = ping("Hello");
+        eavesdrop_tx.send(wave.clone()).unwrap();
+        let captured = tokio::time::timeout(Duration::from_secs(5), endpoint.rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(captured.id(), wave.id());
+
+        // a valid token for an ordinary agent is not enough to capture
+        let user = |agents: HashSet<_>| {
+            CaptureGate::new(
+                TokenAuthenticatorWithRemoteWhitelist::new(
+                    Agent::Point(LESS.clone()),
+                    token.clone(),
+                    HashSet::from([LESS.clone()]),
+                ),
+                registry.clone(),
+                Arc::new(agents),
+                eavesdrop_tx.clone(),
+                Logger::default(),
+            )
+        };
+        match user(HashSet::new()).knock(knock.clone()).await {
+            Ok(_) => panic!("an ordinary agent was allowed to capture"),
+            Err(err) => assert_eq!(err.status(), 403),
+        }
+        assert!(user(HashSet::from([LESS.clone()]))
+            .knock(knock.clone())
+            .await
+            .is_ok());
+
+        knock.auth = Box::new(Substance::Empty);
+        assert!(gate.knock(knock.clone()).await.is_err());
+
+        let anon = CaptureGate::new(
+            AnonHyperAuthenticator::new(),
+            registry,
+            Arc::new(HashSet::new()),
+            eavesdrop_tx,
+            Logger::default(),
+        );
+        assert!(anon.knock(knock).await.is_err());
+    }
+}
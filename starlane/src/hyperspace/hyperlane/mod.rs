@@ -1,4 +1,6 @@
+pub mod capture;
 pub mod tcp;
+pub mod token;
 
 #[cfg(feature = "quic")]
 pub mod quic;
@@ -9,8 +11,8 @@ use derive_name::Name;
 use once_cell::sync::Lazy;
 use starlane_primitive_macros::{push_loc, push_mark};
 use crate::space::err::SpaceErr;
-use crate::space::hyper::{Greet, InterchangeKind, Knock};
-use crate::space::loc::{Layer, PointFactory, Surface, ToSurface};
+use crate::space::hyper::{ClientCert, Greet, InterchangeKind, Knock};
+use crate::space::loc::{Layer, MachineName, PointFactory, Surface, ToSurface};
 use crate::space::log::{Logger, Tracker};
 use crate::space::point::Point;
 use crate::space::substance::{Substance, Token};
@@ -36,6 +38,14 @@ pub static LOCAL_CLIENT: Lazy<Point> =
 pub static LOCAL_CLIENT_RUNNER: Lazy<Point> =
     Lazy::new(|| Point::from_str("LOCAL::client:runner").expect("point"));
 
+/// keepalive waves addressed here are answered by the transport and never leave it
+pub static HEARTBEAT: Lazy<Point> =
+    Lazy::new(|| Point::from_str("LOCAL::hyperlane:heartbeat").expect("point"));
+
+/// flow control credit grants are addressed here and consumed by the transport
+pub static CREDIT: Lazy<Point> =
+    Lazy::new(|| Point::from_str("LOCAL::hyperlane:credit").expect("point"));
+
 pub static HYPERLANE_INDEX: Lazy<AtomicU16> = Lazy::new(|| AtomicU16::new(0));
 
 pub enum HyperwayKind {
@@ -273,6 +283,7 @@ pub enum HyperwayInterchangeCall {
     Wave(Wave),
     Internal(Hyperway),
     Remove(Surface),
+    Eavesdrop(broadcast::Sender<Wave>),
     Mount {
         stub: HyperwayStub,
         init_wave: Option<Wave>,
@@ -545,8 +556,12 @@ impl HyperwayInterchange {
             let logger = logger.clone();
             tokio::spawn(async move {
                 let mut hyperways = HashMap::new();
+                let mut eavesdrop: Option<broadcast::Sender<Wave>> = None;
                 while let Some(call) = call_rx.recv().await {
                     match call {
+                        HyperwayInterchangeCall::Eavesdrop(tx) => {
+                            eavesdrop.replace(tx);
+                        }
                         HyperwayInterchangeCall::Internal(hyperway) => {
                             let mut rx = hyperway.inbound.rx(None).await;
                             hyperways.insert(hyperway.remote.clone(), hyperway);
@@ -577,6 +592,12 @@ impl HyperwayInterchange {
                                         );
                                 }
                                 Some(hyperway) => {
+                                    if let Some(eavesdrop) = eavesdrop.as_ref() {
+                                        // only pay for the clone while someone is listening
+                                        if eavesdrop.receiver_count() > 0 {
+                                            eavesdrop.send(wave.clone());
+                                        }
+                                    }
                                     hyperway.outbound.send(wave).await;
                                 }
                             },
@@ -650,6 +671,13 @@ impl HyperwayInterchange {
         self.singular_to.replace(to);
     }
 
+    /// copy every wave this interchange routes to `tx`
+    pub async fn eavesdrop_to(&self, tx: broadcast::Sender<Wave>) {
+        self.call_tx
+            .send(HyperwayInterchangeCall::Eavesdrop(tx))
+            .await;
+    }
+
     pub async fn add(&self, mut hyperway: Hyperway) {
         if let Some(to) = self.singular_to.as_ref() {
             hyperway.transform_to(to.clone());
@@ -860,6 +888,93 @@ impl HyperAuthenticator for AnonHyperAuthenticatorAssignEndPoint {
     }
 }
 
+/// maps the verified client certificate of a [Knock] to an [Agent::Point].
+/// a [Knock] that carries a certificate which cannot be mapped is rejected.
+/// a [Knock] without a certificate (i.e. one that did not arrive over mutual TLS)
+/// is passed through to the `inner` authenticator unchanged
+#[derive(Clone)]
+pub struct CertHyperAuthenticator<A>
+where
+    A: HyperAuthenticator,
+{
+    pub agents: Arc<HashMap<String, Point>>,
+    pub inner: A,
+}
+
+impl<A> CertHyperAuthenticator<A>
+where
+    A: HyperAuthenticator,
+{
+    pub fn new(agents: Arc<HashMap<String, Point>>, inner: A) -> Self {
+        Self { agents, inner }
+    }
+
+    pub fn agent(&self, cert: &ClientCert) -> Option<Agent> {
+        cert.names()
+            .iter()
+            .find_map(|name| self.agents.get(name))
+            .map(|point| Agent::Point(point.clone()))
+    }
+}
+
+#[async_trait]
+impl<A> HyperAuthenticator for CertHyperAuthenticator<A>
+where
+    A: HyperAuthenticator,
+{
+    async fn auth(&self, knock: Knock) -> Result<HyperwayStub, SpaceErr> {
+        match knock.cert.as_ref() {
+            None => self.inner.auth(knock).await,
+            Some(cert) => {
+                let agent = self.agent(cert).ok_or(SpaceErr::new(
+                    401,
+                    "client certificate is not mapped to an agent",
+                ))?;
+                let mut stub = self.inner.auth(knock).await?;
+                stub.agent = agent;
+                Ok(stub)
+            }
+        }
+    }
+}
+
+/// admits the stars of the other machines in a cluster.  a [Knock] must arrive over
+/// mutual TLS with a client certificate naming one of `machines`; the star is then
+/// trusted as the [Agent::HyperUser]
+#[derive(Clone)]
+pub struct ClusterHyperAuthenticator {
+    pub machines: Arc<HashSet<MachineName>>,
+}
+
+impl ClusterHyperAuthenticator {
+    pub fn new(machines: Arc<HashSet<MachineName>>) -> Self {
+        Self { machines }
+    }
+}
+
+#[async_trait]
+impl HyperAuthenticator for ClusterHyperAuthenticator {
+    async fn auth(&self, knock: Knock) -> Result<HyperwayStub, SpaceErr> {
+        let cert = knock.cert.as_ref().ok_or(SpaceErr::new(
+            401,
+            "star connections require a client certificate",
+        ))?;
+        if !cert.names().iter().any(|name| self.machines.contains(name)) {
+            return Err(SpaceErr::new(
+                401,
+                "client certificate does not name a machine in the cluster",
+            ));
+        }
+        let remote = knock
+            .remote
+            .ok_or(SpaceErr::new(500, "required remote point request"))?;
+        Ok(HyperwayStub {
+            agent: Agent::HyperUser,
+            remote,
+        })
+    }
+}
+
 #[derive(Clone)]
 pub struct TokensFromHeavenHyperAuthenticatorAssignEndPoint {
     pub logger: Logger,
@@ -1073,6 +1188,13 @@ impl HyperGateSelector {
             Ok(())
         }
     }
+
+    pub fn remove(&self, kind: &InterchangeKind) -> Result<(), SpaceErr> {
+        match self.map.remove(kind) {
+            Some(_) => Ok(()),
+            None => Err(format!("no interchange of kind: {}", kind.to_string()).into()),
+        }
+    }
 }
 
 #[async_trait]
@@ -1278,6 +1400,7 @@ where
 pub struct HyperClient {
     tx: mpsc::Sender<Wave>,
     status_rx: watch::Receiver<HyperConnectionStatus>,
+    status_events_tx: broadcast::Sender<HyperConnectionStatus>,
     to_client_listener_tx: broadcast::Sender<Wave>,
     logger: Logger,
     greet_rx: watch::Receiver<Option<Greet>>,
@@ -1296,35 +1419,51 @@ impl HyperClient {
         factory: Box<dyn HyperwayEndpointFactory>,
         exchanger: Option<Exchanger>,
         logger: Logger,
+    ) -> Result<HyperClient, SpaceErr> {
+        Self::new_with_backoff(factory, exchanger, Backoff::default(), logger)
+    }
+
+    pub fn new_with_backoff(
+        factory: Box<dyn HyperwayEndpointFactory>,
+        exchanger: Option<Exchanger>,
+        backoff: Backoff,
+        logger: Logger,
     ) -> Result<HyperClient, SpaceErr> {
         let (to_client_listener_tx, _) = broadcast::channel(1024);
         let (to_hyperway_tx, from_client_rx) = mpsc::channel(1024);
         let (status_watch_tx, mut status_rx) = watch::channel(HyperConnectionStatus::Pending);
+        let (status_events_tx, _) = broadcast::channel(128);
 
         let (status_mpsc_tx, mut status_mpsc_rx): (
             mpsc::Sender<HyperConnectionStatus>,
             mpsc::Receiver<HyperConnectionStatus>,
         ) = mpsc::channel(128);
 
-        tokio::spawn(async move {
-            while let Some(status) = status_mpsc_rx.recv().await {
-                let result = status_watch_tx.send(status.clone());
-                if status == HyperConnectionStatus::Fatal {
-                    break;
-                }
-                if status == HyperConnectionStatus::Closed {
-                    break;
-                }
-                if let Err(_) = result {
-                    break;
+        {
+            let status_events_tx = status_events_tx.clone();
+            tokio::spawn(async move {
+                while let Some(status) = status_mpsc_rx.recv().await {
+                    // there may be no subscribers
+                    status_events_tx.send(status.clone()).unwrap_or_default();
+                    let result = status_watch_tx.send(status.clone());
+                    if status == HyperConnectionStatus::Fatal {
+                        break;
+                    }
+                    if status == HyperConnectionStatus::Closed {
+                        break;
+                    }
+                    if let Err(_) = result {
+                        break;
+                    }
                 }
-            }
-        });
+            });
+        }
 
         let mut from_runner_rx = HyperClientRunner::new(
             factory,
             from_client_rx,
             status_mpsc_tx.clone(),
+            backoff,
             logger.clone(),
         );
 
@@ -1333,6 +1472,7 @@ impl HyperClient {
         let mut client = Self {
             tx: to_hyperway_tx,
             status_rx: status_rx.clone(),
+            status_events_tx,
             to_client_listener_tx: to_client_listener_tx.clone(),
             logger: logger.clone(),
             greet_rx,
@@ -1543,6 +1683,15 @@ impl HyperClient {
         self.to_client_listener_tx.subscribe()
     }
 
+    pub fn status(&self) -> HyperConnectionStatus {
+        self.status_rx.borrow().clone()
+    }
+
+    /// every status change of the connection, including each reconnect
+    pub fn status_events(&self) -> broadcast::Receiver<HyperConnectionStatus> {
+        self.status_events_tx.subscribe()
+    }
+
     pub fn get_greeting(&self) -> Option<Greet> {
         self.greet_rx.borrow().clone()
     }
@@ -1561,32 +1710,22 @@ impl HyperClient {
 
     pub async fn wait_for_ready(&self, duration: Duration) -> Result<(), SpaceErr> {
         let mut status_rx = self.status_rx.clone();
-        let (rtn, mut rtn_rx) = oneshot::channel();
-
-        tokio::spawn(async move {
+        tokio::time::timeout(duration, async move {
             loop {
-                let status = status_rx.borrow().clone();
-
+                let status = status_rx.borrow_and_update().clone();
                 match status {
-                    HyperConnectionStatus::Ready => {
-                        rtn.send(Ok(()));
-                        break;
-                    }
+                    HyperConnectionStatus::Ready => return Ok(()),
                     HyperConnectionStatus::Fatal => {
-                        rtn.send(Err(SpaceErr::server_error(
+                        return Err(SpaceErr::server_error(
                             "Fatal status from HyperClient while waiting for Ready",
-                        )));
-                        break;
+                        ))
                     }
                     _ => {}
                 }
+                status_rx.changed().await?;
             }
-        });
-        let rtn = tokio::time::timeout(duration, rtn_rx).await;
-
-        let rtn = rtn??;
-        rtn
-        //        tokio::time::timeout(duration, rtn_rx).await??
+        })
+        .await?
     }
 }
 
@@ -1642,10 +1781,64 @@ impl From<SpaceErr> for HyperConnectionErr {
     }
 }
 
+/// delay between reconnect attempts of a [HyperClient].  The delay starts at `initial` and is
+/// multiplied by `multiplier` after each failed attempt up to `max`.  `jitter` is the fraction
+/// of each delay that is randomized so that clients of a restarted server do not reconnect
+/// in lockstep.  A connection that is lost before it was up for `stable` counts as another
+/// failed attempt so a server that accepts and then drops connections is not hammered
+#[derive(Clone, Debug)]
+pub struct Backoff {
+    pub initial: Duration,
+    pub max: Duration,
+    pub multiplier: f64,
+    pub jitter: f64,
+    pub stable: Duration,
+}
+
+impl Backoff {
+    pub fn new(initial: Duration, max: Duration) -> Self {
+        Self {
+            initial,
+            max,
+            ..Default::default()
+        }
+    }
+
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let delay = self.initial.as_secs_f64() * self.multiplier.powi(attempt.min(64) as i32);
+        let delay = delay.min(self.max.as_secs_f64());
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        Duration::from_secs_f64(delay * (1.0 - jitter * Self::random()))
+    }
+
+    /// a number in `[0,1)` good enough for jitter
+    fn random() -> f64 {
+        use std::collections::hash_map::RandomState;
+        use std::hash::{BuildHasher, Hasher};
+        let random = RandomState::new().build_hasher().finish();
+        (random >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_millis(250),
+            max: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: 0.5,
+            stable: Duration::from_secs(30),
+        }
+    }
+}
+
 pub struct HyperClientRunner {
     ext: Option<HyperwayEndpoint>,
     factory: Box<dyn HyperwayEndpointFactory>,
     status_tx: mpsc::Sender<HyperConnectionStatus>,
+    backoff: Backoff,
+    /// failed attempts since the last stable connection
+    attempt: u32,
     to_client_tx: mpsc::Sender<Wave>,
     from_client_rx: mpsc::Receiver<Wave>,
     logger: Logger,
@@ -1656,6 +1849,7 @@ impl HyperClientRunner {
         factory: Box<dyn HyperwayEndpointFactory>,
         from_client_rx: mpsc::Receiver<Wave>,
         status_tx: mpsc::Sender<HyperConnectionStatus>,
+        backoff: Backoff,
         logger: Logger,
     ) -> mpsc::Receiver<Wave> {
         let (to_client_tx, from_runner_rx) = mpsc::channel(1024);
@@ -1666,6 +1860,8 @@ impl HyperClientRunner {
             to_client_tx,
             from_client_rx,
             status_tx,
+            backoff,
+            attempt: 0,
             logger,
         };
 
@@ -1728,12 +1924,11 @@ impl HyperClientRunner {
                         }
                         Err(err) => {
                             runner.logger.error(format!("{}", err.to_string()));
-                            process::exit(1);
                         }
                     }
                     // wait a little while before attempting to reconnect
-                    // maybe add exponential backoff later
-                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    tokio::time::sleep(runner.backoff.delay(runner.attempt)).await;
+                    runner.attempt = runner.attempt.saturating_add(1);
                 }
             }
 
@@ -1782,8 +1977,8 @@ impl HyperClientRunner {
                                    runner.to_client_tx.send(wave).await;
                                 }
                                 None => {
-                                   runner.logger.warn("client hyperway_endpoint has been closed.  This can happen if the client sender (tx) has been dropped.");
-                                    break;
+                                   // the transport closed (possibly a dead peer) so reconnect
+                                   return Err(SpaceErr::server_error("client hyperway_endpoint has been closed"));
                                 }
                             }
                         }
@@ -1814,6 +2009,7 @@ impl HyperClientRunner {
                     }
                 }
 
+                let connected = tokio::time::Instant::now();
                 match relay(&mut self).await {
                     Ok(_) => {
                         // natural end... this runner is ready to be dropped
@@ -1823,6 +2019,16 @@ impl HyperClientRunner {
                         //self.logger.error(format!("{}", err.to_string()));
                         // some error occurred when relaying therefore we need to reconnect
                         self.ext = None;
+                        self.status_tx
+                            .send(HyperConnectionStatus::Panic)
+                            .await
+                            .unwrap_or_default();
+                        if connected.elapsed() >= self.backoff.stable {
+                            self.attempt = 0;
+                        } else {
+                            tokio::time::sleep(self.backoff.delay(self.attempt)).await;
+                            self.attempt = self.attempt.saturating_add(1);
+                        }
                     }
                 }
             }
@@ -2303,14 +2509,16 @@ pub mod test {
 
     use crate::hyperspace::hyperlane::test_util::{SingleInterchangePlatform, TestGreeter, WaveTest};
     use crate::hyperspace::hyperlane::{
-        AnonHyperAuthenticator, Bridge, HyperClient, HyperConnectionDetails, HyperGate,
-        HyperGateSelector, HyperRouter, Hyperlane, Hyperway, HyperwayEndpoint,
-        HyperwayEndpointFactory, HyperwayInterchange, HyperwayStub, LocalHyperwayGateUnlocker,
-        MountInterchangeGate,
+        AnonHyperAuthenticator, Backoff, Bridge, CertHyperAuthenticator,
+        ClusterHyperAuthenticator, HyperAuthenticator, HyperClient,
+        HyperConnectionDetails, HyperGate, HyperGateSelector, HyperRouter, Hyperlane, Hyperway,
+        HyperwayEndpoint, HyperwayEndpointFactory, HyperwayInterchange, HyperwayStub,
+        LocalHyperwayGateUnlocker, MountInterchangeGate,
     };
     use starlane_primitive_macros::{create_mark, logger, push_mark};
+    use std::collections::{HashMap, HashSet};
     use crate::space::err::SpaceErr;
-    use crate::space::hyper::InterchangeKind;
+    use crate::space::hyper::{ClientCert, ControlPattern, InterchangeKind, Knock};
     use crate::space::loc::{Layer, ToSurface};
     use crate::space::point::Point;
     use crate::space::settings::Timeouts;
@@ -2510,6 +2718,127 @@ pub mod test {
         }
     }
 
+    #[tokio::test]
+    pub async fn test_cert_authenticator() {
+        let mut agents = HashMap::new();
+        agents.insert("less".to_string(), LESS.clone());
+        let auth = CertHyperAuthenticator::new(Arc::new(agents), AnonHyperAuthenticator::new());
+
+        let mut knock = Knock::new(
+            InterchangeKind::Singleton,
+            LESS.to_surface(),
+            Substance::Empty,
+        );
+
+        knock.cert = Some(ClientCert::new(None, vec!["less".to_string()]));
+        let stub = auth.auth(knock.clone()).await.unwrap();
+        assert_eq!(stub.agent, Agent::Point(LESS.clone()));
+
+        knock.cert = Some(ClientCert::new(Some("fae".to_string()), vec![]));
+        assert!(auth.auth(knock.clone()).await.is_err());
+
+        knock.cert = None;
+        let stub = auth.auth(knock).await.unwrap();
+        assert_eq!(stub.agent, Agent::Anonymous);
+    }
+
+    #[tokio::test]
+    pub async fn test_cluster_authenticator() {
+        let auth = ClusterHyperAuthenticator::new(Arc::new(HashSet::from(["alpha".to_string()])));
+
+        let mut knock = Knock::new(
+            InterchangeKind::Singleton,
+            LESS.to_surface(),
+            Substance::Empty,
+        );
+        assert!(auth.auth(knock.clone()).await.is_err());
+
+        knock.cert = Some(ClientCert::new(Some("beta".to_string()), vec![]));
+        assert!(auth.auth(knock.clone()).await.is_err());
+
+        knock.cert = Some(ClientCert::new(Some("alpha".to_string()), vec![]));
+        let stub = auth.auth(knock).await.unwrap();
+        assert_eq!(stub.agent, Agent::HyperUser);
+        assert_eq!(stub.remote, LESS.to_surface());
+    }
+
+    #[test]
+    pub fn test_gate_selector_remove() {
+        let selector = HyperGateSelector::default();
+        let kind = InterchangeKind::Portal(ControlPattern::Star(LESS.clone()));
+        let gate: Arc<dyn HyperGate> = Arc::new(HyperGateSelector::default());
+
+        selector.add(kind.clone(), gate.clone()).unwrap();
+        assert!(selector.add(kind.clone(), gate.clone()).is_err());
+        selector.remove(&kind).unwrap();
+        assert!(selector.remove(&kind).is_err());
+        selector.add(kind, gate).unwrap();
+    }
+
+    #[test]
+    pub fn test_backoff() {
+        let backoff = Backoff::new(Duration::from_millis(100), Duration::from_secs(1));
+        for attempt in 0..10 {
+            let expect = (100u64 << attempt).min(1000);
+            let delay = backoff.delay(attempt).as_millis() as u64;
+            assert!(delay <= expect);
+            assert!(delay >= expect / 2);
+        }
+
+        let mut backoff = backoff;
+        backoff.jitter = 0.0;
+        assert_eq!(backoff.delay(2), Duration::from_millis(400));
+        assert_eq!(backoff.delay(1000), Duration::from_secs(1));
+    }
+
+    /// accepts every connection and drops it again straight away
+    pub struct FlappingFactory {
+        connects: mpsc::Sender<tokio::time::Instant>,
+    }
+
+    #[async_trait]
+    impl HyperwayEndpointFactory for FlappingFactory {
+        async fn create(
+            &self,
+            _: mpsc::Sender<HyperConnectionDetails>,
+        ) -> Result<HyperwayEndpoint, SpaceErr> {
+            self.connects
+                .send(tokio::time::Instant::now())
+                .await
+                .unwrap_or_default();
+            let (tx, _) = mpsc::channel(1);
+            let (_, rx) = mpsc::channel(1);
+            Ok(HyperwayEndpoint::new(
+                tx,
+                rx,
+                logger!(Point::from_str("flapping").unwrap()),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    pub async fn test_backoff_not_reset_by_unstable_connection() {
+        let (connects, mut connects_rx) = mpsc::channel(16);
+        let factory = Box::new(FlappingFactory { connects });
+        let mut backoff = Backoff::new(Duration::from_millis(50), Duration::from_secs(5));
+        backoff.jitter = 0.0;
+        backoff.stable = Duration::from_secs(10);
+        let logger = logger!(Point::from_str("client").unwrap());
+        let client = HyperClient::new_with_backoff(factory, None, backoff, logger).unwrap();
+
+        let mut last = connects_rx.recv().await.unwrap();
+        for attempt in 0..4u32 {
+            let connect = tokio::time::timeout(Duration::from_secs(5), connects_rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            // each dropped connection doubles the delay before the next
+            assert!(connect - last >= Duration::from_millis(50 << attempt));
+            last = connect;
+        }
+        client.close().await;
+    }
+
     #[tokio::test]
     pub async fn test_single_interchange() {
         let test = SingleInterchangePlatform::new().await;
@@ -1,37 +1,55 @@
 use crate::hyperspace::hyperlane::{
     HyperConnectionDetails, HyperConnectionStatus, HyperGate, HyperGateSelector, HyperwayEndpoint,
-    HyperwayEndpointFactory,
+    HyperwayEndpointFactory, CREDIT, HEARTBEAT,
 };
 use async_trait::async_trait;
-use rcgen::{generate_simple_self_signed, RcgenError};
-use rustls::pki_types::ServerName;
+use chrono::{DateTime, Datelike, Utc};
+use rcgen::{
+    date_time_ymd, generate_simple_self_signed, BasicConstraints, CertificateParams, DnType,
+    ExtendedKeyUsagePurpose, IsCa, KeyPair, KeyUsagePurpose, RcgenError,
+};
+use rustls::pki_types::{CertificateDer, ServerName};
+use rustls::server::WebPkiClientVerifier;
 use rustls::{RootCertStore, ServerConfig};
+use serde::{Deserialize, Serialize};
 use crate::space::err::SpaceErr;
-use crate::space::hyper::Knock;
+use crate::space::hyper::{ClientCert, Knock};
+use crate::space::loc::{Surface, ToSurface};
 use crate::space::log::Logger;
-use crate::space::substance::Substance;
-use crate::space::wave::{PingCore, Wave, WaveVariantDef};
+use crate::space::substance::{Substance, SubstanceMap};
+use crate::space::wave::core::ext::ExtMethod;
+use crate::space::wave::core::ReflectedCore;
+use crate::space::wave::{DirectedProto, PingCore, Wave, WaveVariantDef};
 use crate::space::VERSION;
+use std::collections::{HashMap, VecDeque};
 use std::io;
 use std::io::{BufReader, Read};
+use std::net::{Ipv4Addr, SocketAddr};
 use std::str::FromStr;
 use std::string::FromUtf8Error;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::fs::File;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::{broadcast, mpsc, oneshot};
 use tokio::time::error::Elapsed;
+use tokio::time::{Instant, MissedTickBehavior};
 use tokio_rustls::{TlsAcceptor, TlsConnector, TlsStream};
 use tracing::instrument::WithSubscriber;
+use x509_parser::extensions::GeneralName;
 
 pub struct HyperlaneTcpClient {
     host: String,
     cert_dir: String,
+    client_cert_dir: Option<String>,
     knock: Knock,
     logger: Logger,
     verify: bool,
+    heartbeat: Heartbeat,
+    limits: FrameLimits,
+    metrics: Arc<FrameMetrics>,
 }
 
 impl HyperlaneTcpClient {
@@ -43,11 +61,39 @@ impl HyperlaneTcpClient {
         Self {
             host: host.to_string(),
             cert_dir: cert_dir.to_string(),
+            client_cert_dir: None,
             knock,
             verify,
             logger,
+            heartbeat: Heartbeat::default(),
+            limits: FrameLimits::default(),
+            metrics: Arc::new(FrameMetrics::default()),
         }
     }
+
+    pub fn with_heartbeat(mut self, heartbeat: Heartbeat) -> Self {
+        self.heartbeat = heartbeat;
+        self
+    }
+
+    pub fn with_limits(mut self, limits: FrameLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn metrics(&self) -> Arc<FrameMetrics> {
+        self.metrics.clone()
+    }
+
+    /// present the client certificate found in `client_cert_dir` to a server that
+    /// requires mutual TLS
+    pub fn with_client_cert<S>(mut self, client_cert_dir: S) -> Self
+    where
+        S: ToString,
+    {
+        self.client_cert_dir = Some(client_cert_dir.to_string());
+        self
+    }
 }
 
 #[async_trait]
@@ -58,13 +104,12 @@ impl HyperwayEndpointFactory for HyperlaneTcpClient {
     ) -> Result<HyperwayEndpoint, SpaceErr> {
         let mut root_certs = RootCertStore::empty();
 
-        let ca_file = format!("{}/cert.der", self.cert_dir);
+        let ca_file = CertPaths::trust_anchor(&self.cert_dir);
         //        let key_file = format!("{}/key.der", self.cert_dir);
 
-        let certs = rustls_pemfile::certs(&mut BufReader::new(
-            &mut std::fs::File::open(ca_file).expect("cert file"),
-        ))
-        .collect::<Result<Vec<_>, _>>()?;
+        let certs = parse_certs(&tokio::fs::read(ca_file.as_str()).await.map_err(|err| {
+            SpaceErr::server_error(format!("could not read cert '{}': {}", ca_file, err))
+        })?)?;
         /*        let private_key =
                    rustls_pemfile::private_key(&mut BufReader::new(&mut File::open(key_file)?))?
                        .unwrap();
@@ -76,11 +121,20 @@ impl HyperwayEndpointFactory for HyperlaneTcpClient {
             root.add(c).expect("failed to add cert to root");
         }
 
-        let mut client_config = Arc::new(
-            rustls::ClientConfig::builder()
-                .with_root_certificates(root)
-                .with_no_client_auth(),
-        );
+        let builder = rustls::ClientConfig::builder().with_root_certificates(root);
+        let mut client_config = match self.client_cert_dir.as_ref() {
+            None => Arc::new(builder.with_no_client_auth()),
+            Some(client_cert_dir) => {
+                let identity = CertGenerator::read_from_dir(client_cert_dir.clone())
+                    .await
+                    .map_err(|e| SpaceErr::server_error(e.to_string()))?;
+                Arc::new(
+                    builder
+                        .with_client_auth_cert(identity.cert_chain()?, identity.private_key_der()?)
+                        .map_err(|e| SpaceErr::server_error(e.to_string()))?,
+                )
+            }
+        };
 
         /*
             ClientConfig::builder()
@@ -98,14 +152,22 @@ impl HyperwayEndpointFactory for HyperlaneTcpClient {
         let mut connector: TlsConnector = TlsConnector::from(client_config);
         let stream = tokio::net::TcpStream::connect(self.host.clone()).await?;
 
-        let host = self.host.split(":").next().unwrap().to_string();
-        let server_name = ServerName::try_from(host.clone()).unwrap();
+        let host = server_name(&self.host);
+        let server_name = ServerName::try_from(host.clone())
+            .map_err(|err| SpaceErr::server_error(format!("bad host '{}': {}", host, err)))?;
         let tokio_tls_connector = connector.connect(server_name, stream).await?;
 
-        let mut stream = FrameStream::new(tokio_tls_connector.into());
+        let mut stream = FrameStream::new(TlsStream::from(tokio_tls_connector));
 
-        let endpoint =
-            FrameMuxer::handshake(stream, status_tx.clone(), self.logger.clone()).await?;
+        let endpoint = FrameMuxer::handshake(
+            stream,
+            self.heartbeat.clone(),
+            self.limits.clone(),
+            self.metrics.clone(),
+            status_tx.clone(),
+            self.logger.clone(),
+        )
+        .await?;
 
         let wave: WaveVariantDef<PingCore> = self.knock.clone().into();
         let wave = wave.to_wave();
@@ -115,6 +177,99 @@ impl HyperwayEndpointFactory for HyperlaneTcpClient {
     }
 }
 
+/// the host part of `host:port`, without the brackets of an IPv6 address
+fn server_name(host: &String) -> String {
+    if let Some(rest) = host.strip_prefix('[') {
+        if let Some((ip, _)) = rest.split_once(']') {
+            return ip.to_string();
+        }
+    }
+    match host.rsplit_once(':') {
+        Some((name, _)) if !name.contains(':') => name.to_string(),
+        _ => host.clone(),
+    }
+}
+
+/// locations of a certificate and its private key. Either file may be PEM or DER
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CertPaths {
+    pub cert: String,
+    pub key: String,
+}
+
+impl CertPaths {
+    pub fn new<C: ToString, K: ToString>(cert: C, key: K) -> Self {
+        Self {
+            cert: cert.to_string(),
+            key: key.to_string(),
+        }
+    }
+
+    /// `cert.pem` & `key.pem` in `dir`, or the older `cert.der` & `key.der` if that is
+    /// all `dir` holds
+    pub fn in_dir<S: ToString>(dir: S) -> Self {
+        let dir = dir.to_string();
+        let pem = Self::new(format!("{}/cert.pem", dir), format!("{}/key.pem", dir));
+        let der = Self::new(format!("{}/cert.der", dir), format!("{}/key.der", dir));
+        if !pem.exists() && der.exists() {
+            der
+        } else {
+            pem
+        }
+    }
+
+    pub fn exists(&self) -> bool {
+        std::path::Path::new(&self.cert).exists() && std::path::Path::new(&self.key).exists()
+    }
+
+    /// the certificate a client should trust when `path` is handed to it. `path` may be
+    /// the certificate itself or a directory holding `ca.pem` (when the server cert is
+    /// issued by an authority) or the server's own certificate
+    pub fn trust_anchor(path: &String) -> String {
+        if std::path::Path::new(path).is_file() {
+            return path.clone();
+        }
+        let ca = format!("{}/ca.pem", path);
+        if std::path::Path::new(&ca).exists() {
+            return ca;
+        }
+        Self::in_dir(path).cert
+    }
+}
+
+/// the subject, issuer and lifetime of a certificate
+#[derive(Debug, Clone)]
+pub struct CertInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub subject_alt_names: Vec<String>,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+}
+
+impl CertInfo {
+    pub fn remaining(&self) -> chrono::Duration {
+        self.not_after - Utc::now()
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.not_after <= Utc::now()
+    }
+}
+
+/// parses one or more certificates from PEM, or a single certificate from DER
+pub fn parse_certs(data: &[u8]) -> Result<Vec<CertificateDer<'static>>, SpaceErr> {
+    if is_pem(data) {
+        Ok(rustls_pemfile::certs(&mut BufReader::new(data)).collect::<Result<Vec<_>, _>>()?)
+    } else {
+        Ok(vec![CertificateDer::from(data.to_vec())])
+    }
+}
+
+fn is_pem(data: &[u8]) -> bool {
+    String::from_utf8_lossy(&data[..data.len().min(512)]).contains("-----BEGIN")
+}
+
 pub struct CertGenerator {
     certs: Vec<u8>,
     key: Vec<u8>,
@@ -128,13 +283,98 @@ impl CertGenerator {
         Ok(Self { certs, key })
     }
 
+    /// generate a server certificate valid for `valid_days` from today. The certificate is
+    /// self-signed unless a `ca` is provided to issue it
+    pub fn gen_server(
+        subject_alt_names: Vec<String>,
+        valid_days: u32,
+        ca: Option<&CertGenerator>,
+    ) -> Result<Self, RcgenError> {
+        let mut params = CertificateParams::new(subject_alt_names.clone())?;
+        if let Some(name) = subject_alt_names.first() {
+            params.distinguished_name.push(DnType::CommonName, name.clone());
+        }
+        let today = Utc::now();
+        params.not_before = date_time_ymd(today.year(), today.month() as u8, today.day() as u8);
+        params.not_after =
+            params.not_before + Duration::from_secs(valid_days as u64 * 24 * 60 * 60);
+        params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth];
+        let key_pair = KeyPair::generate()?;
+        let cert = match ca {
+            None => params.self_signed(&key_pair)?,
+            Some(ca) => {
+                let (ca_cert, ca_key) = ca.issuer()?;
+                params.signed_by(&key_pair, &ca_cert, &ca_key)?
+            }
+        };
+        Ok(Self {
+            certs: cert.pem().into(),
+            key: key_pair.serialize_pem().into(),
+        })
+    }
+
+    /// generate a self-signed certificate authority for issuing client certificates
+    pub fn gen_ca<S: ToString>(name: S) -> Result<Self, RcgenError> {
+        let mut params = CertificateParams::new(vec![])?;
+        params
+            .distinguished_name
+            .push(DnType::CommonName, name.to_string());
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params.key_usages = vec![
+            KeyUsagePurpose::KeyCertSign,
+            KeyUsagePurpose::DigitalSignature,
+            KeyUsagePurpose::CrlSign,
+        ];
+        let key_pair = KeyPair::generate()?;
+        let cert = params.self_signed(&key_pair)?;
+        Ok(Self {
+            certs: cert.pem().into(),
+            key: key_pair.serialize_pem().into(),
+        })
+    }
+
+    /// issue a client certificate with subject common name `name` signed by `ca`
+    pub fn gen_client<S: ToString>(name: S, ca: &CertGenerator) -> Result<Self, RcgenError> {
+        let (ca_cert, ca_key) = ca.issuer()?;
+
+        let mut params = CertificateParams::new(vec![])?;
+        params
+            .distinguished_name
+            .push(DnType::CommonName, name.to_string());
+        params.key_usages = vec![KeyUsagePurpose::DigitalSignature];
+        params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ClientAuth];
+        let key_pair = KeyPair::generate()?;
+        let cert = params.signed_by(&key_pair, &ca_cert, &ca_key)?;
+        Ok(Self {
+            certs: cert.pem().into(),
+            key: key_pair.serialize_pem().into(),
+        })
+    }
+
+    /// rebuild a ca certificate from its PEM so it can sign others
+    fn issuer(&self) -> Result<(rcgen::Certificate, KeyPair), RcgenError> {
+        let key = KeyPair::from_pem(String::from_utf8_lossy(&self.key).as_ref())?;
+        let cert =
+            CertificateParams::from_ca_cert_pem(String::from_utf8_lossy(&self.certs).as_ref())?
+                .self_signed(&key)?;
+        Ok((cert, key))
+    }
+
     pub async fn read_from_dir(dir: String) -> Result<Self, Error> {
+        Self::load(&CertPaths::in_dir(dir)).await
+    }
+
+    pub async fn load(paths: &CertPaths) -> Result<Self, Error> {
         let mut certs_data = vec![];
-        let mut certs = File::open(format!("{}/cert.der", dir)).await?;
+        let mut certs = File::open(paths.cert.as_str())
+            .await
+            .map_err(|err| Error::new(format!("could not open '{}': {}", paths.cert, err)))?;
         certs.read_to_end(&mut certs_data).await?;
 
         let mut key_data = vec![];
-        let mut key = File::open(format!("{}/key.der", dir)).await?;
+        let mut key = File::open(paths.key.as_str())
+            .await
+            .map_err(|err| Error::new(format!("could not open '{}': {}", paths.key, err)))?;
         key.read_to_end(&mut key_data).await?;
 
         Ok(Self {
@@ -151,6 +391,26 @@ impl CertGenerator {
         self.key.clone()
     }
 
+    pub fn cert_chain(&self) -> Result<Vec<CertificateDer<'static>>, SpaceErr> {
+        parse_certs(self.certs.as_slice())
+    }
+
+    pub fn private_key_der(&self) -> Result<rustls::pki_types::PrivateKeyDer<'static>, SpaceErr> {
+        if is_pem(self.key.as_slice()) {
+            rustls_pemfile::private_key(&mut BufReader::new(self.key.as_slice()))?
+                .ok_or("no private key".into())
+        } else {
+            rustls::pki_types::PrivateKeyDer::try_from(self.key.clone())
+                .map_err(|err| SpaceErr::server_error(err))
+        }
+    }
+
+    /// describes the leaf certificate
+    pub fn info(&self) -> Result<CertInfo, Error> {
+        let chain = self.cert_chain()?;
+        cert_info(chain.first().ok_or("no certificate")?)
+    }
+
     pub async fn write_to_dir(&self, dir: String) -> io::Result<()> {
         let mut certs = File::create(format!("{}/cert.der", dir)).await?;
         certs.write_all(&self.certs()).await?;
@@ -158,6 +418,27 @@ impl CertGenerator {
         key.write_all(&self.private_key()).await?;
         Ok(())
     }
+
+    /// write the certificate and key as PEM. The key is only readable by the owner
+    pub async fn write_pem(&self, paths: &CertPaths) -> io::Result<()> {
+        for path in [&paths.cert, &paths.key] {
+            if let Some(dir) = std::path::Path::new(path).parent() {
+                tokio::fs::create_dir_all(dir).await?;
+            }
+        }
+        tokio::fs::write(paths.cert.as_str(), self.certs()).await?;
+        tokio::fs::write(paths.key.as_str(), self.private_key()).await?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            tokio::fs::set_permissions(
+                paths.key.as_str(),
+                std::fs::Permissions::from_mode(0o600),
+            )
+            .await?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Clone)]
@@ -188,24 +469,50 @@ impl Frame {
         )?)
     }
 
-    pub async fn from_stream<'a>(read: &'a mut TlsStream<TcpStream>) -> Result<Frame, SpaceErr> {
-        let size = read.read_u32().await? as usize;
-        let mut data = Vec::with_capacity(size as usize);
-
-        while data.len() < size {
-            read.read_buf(&mut data).await?;
-        }
+    /// reads the length prefix of the next frame
+    pub async fn read_len<R>(read: &mut R) -> Result<u32, SpaceErr>
+    where
+        R: AsyncRead + Unpin,
+    {
+        Ok(read.read_u32().await?)
+    }
 
+    /// reads a frame of `len` bytes.  The length must already be checked against the limit
+    pub async fn read_data<R>(read: &mut R, len: u32) -> Result<Frame, SpaceErr>
+    where
+        R: AsyncRead + Unpin,
+    {
+        let mut data = vec![0u8; len as usize];
+        read.read_exact(data.as_mut_slice()).await?;
         Ok(Self { data })
     }
 
-    pub async fn to_stream<'a>(&self, write: &'a mut TlsStream<TcpStream>) -> Result<(), SpaceErr> {
+    /// reads the next frame and rejects it before allocating if it is larger than `max_frame_size`
+    pub async fn from_stream<R>(read: &mut R, max_frame_size: u32) -> Result<Frame, SpaceErr>
+    where
+        R: AsyncRead + Unpin,
+    {
+        let len = Self::read_len(read).await?;
+        if len > max_frame_size {
+            return Err(Self::oversized(len, max_frame_size));
+        }
+        Self::read_data(read, len).await
+    }
+
+    pub async fn to_stream<W>(&self, write: &mut W) -> Result<(), SpaceErr>
+    where
+        W: AsyncWrite + Unpin,
+    {
         write.write_u32(self.data.len() as u32).await?;
         write.write_all(self.data.as_slice()).await?;
         write.flush().await?;
         Ok(())
     }
 
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
     pub fn to_wave(self) -> Result<Wave, SpaceErr> {
         Ok(bincode::deserialize(self.data.as_slice())?)
     }
@@ -215,10 +522,257 @@ impl Frame {
             data: bincode::serialize(&wave)?,
         })
     }
+
+    fn oversized(len: u32, max_frame_size: u32) -> SpaceErr {
+        SpaceErr::new(
+            413,
+            format!(
+                "frame of {} bytes exceeds the max frame size of {} bytes",
+                len, max_frame_size
+            ),
+        )
+    }
+}
+
+/// limits a [FrameMuxer] proposes during the handshake.  Both sides use the smaller of
+/// each value so that neither side ever has to accept more than it proposed
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct FrameLimits {
+    /// largest frame in bytes
+    pub max_frame_size: u32,
+    /// number of waves a side may send on each hyperway before it must wait for the
+    /// remote to grant more
+    pub credit: u32,
+}
+
+impl FrameLimits {
+    pub fn new(max_frame_size: u32, credit: u32) -> Self {
+        Self {
+            max_frame_size,
+            credit,
+        }
+    }
+
+    pub fn negotiate(&self, remote: &FrameLimits) -> FrameLimits {
+        Self {
+            max_frame_size: self.max_frame_size.min(remote.max_frame_size),
+            credit: self.credit.min(remote.credit).max(1),
+        }
+    }
+
+    fn to_frame(&self) -> Result<Frame, SpaceErr> {
+        Ok(Frame {
+            data: bincode::serialize(self)?,
+        })
+    }
+
+    fn from_frame(frame: Frame) -> Result<Self, SpaceErr> {
+        Ok(bincode::deserialize(frame.data.as_slice())?)
+    }
+}
+
+impl Default for FrameLimits {
+    fn default() -> Self {
+        Self::new(64 * 1024 * 1024, 256)
+    }
+}
+
+/// counters shared by every [FrameMuxer] of a client or server
+#[derive(Debug, Default)]
+pub struct FrameMetrics {
+    pub frames_in: AtomicU64,
+    pub frames_out: AtomicU64,
+    /// frames from the remote that exceeded the max frame size (the connection is closed)
+    pub oversized_in: AtomicU64,
+    /// outbound waves that exceeded the max frame size and were dropped
+    pub oversized_out: AtomicU64,
+    /// waves the remote sent without having credit (the connection is closed)
+    pub credit_violations: AtomicU64,
+}
+
+impl FrameMetrics {
+    fn inc(counter: &AtomicU64) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// keepalive for a [FrameMuxer].  A heartbeat is sent every `interval` and the connection
+/// is closed when nothing at all has been received from the remote for `idle_timeout`.
+/// Without it a half open TCP connection is never noticed
+#[derive(Clone, Debug)]
+pub struct Heartbeat {
+    pub interval: Duration,
+    pub idle_timeout: Duration,
+}
+
+impl Heartbeat {
+    pub fn new(interval: Duration, idle_timeout: Duration) -> Self {
+        Self {
+            interval,
+            idle_timeout,
+        }
+    }
+
+    /// `interval` and `idle_timeout` in seconds, each falling back to the [Default].
+    /// The idle timeout should span a few intervals so one late heartbeat is not fatal
+    pub fn from_secs(interval: Option<u64>, idle_timeout: Option<u64>) -> Self {
+        let default = Self::default();
+        Self {
+            interval: interval.map(Duration::from_secs).unwrap_or(default.interval),
+            idle_timeout: idle_timeout
+                .map(Duration::from_secs)
+                .unwrap_or(default.idle_timeout),
+        }
+    }
+
+    fn ping() -> Result<Wave, SpaceErr> {
+        let mut proto = DirectedProto::ping();
+        proto.from(HEARTBEAT.clone().to_surface());
+        proto.to(HEARTBEAT.clone().to_surface());
+        proto.method(ExtMethod::new("Heartbeat")?);
+        Ok(proto.build()?.to_wave())
+    }
+
+    fn pong(ping: Wave) -> Result<Wave, SpaceErr> {
+        Ok(ping
+            .to_directed()?
+            .reflection()?
+            .make(ReflectedCore::ok(), HEARTBEAT.clone().to_surface())
+            .to_wave())
+    }
+
+    fn is_heartbeat(wave: &Wave) -> bool {
+        let to = wave.to();
+        to.is_single() && to.unwrap_single().point == *HEARTBEAT
+    }
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(15), Duration::from_secs(45))
+    }
+}
+
+/// the most waves a [FrameMuxer] has outstanding across all of its hyperways, whatever
+/// credit each one has left.  It bounds what a remote can queue by spreading its waves
+/// over many hyperways
+const MAX_OUTSTANDING: u32 = 1024;
+
+/// credit is kept per hyperway, the surfaces a wave goes between, so that waves piling
+/// up for one particle never hold back the others sharing the connection
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+struct Way {
+    from: Surface,
+    /// `None` for a ripple to more than one recipient
+    to: Option<Surface>,
+}
+
+impl Way {
+    fn of(wave: &Wave) -> Self {
+        Self {
+            from: wave.from().clone(),
+            to: wave.to().single_or().ok(),
+        }
+    }
+}
+
+/// the credit left on each [Way] out of `limit`.  A way at its `limit` has no entry
+struct Credits {
+    limit: u32,
+    ways: HashMap<Way, u32>,
+    /// credit taken across every way that hasn't been given back
+    outstanding: u32,
+}
+
+impl Credits {
+    fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            ways: HashMap::new(),
+            outstanding: 0,
+        }
+    }
+
+    fn get(&self, way: &Way) -> u32 {
+        self.ways.get(way).cloned().unwrap_or(self.limit)
+    }
+
+    fn available(&self, way: &Way) -> bool {
+        self.get(way) > 0 && self.outstanding < MAX_OUTSTANDING
+    }
+
+    /// false when `way` has no credit left
+    fn take(&mut self, way: &Way) -> bool {
+        if !self.available(way) {
+            return false;
+        }
+        self.ways.insert(way.clone(), self.get(way) - 1);
+        self.outstanding += 1;
+        true
+    }
+
+    fn give(&mut self, way: &Way, credit: u32) {
+        let before = self.get(way);
+        let after = before.saturating_add(credit).min(self.limit);
+        if after == self.limit {
+            self.ways.remove(way);
+        } else {
+            self.ways.insert(way.clone(), after);
+        }
+        self.outstanding = self.outstanding.saturating_sub(after - before);
+    }
+}
+
+/// grants the remote permission to send `credit` more waves on a [Way]
+struct Credit;
+
+impl Credit {
+    fn grant(way: &Way, credit: u32) -> Result<Wave, SpaceErr> {
+        let mut map = SubstanceMap::new();
+        map.insert("from".to_string(), Substance::Surface(way.from.clone()));
+        if let Some(to) = &way.to {
+            map.insert("to".to_string(), Substance::Surface(to.clone()));
+        }
+        map.insert("credit".to_string(), Substance::Int(credit as i64));
+        let mut proto = DirectedProto::signal();
+        proto.from(CREDIT.clone().to_surface());
+        proto.to(CREDIT.clone().to_surface());
+        proto.method(ExtMethod::new("Credit")?);
+        proto.body(Substance::Map(map));
+        Ok(proto.build()?.to_wave())
+    }
+
+    fn is_grant(wave: &Wave) -> bool {
+        let to = wave.to();
+        to.is_single() && to.unwrap_single().point == *CREDIT
+    }
+
+    /// the way and credit of a grant, `None` if it is malformed
+    fn granted(wave: &Wave) -> Option<(Way, u32)> {
+        let map = match wave.clone().to_directed().ok()?.body().clone() {
+            Substance::Map(map) => map,
+            _ => return None,
+        };
+        let from = match map.get("from") {
+            Some(Substance::Surface(from)) => from.clone(),
+            _ => return None,
+        };
+        let to = match map.get("to") {
+            Some(Substance::Surface(to)) => Some(to.clone()),
+            _ => None,
+        };
+        match map.get("credit") {
+            Some(Substance::Int(credit)) => Some((Way { from, to }, (*credit).max(0) as u32)),
+            _ => None,
+        }
+    }
 }
 
 pub struct FrameMuxer {
     stream: FrameStream,
+    heartbeat: Heartbeat,
+    limits: FrameLimits,
+    metrics: Arc<FrameMetrics>,
     tx: mpsc::Sender<Wave>,
     rx: mpsc::Receiver<Wave>,
     terminate_rx: mpsc::Receiver<()>,
@@ -228,6 +782,9 @@ pub struct FrameMuxer {
 impl FrameMuxer {
     pub async fn handshake(
         mut stream: FrameStream,
+        heartbeat: Heartbeat,
+        limits: FrameLimits,
+        metrics: Arc<FrameMetrics>,
         status_tx: mpsc::Sender<HyperConnectionDetails>,
         logger: Logger,
     ) -> Result<HyperwayEndpoint, SpaceErr> {
@@ -265,15 +822,30 @@ impl FrameMuxer {
             .into()));
         }
 
-        Ok(Self::new(stream, logger))
+        stream.write_frame(limits.to_frame()?).await?;
+        let remote = tokio::time::timeout(Duration::from_secs(30), stream.frame()).await??;
+        let limits = limits.negotiate(&FrameLimits::from_frame(remote)?);
+
+        Ok(Self::new(stream, heartbeat, limits, metrics, logger))
     }
 
-    pub fn new(stream: FrameStream, logger: Logger) -> HyperwayEndpoint {
-        let (in_tx, in_rx) = mpsc::channel(1024);
+    pub fn new(
+        stream: FrameStream,
+        heartbeat: Heartbeat,
+        limits: FrameLimits,
+        metrics: Arc<FrameMetrics>,
+        logger: Logger,
+    ) -> HyperwayEndpoint {
+        // credit is only granted once a wave gets in here.  Waves waiting for room are
+        // bounded by MAX_OUTSTANDING
+        let (in_tx, in_rx) = mpsc::channel(limits.credit as usize);
         let (out_tx, out_rx) = mpsc::channel(1024);
         let (terminate_tx, mut terminate_rx) = mpsc::channel(1);
         let mut muxer = Self {
             stream,
+            heartbeat,
+            limits,
+            metrics,
             tx: in_tx,
             rx: out_rx,
             terminate_rx,
@@ -282,7 +854,7 @@ impl FrameMuxer {
         {
             let logger = logger.clone();
             tokio::spawn(async move {
-                logger.result(muxer.mux().await).unwrap();
+                logger.result(muxer.mux().await).unwrap_or_default();
             });
         }
 
@@ -294,52 +866,224 @@ impl FrameMuxer {
         HyperwayEndpoint::new_with_drop(out_tx, in_rx, oneshot_terminate_tx, logger)
     }
 
-    pub async fn mux(mut self) -> Result<(), SpaceErr> {
-        loop {
-            tokio::select! {
-                wave = self.rx.recv() => {
-                    match wave {
-                        None => {
-                            self.logger.warn("rx discon");
-                            break
-                        },
-                        Some(wave) => {
-                           self.stream.write_wave(wave.clone()).await?;
+    pub async fn mux(self) -> Result<(), SpaceErr> {
+        let FrameMuxer {
+            stream,
+            heartbeat,
+            limits,
+            metrics,
+            tx,
+            mut rx,
+            mut terminate_rx,
+            logger,
+        } = self;
+        let (mut read, mut write) = tokio::io::split(stream.stream);
+
+        // frames are read in their own task because a partially read frame
+        // would be lost if the read were cancelled by another select! branch
+        let (frame_tx, mut frame_rx) = mpsc::channel(1024);
+        let reader = {
+            let metrics = metrics.clone();
+            let max_frame_size = limits.max_frame_size;
+            tokio::spawn(async move {
+                loop {
+                    let frame = match Frame::read_len(&mut read).await {
+                        Ok(len) if len > max_frame_size => {
+                            FrameMetrics::inc(&metrics.oversized_in);
+                            Err(Frame::oversized(len, max_frame_size))
                         }
+                        Ok(len) => Frame::read_data(&mut read, len).await,
+                        Err(err) => Err(err),
+                    };
+                    let err = frame.is_err();
+                    if frame_tx.send(frame).await.is_err() || err {
+                        break;
                     }
                 }
-                wave = self.stream.read_wave() => {
-                    match wave {
-                       Ok(wave) => {
-                            self.tx.send(wave).await?;
-                       },
-                       Err(err) => {
-                            self.logger.error(format!("read stream err: {}",err.to_string()));
+            })
+        };
+
+        let mut ticker = tokio::time::interval_at(
+            Instant::now() + heartbeat.interval,
+            heartbeat.interval,
+        );
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut last_received = Instant::now();
+
+        // waves this side may still send on each way
+        let mut credit = Credits::new(limits.credit);
+        // waves the remote may still send on each way
+        let mut remote_credit = Credits::new(limits.credit);
+        // waves delivered on each way since credit was last granted to the remote
+        let mut consumed: HashMap<Way, u32> = HashMap::new();
+        let mut consumed_total = 0u32;
+        // received waves waiting for room in `tx`. never more than the credit of the remote
+        let mut pending: VecDeque<(Way, Wave)> = VecDeque::new();
+        // outbound frames waiting for credit on their way
+        let mut blocked: HashMap<Way, VecDeque<Frame>> = HashMap::new();
+        let mut blocked_len = 0usize;
+
+        let result = async {
+            loop {
+                tokio::select! {
+                    wave = rx.recv(), if blocked_len < MAX_OUTSTANDING as usize => {
+                        match wave {
+                            None => {
+                                logger.warn("rx discon");
+                                break
+                            },
+                            Some(wave) => {
+                                let frame = Frame::from_wave(wave.clone())?;
+                                if frame.len() > limits.max_frame_size as usize {
+                                    FrameMetrics::inc(&metrics.oversized_out);
+                                    let err = Frame::oversized(frame.len() as u32, limits.max_frame_size);
+                                    logger.warn(format!("dropped outbound wave: {}", err.to_string()));
+                                    // let the sender know rather than leaving it to time out
+                                    if wave.is_directed() {
+                                        let wave = wave.to_directed()?;
+                                        // a ripple to many recipients has no single surface to reflect from
+                                        if let (false, Ok(from)) = (wave.is_signal(), wave.to().single_or()) {
+                                            let reflect = wave.reflection()?.make(ReflectedCore::status(413), from);
+                                            let tx = tx.clone();
+                                            tokio::spawn(async move {
+                                                tx.send(reflect.to_wave()).await.unwrap_or_default();
+                                            });
+                                        }
+                                    }
+                                    continue;
+                                }
+                                let way = Way::of(&wave);
+                                let queue = blocked.entry(way.clone()).or_default();
+                                if queue.is_empty() && credit.take(&way) {
+                                    frame.to_stream(&mut write).await?;
+                                    FrameMetrics::inc(&metrics.frames_out);
+                                } else {
+                                    queue.push_back(frame);
+                                    blocked_len += 1;
+                                }
+                                if queue.is_empty() {
+                                    blocked.remove(&way);
+                                }
+                            }
+                        }
+                    }
+                    frame = frame_rx.recv() => {
+                        match frame {
+                           Some(Ok(frame)) => {
+                                last_received = Instant::now();
+                                FrameMetrics::inc(&metrics.frames_in);
+                                let wave = frame.to_wave()?;
+                                if Heartbeat::is_heartbeat(&wave) {
+                                    if wave.is_directed() {
+                                        Frame::from_wave(Heartbeat::pong(wave)?)?.to_stream(&mut write).await?;
+                                    }
+                                } else if Credit::is_grant(&wave) {
+                                    if let Some((way, granted)) = Credit::granted(&wave) {
+                                        credit.give(&way, granted);
+                                    }
+                                    // a grant on one way may free room for waves blocked on another
+                                    let ways: Vec<Way> = blocked.keys().cloned().collect();
+                                    for way in ways {
+                                        if let Some(queue) = blocked.get_mut(&way) {
+                                            while !queue.is_empty() && credit.take(&way) {
+                                                if let Some(frame) = queue.pop_front() {
+                                                    frame.to_stream(&mut write).await?;
+                                                    FrameMetrics::inc(&metrics.frames_out);
+                                                    blocked_len -= 1;
+                                                }
+                                            }
+                                            if queue.is_empty() {
+                                                blocked.remove(&way);
+                                            }
+                                        }
+                                    }
+                                } else {
+                                    let way = Way::of(&wave);
+                                    if !remote_credit.take(&way) {
+                                        FrameMetrics::inc(&metrics.credit_violations);
+                                        logger.error("remote sent a wave without credit");
+                                        break;
+                                    }
+                                    pending.push_back((way, wave));
+                                }
+                           },
+                           Some(Err(err)) => {
+                                logger.error(format!("read stream err: {}",err.to_string()));
+                                break;
+                           }
+                           None => break
+                        }
+                    }
+                    permit = tx.reserve(), if !pending.is_empty() => {
+                        let permit = permit?;
+                        if let Some((way, wave)) = pending.pop_front() {
+                            permit.send(wave);
+                            let count = consumed.entry(way.clone()).or_default();
+                            *count += 1;
+                            consumed_total += 1;
+                            // grant every way once the remote nears MAX_OUTSTANDING so waves
+                            // spread thinly over many ways can't starve it of credit
+                            let ways: Vec<Way> = if consumed_total >= MAX_OUTSTANDING / 2 {
+                                consumed.keys().cloned().collect()
+                            } else if *count >= (limits.credit / 2).max(1) {
+                                vec![way]
+                            } else {
+                                vec![]
+                            };
+                            for way in ways {
+                                let count = consumed.remove(&way).unwrap_or_default();
+                                consumed_total -= count;
+                                Frame::from_wave(Credit::grant(&way, count)?)?.to_stream(&mut write).await?;
+                                remote_credit.give(&way, count);
+                            }
+                        }
+                    }
+                    _ = ticker.tick() => {
+                        if last_received.elapsed() >= heartbeat.idle_timeout && frame_rx.is_empty() {
+                            logger.warn(format!("nothing received for {:?} closing connection", heartbeat.idle_timeout));
                             break;
-                       }
+                        }
+                        Frame::from_wave(Heartbeat::ping()?)?.to_stream(&mut write).await?;
                     }
-                }
-                _ = self.terminate_rx.recv() => {
-                     self.logger.warn(format!("terminated"));
-                     return Ok(())
+                    _ = terminate_rx.recv() => {
+                         logger.warn(format!("terminated"));
+                         break;
                     }
+                }
             }
+            Ok::<(), SpaceErr>(())
         }
-        Ok(())
+        .await;
+
+        reader.abort();
+        result
     }
 }
 
+/// a byte stream frames can be muxed over (normally a [TlsStream])
+pub trait FrameIo: AsyncRead + AsyncWrite + Unpin + Send + 'static {}
+
+impl<S> FrameIo for S where S: AsyncRead + AsyncWrite + Unpin + Send + 'static {}
+
 pub struct FrameStream {
-    stream: TlsStream<TcpStream>,
+    stream: Box<dyn FrameIo>,
 }
 
 impl FrameStream {
-    pub fn new(stream: TlsStream<TcpStream>) -> Self {
-        Self { stream }
+    /// frames exchanged before the [FrameLimits] are negotiated are never larger than this
+    pub const HANDSHAKE_FRAME_SIZE: u32 = 64 * 1024;
+
+    pub fn new<S>(stream: S) -> Self
+    where
+        S: FrameIo,
+    {
+        Self {
+            stream: Box::new(stream),
+        }
     }
 
     pub async fn frame(&mut self) -> Result<Frame, SpaceErr> {
-        Frame::from_stream(&mut self.stream).await
+        Frame::from_stream(&mut self.stream, Self::HANDSHAKE_FRAME_SIZE).await
     }
 
     pub async fn read_version(&mut self) -> Result<semver::Version, SpaceErr> {
@@ -371,19 +1115,29 @@ impl FrameStream {
     }
 }
 
-pub struct HyperlaneTcpServerApi {}
+pub struct HyperlaneTcpServerApi {
+    metrics: Arc<FrameMetrics>,
+}
 
 impl HyperlaneTcpServerApi {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(metrics: Arc<FrameMetrics>) -> Self {
+        Self { metrics }
+    }
+
+    pub fn metrics(&self) -> Arc<FrameMetrics> {
+        self.metrics.clone()
     }
 }
 
 pub struct HyperlaneTcpServer {
     gate: Arc<HyperGateSelector>,
-    listener: TcpListener,
+    listeners: Vec<TcpListener>,
     logger: Logger,
     acceptor: TlsAcceptor,
+    client_auth: bool,
+    heartbeat: Heartbeat,
+    limits: FrameLimits,
+    metrics: Arc<FrameMetrics>,
     server_kill_tx: broadcast::Sender<()>,
     server_kill_rx: broadcast::Receiver<()>,
 }
@@ -395,26 +1149,59 @@ impl HyperlaneTcpServer {
         gate: Arc<HyperGateSelector>,
         logger: Logger,
     ) -> Result<Self, Error> {
-        let (server_kill_tx, server_kill_rx) = broadcast::channel(1);
+        Self::new_with_client_ca(port, CertPaths::in_dir(cert_dir), None, gate, logger).await
+    }
 
-        // load certificate
-        let cert_path = format!("{}/cert.der", cert_dir);
-        let key_path = format!("{}/key.der", cert_dir);
+    /// when `client_ca` (a PEM file) is set the server requires mutual TLS and rejects
+    /// any client that does not present a certificate issued by that authority
+    pub async fn new_with_client_ca(
+        port: u16,
+        cert: CertPaths,
+        client_ca: Option<String>,
+        gate: Arc<HyperGateSelector>,
+        logger: Logger,
+    ) -> Result<Self, Error> {
+        let addrs = vec![SocketAddr::from((Ipv4Addr::LOCALHOST, port))];
+        Self::bind(addrs, cert, client_ca, gate, logger).await
+    }
 
-        let mut file = BufReader::new(std::fs::File::open(cert_path)?);
-        let ca_certs = rustls_pemfile::certs(&mut file)
-            .map(|i| i.unwrap())
-            .collect();
+    /// listen on every address in `addrs`. See [bind_listeners] for how IPv6 addresses
+    /// are bound
+    pub async fn bind(
+        addrs: Vec<SocketAddr>,
+        cert: CertPaths,
+        client_ca: Option<String>,
+        gate: Arc<HyperGateSelector>,
+        logger: Logger,
+    ) -> Result<Self, Error> {
+        let (server_kill_tx, server_kill_rx) = broadcast::channel(1);
 
-        let mut file = BufReader::new(std::fs::File::open(key_path)?);
-        let private_key =
-            rustls_pemfile::private_key(&mut file)?.ok_or(Error::new("no private key"))?;
+        // load certificate
+        let identity = CertGenerator::load(&cert).await?;
+        let ca_certs = identity.cert_chain()?;
+        let private_key = identity.private_key_der()?;
+
+        let builder = match client_ca.as_ref() {
+            None => ServerConfig::builder().with_no_client_auth(),
+            Some(client_ca) => {
+                let mut roots = RootCertStore::empty();
+                for cert in parse_certs(&std::fs::read(client_ca)?)? {
+                    roots
+                        .add(cert)
+                        .map_err(|e| Error::new(format!("bad client ca: {}", e)))?;
+                }
+                let verifier = WebPkiClientVerifier::builder(Arc::new(roots))
+                    .build()
+                    .map_err(|e| Error::new(format!("bad client ca: {}", e)))?;
+                ServerConfig::builder().with_client_cert_verifier(verifier)
+            }
+        };
+        let client_auth = client_ca.is_some();
 
         let server_config = Arc::new(
-            ServerConfig::builder()
-                .with_no_client_auth()
+            builder
                 .with_single_cert(ca_certs, private_key)
-                .expect("bad certifcate/key"), /*            ServerConfig::builder()
+                .map_err(|e| Error::new(format!("bad certificate/key: {}", e)))?, /*            ServerConfig::builder()
                                                               .with_safe_default_cipher_suites()
                                                               .with_safe_default_kx_groups()
                                                               .with_safe_default_protocol_versions()
@@ -427,33 +1214,73 @@ impl HyperlaneTcpServer {
         );
 
         let mut acceptor = TlsAcceptor::from(server_config);
-        let listener = TcpListener::bind(format!("127.0.0.1:{}", port))
-            .await
-            .unwrap();
+        let listeners = bind_listeners(&addrs)?;
 
         Ok(Self {
             acceptor,
             gate,
-            listener,
+            listeners,
             logger,
+            client_auth,
+            heartbeat: Heartbeat::default(),
+            limits: FrameLimits::default(),
+            metrics: Arc::new(FrameMetrics::default()),
             server_kill_tx,
             server_kill_rx,
         })
     }
 
+    pub fn with_heartbeat(mut self, heartbeat: Heartbeat) -> Self {
+        self.heartbeat = heartbeat;
+        self
+    }
+
+    pub fn with_limits(mut self, limits: FrameLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn local_addrs(&self) -> Vec<SocketAddr> {
+        self.listeners
+            .iter()
+            .filter_map(|listener| listener.local_addr().ok())
+            .collect()
+    }
+
     pub fn start(mut self) -> Result<HyperlaneTcpServerApi, Error> {
+        let api = HyperlaneTcpServerApi::new(self.metrics.clone());
         tokio::spawn(async move {
             self.run().await;
         });
-        Ok(HyperlaneTcpServerApi::new())
+        Ok(api)
     }
 
     async fn run(mut self) {
+        let listeners = std::mem::take(&mut self.listeners);
+        let server = Arc::new(self);
+        for listener in listeners {
+            let server = server.clone();
+            tokio::spawn(async move { server.accept(listener).await });
+        }
+    }
+
+    async fn accept(&self, listener: TcpListener) {
         loop {
-            let stream = self.listener.accept().await.unwrap().0;
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(err) => {
+                    self.logger
+                        .error(format!("control port accept failed: {}", err.to_string()));
+                    continue;
+                }
+            };
             let acceptor = self.acceptor.clone();
             let gate = self.gate.clone();
             let logger = self.logger.clone();
+            let client_auth = self.client_auth;
+            let heartbeat = self.heartbeat.clone();
+            let limits = self.limits.clone();
+            let metrics = self.metrics.clone();
             let mut server_kill_rx = self.server_kill_tx.subscribe();
 
             tokio::spawn(async move {
@@ -461,12 +1288,29 @@ impl HyperlaneTcpServer {
                     stream: TcpStream,
                     acceptor: TlsAcceptor,
                     gate: Arc<HyperGateSelector>,
+                    client_auth: bool,
+                    heartbeat: Heartbeat,
+                    limits: FrameLimits,
+                    metrics: Arc<FrameMetrics>,
                     server_kill_rx: broadcast::Receiver<()>,
                     logger: Logger,
                 ) -> Result<(), Error> {
                     let mut stream = acceptor.accept(stream).await?;
 
-                    let mut stream = FrameStream::new(stream.into());
+                    let cert = match client_auth {
+                        true => {
+                            let der = stream
+                                .get_ref()
+                                .1
+                                .peer_certificates()
+                                .and_then(|certs| certs.first())
+                                .ok_or("client did not present a certificate")?;
+                            Some(client_cert(der)?)
+                        }
+                        false => None,
+                    };
+
+                    let mut stream = FrameStream::new(TlsStream::from(stream));
 
                     let (status_tx, mut status_rx): (
                         mpsc::Sender<HyperConnectionDetails>,
@@ -484,14 +1328,24 @@ impl HyperlaneTcpServer {
                             }
                         });
                     }
-                    let mut mux = FrameMuxer::handshake(stream, status_tx, logger.clone()).await?;
+                    let mut mux = FrameMuxer::handshake(
+                        stream,
+                        heartbeat,
+                        limits,
+                        metrics,
+                        status_tx,
+                        logger.clone(),
+                    )
+                    .await?;
 
                     let knock = tokio::time::timeout(Duration::from_secs(30), mux.rx.recv())
                         .await?
                         .ok_or("expected wave")?;
                     let knock = knock.to_directed()?;
                     if let Substance::Knock(knock) = knock.body() {
-                        let mut endpoint = gate.knock(knock.clone()).await?;
+                        let mut knock = knock.clone();
+                        knock.cert = cert;
+                        let mut endpoint = logger.result(gate.knock(knock).await)?;
                         mux.connect(endpoint);
                     } else {
                         let msg = format!(
@@ -503,12 +1357,128 @@ impl HyperlaneTcpServer {
 
                     Ok(())
                 }
-                serve(stream, acceptor, gate, server_kill_rx, logger).await;
+                serve(
+                    stream,
+                    acceptor,
+                    gate,
+                    client_auth,
+                    heartbeat,
+                    limits,
+                    metrics,
+                    server_kill_rx,
+                    logger,
+                )
+                .await;
             });
         }
     }
 }
 
+/// binds a listener to each of `addrs`. An IPv6 wildcard (`::`) listed on its own is
+/// dual-stack and accepts IPv4 connections as well. When IPv4 addresses are listed beside
+/// IPv6 ones the IPv6 sockets are restricted to IPv6 so both can bind the same port
+pub fn bind_listeners(addrs: &Vec<SocketAddr>) -> Result<Vec<TcpListener>, Error> {
+    let v6only = addrs.iter().any(|addr| addr.is_ipv4());
+    let mut listeners = vec![];
+    for addr in addrs {
+        let socket = bind_socket(addr, v6only)
+            .map_err(|err| Error::new(format!("could not bind to '{}': {}", addr, err)))?;
+        socket.listen(1024)?;
+        let listener: std::net::TcpListener = socket.into();
+        listener.set_nonblocking(true)?;
+        listeners.push(TcpListener::from_std(listener)?);
+    }
+    Ok(listeners)
+}
+
+/// the addresses of `addrs` that another process is already listening on.  Each address
+/// is checked the way [bind_listeners] would bind it so a wildcard held by another
+/// process is reported as a conflict for any specific address on the same port
+pub fn addrs_in_use(addrs: &Vec<SocketAddr>) -> Vec<SocketAddr> {
+    let v6only = addrs.iter().any(|addr| addr.is_ipv4());
+    addrs
+        .iter()
+        .filter(|addr| match bind_socket(addr, v6only) {
+            Ok(_) => false,
+            Err(err) => err.kind() == io::ErrorKind::AddrInUse,
+        })
+        .cloned()
+        .collect()
+}
+
+fn bind_socket(addr: &SocketAddr, v6only: bool) -> io::Result<socket2::Socket> {
+    let socket = socket2::Socket::new(
+        socket2::Domain::for_address(*addr),
+        socket2::Type::STREAM,
+        Some(socket2::Protocol::TCP),
+    )?;
+    if addr.is_ipv6() {
+        socket.set_only_v6(v6only)?;
+    }
+    // same as tokio's `TcpListener::bind` so a restart is not blocked by TIME_WAIT
+    #[cfg(unix)]
+    socket.set_reuse_address(true)?;
+    socket.bind(&(*addr).into())?;
+    Ok(socket)
+}
+
+/// reads the subject, issuer, names and validity of `der`
+pub fn cert_info(der: &CertificateDer) -> Result<CertInfo, Error> {
+    let (_, cert) = x509_parser::parse_x509_certificate(der.as_ref())
+        .map_err(|e| Error::new(format!("could not parse certificate: {}", e)))?;
+    let time = |time: &x509_parser::time::ASN1Time| {
+        DateTime::<Utc>::from_timestamp(time.timestamp(), 0)
+            .ok_or(Error::new("certificate validity is out of range"))
+    };
+    let mut subject_alt_names = vec![];
+    if let Ok(Some(ext)) = cert.subject_alternative_name() {
+        for name in ext.value.general_names.iter() {
+            match name {
+                GeneralName::DNSName(name) => subject_alt_names.push(name.to_string()),
+                GeneralName::IPAddress(ip) => {
+                    if let Ok(ip) = <[u8; 4]>::try_from(*ip) {
+                        subject_alt_names.push(std::net::IpAddr::from(ip).to_string());
+                    } else if let Ok(ip) = <[u8; 16]>::try_from(*ip) {
+                        subject_alt_names.push(std::net::IpAddr::from(ip).to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(CertInfo {
+        subject: cert.subject().to_string(),
+        issuer: cert.issuer().to_string(),
+        subject_alt_names,
+        not_before: time(&cert.validity().not_before)?,
+        not_after: time(&cert.validity().not_after)?,
+    })
+}
+
+/// extract the identity names of a verified client certificate
+pub fn client_cert(der: &CertificateDer) -> Result<ClientCert, Error> {
+    let (_, cert) = x509_parser::parse_x509_certificate(der.as_ref())
+        .map_err(|e| Error::new(format!("could not parse client certificate: {}", e)))?;
+    let subject = cert
+        .subject()
+        .iter_common_name()
+        .next()
+        .and_then(|cn| cn.as_str().ok())
+        .map(|cn| cn.to_string());
+    let mut sans = vec![];
+    if let Ok(Some(ext)) = cert.subject_alternative_name() {
+        for name in ext.value.general_names.iter() {
+            match name {
+                GeneralName::DNSName(name) => sans.push(name.to_string()),
+                GeneralName::URI(name) => sans.push(name.to_string()),
+                GeneralName::RFC822Name(name) => sans.push(name.to_string()),
+                _ => {}
+            }
+        }
+    }
+    Ok(ClientCert::new(subject, sans))
+}
+
 pub fn add(left: usize, right: usize) -> usize {
     left + right
 }
@@ -576,7 +1546,23 @@ impl From<&str> for Error {
 
 #[cfg(test)]
 mod tests {
-    use crate::hyperspace::hyperlane::tcp::{CertGenerator, Error, HyperlaneTcpClient, HyperlaneTcpServer};
+    use crate::hyperspace::hyperlane::tcp::{
+        addrs_in_use, bind_listeners, cert_info, server_name, CertGenerator, CertPaths, Error,
+        Frame, FrameLimits, FrameMetrics, FrameMuxer, FrameStream, Heartbeat, HyperlaneTcpClient,
+        HyperlaneTcpServer,
+    };
+    use crate::hyperspace::hyperlane::HyperwayEndpoint;
+    use crate::hyperspace::test_util::temp_dir;
+    use crate::space::err::StatusErr;
+    use crate::space::log::Logger;
+    use crate::space::substance::Substance;
+    use crate::space::wave::core::ext::ExtMethod;
+    use crate::space::wave::{DirectedProto, Wave};
+    use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+    use tokio::io::{AsyncWriteExt, DuplexStream};
+    use tokio::sync::mpsc::error::TryRecvError;
     use crate::hyperspace::hyperlane::test_util::{
         LargeFrameTest, SingleInterchangePlatform, WaveTest, FAE, LESS,
     };
@@ -609,6 +1595,260 @@ mod tests {
 
      */
 
+    fn muxer(stream: DuplexStream, limits: FrameLimits) -> (HyperwayEndpoint, Arc<FrameMetrics>) {
+        muxer_with_heartbeat(stream, limits, Heartbeat::default())
+    }
+
+    fn muxer_with_heartbeat(
+        stream: DuplexStream,
+        limits: FrameLimits,
+        heartbeat: Heartbeat,
+    ) -> (HyperwayEndpoint, Arc<FrameMetrics>) {
+        let metrics = Arc::new(FrameMetrics::default());
+        let endpoint = FrameMuxer::new(
+            FrameStream::new(stream),
+            heartbeat,
+            limits,
+            metrics.clone(),
+            Logger::default(),
+        );
+        (endpoint, metrics)
+    }
+
+    fn signal(size: usize) -> Wave {
+        let mut proto = DirectedProto::signal();
+        proto.from(LESS.to_surface());
+        proto.to(FAE.to_surface());
+        proto.method(ExtMethod::new("Test").unwrap());
+        proto.body(Substance::Bin(vec![0u8; size]));
+        proto.build().unwrap().to_wave()
+    }
+
+    #[tokio::test]
+    async fn test_oversized_frame() {
+        let (mut less, mut fae) = tokio::io::duplex(1024);
+        less.write_u32(1_000_000).await.unwrap();
+        let err = Frame::from_stream(&mut fae, 1024).await;
+        assert_eq!(err.err().unwrap().status(), 413);
+
+        // negotiation never raises a limit
+        let limits = FrameLimits::new(1024, 8).negotiate(&FrameLimits::new(4096, 2));
+        assert_eq!(limits, FrameLimits::new(1024, 2));
+    }
+
+    #[tokio::test]
+    async fn test_muxer_drops_oversized_outbound() {
+        let (less, fae) = tokio::io::duplex(64 * 1024);
+        let limits = FrameLimits::new(1024, 8);
+        let (less, less_metrics) = muxer(less, limits.clone());
+        let (mut fae, _) = muxer(fae, limits);
+
+        less.tx.send(signal(4096)).await.unwrap();
+        less.tx.send(signal(16)).await.unwrap();
+
+        let wave = tokio::time::timeout(Duration::from_secs(5), fae.rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(wave.body(), &Substance::Bin(vec![0u8; 16]));
+        assert_eq!(less_metrics.oversized_out.load(Ordering::Relaxed), 1);
+        assert_eq!(less_metrics.frames_out.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_muxer_rejects_oversized_inbound() {
+        let (mut less, fae) = tokio::io::duplex(64 * 1024);
+        let (mut fae, metrics) = muxer(fae, FrameLimits::new(1024, 8));
+
+        less.write_u32(1_000_000).await.unwrap();
+        less.write_all(&[0u8; 1024]).await.unwrap();
+
+        let closed = tokio::time::timeout(Duration::from_secs(5), fae.rx.recv())
+            .await
+            .unwrap();
+        assert!(closed.is_none());
+        assert_eq!(metrics.oversized_in.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_muxer_credit() {
+        // a well behaved remote waits for credit so a slow reader is never flooded
+        let (less, fae) = tokio::io::duplex(64 * 1024);
+        let limits = FrameLimits::new(1024, 2);
+        let (less, less_metrics) = muxer(less, limits.clone());
+        let (mut fae, fae_metrics) = muxer(fae, limits);
+        for _ in 0..10 {
+            less.tx.send(signal(16)).await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        // two in fae's channel plus the two it granted credit for
+        assert_eq!(less_metrics.frames_out.load(Ordering::Relaxed), 4);
+        for _ in 0..10 {
+            tokio::time::timeout(Duration::from_secs(5), fae.rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+        }
+        assert_eq!(fae_metrics.credit_violations.load(Ordering::Relaxed), 0);
+
+        // a remote that ignores credit is disconnected
+        let (mut less, fae) = tokio::io::duplex(64 * 1024);
+        let (mut fae, metrics) = muxer(fae, FrameLimits::new(1024, 2));
+        for _ in 0..10 {
+            Frame::from_wave(signal(16))
+                .unwrap()
+                .to_stream(&mut less)
+                .await
+                .unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(metrics.credit_violations.load(Ordering::Relaxed), 1);
+        while let Some(_) = tokio::time::timeout(Duration::from_secs(5), fae.rx.recv())
+            .await
+            .unwrap()
+        {}
+    }
+
+    #[tokio::test]
+    async fn test_muxer_credit_per_way() {
+        // a way that has used up its credit doesn't hold back the others on the connection
+        let (less, fae) = tokio::io::duplex(64 * 1024);
+        let limits = FrameLimits::new(1024, 2);
+        let (less, less_metrics) = muxer(less, limits.clone());
+        let (mut fae, fae_metrics) = muxer(fae, limits);
+        for _ in 0..10 {
+            less.tx.send(signal(16)).await.unwrap();
+        }
+        let mut proto = DirectedProto::signal();
+        proto.from(FAE.to_surface());
+        proto.to(LESS.to_surface());
+        proto.method(ExtMethod::new("Test").unwrap());
+        let wave = proto.build().unwrap().to_wave();
+        less.tx.send(wave).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(less_metrics.frames_out.load(Ordering::Relaxed), 5);
+        for _ in 0..11 {
+            tokio::time::timeout(Duration::from_secs(5), fae.rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+        }
+        assert_eq!(fae_metrics.credit_violations.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn test_server_cert() {
+        let dir = temp_dir("certs").to_str().unwrap().to_string();
+
+        let ca = CertGenerator::gen_ca("test ca").unwrap();
+        let cert = CertGenerator::gen_server(
+            vec!["starlane.example".to_string(), "10.0.0.1".to_string()],
+            30,
+            Some(&ca),
+        )
+        .unwrap();
+        let paths = CertPaths::in_dir(dir.clone());
+        assert!(paths.cert.ends_with("cert.pem"));
+        cert.write_pem(&paths).await.unwrap();
+        assert_eq!(CertPaths::trust_anchor(&dir), paths.cert);
+        std::fs::write(format!("{}/ca.pem", dir), ca.certs()).unwrap();
+        assert_eq!(CertPaths::trust_anchor(&dir), format!("{}/ca.pem", dir));
+
+        let info = CertGenerator::load(&paths).await.unwrap().info().unwrap();
+        assert_eq!(
+            info.subject_alt_names,
+            vec!["starlane.example".to_string(), "10.0.0.1".to_string()]
+        );
+        assert!(info.issuer.contains("test ca"));
+        assert!(!info.is_expired());
+        assert!(info.remaining().num_days() <= 30);
+        assert!(info.remaining().num_days() >= 28);
+
+        // a DER encoded cert & key load the same as PEM
+        let der = CertPaths::new(format!("{}/cert.der", dir), format!("{}/key.der", dir));
+        let chain = cert.cert_chain().unwrap();
+        std::fs::write(&der.cert, chain.first().unwrap().as_ref()).unwrap();
+        std::fs::write(&der.key, cert.private_key_der().unwrap().secret_der()).unwrap();
+        let loaded = CertGenerator::load(&der).await.unwrap();
+        assert_eq!(loaded.cert_chain().unwrap(), chain);
+        assert!(loaded.private_key_der().is_ok());
+        assert_eq!(
+            cert_info(chain.first().unwrap()).unwrap().not_after,
+            info.not_after
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bind_addresses() {
+        let port = port_check::free_local_ipv4_port().unwrap();
+        let loopback = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+        let any = SocketAddr::from((Ipv4Addr::UNSPECIFIED, port));
+        let other = SocketAddr::from((Ipv4Addr::new(127, 0, 0, 2), port));
+
+        let listeners = bind_listeners(&vec![loopback]).unwrap();
+        assert_eq!(addrs_in_use(&vec![loopback, other]), vec![loopback]);
+        assert_eq!(addrs_in_use(&vec![any]), vec![any]);
+        assert!(bind_listeners(&vec![loopback]).is_err());
+        drop(listeners);
+        assert!(addrs_in_use(&vec![loopback]).is_empty());
+
+        // skipped when the host has no IPv6
+        let wildcard = SocketAddr::from((Ipv6Addr::UNSPECIFIED, port));
+        if let Ok(listeners) = bind_listeners(&vec![wildcard]) {
+            // a lone IPv6 wildcard also holds the port for IPv4
+            assert_eq!(addrs_in_use(&vec![loopback]), vec![loopback]);
+            drop(listeners);
+
+            let listeners = bind_listeners(&vec![loopback, wildcard]).unwrap();
+            assert_eq!(listeners.len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_server_name() {
+        assert_eq!(server_name(&"localhost:4343".to_string()), "localhost");
+        assert_eq!(server_name(&"10.0.0.1:4343".to_string()), "10.0.0.1");
+        assert_eq!(server_name(&"[::1]:4343".to_string()), "::1");
+        assert_eq!(server_name(&"localhost".to_string()), "localhost");
+    }
+
+    #[test]
+    fn test_heartbeat() {
+        let ping = Heartbeat::ping().unwrap();
+        assert!(ping.is_directed());
+        assert!(Heartbeat::is_heartbeat(&ping));
+
+        let pong = Heartbeat::pong(ping).unwrap();
+        assert!(pong.is_reflected());
+        assert!(Heartbeat::is_heartbeat(&pong));
+
+        let heartbeat = Heartbeat::from_secs(Some(5), None);
+        assert_eq!(heartbeat.interval, Duration::from_secs(5));
+        assert_eq!(heartbeat.idle_timeout, Heartbeat::default().idle_timeout);
+    }
+
+    #[tokio::test]
+    async fn test_muxer_detects_half_open() {
+        let heartbeat = Heartbeat::new(Duration::from_millis(50), Duration::from_millis(250));
+
+        // a live remote answers every heartbeat so the connection outlives the idle timeout
+        let (less, fae) = tokio::io::duplex(64 * 1024);
+        let (mut less, _) = muxer_with_heartbeat(less, FrameLimits::default(), heartbeat.clone());
+        let (_fae, _) = muxer_with_heartbeat(fae, FrameLimits::default(), heartbeat.clone());
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(less.rx.try_recv().err(), Some(TryRecvError::Empty));
+
+        // a remote that is still connected but never sends anything is given up on
+        let (less, _silent) = tokio::io::duplex(64 * 1024);
+        let (mut less, _) = muxer_with_heartbeat(less, FrameLimits::default(), heartbeat);
+        let closed = tokio::time::timeout(Duration::from_secs(5), less.rx.recv())
+            .await
+            .unwrap();
+        assert!(closed.is_none());
+    }
+
     //#[tokio::test]
     async fn test_tcp() -> Result<(), Error> {
         let platform = SingleInterchangePlatform::new().await;
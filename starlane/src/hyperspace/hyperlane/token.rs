@@ -0,0 +1,387 @@
+use crate::hyperspace::hyperlane::{HyperAuthenticator, HyperwayStub};
+use crate::space::err::SpaceErr;
+use crate::space::hyper::Knock;
+use crate::space::point::Point;
+use crate::space::substance::{Substance, Token};
+use crate::space::wave::Agent;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// a token issued to an [Agent]. the `token` itself is the secret
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct TokenRecord {
+    pub token: Token,
+    pub agent: Point,
+    pub created: DateTime<Utc>,
+    #[serde(default)]
+    pub expires: Option<DateTime<Utc>>,
+    /// the [TokenStore::scoped] store that issued the token
+    #[serde(default)]
+    pub scope: Option<Point>,
+}
+
+impl TokenRecord {
+    pub fn new(agent: Point, ttl: Option<Duration>) -> Result<Self, SpaceErr> {
+        let created = Utc::now();
+        let expires = match ttl {
+            None => None,
+            Some(ttl) => Some(
+                created
+                    + chrono::Duration::from_std(ttl)
+                        .map_err(|err| SpaceErr::new(500, err.to_string()))?,
+            ),
+        };
+        Ok(Self {
+            token: Token::new_uuid(),
+            agent,
+            created,
+            expires,
+            scope: None,
+        })
+    }
+
+    pub fn is_expired(&self) -> bool {
+        match &self.expires {
+            None => false,
+            Some(expires) => *expires <= Utc::now(),
+        }
+    }
+}
+
+/// tokens persisted as yaml in a single file.  The file is re-read on every lookup
+/// so tokens created or revoked by the cli take effect on a running server
+#[derive(Debug, Clone)]
+pub struct TokenStore {
+    path: String,
+    scope: Option<Point>,
+}
+
+impl TokenStore {
+    pub fn new(path: String) -> Self {
+        Self { path, scope: None }
+    }
+
+    /// a view of the same file that only sees (and only revokes) the tokens it issued
+    /// itself.  The unscoped store still sees every token so the control hyperlane
+    /// accepts them
+    pub fn scoped(&self, scope: Point) -> Self {
+        Self {
+            path: self.path.clone(),
+            scope: Some(scope),
+        }
+    }
+
+    pub fn path(&self) -> &String {
+        &self.path
+    }
+
+    pub fn scope(&self) -> Option<&Point> {
+        self.scope.as_ref()
+    }
+
+    fn in_scope(&self, record: &TokenRecord) -> bool {
+        match &self.scope {
+            None => true,
+            Some(scope) => record.scope.as_ref() == Some(scope),
+        }
+    }
+
+    pub fn list(&self) -> Result<Vec<TokenRecord>, SpaceErr> {
+        Ok(self
+            .all()?
+            .into_iter()
+            .filter(|record| self.in_scope(record))
+            .collect())
+    }
+
+    /// every record in the file whatever its scope
+    fn all(&self) -> Result<Vec<TokenRecord>, SpaceErr> {
+        if !std::fs::exists(&self.path)? {
+            return Ok(vec![]);
+        }
+        let content = std::fs::read_to_string(&self.path)?;
+        serde_yaml::from_str(content.as_str()).map_err(|err| {
+            SpaceErr::new(
+                500,
+                format!("could not process token store '{}': {}", self.path, err),
+            )
+        })
+    }
+
+    pub fn get(&self, token: &Token) -> Result<Option<TokenRecord>, SpaceErr> {
+        Ok(self
+            .list()?
+            .into_iter()
+            .find(|record| record.token == *token && !record.is_expired()))
+    }
+
+    pub fn create(&self, agent: Point, ttl: Option<Duration>) -> Result<TokenRecord, SpaceErr> {
+        let mut record = TokenRecord::new(agent, ttl)?;
+        record.scope = self.scope.clone();
+        self.write(|| {
+            let mut records = self.all()?;
+            records.retain(|record| !record.is_expired());
+            records.push(record.clone());
+            self.save(&records)?;
+            Ok(record)
+        })
+    }
+
+    /// removes every token that matches `token` or was issued to `agent`
+    /// and returns the number of tokens that were removed
+    pub fn revoke(&self, token: Option<&Token>, agent: Option<&Point>) -> Result<usize, SpaceErr> {
+        self.revoke_where(|record| {
+            let token = token.map(|token| record.token == *token).unwrap_or(false);
+            let agent = agent.map(|agent| record.agent == *agent).unwrap_or(false);
+            token || agent
+        })
+    }
+
+    /// removes every token this store can see
+    pub fn revoke_all(&self) -> Result<usize, SpaceErr> {
+        self.revoke_where(|_| true)
+    }
+
+    fn revoke_where<F>(&self, revoke: F) -> Result<usize, SpaceErr>
+    where
+        F: Fn(&TokenRecord) -> bool,
+    {
+        self.write(|| {
+            let records = self.all()?;
+            let before = records.len();
+            let records: Vec<TokenRecord> = records
+                .into_iter()
+                .filter(|record| !(self.in_scope(record) && revoke(record)))
+                .collect();
+            let revoked = before - records.len();
+            if revoked > 0 {
+                self.save(&records)?;
+            }
+            Ok(revoked)
+        })
+    }
+
+    /// run the read-modify-write `f` holding an exclusive advisory lock on `{path}.lock`
+    /// so writers in other processes, like the cli and a running server, never overwrite
+    /// each other's changes.  The store itself can't be locked since it is renamed into place
+    fn write<F, R>(&self, f: F) -> Result<R, SpaceErr>
+    where
+        F: FnOnce() -> Result<R, SpaceErr>,
+    {
+        let path = PathBuf::from(format!("{}.lock", self.path));
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .open(path)?;
+        let mut lock = fd_lock::RwLock::new(file);
+        let _write = lock.write()?;
+        f()
+    }
+
+    /// [TokenStore::create] on a blocking thread so the store's file i/o never
+    /// stalls the async runtime
+    pub async fn issue(
+        &self,
+        agent: Point,
+        ttl: Option<Duration>,
+    ) -> Result<TokenRecord, SpaceErr> {
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || store.create(agent, ttl))
+            .await
+            .map_err(|err| SpaceErr::server_error(err.to_string()))?
+    }
+
+    /// [TokenStore::get] on a blocking thread
+    pub async fn lookup(&self, token: &Token) -> Result<Option<TokenRecord>, SpaceErr> {
+        let store = self.clone();
+        let token = token.clone();
+        tokio::task::spawn_blocking(move || store.get(&token))
+            .await
+            .map_err(|err| SpaceErr::server_error(err.to_string()))?
+    }
+
+    fn save(&self, records: &Vec<TokenRecord>) -> Result<(), SpaceErr> {
+        let ser =
+            serde_yaml::to_string(records).map_err(|err| SpaceErr::new(500, err.to_string()))?;
+        let path: PathBuf = self.path.clone().into();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        // write then rename so a server reading the store never sees a partial file
+        let tmp = path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4().simple()));
+        std::fs::write(&tmp, ser)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o600))?;
+        }
+        std::fs::rename(tmp, path)?;
+        Ok(())
+    }
+}
+
+/// authenticates a [Knock] that carries a [Substance::Token] against a [TokenStore] and
+/// assigns the [Agent] the token was issued to.  A [Knock] with any other auth is passed
+/// through to the `inner` authenticator unchanged
+#[derive(Clone)]
+pub struct TokenStoreHyperAuthenticator<A>
+where
+    A: HyperAuthenticator,
+{
+    pub store: Option<TokenStore>,
+    pub inner: A,
+}
+
+impl<A> TokenStoreHyperAuthenticator<A>
+where
+    A: HyperAuthenticator,
+{
+    pub fn new(store: Option<TokenStore>, inner: A) -> Self {
+        Self { store, inner }
+    }
+}
+
+#[async_trait]
+impl<A> HyperAuthenticator for TokenStoreHyperAuthenticator<A>
+where
+    A: HyperAuthenticator,
+{
+    async fn auth(&self, knock: Knock) -> Result<HyperwayStub, SpaceErr> {
+        let token = match &*knock.auth {
+            Substance::Token(token) => token.clone(),
+            _ => return self.inner.auth(knock).await,
+        };
+        let store = self
+            .store
+            .as_ref()
+            .ok_or(SpaceErr::new(401, "token authentication is not enabled"))?;
+        let record = store
+            .lookup(&token)
+            .await?
+            .ok_or(SpaceErr::new(401, "invalid or expired token"))?;
+        let mut stub = self.inner.auth(knock).await?;
+        stub.agent = Agent::Point(record.agent);
+        Ok(stub)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::hyperspace::hyperlane::token::{TokenStore, TokenStoreHyperAuthenticator};
+    use crate::hyperspace::hyperlane::{AnonHyperAuthenticator, HyperAuthenticator};
+    use crate::hyperspace::test_util::temp_dir;
+    use crate::space::hyper::Knock;
+    use crate::space::loc::ToSurface;
+    use crate::space::point::Point;
+    use crate::space::substance::{Substance, Token};
+    use crate::space::wave::Agent;
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    #[tokio::test]
+    pub async fn test_token_store_authenticator() {
+        let dir = temp_dir("tokens");
+        let store = TokenStore::new(dir.join("tokens.yaml").to_str().unwrap().to_string());
+        let agent = Point::from_str("users:scott").unwrap();
+        let record = store.create(agent.clone(), None).unwrap();
+        let expired = store
+            .create(agent.clone(), Some(Duration::from_secs(0)))
+            .unwrap();
+        assert_eq!(store.list().unwrap().len(), 2);
+
+        let auth =
+            TokenStoreHyperAuthenticator::new(Some(store.clone()), AnonHyperAuthenticator::new());
+        let remote = Point::from_str("remote").unwrap().to_surface();
+        let knock = |auth: Substance| {
+            let mut knock = Knock::default();
+            knock.remote = Some(remote.clone());
+            knock.auth = Box::new(auth);
+            knock
+        };
+
+        let stub = auth
+            .auth(knock(Substance::Token(record.token.clone())))
+            .await
+            .unwrap();
+        assert_eq!(stub.agent, Agent::Point(agent.clone()));
+
+        let stub = auth.auth(knock(Substance::Empty)).await.unwrap();
+        assert_eq!(stub.agent, Agent::Anonymous);
+
+        assert!(auth
+            .auth(knock(Substance::Token(expired.token)))
+            .await
+            .is_err());
+        assert!(auth
+            .auth(knock(Substance::Token(Token::new_uuid())))
+            .await
+            .is_err());
+
+        assert_eq!(store.revoke(Some(&record.token), None).unwrap(), 1);
+        assert!(auth
+            .auth(knock(Substance::Token(record.token)))
+            .await
+            .is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    pub fn test_scoped_token_store() {
+        let dir = temp_dir("tokens");
+        let store = TokenStore::new(dir.join("tokens.yaml").to_str().unwrap().to_string());
+        let userbase = Point::from_str("localhost:users").unwrap();
+        let scoped = store.scoped(userbase.clone());
+        let agent = Point::from_str("localhost:users:scott").unwrap();
+
+        let unscoped = store.create(agent.clone(), None).unwrap();
+        let issued = scoped.create(agent.clone(), None).unwrap();
+        assert_eq!(None, unscoped.scope);
+        assert_eq!(Some(userbase), issued.scope);
+
+        // the scoped store only sees its own tokens while the unscoped store sees both
+        assert_eq!(vec![issued.clone()], scoped.list().unwrap());
+        assert_eq!(None, scoped.get(&unscoped.token).unwrap());
+        assert_eq!(Some(issued.clone()), store.get(&issued.token).unwrap());
+        assert_eq!(2, store.list().unwrap().len());
+
+        assert_eq!(0, scoped.revoke(Some(&unscoped.token), None).unwrap());
+        assert_eq!(1, scoped.revoke_all().unwrap());
+        assert_eq!(vec![unscoped], store.list().unwrap());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    pub async fn test_token_store_concurrent_issue() {
+        let dir = temp_dir("tokens");
+        let store = TokenStore::new(dir.join("tokens.yaml").to_str().unwrap().to_string());
+        let agent = Point::from_str("users:scott").unwrap();
+
+        // no issue may be lost to another writing the store at the same time
+        let mut issues = vec![];
+        for _ in 0..16 {
+            let store = store.clone();
+            let agent = agent.clone();
+            issues.push(tokio::spawn(async move { store.issue(agent, None).await }));
+        }
+        for issue in issues {
+            let record = issue.await.unwrap().unwrap();
+            assert_eq!(
+                Some(record.clone()),
+                store.lookup(&record.token).await.unwrap()
+            );
+        }
+        assert_eq!(store.list().unwrap().len(), 16);
+
+        // only the store and its lock are left behind
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
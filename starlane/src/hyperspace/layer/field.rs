@@ -361,7 +361,7 @@ impl PipeEx {
                 }
             }
             DirectedKind::Signal => {
-                transmitter.direct(proto).await?;
+                transmitter.direct::<_, ()>(proto).await?;
                 Ok(())
             }
         }
@@ -401,7 +401,7 @@ impl PipeEx {
                 }
             }
             DirectedKind::Signal => {
-                transmitter.direct(proto).await?;
+                transmitter.direct::<()>(proto).await?;
                 Ok(())
             }
         }
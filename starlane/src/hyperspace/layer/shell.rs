@@ -282,7 +282,7 @@ impl CommandExecutor {
         for transfer in &ctx.transfers {
             env.set_file(transfer.id.clone(), transfer.content.clone())
         }
-        let mut command: Command = command.to_resolved(&self.env)?;
+        let mut command: Command = command.to_resolved(&env)?;
 
         if let Command::Create(create) = &mut command {
             if ctx.transfers.len() == 1 {
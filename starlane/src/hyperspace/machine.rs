@@ -1,9 +1,10 @@
 use crate::hyperspace::driver::DriverErr;
 use crate::hyperspace::err::{err, HyperErr2};
+use crate::hyperspace::hyperlane::capture::CaptureGate;
 use crate::hyperspace::hyperlane::{
-    HyperClient, HyperConnectionDetails, HyperGate, HyperGateSelector, Hyperway, HyperwayEndpoint,
-    HyperwayEndpointFactory, HyperwayInterchange, LayerTransform, MountInterchangeGate,
-    SimpleGreeter,
+    ClusterHyperAuthenticator, HyperClient, HyperConnectionDetails, HyperGate, HyperGateSelector,
+    Hyperway, HyperwayEndpoint, HyperwayEndpointFactory, HyperwayInterchange, HyperwayStub,
+    LayerTransform, MountInterchangeGate, SimpleGreeter,
 };
 use crate::hyperspace::platform::Platform;
 use crate::hyperspace::reg::Registry;
@@ -12,6 +13,7 @@ use crate::hyperspace::service::{
 };
 use crate::hyperspace::star::{HyperStar, HyperStarApi, HyperStarSkel, HyperStarTx, StarCon, StarTemplate};
 use crate::hyperspace::template::Templates;
+use crate::hyperspace::topology::TopologyConfig;
 use async_trait::async_trait;
 use dashmap::DashMap;
 use futures::future::{join_all, select_all, BoxFuture};
@@ -21,7 +23,7 @@ use crate::space::artifact::asynch::{ArtErr, ArtifactFetcher, Artifacts};
 use crate::space::command::direct::create::KindTemplate;
 use crate::space::err::{HyperSpatialError, SpaceErr, SpatialError};
 use crate::space::hyper::{InterchangeKind, Knock};
-use crate::space::kind::{BaseKind, Kind, StarSub};
+use crate::space::kind::{BaseKind, Kind, StarStub, StarSub};
 use crate::space::loc::{Layer, MachineName, StarHandle, StarKey, Surface, ToPoint, ToSurface};
 use crate::space::log::Logger;
 use crate::space::particle::property::PropertiesConfig;
@@ -111,7 +113,7 @@ impl MachineApi {
         self.tx
             .send(MachineCall::EndpointFactory { from, to, rtn })
             .await;
-        Ok(rtn_rx.await?)
+        Ok(rtn_rx.await??)
     }
 
     pub async fn add_interchange(
@@ -126,6 +128,12 @@ impl MachineApi {
         rtn_rx.await?
     }
 
+    pub async fn remove_interchange(&self, kind: InterchangeKind) -> Result<(), SpaceErr> {
+        let (rtn, rtn_rx) = oneshot::channel();
+        self.tx.send(MachineCall::RemoveGate { kind, rtn }).await?;
+        rtn_rx.await?
+    }
+
     pub async fn knock(&self, knock: Knock) -> Result<HyperwayEndpoint, SpaceErr> {
         let (rtn, rtn_rx) = oneshot::channel();
         self.tx.send(MachineCall::Knock { knock, rtn }).await;
@@ -199,6 +207,7 @@ where
     pub call_rx: mpsc::Receiver<MachineCall>,
     pub termination_broadcast_tx: broadcast::Sender<Result<(), String>>,
     pub logger: Logger,
+    pub services: Templates<ServiceTemplate>,
 }
 
 impl<P> Machine<P>
@@ -220,7 +229,8 @@ where
         call_tx: mpsc::Sender<MachineCall>,
         call_rx: mpsc::Receiver<MachineCall>,
     ) -> Result<MachineApi, HyperErr2> {
-        let template = platform.machine_template();
+        let template = platform.machine_template()?;
+        let services = template.services.clone();
         let machine_name = platform.machine_name();
         let artifacts = platform.artifact_hub();
         let registry = platform.global_registry().await?;
@@ -261,7 +271,11 @@ where
         };
 
         let mut stars = HashMap::new();
-        let mut gates = Arc::new(DashMap::new());
+        let mut gates: Arc<DashMap<InterchangeKind, Arc<dyn HyperGate>>> =
+            Arc::new(DashMap::new());
+        let (eavesdrop_tx, _) = broadcast::channel(1024);
+        // local stars jump through their gates so only the stars of other machines knock
+        let cluster_machines = Arc::new(platform.cluster_machines());
         let star_templates = template.with_machine_star(machine_name);
 
         for star_template in star_templates {
@@ -276,6 +290,18 @@ where
                 HyperStarSkel::new(star_template.clone(), skel.clone(), &mut star_tx).await;
 
             let mut drivers = platform.drivers_builder(&star_template.kind);
+            star_template
+                .drivers
+                .apply(&star_template.kind, &mut drivers, |sub| {
+                    platform.drivers_builder(sub)
+                })
+                .map_err(|err| {
+                    err!(
+                        "drivers of star '{}' could not be overridden: {}",
+                        star_template.key.to_string(),
+                        err.to_string()
+                    )
+                })?;
 
             let mut interchange =
                 HyperwayInterchange::new(machine_star.point.clone(), push_mark!(logger));
@@ -288,9 +314,10 @@ where
             let hyperway_endpoint = hyperway.hyperway_endpoint_far(None).await;
             interchange.add(hyperway).await;
             interchange.singular_to(star_hop.clone());
+            interchange.eavesdrop_to(eavesdrop_tx.clone()).await;
 
             let interchange = Arc::new(interchange);
-            let auth = skel.platform.star_auth(&star_template.key)?;
+            let auth = ClusterHyperAuthenticator::new(cluster_machines.clone());
             let greeter = SimpleGreeter::new(star_hop.clone(), star_port.clone());
             let gate: Arc<dyn HyperGate> = Arc::new(MountInterchangeGate::new(
                 auth,
@@ -336,6 +363,22 @@ where
             stars.insert(star_point.clone(), star_api);
         }
 
+        {
+            let machine_key = StarKey::machine(skel.name.clone());
+            let auth = skel.platform.star_auth(&machine_key)?;
+            let logger = push_loc!((skel.logger, machine_star.point.push("capture").unwrap()));
+            gates.insert(
+                InterchangeKind::Capture,
+                Arc::new(CaptureGate::new(
+                    auth,
+                    skel.registry.clone(),
+                    Arc::new(skel.platform.capture_agents()),
+                    eavesdrop_tx,
+                    logger,
+                )),
+            );
+        }
+
         let mut gate_selector = Arc::new(HyperGateSelector::new(gates));
         skel.platform.start_services(&gate_selector).await;
         let gate: Arc<dyn HyperGate> = gate_selector.clone();
@@ -419,6 +462,7 @@ where
             call_tx,
             call_rx,
             termination_broadcast_tx: term_tx,
+            services,
         };
 
         /// SETUP ARTIFAC
@@ -496,6 +540,9 @@ where
                 MachineCall::AddGate { kind, gate, rtn } => {
                     rtn.send(self.gate_selector.add(kind.clone(), gate));
                 }
+                MachineCall::RemoveGate { kind, rtn } => {
+                    rtn.send(self.gate_selector.remove(&kind));
+                }
                 MachineCall::Knock { knock, rtn } => {
                     let gate_selector = self.gate_selector.clone();
                     let logger =
@@ -505,12 +552,33 @@ where
                             .unwrap_or_default();
                     });
                 }
+                MachineCall::Jump { kind, stub, rtn } => {
+                    let gate_selector = self.gate_selector.clone();
+                    tokio::spawn(async move {
+                        rtn.send(gate_selector.jump(kind, stub).await)
+                            .unwrap_or_default();
+                    });
+                }
                 MachineCall::EndpointFactory { from, to, rtn } => {
-                    let factory = Box::new(MachineHyperwayEndpointFactory::new(
-                        from,
-                        to,
-                        self.call_tx.clone(),
-                    ));
+                    // stars hosted by another machine in the cluster are reached over the
+                    // platform's remote connection
+                    let factory: Result<Box<dyn HyperwayEndpointFactory>, SpaceErr> =
+                        if self.stars.contains_key(&to.to_point()) {
+                            Ok(Box::new(MachineHyperwayEndpointFactory::new(
+                                from,
+                                to,
+                                self.call_tx.clone(),
+                            )))
+                        } else {
+                            match self
+                                .skel
+                                .platform
+                                .remote_connection_factory_for_star(&from, &to)
+                            {
+                                Ok(factory) => Ok(Box::new(factory)),
+                                Err(err) => Err(SpaceErr::server_error(err.to_string())),
+                            }
+                        };
                     rtn.send(factory).unwrap_or_default();
                 }
                 #[cfg(test)]
@@ -532,14 +600,11 @@ where
                     rtn.send(self.skel.registry.clone());
                 }
                 MachineCall::SelectService { selector, rtn } => {
-                    match self
-                        .skel
-                        .platform
-                        .machine_template()
-                        .services
-                        .select_one(&selector)
-                    {
-                        None => rtn.send(Err(ServiceErr::NoTemplate(selector))),
+                    match self.services.select(&selector).first() {
+                        None => rtn.send(Err(ServiceErr::NoTemplate {
+                            candidates: self.services.near_misses(&selector),
+                            selector,
+                        })),
                         Some(template) => rtn.send(Ok(template.clone())),
                     };
                 }
@@ -563,14 +628,24 @@ pub enum MachineCall {
         gate: Arc<dyn HyperGate>,
         rtn: oneshot::Sender<Result<(), SpaceErr>>,
     },
+    RemoveGate {
+        kind: InterchangeKind,
+        rtn: oneshot::Sender<Result<(), SpaceErr>>,
+    },
     Knock {
         knock: Knock,
         rtn: oneshot::Sender<Result<HyperwayEndpoint, SpaceErr>>,
     },
+    /// enter a gate of this machine without knocking
+    Jump {
+        kind: InterchangeKind,
+        stub: HyperwayStub,
+        rtn: oneshot::Sender<Result<HyperwayEndpoint, SpaceErr>>,
+    },
     EndpointFactory {
         from: StarKey,
         to: StarKey,
-        rtn: oneshot::Sender<Box<dyn HyperwayEndpointFactory>>,
+        rtn: oneshot::Sender<Result<Box<dyn HyperwayEndpointFactory>, SpaceErr>>,
     },
     SelectService {
         selector: ServiceSelector,
@@ -632,69 +707,96 @@ impl MachineTemplate {
     }
 }
 
-impl Default for MachineTemplate {
-    fn default() -> Self {
-        let constellation = "central".to_string();
-
-        let mut central = StarTemplate::new(StarKey::central(), StarSub::Central);
-        let mut nexus = StarTemplate::new(
-            StarKey::new(&constellation, &StarHandle::name("nexus")),
-            StarSub::Nexus,
-        );
-        let mut supe = StarTemplate::new(
-            StarKey::new(&constellation, &StarHandle::name("super")),
-            StarSub::Super,
-        );
-        let mut maelstrom = StarTemplate::new(
-            StarKey::new(&constellation, &StarHandle::name("maelstrom")),
-            StarSub::Maelstrom,
-        );
-        let mut scribe = StarTemplate::new(
-            StarKey::new(&constellation, &StarHandle::name("scribe")),
-            StarSub::Scribe,
-        );
-        let mut jump = StarTemplate::new(
-            StarKey::new(&constellation, &StarHandle::name("jump")),
-            StarSub::Jump,
-        );
-        let mut fold = StarTemplate::new(
-            StarKey::new(&constellation, &StarHandle::name("fold")),
-            StarSub::Fold,
-        );
+impl MachineTemplate {
+    /// a template for a machine that hosts `stars` in a cluster whose other machines
+    /// host `remotes`
+    pub fn cluster(stars: Vec<StarStub>, remotes: Vec<StarStub>) -> Self {
+        let stars = Self::connect_stars(stars, remotes);
+        let services = Self::default_services();
+        Self { stars, services }
+    }
 
-        nexus.receive(central.to_stub());
-        nexus.receive(supe.to_stub());
-        nexus.receive(maelstrom.to_stub());
-        nexus.receive(scribe.to_stub());
-        nexus.receive(jump.to_stub());
-        nexus.receive(fold.to_stub());
-
-        central.connect(nexus.to_stub());
-        supe.connect(nexus.to_stub());
-        maelstrom.connect(nexus.to_stub());
-        scribe.connect(nexus.to_stub());
-        jump.connect(nexus.to_stub());
-        fold.connect(nexus.to_stub());
-
-        let mut stars = vec![];
-        stars.push(central);
-        stars.push(nexus);
-        stars.push(supe);
-        stars.push(maelstrom);
-        stars.push(scribe);
-        stars.push(jump);
-        stars.push(fold);
+    /// a standalone machine hosting the stars of `topology`
+    pub fn topology(topology: &TopologyConfig) -> Result<Self, SpaceErr> {
+        let stars = topology.stars()?;
+        let services = Self::default_services();
+        Ok(Self { stars, services })
+    }
 
+    /// the local filestore service for each driver that keeps its content in one.
+    /// A Repo shares its filestore with the Bundles and Artifacts published into it
+    fn default_services() -> Templates<ServiceTemplate> {
         let config = service_conf();
-        let filestore = ServiceTemplate {
-            name: "repo-filestore".to_string(),
+        let filestore = |name: &str, base: BaseKind| ServiceTemplate {
+            name: name.to_string(),
             kind: ServiceKind::FileStore,
-            driver: OptSelector::Selector(KindSelector::from_base(BaseKind::Repo)),
-            config,
+            driver: OptSelector::Selector(KindSelector::from_base(base)),
+            config: config.clone(),
         };
-        let services = Templates::new(vec![filestore]);
+        Templates::new(vec![
+            filestore("repo-filestore", BaseKind::Repo),
+            filestore("repo-filestore", BaseKind::Bundle),
+            filestore("repo-filestore", BaseKind::Artifact),
+            filestore("filestore", BaseKind::FileStore),
+            filestore("filestore", BaseKind::File),
+        ])
+    }
 
-        Self { stars, services }
+    /// `services` are selected ahead of the built in `repo-filestore`
+    pub fn with_services(mut self, mut services: Vec<ServiceTemplate>) -> Self {
+        services.append(&mut self.services);
+        self.services = Templates::new(services);
+        self
+    }
+
+    /// every star that is not a Nexus connects to every Nexus (local or remote) and
+    /// every Nexus receives every star that is not a Nexus
+    pub fn connect_stars(stars: Vec<StarStub>, remotes: Vec<StarStub>) -> Vec<StarTemplate> {
+        let nexus = |stub: &StarStub| stub.kind == StarSub::Nexus;
+        let all: Vec<StarStub> = stars
+            .iter()
+            .chain(remotes.iter())
+            .filter(|stub| stub.kind != StarSub::Machine)
+            .cloned()
+            .collect();
+
+        stars
+            .into_iter()
+            .map(|stub| {
+                let mut star = StarTemplate::new(stub.key.clone(), stub.kind.clone());
+                for other in all.iter().filter(|other| other.key != stub.key) {
+                    if nexus(&stub) && !nexus(other) {
+                        star.receive(other.clone());
+                    } else if !nexus(&stub) && nexus(other) {
+                        star.connect(other.clone());
+                    }
+                }
+                star
+            })
+            .collect()
+    }
+
+    /// the stars of a standalone machine
+    pub fn default_stars() -> Vec<StarStub> {
+        let constellation = "central".to_string();
+        let star = |name: &str, kind: StarSub| {
+            StarStub::new(StarKey::new(&constellation, &StarHandle::name(name)), kind)
+        };
+        vec![
+            StarStub::new(StarKey::central(), StarSub::Central),
+            star("nexus", StarSub::Nexus),
+            star("super", StarSub::Super),
+            star("maelstrom", StarSub::Maelstrom),
+            star("scribe", StarSub::Scribe),
+            star("jump", StarSub::Jump),
+            star("fold", StarSub::Fold),
+        ]
+    }
+}
+
+impl Default for MachineTemplate {
+    fn default() -> Self {
+        Self::cluster(Self::default_stars(), vec![])
     }
 }
 
@@ -716,17 +818,22 @@ impl HyperwayEndpointFactory for MachineHyperwayEndpointFactory {
         &self,
         status_tx: mpsc::Sender<HyperConnectionDetails>,
     ) -> Result<HyperwayEndpoint, SpaceErr> {
-        let knock = Knock::new(
-            InterchangeKind::Star(self.to.clone()),
+        let stub = HyperwayStub::new(
             self.from
                 .clone()
                 .to_point()
                 .to_surface()
                 .with_layer(Layer::Gravity),
-            Substance::Empty,
+            Agent::HyperUser,
         );
         let (rtn, mut rtn_rx) = oneshot::channel();
-        self.call_tx.send(MachineCall::Knock { knock, rtn }).await;
+        self.call_tx
+            .send(MachineCall::Jump {
+                kind: InterchangeKind::Star(self.to.clone()),
+                stub,
+                rtn,
+            })
+            .await;
         tokio::time::timeout(Duration::from_secs(60), rtn_rx).await??
     }
 }
@@ -746,6 +853,7 @@ impl HyperwayEndpointFactory for MachineApiExtFactory {
             kind: InterchangeKind::DefaultControl,
             auth: Box::new(Substance::Empty),
             remote: None,
+            cert: None,
         };
 
         self.machine_api.knock(knock).await
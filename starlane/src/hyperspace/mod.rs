@@ -16,9 +16,16 @@ pub mod registry;
 pub mod shutdown;
 pub mod tests;
 
+pub mod cluster;
 pub mod database;
 pub mod service;
 pub mod template;
+pub mod topology;
+
+#[cfg(all(test, feature = "postgres"))]
+pub mod harness;
+#[cfg(test)]
+pub mod test_util;
 
 
 
@@ -1,4 +1,4 @@
-use crate::hyperspace::driver::DriversBuilder;
+use crate::hyperspace::driver::{database, loader, userbase, DriversBuilder};
 use crate::hyperspace::foundation::Foundation;
 use crate::hyperspace::hyperlane::{HyperAuthenticator, HyperGateSelector, HyperwayEndpointFactory};
 use crate::hyperspace::machine::{Machine, MachineApi, MachineTemplate};
@@ -10,14 +10,14 @@ use starlane_primitive_macros::logger;
 use crate::space::artifact::asynch::Artifacts;
 use crate::space::command::direct::create::KindTemplate;
 use crate::space::err::SpaceErr;
-use crate::space::kind::{
-    ArtifactSubKind, BaseKind, FileSubKind, Kind, Specific, StarSub, UserBaseSubKind,
-    UserBaseSubKindBase,
-};
+use crate::space::kind::{ArtifactSubKind, BaseKind, FileSubKind, Kind, StarSub};
 use crate::space::loc::{MachineName, StarKey, ToBaseKind};
 use crate::space::log::Logger;
 use crate::space::particle::property::{PropertiesConfig, PropertiesConfigBuilder};
+use crate::space::point::Point;
 use crate::space::settings::Timeouts;
+use std::collections::HashSet;
+use std::net::{IpAddr, Ipv4Addr};
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -61,12 +61,26 @@ where
 
     fn star_auth(&self, star: &StarKey) -> Result<Self::StarAuth, Self::Err>;
 
+    /// the other machines of the cluster whose stars may knock on this machine's star
+    /// gates. A standalone machine admits none
+    fn cluster_machines(&self) -> HashSet<MachineName> {
+        HashSet::new()
+    }
+
+    /// agents other than the HyperUser and those with super access that may capture
+    /// the traffic of the machine
+    fn capture_agents(&self) -> HashSet<Point> {
+        HashSet::new()
+    }
+
+    /// a connection from local star `from` to star `to` which is hosted by another machine
     fn remote_connection_factory_for_star(
         &self,
-        star: &StarKey,
+        from: &StarKey,
+        to: &StarKey,
     ) -> Result<Self::RemoteStarConnectionFactory, Self::Err>;
 
-    fn machine_template(&self) -> MachineTemplate;
+    fn machine_template(&self) -> Result<MachineTemplate, Self::Err>;
     fn machine_name(&self) -> MachineName;
 
     //    fn select_service(&self, kind: &KindSelector, star: &StarKey, point: &Point ) ->
@@ -83,6 +97,18 @@ where
                 builder.add_point("bin", true, true).unwrap();
                 builder.build().unwrap()
             }
+            BaseKind::WebServer => {
+                builder.add_point("bind", true, true).unwrap();
+                builder.add_string("host").unwrap();
+                builder.add_string("port").unwrap();
+                builder.build().unwrap()
+            }
+            BaseKind::Driver => {
+                builder.add_point(loader::DRIVER_KIND, true, false).unwrap();
+                builder.add_point(loader::DRIVER_BIN, true, false).unwrap();
+                builder.add_string(loader::DRIVER_CODEC).unwrap();
+                builder.build().unwrap()
+            }
             _ => builder.build().unwrap(),
         }
     }
@@ -100,6 +126,11 @@ where
         Ok(8080u16)
     }
 
+    /// the address a WebServer binds unless its `host` property says otherwise
+    fn web_host(&self) -> Result<IpAddr, Self::Err> {
+        Ok(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
+    }
+
     fn data_dir(&self) -> String {
         "./data/".to_string()
     }
@@ -121,9 +152,7 @@ where
                     return Ok(Kind::File(file_kind));
                 }
             },
-            BaseKind::Database => {
-                unimplemented!("need to write a SpecificPattern matcher...")
-            }
+            BaseKind::Database => return database::select_kind(template),
             BaseKind::BundleSeries => Kind::BundleSeries,
             BaseKind::Bundle => Kind::Bundle,
             BaseKind::Artifact => match &template.sub {
@@ -136,21 +165,10 @@ where
                 }
             },
             BaseKind::Control => Kind::Control,
-            BaseKind::UserBase => match &template.sub {
-                None => {
-                    return Err(SpaceErr::expect_sub::<UserBaseSubKindBase>(
-                        BaseKind::UserBase,
-                    ));
-                }
-                Some(sub) => {
-                    let specific =
-                        Specific::from_str("old.io:redhat.com:keycloak:community:18.0.0")?;
-                    let sub = UserBaseSubKind::OAuth(specific);
-                    Kind::UserBase(sub)
-                }
-            },
+            BaseKind::UserBase => return userbase::select_kind(template),
             BaseKind::Repo => Kind::Repo,
             BaseKind::Portal => Kind::Portal,
+            BaseKind::Remote => Kind::Remote,
             BaseKind::Star => {
                 return Err(SpaceErr::unimplemented(
                     "stars cannot be created via the template",
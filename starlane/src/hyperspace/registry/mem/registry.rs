@@ -37,12 +37,18 @@ pub struct MemoryRegistryCtx {
 
 pub struct MemoryRegistry {
     ctx: MemoryRegistryCtx,
+    access: Access,
 }
 
 impl MemoryRegistry {
     pub fn new() -> Self {
+        Self::with_access(Access::Super)
+    }
+
+    /// every agent is given `access` on every particle
+    pub fn with_access(access: Access) -> Self {
         let ctx = MemoryRegistryCtx::new();
-        Self { ctx }
+        Self { ctx, access }
     }
 
     fn ctx(&self) -> &MemoryRegistryCtx {
@@ -168,7 +174,7 @@ impl RegistryApi for MemoryRegistry {
     }
 
     async fn access<'a>(&'a self, to: &'a Point, on: &'a Point) -> Result<Access, RegErr> {
-        Ok(Access::Super)
+        Ok(self.access.clone())
     }
 
     async fn chown<'a>(
@@ -390,7 +390,7 @@ impl RegistryApi for PostgresRegistry {
                         false => 0,
                     };
 
-                    let statement = format!("INSERT INTO properties (resource_id,key,value,lock) VALUES ((SELECT id FROM particles WHERE parent='{}' AND point_segment='{}'),'{}' ,'{}','{}') ON CONFLICT(resource_id,key) DO UPDATE SET value='{}' WHERE lock=false", parent, point_segment, key.to_string(), value.to_string(), value.to_string(), lock);
+                    let statement = format!("INSERT INTO properties (resource_id,key,value,lock) VALUES ((SELECT id FROM particles WHERE parent='{}' AND point_segment='{}'),'{}' ,'{}','{}') ON CONFLICT(resource_id,key) DO UPDATE SET value='{}' WHERE lock=false", parent, point_segment, key.to_string(), value.to_string(), lock, value.to_string());
                     trans.execute(statement.as_str()).await?;
                 }
                 PropertyMod::UnSet(key) => {
@@ -1554,12 +1554,13 @@ pub mod test {
 
         fn remote_connection_factory_for_star(
             &self,
-            star: &StarKey,
+            from: &StarKey,
+            to: &StarKey,
         ) -> Result<Self::RemoteStarConnectionFactory, Self::Err> {
             todo!()
         }
 
-        fn machine_template(&self) -> MachineTemplate {
+        fn machine_template(&self) -> Result<MachineTemplate, Self::Err> {
             todo!()
         }
 
@@ -3,8 +3,9 @@ use crate::hyperspace::executor::cli::HostEnv;
 use crate::hyperspace::executor::dialect::filestore::{FileStore, FileStoreErr, FILE_STORE_ROOT};
 use crate::hyperspace::executor::{ExeConf, Executor};
 use crate::hyperspace::host::err::HostErr;
-use crate::hyperspace::host::{ExeStub, Host, HostCli};
+use crate::hyperspace::host::{ExeStub, Host, HostCli, WasmStub};
 use crate::hyperspace::machine::MachineErr;
+use crate::env::STARLANE_DATA_DIR;
 use itertools::Itertools;
 use nom::AsBytes;
 use crate::space::err::SpaceErr;
@@ -15,6 +16,8 @@ use crate::space::point::Point;
 use crate::space::selector::KindSelector;
 use crate::space::util::{IdSelector, MatchSelector, OptSelector, RegexMatcher, ValueMatcher};
 use crate::space::wave::exchange::asynch::{DirectedHandler, Router};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::env;
 use std::fmt::{Display, Formatter};
 use std::future::Future;
@@ -90,7 +93,9 @@ impl ServiceRunner {
     }
 }
 
-#[derive(Hash, Clone, Eq, PartialEq, Debug, EnumString, strum_macros::Display)]
+#[derive(
+    Hash, Clone, Eq, PartialEq, Debug, EnumString, strum_macros::Display, Serialize, Deserialize,
+)]
 pub enum ServiceKind {
     FileStore,
 }
@@ -170,6 +175,79 @@ impl PartialEq<ServiceTemplate> for ServiceSelector {
     }
 }
 
+impl ServiceSelector {
+    /// a template that differs from the selector in exactly one of name, kind and driver
+    pub fn is_near_miss(&self, template: &ServiceTemplate) -> bool {
+        let matches = [
+            self.name == template.name,
+            self.kind == template.kind,
+            template.driver == self.driver,
+        ];
+        matches.iter().filter(|m| !**m).count() == 1
+    }
+}
+
+/// formatted like a [ServiceSelector]: `name<DriverKind:ServiceKind>`
+impl Display for ServiceTemplate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let driver = match &self.driver {
+            OptSelector::Selector(selector) => selector.to_string(),
+            OptSelector::Always | OptSelector::Some => "*".to_string(),
+            OptSelector::None | OptSelector::Never => "!".to_string(),
+        };
+        write!(f, "{}<{}:{}>", self.name, driver, self.kind.to_string())
+    }
+}
+
+/// a service declared in the context config
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ServiceConfig {
+    pub name: String,
+    pub kind: ServiceKind,
+    /// a [KindSelector] matching the drivers allowed to use this service.  Every driver
+    /// may use it when absent
+    #[serde(default)]
+    pub driver: Option<String>,
+    /// path of the executable that provides the service.  A `.wasm` file is run as a
+    /// wasi module
+    pub exe: String,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+impl ServiceConfig {
+    pub fn template(&self) -> Result<ServiceTemplate, SpaceErr> {
+        let driver = match &self.driver {
+            None => OptSelector::Always,
+            Some(driver) => OptSelector::Selector(KindSelector::from_str(driver.as_str())?),
+        };
+        let mut builder = HostEnv::builder();
+        for (key, value) in self.env.iter() {
+            builder.env(key, value);
+        }
+        let stub = ExeStub::new(self.exe.clone(), builder.build());
+        Ok(ServiceTemplate {
+            name: self.name.clone(),
+            kind: self.kind.clone(),
+            driver,
+            config: ServiceConf::Exe(ExeConf::Host(Host::Cli(host_cli(&self.kind, stub)))),
+        })
+    }
+}
+
+/// a `.wasm` stub runs as a wasi module which may only access the directories
+/// its [ServiceKind] stores to
+pub fn host_cli(kind: &ServiceKind, stub: ExeStub) -> HostCli {
+    if stub.loc.ends_with(".wasm") {
+        let preopen = match kind {
+            ServiceKind::FileStore => vec![FILE_STORE_ROOT.to_string()],
+        };
+        HostCli::Wasm(WasmStub::new(stub, preopen))
+    } else {
+        HostCli::Os(stub)
+    }
+}
+
 // at this time, Conf and Runner do not differ
 pub type ServiceConf = ServiceRunner;
 
@@ -249,31 +327,38 @@ pub trait ServiceCore<C>
 
  */
 
+/// name of the local filestore service binary which is expected to sit alongside
+/// the running starlane executable
+pub const FILESTORE_SERVICE_BIN: &'static str = "starlane-cli-filestore-service";
+
+/// the filestore service built for `wasm32-wasip1` (by `make filestore-wasm`) which is
+/// used when there is no native [FILESTORE_SERVICE_BIN]
+pub const FILESTORE_SERVICE_WASM: &'static str = "starlane-cli-filestore-service.wasm";
+
+/// the local filestore service rooted at `{STARLANE_DATA_DIR}/filestore`
 pub fn service_conf() -> ServiceConf {
-    /*
     let mut builder = HostEnv::builder();
-    builder.pwd(
-        absolute(env::current_dir().unwrap())
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_string(),
-    );
-    println!("{}", env::current_dir().unwrap().to_str().unwrap());
     builder.env(
         FILE_STORE_ROOT,
-        STARLANE_DATA_DIR.to_string(),
+        format!("{}/filestore", STARLANE_DATA_DIR.as_str()),
     );
     let env = builder.build();
-    let path = "../target/debug/starlane-cli-filestore-service".to_string();
-    let args: Option<Vec<String>> = Option::None;
-
-    let stub = ExeStub::new(path.into(), env);
 
-    ServiceConf::Exe(ExeConf::Host(Host::Cli(HostCli::Os(stub))))
+    let dir = env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(PathBuf::from))
+        .unwrap_or_default();
+    let native = dir.join(FILESTORE_SERVICE_BIN);
+    let wasm = dir.join(FILESTORE_SERVICE_WASM);
+    let path = if !native.exists() && wasm.exists() {
+        wasm
+    } else {
+        native
+    };
+    let stub = ExeStub::new(path.to_string_lossy().to_string(), env);
+    let host = host_cli(&ServiceKind::FileStore, stub);
 
-     */
-    todo!("service_config() needs a minor refactor before it can be used again")
+    ServiceConf::Exe(ExeConf::Host(Host::Cli(host)))
 }
 
 #[cfg(test)]
@@ -284,44 +369,71 @@ pub mod tests {
     use crate::hyperspace::executor::dialect::filestore::{FileStore, FileStoreIn, FileStoreOut};
     use crate::hyperspace::executor::{ExeConf, Executor};
     use crate::hyperspace::host::HostCli;
+    use crate::hyperspace::executor::dialect::filestore::FILE_STORE_ROOT;
     use crate::hyperspace::service::{
-        service_conf, Service, ServiceConf, ServiceErr, ServiceKind, ServiceTemplate,
+        service_conf, FileStoreService, Service, ServiceConf, ServiceConfig, ServiceErr,
+        ServiceKind, ServiceSelector, ServiceTemplate, FILESTORE_SERVICE_BIN,
     };
+    use crate::hyperspace::template::Templates;
+    use crate::hyperspace::test_util::temp_dir;
     use crate::space::kind::{BaseKind, Kind};
     use crate::space::selector::KindSelector;
-    use crate::space::util::OptSelector;
+    use crate::space::util::{IdSelector, OptSelector};
     use std::path::{absolute, PathBuf};
     use std::{env, io};
     use tokio::fs;
 
-    fn filestore() -> FileStore {
-        if std::fs::exists("./tmp").unwrap() {
-            std::fs::remove_dir_all("./tmp").unwrap();
+    /// the [FILESTORE_SERVICE_BIN] that `cargo test --workspace` builds beside the
+    /// directory of the test binary
+    fn filestore_service_bin() -> String {
+        let bin = env::current_exe()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .join(FILESTORE_SERVICE_BIN);
+        if !bin.exists() {
+            panic!(
+                "'{}' not found: build it with `cargo build -p {}`",
+                bin.display(),
+                FILESTORE_SERVICE_BIN
+            );
         }
-        let mut builder = HostEnv::builder();
-        builder.pwd(
-            absolute(env::current_dir().unwrap())
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .to_string(),
-        );
-        println!("{}", env::current_dir().unwrap().to_str().unwrap());
-        builder.env(
-            "FILE_STORE_ROOT",
-            format!("{}/tmp", env::current_dir().unwrap().to_str().unwrap()),
-        );
-        let env = builder.build();
-        let path = "../target/debug/starlane-cli-filestore-service".to_string();
-        let args: Option<Vec<String>> = Option::None;
-        let stub = ExeStub::new(path.into(), env);
-        //        let info = ExeInfo::new(HostDialect::Cli(HostRunner::Os), stub);
-
-        let info = ExeConf::Host(Host::Cli(HostCli::Os(stub.clone())));
+        bin.display().to_string()
+    }
 
+    /// a filestore rooted at a fresh temporary directory
+    fn filestore() -> FileStore {
+        let root = temp_dir("filestore");
+        let mut builder = HostEnv::builder();
+        builder.pwd(env::temp_dir().display());
+        builder.env(FILE_STORE_ROOT, root.display());
+        let stub = ExeStub::new(filestore_service_bin(), builder.build());
+        let info = ExeConf::Host(Host::Cli(HostCli::Os(stub)));
         info.create().unwrap()
     }
 
+    /// a FileStore service running the locally built filestore binary rooted at `root`
+    /// which is emptied first
+    pub fn local_filestore(root: &str) -> FileStoreService {
+        if std::fs::exists(root).unwrap() {
+            std::fs::remove_dir_all(root).unwrap();
+        }
+        let pwd = absolute(env::current_dir().unwrap()).unwrap();
+        let mut builder = HostEnv::builder();
+        builder.pwd(pwd.display());
+        builder.env(FILE_STORE_ROOT, pwd.join(root).display());
+        let stub = ExeStub::new(filestore_service_bin(), builder.build());
+        let template = ServiceTemplate {
+            name: "local-filestore".to_string(),
+            kind: ServiceKind::FileStore,
+            driver: OptSelector::Always,
+            config: ServiceConf::Exe(ExeConf::Host(Host::Cli(HostCli::Os(stub)))),
+        };
+        Service::new(template).filestore().unwrap()
+    }
+
     pub async fn filestore_from_service() -> Result<Service<FileStore>, ServiceErr> {
         let config = service_conf();
 
@@ -337,6 +449,90 @@ pub mod tests {
         Ok(service.try_into()?)
     }
 
+    #[test]
+    pub fn test_wasm_service() {
+        let config: ServiceConfig = serde_yaml::from_str(
+            "name: wasm-store\nkind: FileStore\nexe: /opt/filestore.wasm\nenv:\n  FILE_STORE_ROOT: /tmp/wasm\n",
+        )
+        .unwrap();
+        let ServiceConf::Exe(ExeConf::Host(Host::Cli(HostCli::Wasm(wasm)))) =
+            config.template().unwrap().config
+        else {
+            panic!("expected a wasm host for a .wasm exe");
+        };
+        assert_eq!(vec!["/tmp/wasm".to_string()], wasm.dirs());
+
+        let host = HostCli::Wasm(wasm).with_env("FILE_STORE_ROOT", "/tmp/wasm/sub");
+        assert_eq!(
+            Some(&"/tmp/wasm/sub".to_string()),
+            host.env("FILE_STORE_ROOT")
+        );
+    }
+
+    #[test]
+    pub fn test_select_templates() {
+        let config: ServiceConfig = serde_yaml::from_str(
+            "name: repo-store\nkind: FileStore\ndriver: Repo\nexe: /usr/bin/filestore\nenv:\n  FILE_STORE_ROOT: /tmp/repo\n",
+        )
+        .unwrap();
+        let repo = config.template().unwrap();
+        assert!(repo.to_string().starts_with("repo-store<Repo"));
+        assert!(repo.to_string().ends_with(":FileStore>"));
+
+        let shared = ServiceTemplate {
+            name: "shared".to_string(),
+            kind: ServiceKind::FileStore,
+            driver: OptSelector::Always,
+            config: service_conf(),
+        };
+        let other = ServiceTemplate {
+            name: "other".to_string(),
+            driver: repo.driver.clone(),
+            ..shared.clone()
+        };
+        let templates = Templates::new(vec![repo, shared, other]);
+
+        let selector = ServiceSelector {
+            name: IdSelector::Always,
+            kind: ServiceKind::FileStore,
+            driver: Some(Kind::Repo),
+        };
+        let names: Vec<String> = templates
+            .select(&selector)
+            .into_iter()
+            .map(|template| template.name)
+            .collect();
+        assert_eq!(vec!["repo-store".to_string(), "shared".to_string()], names);
+
+        let selector = ServiceSelector {
+            name: IdSelector::single("repo-store".to_string()),
+            kind: ServiceKind::FileStore,
+            driver: Some(Kind::FileStore),
+        };
+        assert!(templates.select(&selector).is_empty());
+        // `other` differs in both name and driver so it is no near miss
+        let near_misses = templates.near_misses(&selector);
+        assert_eq!(2, near_misses.len());
+        assert!(near_misses[0].starts_with("repo-store<Repo"));
+        assert_eq!("shared<*:FileStore>", near_misses[1]);
+
+        let selector = ServiceSelector {
+            name: IdSelector::single("nope".to_string()),
+            kind: ServiceKind::FileStore,
+            driver: Some(Kind::FileStore),
+        };
+        assert_eq!(
+            vec!["shared<*:FileStore>".to_string()],
+            templates.near_misses(&selector)
+        );
+
+        let config = ServiceConfig {
+            driver: Some("Nope<".to_string()),
+            ..config
+        };
+        assert!(config.template().is_err());
+    }
+
     /*
     #[tokio::test]
     pub async fn test_dialect_old() {
@@ -479,9 +675,21 @@ pub enum ServiceErr {
     #[error(transparent)]
     HostErr(#[from] HostErr),
     #[error(
-        "no template available that matches ServiceSelector: '{0}' (name<DriverKind:ServiceKind>)"
+        "no template available that matches ServiceSelector: '{selector}' (name<DriverKind:ServiceKind>){}",
+        near_misses(.candidates)
     )]
-    NoTemplate(ServiceSelector),
+    NoTemplate {
+        selector: ServiceSelector,
+        candidates: Vec<String>,
+    },
     #[error("call not processed")]
     CallRecvErr(#[from] tokio::sync::oneshot::error::RecvError),
 }
+
+fn near_misses(candidates: &Vec<String>) -> String {
+    if candidates.is_empty() {
+        "".to_string()
+    } else {
+        format!("; near misses: {}", candidates.join(", "))
+    }
+}
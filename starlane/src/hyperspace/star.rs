@@ -12,6 +12,7 @@ use crate::hyperspace::reg::{Registration, Registry};
 use crate::hyperspace::registry::err::RegErr;
 use crate::hyperspace::service::ServiceTemplate;
 use crate::hyperspace::template::Templates;
+use crate::hyperspace::topology::DriverOverrides;
 use anyhow::{Context, Error};
 use async_recursion::async_recursion;
 use async_trait::async_trait;
@@ -292,6 +293,13 @@ impl HyperStarSkel {
             ))?;
         }
 
+        self.create_and_assign(create).await
+    }
+
+    /// create the particle wherever `create` places it and assign it to this star.
+    /// used by drivers that provision particles beneath the particles they host
+    #[track_caller]
+    pub async fn create_and_assign(&self, create: Create) -> Result<Details, StarErr> {
         let logger = push_mark!(self.logger);
         let global = GlobalExecutionChamber::new(self.clone());
         let details = global.create(&create, &Agent::HyperUser).await?;
@@ -1138,7 +1146,7 @@ impl HyperStar {
             // The reason for this is that it is the Hyperway that handles things like Priority, Urgency
             // and hopefully in the future durability, whereas within the star itself all waves are
             // treated equally.
-            logger.result(
+            logger.result::<(), _>(
                 self.hyperway_transmitter
                     .direct(
                         transport.wrap_in_hop(
@@ -1151,7 +1159,7 @@ impl HyperStar {
             Ok(())
         } else if self.skel.adjacents.contains_key(&transport.to.point) {
             let to = transport.to.clone();
-            logger.result(
+            logger.result::<(), _>(
                 self.hyperway_transmitter
                     .direct(transport.wrap_in_hop(self.gravity.clone(), to))
                     .await,
@@ -1159,7 +1167,7 @@ impl HyperStar {
             Ok(())
         } else if self.forwarders.len() == 1 {
             let to = self.forwarders.first().unwrap().clone().to_surface();
-            logger.result(
+            logger.result::<(), _>(
                 self.hyperway_transmitter
                     .direct(transport.wrap_in_hop(self.gravity.clone(), to))
                     .await,
@@ -1711,6 +1719,7 @@ pub struct StarTemplate {
     pub key: StarKey,
     pub kind: StarSub,
     pub connections: Vec<StarCon>,
+    pub drivers: DriverOverrides,
 }
 
 impl StarTemplate {
@@ -1719,6 +1728,7 @@ impl StarTemplate {
             key,
             kind,
             connections: vec![],
+            drivers: DriverOverrides::default(),
             //            services: Templates::default(),
         }
     }
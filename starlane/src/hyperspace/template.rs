@@ -32,26 +32,24 @@ where
 }
 
 impl Templates<ServiceTemplate> {
+    /// every template matching the selector's name, kind and driver in the order declared
     pub fn select(&self, selector: &ServiceSelector) -> Vec<ServiceTemplate> {
-        todo!()
-        //let mut rtn = vec![];
-        /*        for template in &self.templates {
-                   if selector.matches(&template.kind) {
-                       rtn.push(template.clone());
-                   }
-               }
-               rtn
-
-        */
+        self.templates
+            .iter()
+            .filter(|template| *selector == **template)
+            .cloned()
+            .collect()
     }
 
-    /*
-    /// return the first match found
-    pub fn select_one(&self, selector: &ServiceSelector) -> Option<ServiceTemplate> {
-        self.select(selector).first().cloned()
+    /// templates that match the selector in some but not all respects, formatted the same
+    /// way as the selector so they can be listed when nothing matches
+    pub fn near_misses(&self, selector: &ServiceSelector) -> Vec<String> {
+        self.templates
+            .iter()
+            .filter(|template| selector.is_near_miss(template))
+            .map(|template| template.to_string())
+            .collect()
     }
-
-     */
 }
 
 impl<T> Default for Templates<T>
@@ -0,0 +1,9 @@
+use std::path::PathBuf;
+
+/// a fresh directory beneath the system temp dir for one test.  The test removes it
+/// once it is done
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("starlane-{}-{}", name, uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,430 @@
+use crate::hyperspace::driver::{DriversBuilder, HyperDriverFactory};
+use crate::hyperspace::star::StarTemplate;
+use crate::space::err::SpaceErr;
+use crate::space::kind::{BaseKind, StarStub, StarSub};
+use crate::space::loc::{StarHandle, StarKey, ToBaseKind};
+use crate::space::selector::KindSelector;
+use crate::space::util::ValueMatcher;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+use strum::IntoEnumIterator;
+
+/// every star of a topology belongs to this constellation so the Central star keeps
+/// the key of [StarKey::central]
+pub const CONSTELLATION: &'static str = "central";
+
+/// changes to the drivers a star hosts by default for its [StarSub].  Both lists hold
+/// [KindSelector]s such as `WebServer` or `Database<Relational>`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DriverOverrides {
+    /// drivers this star hosts that are normally hosted by a star of another [StarSub]
+    #[serde(default)]
+    pub add: Vec<String>,
+    /// drivers of this star's [StarSub] that this star does not host
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+impl DriverOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.add.is_empty() && self.remove.is_empty()
+    }
+
+    fn selectors(list: &Vec<String>) -> Result<Vec<KindSelector>, SpaceErr> {
+        list.iter()
+            .map(|selector| {
+                KindSelector::from_str(selector.as_str()).map_err(|err| {
+                    SpaceErr::bad_request(format!(
+                        "'{}' is not a valid KindSelector: {}",
+                        selector,
+                        err.to_string()
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    /// `catalog` is the default builder of each [StarSub] which is where added drivers
+    /// are taken from.  Only the catalogs of other stars that are needed to find every
+    /// added driver are built since building one creates all of its factories
+    pub fn apply<F>(
+        &self,
+        star: &StarSub,
+        builder: &mut DriversBuilder,
+        catalog: F,
+    ) -> Result<(), SpaceErr>
+    where
+        F: Fn(&StarSub) -> DriversBuilder,
+    {
+        for selector in Self::selectors(&self.remove)? {
+            builder.remove(&selector);
+        }
+        let mut missing: Vec<(KindSelector, &String)> = Self::selectors(&self.add)?
+            .into_iter()
+            .zip(self.add.iter())
+            .collect();
+        for sub in StarSub::iter() {
+            if missing.is_empty() {
+                break;
+            }
+            if sub == *star || sub == StarSub::Machine {
+                continue;
+            }
+            let factories: Vec<Arc<dyn HyperDriverFactory>> = catalog(&sub)
+                .factories()
+                .into_iter()
+                .filter(|factory| match factory.kind().to_base() {
+                    BaseKind::Driver | BaseKind::Star | BaseKind::Base => false,
+                    _ => true,
+                })
+                .collect();
+            missing.retain(|(selector, _)| {
+                match factories
+                    .iter()
+                    .find(|factory| selector.is_match(&factory.kind()).is_ok())
+                {
+                    Some(factory) => {
+                        builder.add_post(factory.clone());
+                        false
+                    }
+                    None => true,
+                }
+            });
+        }
+        match missing.first() {
+            None => Ok(()),
+            Some((_, name)) => Err(SpaceErr::bad_request(format!(
+                "no star hosts a driver for '{}' that could be added",
+                name
+            ))),
+        }
+    }
+}
+
+/// a star declared in the topology.  `connect` and `receive` name other stars of the
+/// topology and only one side of each connection needs to declare it
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TopologyStar {
+    pub name: String,
+    pub kind: StarSub,
+    #[serde(default)]
+    pub connect: Vec<String>,
+    #[serde(default)]
+    pub receive: Vec<String>,
+    #[serde(default)]
+    pub drivers: DriverOverrides,
+}
+
+impl TopologyStar {
+    pub fn key(&self) -> StarKey {
+        StarKey::new(&CONSTELLATION.to_string(), &StarHandle::name(&self.name))
+    }
+}
+
+/// the stars hosted by a standalone machine as declared in `topology.yaml` within the
+/// context directory.  A [crate::hyperspace::machine::MachineTemplate::default] machine
+/// looks like this:
+///
+/// ```yaml
+/// stars:
+///   - name: central
+///     kind: Central
+///     connect: [nexus]
+///   - name: nexus
+///     kind: Nexus
+///     receive: [super, maelstrom, scribe, jump, fold]
+///   - name: super
+///     kind: Super
+///   ...
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TopologyConfig {
+    pub stars: Vec<TopologyStar>,
+}
+
+impl TopologyConfig {
+    /// every problem with the topology in one error so they can be fixed together
+    pub fn validate(&self) -> Result<(), SpaceErr> {
+        let mut errs = vec![];
+        if self.stars.is_empty() {
+            errs.push("at least one star must be declared".to_string());
+        }
+
+        let mut names = HashMap::new();
+        for star in self.stars.iter() {
+            if names.insert(star.name.as_str(), star).is_some() {
+                errs.push(format!("star '{}' is declared more than once", star.name));
+            }
+            if star.kind == StarSub::Machine {
+                errs.push(format!(
+                    "star '{}' cannot be a Machine star which every machine adds for itself",
+                    star.name
+                ));
+            }
+            if star.kind == StarSub::Central && star.name != CONSTELLATION {
+                errs.push(format!(
+                    "the Central star must be named '{}' not '{}'",
+                    CONSTELLATION, star.name
+                ));
+            }
+            for selector in star.drivers.add.iter().chain(star.drivers.remove.iter()) {
+                if KindSelector::from_str(selector.as_str()).is_err() {
+                    errs.push(format!(
+                        "star '{}' overrides drivers with an invalid KindSelector: '{}'",
+                        star.name, selector
+                    ));
+                }
+            }
+        }
+
+        let centrals = self
+            .stars
+            .iter()
+            .filter(|star| star.kind == StarSub::Central)
+            .count();
+        if centrals != 1 {
+            errs.push(format!(
+                "exactly one Central star must be declared but found {}",
+                centrals
+            ));
+        }
+
+        let mut connections = HashMap::new();
+        for (from, to) in self.connections() {
+            for name in [from, to] {
+                if !names.contains_key(name) {
+                    errs.push(format!(
+                        "star '{}' is connected to undeclared star '{}'",
+                        if name == from { to } else { from },
+                        name
+                    ));
+                }
+            }
+            if from == to {
+                errs.push(format!("star '{}' cannot connect to itself", from));
+            } else if connections.contains_key(&(to, from)) {
+                errs.push(format!(
+                    "stars '{}' and '{}' each connect to the other",
+                    from, to
+                ));
+            }
+            connections.insert((from, to), ());
+        }
+        // a connection carries waves both ways so every star must be reachable from Central
+        // following connections in either direction
+        let central = self.stars.iter().find(|star| star.kind == StarSub::Central);
+        if let (1, Some(central)) = (centrals, central) {
+            let mut reached = HashSet::from([central.name.as_str()]);
+            let mut frontier = vec![central.name.as_str()];
+            while let Some(name) = frontier.pop() {
+                for (from, to) in connections.keys() {
+                    let other = match name {
+                        name if name == *from => *to,
+                        name if name == *to => *from,
+                        _ => continue,
+                    };
+                    if reached.insert(other) {
+                        frontier.push(other);
+                    }
+                }
+            }
+            for star in self.stars.iter() {
+                if !reached.contains(star.name.as_str()) {
+                    errs.push(format!(
+                        "star '{}' cannot be reached from the Central star '{}'",
+                        star.name, central.name
+                    ));
+                }
+            }
+        }
+
+        if errs.is_empty() {
+            Ok(())
+        } else {
+            Err(SpaceErr::bad_request(format!(
+                "invalid topology:\n  {}",
+                errs.join("\n  ")
+            )))
+        }
+    }
+
+    /// each connection as `(connector, receiver)`
+    fn connections(&self) -> Vec<(&str, &str)> {
+        let mut rtn = vec![];
+        for star in self.stars.iter() {
+            let connects = star
+                .connect
+                .iter()
+                .map(|other| (star.name.as_str(), other.as_str()));
+            let receives = star
+                .receive
+                .iter()
+                .map(|other| (other.as_str(), star.name.as_str()));
+            for connection in connects.chain(receives) {
+                if !rtn.contains(&connection) {
+                    rtn.push(connection);
+                }
+            }
+        }
+        rtn
+    }
+
+    pub fn stars(&self) -> Result<Vec<StarTemplate>, SpaceErr> {
+        self.validate()?;
+        let stubs: HashMap<&str, StarStub> = self
+            .stars
+            .iter()
+            .map(|star| {
+                (
+                    star.name.as_str(),
+                    StarStub::new(star.key(), star.kind.clone()),
+                )
+            })
+            .collect();
+        let mut templates: HashMap<&str, StarTemplate> = self
+            .stars
+            .iter()
+            .map(|star| {
+                let mut template = StarTemplate::new(star.key(), star.kind.clone());
+                template.drivers = star.drivers.clone();
+                (star.name.as_str(), template)
+            })
+            .collect();
+        for (from, to) in self.connections() {
+            templates
+                .get_mut(from)
+                .unwrap()
+                .connect(stubs.get(to).unwrap().clone());
+            templates
+                .get_mut(to)
+                .unwrap()
+                .receive(stubs.get(from).unwrap().clone());
+        }
+        Ok(self
+            .stars
+            .iter()
+            .map(|star| templates.remove(star.name.as_str()).unwrap())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::hyperspace::star::StarCon;
+    use crate::hyperspace::topology::TopologyConfig;
+    use crate::space::kind::StarSub;
+    use crate::space::loc::StarKey;
+
+    #[test]
+    pub fn test_topology() {
+        let topology: TopologyConfig = serde_yaml::from_str(
+            r#"
+stars:
+  - name: central
+    kind: Central
+    connect: [nexus]
+  - name: nexus
+    kind: Nexus
+    receive: [maelstrom, maelstrom-2]
+  - name: maelstrom
+    kind: Maelstrom
+  - name: maelstrom-2
+    kind: Maelstrom
+    drivers:
+      remove: [Host]
+"#,
+        )
+        .unwrap();
+        let stars = topology.stars().unwrap();
+        assert_eq!(4, stars.len());
+        assert_eq!(StarKey::central(), stars[0].key);
+        let nexus = &stars[1];
+        assert_eq!(StarSub::Nexus, nexus.kind);
+        assert_eq!(3, nexus.connections.len());
+        assert!(!nexus.connections[0].is_connector());
+        assert!(stars[2]
+            .connections
+            .iter()
+            .all(|con| matches!(con, StarCon::Connector(stub) if stub.key == nexus.key)));
+        assert_eq!(vec!["Host".to_string()], stars[3].drivers.remove);
+    }
+
+    #[test]
+    pub fn test_single_star() {
+        let topology: TopologyConfig =
+            serde_yaml::from_str("stars:\n  - name: central\n    kind: Central\n").unwrap();
+        assert_eq!(1, topology.stars().unwrap().len());
+    }
+
+    #[test]
+    pub fn test_invalid_topology() {
+        let topology: TopologyConfig = serde_yaml::from_str(
+            r#"
+stars:
+  - name: hub
+    kind: Central
+    connect: [nowhere, hub]
+  - name: machine
+    kind: Machine
+  - name: machine
+    kind: Fold
+    drivers:
+      add: ["Nope<"]
+"#,
+        )
+        .unwrap();
+        let err = topology.validate().unwrap_err().to_string();
+        assert!(err.contains("'machine' is declared more than once"));
+        assert!(err.contains("cannot be a Machine star"));
+        assert!(err.contains("must be named 'central' not 'hub'"));
+        assert!(err.contains("undeclared star 'nowhere'"));
+        assert!(err.contains("'hub' cannot connect to itself"));
+        assert!(err.contains("invalid KindSelector: 'Nope<'"));
+
+        let topology: TopologyConfig = serde_yaml::from_str(
+            r#"
+stars:
+  - name: central
+    kind: Central
+    connect: [nexus]
+  - name: nexus
+    kind: Nexus
+  - name: scribe
+    kind: Scribe
+"#,
+        )
+        .unwrap();
+        let err = topology.validate().unwrap_err().to_string();
+        assert!(err.contains("'scribe' cannot be reached from the Central star 'central'"));
+        assert!(!err.contains("'nexus' cannot be reached"));
+
+        // every star has a connection but a and b are cut off from central and nexus
+        let topology: TopologyConfig = serde_yaml::from_str(
+            r#"
+stars:
+  - name: central
+    kind: Central
+    connect: [nexus]
+  - name: nexus
+    kind: Nexus
+  - name: a
+    kind: Fold
+    connect: [b]
+  - name: b
+    kind: Scribe
+"#,
+        )
+        .unwrap();
+        let err = topology.validate().unwrap_err().to_string();
+        assert!(err.contains("'a' cannot be reached"));
+        assert!(err.contains("'b' cannot be reached"));
+        assert!(!err.contains("'nexus' cannot be reached"));
+
+        let topology: TopologyConfig = serde_yaml::from_str("stars: []\n").unwrap();
+        let err = topology.validate().unwrap_err().to_string();
+        assert!(err.contains("at least one star"));
+        assert!(err.contains("exactly one Central star"));
+    }
+}
@@ -122,6 +122,22 @@ pub fn main() -> Result<(), anyhow::Error> {
                 }
             }
         }
+        Commands::Certs(args) => {
+            let runtime = Builder::new_multi_thread().enable_all().build()?;
+            runtime.block_on(async move { cli::certs(args).await })
+        }
+        Commands::Token(args) => {
+            let runtime = Builder::new_multi_thread().enable_all().build()?;
+            runtime.block_on(async move { cli::token(args).await })
+        }
+        Commands::Capture(args) => {
+            let runtime = Builder::new_multi_thread().enable_all().build()?;
+            runtime.block_on(async move { cli::capture(args).await })
+        }
+        Commands::Replay(args) => {
+            let runtime = Builder::new_multi_thread().enable_all().build()?;
+            runtime.block_on(async move { cli::replay(args).await })
+        }
         Commands::Version => {
             println!("{}", VERSION.to_string());
             Ok(())
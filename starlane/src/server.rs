@@ -5,39 +5,67 @@ use crate::hyperspace::registry::postgres::{
 };
 
 use crate::hyperspace::driver::{DriverAvail, DriversBuilder};
+use crate::hyperspace::driver::artifact::{
+    ArtifactDriverFactory, BundleDriverFactory, BundleSeriesDriverFactory,
+    FileStoreArtifactFetcher, RepoDriverFactory,
+};
 use crate::hyperspace::driver::base::BaseDriverFactory;
 use crate::hyperspace::driver::control::ControlDriverFactory;
+use crate::hyperspace::driver::database::DatabaseDriverFactory;
+use crate::hyperspace::driver::file::FileDriverFactory;
+use crate::hyperspace::driver::filestore::FileStoreDriverFactory;
+use crate::hyperspace::driver::host::HostDriverFactory;
+use crate::hyperspace::driver::mechtron::MechtronDriverFactory;
+use crate::hyperspace::driver::portal::PortalDriverFactory;
 use crate::hyperspace::driver::root::RootDriverFactory;
-use crate::space::artifact::asynch::Artifacts;
+use crate::hyperspace::driver::userbase::{UserBaseDriverFactory, UserDriverFactory};
+use crate::hyperspace::driver::web::WebServerDriverFactory;
+use crate::space::artifact::asynch::{Artifacts, ArtifactsBuilder};
+use crate::space::artifact::builtin::BUILTIN_FETCHER;
 use crate::space::kind::StarSub;
-use crate::space::loc::{MachineName, StarKey};
+use crate::space::loc::{MachineName, StarKey, ToPoint};
+use crate::space::err::SpaceErr;
+use crate::space::log::Logger;
 use crate::space::point::Point;
 use std::fs;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::Path;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::hyperspace::database::{Database, LiveDatabase};
-use crate::env::{config_path, STARLANE_CONTROL_PORT, STARLANE_DATA_DIR, STARLANE_HOME};
+use crate::env::{
+    config_path, server_certs_dir, tokens_path, topology, topology_path, STARLANE_CONTROL_PORT,
+    STARLANE_DATA_DIR, STARLANE_HOME,
+};
 use crate::hyperspace::err::HypErr;
-use crate::hyperspace::hyperlane::tcp::{CertGenerator, HyperlaneTcpServer};
-use crate::hyperspace::hyperlane::{AnonHyperAuthenticator, HyperGateSelector, LocalHyperwayGateJumper};
+use crate::hyperspace::cluster::ClusterConfig;
+use crate::hyperspace::hyperlane::tcp::{
+    addrs_in_use, CertGenerator, CertInfo, CertPaths, Heartbeat, HyperlaneTcpClient,
+    HyperlaneTcpServer,
+};
+use crate::hyperspace::hyperlane::token::{TokenStore, TokenStoreHyperAuthenticator};
+use crate::hyperspace::hyperlane::{
+    AnonHyperAuthenticator, CertHyperAuthenticator, HyperGateSelector,
+};
 use crate::hyperspace::platform::{Platform, PlatformConfig};
 use crate::hyperspace::reg::{PgRegistryConfig, Registry, RegistryWrapper};
 use crate::hyperspace::registry::err::RegErr;
 use crate::hyperspace::registry::postgres::embed::PgEmbedSettings;
 use crate::hyperspace::registry::postgres::PostgresDbKey;
 use crate::hyperspace::shutdown::panic_shutdown;
+use crate::hyperspace::service::{ServiceConfig, ServiceTemplate};
 use anyhow::anyhow;
-use port_check::is_local_ipv4_port_free;
 use serde::{Deserialize, Serialize};
 use starlane_primitive_macros::{logger, push_loc};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::ops::Deref;
 use wasmer_wasix::virtual_net::VirtualConnectedSocketExt;
 use crate::hyperspace::driver::space::SpaceDriverFactory;
 use crate::hyperspace::foundation::{Foundation, StandAloneFoundation};
 use crate::hyperspace::machine::MachineTemplate;
+use crate::hyperspace::topology::TopologyConfig;
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct StarlaneConfig {
@@ -45,8 +73,237 @@ pub struct StarlaneConfig {
     pub home: String,
     pub can_nuke: bool,
     pub can_scorch: bool,
+    /// the port of every [StarlaneConfig::control_addrs], `STARLANE_PORT` or 4343 by default
     pub control_port: u16,
     pub registry: PgRegistryConfig,
+    #[serde(default)]
+    pub client_auth: Option<ClientAuthConfig>,
+    /// when set this machine joins a cluster instead of hosting every star itself
+    #[serde(default)]
+    pub cluster: Option<ClusterConfig>,
+    #[serde(default)]
+    pub tls: TlsConfig,
+    /// keepalive of the control port and of the connections to other cluster members
+    #[serde(default)]
+    pub heartbeat: HeartbeatConfig,
+    /// addresses the control port listens on. `::` on its own accepts both IPv6 and IPv4
+    #[serde(default = "StarlaneConfig::default_bind_address")]
+    pub bind_address: Vec<IpAddr>,
+    /// services drivers may select in addition to the built in `repo-filestore`
+    #[serde(default)]
+    pub services: Vec<ServiceConfig>,
+    /// agents besides the HyperUser and super users that may capture traffic
+    #[serde(default)]
+    pub capture_agents: Vec<Point>,
+    /// loaded from [crate::env::topology_path] rather than this config
+    #[serde(skip)]
+    pub topology: Option<TopologyConfig>,
+}
+
+impl StarlaneConfig {
+    fn default_bind_address() -> Vec<IpAddr> {
+        vec![IpAddr::V4(Ipv4Addr::LOCALHOST)]
+    }
+
+    pub fn control_addrs(&self) -> Vec<SocketAddr> {
+        self.bind_address
+            .iter()
+            .map(|ip| SocketAddr::new(ip.clone(), self.control_port))
+            .collect()
+    }
+
+    /// fails on the first service whose driver selector does not parse
+    pub fn service_templates(&self) -> Result<Vec<ServiceTemplate>, SpaceErr> {
+        self.services
+            .iter()
+            .map(|service| {
+                service.template().map_err(|err| {
+                    SpaceErr::bad_request(format!(
+                        "service '{}' is invalid: {}",
+                        service.name,
+                        err.to_string()
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    pub fn cert_agents(&self) -> Arc<HashMap<String, Point>> {
+        match self.client_auth.as_ref() {
+            None => Arc::new(HashMap::new()),
+            Some(client_auth) => Arc::new(client_auth.agents.clone()),
+        }
+    }
+
+    /// a client that connects local star `from` to star `to` on another machine of the
+    /// cluster
+    pub fn remote_connection_factory_for_star(
+        &self,
+        from: &StarKey,
+        to: &StarKey,
+        logger: Logger,
+    ) -> Result<HyperlaneTcpClient, SpaceErr> {
+        let cluster = self.cluster.as_ref().ok_or(SpaceErr::server_error(format!(
+            "star '{}' is not local and this machine is not part of a cluster",
+            to.to_string()
+        )))?;
+        Ok(cluster
+            .connection_factory(from, to, logger)?
+            .with_heartbeat(self.heartbeat.heartbeat()))
+    }
+}
+
+/// mutual TLS for the control port. clients must present a certificate issued by the
+/// authority in `ca_dir` whose subject or subject alternative name is a key of `agents`
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ClientAuthConfig {
+    pub ca_dir: String,
+    #[serde(default)]
+    pub agents: HashMap<String, Point>,
+}
+
+impl ClientAuthConfig {
+    pub fn new(ca_dir: String) -> Self {
+        Self {
+            ca_dir,
+            agents: HashMap::new(),
+        }
+    }
+
+    /// the authority's certificate, `cert.pem` unless only a DER cert is in `ca_dir`
+    pub fn ca_cert(&self) -> String {
+        CertPaths::in_dir(self.ca_dir.clone()).cert
+    }
+}
+
+/// seconds between heartbeats and without anything received before a connection is
+/// considered dead.  Either falls back to the [Heartbeat] default when not set
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct HeartbeatConfig {
+    #[serde(default)]
+    pub interval: Option<u64>,
+    #[serde(default)]
+    pub idle_timeout: Option<u64>,
+}
+
+impl HeartbeatConfig {
+    pub fn heartbeat(&self) -> Heartbeat {
+        Heartbeat::from_secs(self.interval, self.idle_timeout)
+    }
+}
+
+/// the certificate presented by the control port. Unless `cert` and `key` point to a
+/// certificate managed elsewhere, starlane generates one in [server_certs_dir] (issued by
+/// the authority in `ca_dir` when set) which `starlane certs rotate` replaces
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TlsConfig {
+    #[serde(default)]
+    pub cert: Option<String>,
+    #[serde(default)]
+    pub key: Option<String>,
+    #[serde(default = "TlsConfig::default_subject_alt_names")]
+    pub subject_alt_names: Vec<String>,
+    #[serde(default)]
+    pub ca_dir: Option<String>,
+    #[serde(default = "TlsConfig::default_valid_days")]
+    pub valid_days: u32,
+    /// how many days before expiry the server starts warning in its logs
+    #[serde(default = "TlsConfig::default_warn_days")]
+    pub warn_days: u32,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        Self {
+            cert: None,
+            key: None,
+            subject_alt_names: Self::default_subject_alt_names(),
+            ca_dir: None,
+            valid_days: Self::default_valid_days(),
+            warn_days: Self::default_warn_days(),
+        }
+    }
+}
+
+impl TlsConfig {
+    fn default_subject_alt_names() -> Vec<String> {
+        vec!["localhost".to_string()]
+    }
+
+    fn default_valid_days() -> u32 {
+        365
+    }
+
+    fn default_warn_days() -> u32 {
+        30
+    }
+
+    /// true when starlane generates and rotates the certificate itself
+    pub fn is_managed(&self) -> bool {
+        self.cert.is_none() && self.key.is_none()
+    }
+
+    pub fn paths(&self) -> Result<CertPaths, SpaceErr> {
+        match (self.cert.as_ref(), self.key.as_ref()) {
+            (None, None) => Ok(CertPaths::in_dir(server_certs_dir())),
+            (Some(cert), Some(key)) => Ok(CertPaths::new(cert, key)),
+            _ => Err(SpaceErr::server_error(
+                "tls config must set both 'cert' and 'key' or neither",
+            )),
+        }
+    }
+
+    /// generates the certificate if it is managed and missing
+    pub async fn ensure(&self) -> Result<CertPaths, SpaceErr> {
+        let paths = self.paths()?;
+        if self.is_managed() && !paths.exists() {
+            self.generate().await?;
+        }
+        Ok(paths)
+    }
+
+    /// replaces a managed certificate with a newly generated one
+    pub async fn rotate(&self) -> Result<CertInfo, SpaceErr> {
+        if !self.is_managed() {
+            return Err(SpaceErr::server_error(format!(
+                "certificate '{}' is not managed by starlane and cannot be rotated",
+                self.paths()?.cert
+            )));
+        }
+        self.generate().await
+    }
+
+    async fn generate(&self) -> Result<CertInfo, SpaceErr> {
+        let ca = match self.ca_dir.as_ref() {
+            None => None,
+            Some(ca_dir) => Some(
+                CertGenerator::read_from_dir(ca_dir.clone())
+                    .await
+                    .map_err(|err| SpaceErr::server_error(err.to_string()))?,
+            ),
+        };
+        let cert =
+            CertGenerator::gen_server(self.subject_alt_names.clone(), self.valid_days, ca.as_ref())
+                .map_err(|err| SpaceErr::server_error(err.to_string()))?;
+
+        let dir = server_certs_dir();
+        // the server cert is written as PEM so drop any cert left by older versions
+        for old in ["cert.der", "key.der"] {
+            let _ = fs::remove_file(format!("{}/{}", dir, old));
+        }
+        cert.write_pem(&CertPaths::in_dir(dir.clone())).await?;
+
+        // clients trust `ca.pem` over the server cert when it is present
+        let ca_pem = format!("{}/ca.pem", dir);
+        match ca.as_ref() {
+            None => {
+                let _ = fs::remove_file(ca_pem);
+            }
+            Some(ca) => fs::write(ca_pem, ca.certs())?,
+        }
+
+        cert.info().map_err(|err| SpaceErr::server_error(err.to_string()))
+    }
 }
 
 impl PlatformConfig for StarlaneConfig {
@@ -74,8 +331,16 @@ impl Default for StarlaneConfig {
             home: STARLANE_HOME.to_string(),
             can_nuke: false,
             can_scorch: false,
-            control_port: 4343u16,
+            control_port: STARLANE_CONTROL_PORT.clone(),
             registry: PgRegistryConfig::default(),
+            client_auth: None,
+            cluster: None,
+            tls: TlsConfig::default(),
+            heartbeat: HeartbeatConfig::default(),
+            bind_address: Self::default_bind_address(),
+            services: vec![],
+            capture_agents: vec![],
+            topology: None,
         }
     }
 }
@@ -86,6 +351,7 @@ pub struct Starlane {
     artifacts: Artifacts,
     registry: Registry,
     foundation: StandAloneFoundation,
+    db: Database<PostgresConnectInfo>,
 }
 
 /*
@@ -105,13 +371,41 @@ impl Into<Database<PostgresConnectInfo>> for Database<PgEmbedSettings> {
 
  */
 
+/// only one of the cluster in `config.yaml` and `topology.yaml` may declare the stars
+fn stars_declared_twice() -> anyhow::Error {
+    anyhow!(
+        "the cluster in '{}' and the topology in '{}' cannot both declare this machine's stars",
+        config_path(),
+        topology_path()
+    )
+}
+
 #[cfg(feature = "postgres")]
 impl Starlane {
     pub async fn new(
-        config: StarlaneConfig,
+        mut config: StarlaneConfig,
         foundation: StandAloneFoundation,
     ) -> Result<Starlane, HypErr> {
-        let artifacts = Artifacts::just_builtins();
+        // the topology is kept beside the config and read whether or not there is one
+        config.topology = topology()?;
+        if config.topology.is_some() && config.cluster.is_some() {
+            Err(stars_declared_twice())?;
+        }
+        // the stars of other machines are only admitted with a verified client certificate
+        if config.cluster.is_some() && config.client_auth.is_none() {
+            Err(anyhow!(
+                "the cluster in '{}' requires 'client_auth' so the other machines can be verified",
+                config_path()
+            ))?;
+        }
+
+        let services = MachineTemplate::default()
+            .with_services(config.service_templates()?)
+            .services;
+        let mut artifacts = ArtifactsBuilder::new();
+        artifacts.push(BUILTIN_FETCHER.clone());
+        artifacts.push(Arc::new(FileStoreArtifactFetcher::new(services)));
+        let artifacts = artifacts.build();
 
         let db = match config.clone().registry {
             PgRegistryConfig::Embedded(db) => {
@@ -134,6 +428,7 @@ impl Starlane {
         let mut set = HashSet::new();
         set.insert(db.database.clone());
         let ctx = Arc::new(PostgresRegistryContext::new(set, Box::new(lookups.clone())).await?);
+        let database = db.database.clone();
         let handle = PostgresRegistryContextHandle::new(&db.database, ctx, db.handle);
 
         let logger = logger!(&Point::global_registry());
@@ -146,6 +441,7 @@ impl Starlane {
             registry,
             artifacts,
             foundation,
+            db: database,
         })
     }
 }
@@ -166,8 +462,8 @@ where
 {
     type Err = HypErr;
 
-    type StarAuth = AnonHyperAuthenticator;
-    type RemoteStarConnectionFactory = LocalHyperwayGateJumper;
+    type StarAuth = CertHyperAuthenticator<TokenStoreHyperAuthenticator<AnonHyperAuthenticator>>;
+    type RemoteStarConnectionFactory = HyperlaneTcpClient;
 
     type Foundation = StandAloneFoundation;
 
@@ -178,22 +474,58 @@ where
     }
 
     fn star_auth(&self, star: &StarKey) -> Result<Self::StarAuth, Self::Err> {
-        Ok(AnonHyperAuthenticator::new())
+        Ok(CertHyperAuthenticator::new(
+            self.config.cert_agents(),
+            TokenStoreHyperAuthenticator::new(
+                Some(TokenStore::new(tokens_path())),
+                AnonHyperAuthenticator::new(),
+            ),
+        ))
+    }
+
+    fn cluster_machines(&self) -> HashSet<MachineName> {
+        match self.config.cluster.as_ref() {
+            None => HashSet::new(),
+            Some(cluster) => cluster
+                .machines
+                .iter()
+                .map(|machine| machine.name.clone())
+                .collect(),
+        }
+    }
+
+    fn capture_agents(&self) -> HashSet<Point> {
+        self.config.capture_agents.iter().cloned().collect()
     }
 
     fn remote_connection_factory_for_star(
         &self,
-        star: &StarKey,
+        from: &StarKey,
+        to: &StarKey,
     ) -> Result<Self::RemoteStarConnectionFactory, Self::Err> {
-        todo!()
+        let logger = push_loc!((self.logger(), from.to_point()));
+        Ok(self
+            .config
+            .remote_connection_factory_for_star(from, to, logger)?)
     }
 
-    fn machine_template(&self) -> MachineTemplate {
-        MachineTemplate::default()
+    fn machine_template(&self) -> Result<MachineTemplate, Self::Err> {
+        let template = match (self.config.cluster.as_ref(), self.config.topology.as_ref()) {
+            (Some(_), Some(_)) => Err(stars_declared_twice())?,
+            (Some(cluster), None) => {
+                MachineTemplate::cluster(cluster.stars.clone(), cluster.remote_stars())
+            }
+            (None, Some(topology)) => MachineTemplate::topology(topology)?,
+            (None, None) => MachineTemplate::default(),
+        };
+        Ok(template.with_services(self.config.service_templates()?))
     }
 
     fn machine_name(&self) -> MachineName {
-        "singularity".to_string()
+        match self.config.cluster.as_ref() {
+            None => "singularity".to_string(),
+            Some(cluster) => cluster.name.clone(),
+        }
     }
 
     fn drivers_builder(&self, kind: &StarSub) -> DriversBuilder {
@@ -215,26 +547,48 @@ where
             }
             StarSub::Nexus => {}
             StarSub::Maelstrom => {
-                /*                builder.add_post(Arc::new(HostDriverFactory::new()));
-                               builder.add_post(Arc::new(MechtronDriverFactory::new()));
-
-                */
+                builder.add_post(Arc::new(HostDriverFactory::new()));
+                builder.add_post(Arc::new(MechtronDriverFactory::new()));
             }
             StarSub::Scribe => {
-                /*builder.add_post(Arc::new(RepoDriverFactory::new()));
+                builder.add_post(Arc::new(RepoDriverFactory::new()));
                 builder.add_post(Arc::new(BundleSeriesDriverFactory::new()));
                 builder.add_post(Arc::new(BundleDriverFactory::new()));
                 builder.add_post(Arc::new(ArtifactDriverFactory::new()));
-
-                 */
+                builder.add_post(Arc::new(FileStoreDriverFactory::new(DriverAvail::External)));
+                builder.add_post(Arc::new(FileDriverFactory::new()));
             }
             StarSub::Jump => {
-                //builder.add_post(Arc::new(WebDriverFactory::new()));
+                let portals =
+                    PortalDriverFactory::new_with_cert_agents(self.config.cert_agents())
+                        .with_tokens(TokenStore::new(tokens_path()));
+                let remotes = portals.remotes();
+                builder.add_post(Arc::new(portals));
+                builder.add_post(Arc::new(remotes));
+                let addr = self
+                    .web_host()
+                    .and_then(|host| Ok(SocketAddr::new(host, self.web_port()?)));
+                match addr {
+                    Ok(addr) => builder.add_post(Arc::new(WebServerDriverFactory::new(addr))),
+                    Err(err) => self.logger().error(format!(
+                        "WebServer driver not available: {}",
+                        err.to_string()
+                    )),
+                }
                 // builder.add_post(Arc::new(ControlDriverFactory::new()));
             }
-            StarSub::Fold => {}
+            StarSub::Fold => {
+                builder.add_post(Arc::new(DatabaseDriverFactory::new(self.db.clone())));
+                builder.add_post(Arc::new(UserBaseDriverFactory::new(TokenStore::new(
+                    tokens_path(),
+                ))));
+                builder.add_post(Arc::new(UserDriverFactory::new()));
+            }
             StarSub::Machine => {
-                builder.add_post(Arc::new(ControlDriverFactory::new()));
+                builder.add_post(Arc::new(
+                    ControlDriverFactory::new_with_cert_agents(self.config.cert_agents())
+                        .with_tokens(TokenStore::new(tokens_path())),
+                ));
             }
         }
 
@@ -254,38 +608,46 @@ where
     }
 
     async fn start_services(&self, gate: &Arc<HyperGateSelector>) {
-        let dir = match dirs::home_dir() {
-            None => ".starlane/localhost/certs".to_string(),
-            Some(path) => format!("{}/.starlane/localhost/certs", path.display()),
-        };
-        fs::create_dir_all(dir.as_str());
-
-        let cert = format!("{}/cert.der", dir.as_str());
-        let key = format!("{}/key.der", dir.as_str());
-        let cert_path = Path::new(&cert);
-        let key_path = Path::new(&key);
-
-        if !cert_path.exists() || !key_path.exists() {
-            CertGenerator::gen(vec!["localhost".to_string()])
-                .unwrap()
-                .write_to_dir(dir.clone())
-                .await
-                .unwrap();
+        let cert = match self.config.tls.ensure().await {
+            Ok(cert) => cert,
+            Err(err) => {
+                panic_shutdown(format!(
+                    "could not load the control port certificate: {}",
+                    err.to_string()
+                ));
+                return;
+            }
         };
 
         let logger = push_loc!((self.logger(), Point::from_str("control-blah").unwrap()));
 
-        if !is_local_ipv4_port_free(STARLANE_CONTROL_PORT.clone()) {
+        let addrs = self.config.control_addrs();
+        let in_use = addrs_in_use(&addrs);
+        if !in_use.is_empty() {
             panic_shutdown(format!(
-                "starlane port '{}' is being used by another process",
-                STARLANE_CONTROL_PORT.to_string()
+                "starlane control address(es) '{}' are being used by another process",
+                in_use
+                    .iter()
+                    .map(|addr| addr.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
             ));
         }
 
-        let server =
-            HyperlaneTcpServer::new(STARLANE_CONTROL_PORT.clone(), dir, gate.clone(), logger)
-                .await
-                .unwrap();
+        let client_ca = self.config.client_auth.as_ref().map(|auth| auth.ca_cert());
+
+        watch_cert_expiry(cert.clone(), self.config.tls.warn_days, logger.clone());
+
+        let server = HyperlaneTcpServer::bind(
+            addrs,
+            cert,
+            client_ca,
+            gate.clone(),
+            logger,
+        )
+        .await
+        .unwrap()
+        .with_heartbeat(self.config.heartbeat.heartbeat());
         server.start().unwrap();
     }
 
@@ -302,6 +664,42 @@ where
     }
 }
 
+/// checks the control port certificate daily and warns once it is within `warn_days`
+/// of expiring. The certificate is re-read each time so a rotation silences the warning
+fn watch_cert_expiry(cert: CertPaths, warn_days: u32, logger: Logger) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(24 * 60 * 60));
+        loop {
+            interval.tick().await;
+            let info = match CertGenerator::load(&cert).await.and_then(|c| c.info()) {
+                Ok(info) => info,
+                Err(err) => {
+                    logger.warn(format!(
+                        "could not check expiry of certificate '{}': {}",
+                        cert.cert,
+                        err.to_string()
+                    ));
+                    continue;
+                }
+            };
+            if info.is_expired() {
+                logger.error(format!(
+                    "certificate '{}' expired on {}. run `starlane certs rotate`",
+                    cert.cert,
+                    info.not_after.to_rfc3339()
+                ));
+            } else if info.remaining() <= chrono::Duration::days(warn_days as i64) {
+                logger.warn(format!(
+                    "certificate '{}' expires in {} days on {}. run `starlane certs rotate`",
+                    cert.cert,
+                    info.remaining().num_days(),
+                    info.not_after.to_rfc3339()
+                ));
+            }
+        }
+    });
+}
+
 #[cfg(feature = "postgres")]
 #[derive(Clone)]
 pub struct PostgresLookups(PgRegistryConfig);
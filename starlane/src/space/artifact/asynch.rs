@@ -375,6 +375,7 @@ where
 
 pub struct ArtifactHub {
     skel: ArtifactsSkel,
+    fetcher: Arc<dyn ArtifactFetcher>,
     pub bind: ArtifactCache<BindConfig>,
     pub mechtron: ArtifactCache<MechtronConfig>,
     pub selector: PointSelector,
@@ -385,6 +386,7 @@ impl ArtifactHub {
         ArtifactHub {
             bind: ArtifactCache::new(fetcher.clone(), skel.clone()),
             mechtron: ArtifactCache::new(fetcher.clone(), skel.clone()),
+            fetcher,
             skel,
             selector: PointSelector::always(),
         }
@@ -397,6 +399,11 @@ impl ArtifactHub {
     pub async fn mechtron_conf(&self, point: &Point) -> Result<ArtRef<MechtronConfig>, ArtErr> {
         self.mechtron.get(point).await
     }
+
+    /// the artifact's bytes as stored, for artifacts that are not text configs (i.e. wasm)
+    pub async fn raw(&self, point: &Point) -> Result<Arc<Bin>, ArtErr> {
+        self.fetcher.fetch(point).await
+    }
 }
 
 pub struct ArtifactsBuilder {
@@ -407,6 +414,17 @@ impl ArtifactsBuilder {
     pub fn new() -> Self {
         Self { fetchers: vec![] }
     }
+
+    /// each fetcher gets its own hub; hubs are consulted in the order the
+    /// fetchers were added
+    pub fn build(self) -> Artifacts {
+        let hubs = self
+            .fetchers
+            .into_iter()
+            .map(|fetcher| Arc::new(ArtifactHub::new(fetcher, ArtifactsSkel::default())))
+            .collect();
+        Artifacts { hubs }
+    }
 }
 
 impl Deref for ArtifactsBuilder {
@@ -440,6 +458,8 @@ impl Artifacts {
             if hub.selector.is_match(point).is_ok() {
                 match hub.bind.get(point).await {
                     Ok(art) => return Ok(art),
+                    // give the next hub a chance to find it
+                    Err(ArtErr::NotFound(_)) => {}
                     Err(err) => {
                         return Err(err);
                     }
@@ -456,7 +476,7 @@ impl Artifacts {
         for hub in &self.hubs {
             match hub.mechtron.get(point).await {
                 Ok(art) => return Some(Ok(art)),
-                Err(ArtErr::NotFound(_)) => return None,
+                Err(ArtErr::NotFound(_)) => {}
                 Err(err) => {
                     return Some(Err(err));
                 }
@@ -464,6 +484,19 @@ impl Artifacts {
         }
         None
     }
+
+    pub async fn get_raw(&self, point: &Point) -> Result<Arc<Bin>, ArtErr> {
+        for hub in &self.hubs {
+            match hub.raw(point).await {
+                Ok(bin) => return Ok(bin),
+                Err(ArtErr::NotFound(_)) => {}
+                Err(err) => {
+                    return Err(err);
+                }
+            }
+        }
+        Err(ArtErr::NotFound(point.clone()))
+    }
 }
 
 pub struct FetchChamber {
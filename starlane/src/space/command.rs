@@ -1023,6 +1023,8 @@ pub enum Command {
     Get(Get),
     Write(Write),
     Read(Read),
+    /// the status history of the drivers on every star the executing star can reach
+    Drivers,
 }
 
 impl ChildSubstance for Command {}
@@ -1049,6 +1051,7 @@ pub enum CommandCtx {
     Get(GetCtx),
     Update(WriteCtx),
     Read(ReadCtx),
+    Drivers,
 }
 
 pub enum CommandVar {
@@ -1059,6 +1062,7 @@ pub enum CommandVar {
     Get(GetVar),
     Update(WriteVar),
     Read(ReadVar),
+    Drivers,
 }
 
 impl FromStr for CommandVar {
@@ -1087,6 +1091,7 @@ impl ToResolved<CommandCtx> for CommandVar {
             CommandVar::Delete(i) => CommandCtx::Delete(i.to_resolved(env)?),
             CommandVar::Update(update) => CommandCtx::Update(update.to_resolved(env)?),
             CommandVar::Read(read) => CommandCtx::Read(read.to_resolved(env)?),
+            CommandVar::Drivers => CommandCtx::Drivers,
         })
     }
 }
@@ -1101,6 +1106,7 @@ impl ToResolved<Command> for CommandCtx {
             CommandCtx::Delete(i) => Command::Delete(i.to_resolved(env)?),
             CommandCtx::Update(update) => Command::Write(update.to_resolved(env)?),
             CommandCtx::Read(read) => Command::Read(read.to_resolved(env)?),
+            CommandCtx::Drivers => Command::Drivers,
         })
     }
 }
@@ -190,6 +190,36 @@ pub struct Assign {
     pub state: StateSrc,
 }
 
+/// tells the driver hosting a particle that it is being deleted so anything
+/// it keeps outside of the registry can be released
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Retract {
+    pub details: Details,
+}
+
+impl Retract {
+    pub fn new(details: Details) -> Self {
+        Self { details }
+    }
+
+    pub fn kind(&self) -> &Kind {
+        &self.details.stub.kind
+    }
+}
+
+impl Into<Substance> for Retract {
+    fn into(self) -> Substance {
+        Substance::Hyper(HyperSubstance::Retract(self))
+    }
+}
+
+impl Into<DirectedCore> for Retract {
+    fn into(self) -> DirectedCore {
+        DirectedCore::new(HypMethod::Retract.into())
+            .with_body(Substance::Hyper(HyperSubstance::Retract(self)))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub struct HostCmd {
     pub kind: AssignmentKind,
@@ -249,6 +279,7 @@ pub enum HyperSubstance {
     Empty,
     Provision(Provision),
     Assign(Assign),
+    Retract(Retract),
     Host(HostCmd),
     Event(HyperEvent),
     Log(Log),
@@ -262,6 +293,7 @@ impl HyperSubstance {
             HyperSubstance::Empty => HyperSubstanceKind::Empty,
             HyperSubstance::Provision(_) => HyperSubstanceKind::Provision,
             HyperSubstance::Assign(_) => HyperSubstanceKind::Assign,
+            HyperSubstance::Retract(_) => HyperSubstanceKind::Retract,
             HyperSubstance::Host(_) => HyperSubstanceKind::Host,
             HyperSubstance::Event(_) => HyperSubstanceKind::Event,
             HyperSubstance::Log(_) => HyperSubstanceKind::Log,
@@ -286,6 +318,7 @@ pub enum HyperSubstanceKind {
     Empty,
     Provision,
     Assign,
+    Retract,
     Host,
     Event,
     Log,
@@ -396,6 +429,8 @@ pub enum InterchangeKind {
     Control(ControlPattern),
     Portal(ControlPattern),
     Star(StarKey),
+    /// streams a copy of the waves routed by the machine's stars
+    Capture,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, strum_macros::Display, Hash)]
@@ -409,6 +444,10 @@ pub struct Knock {
     pub kind: InterchangeKind,
     pub auth: Box<Substance>,
     pub remote: Option<Surface>,
+    /// the verified client certificate of the connection this `Knock` arrived on.
+    /// only ever set by the receiving server, it is never read from the wire
+    #[serde(skip)]
+    pub cert: Option<ClientCert>,
 }
 
 impl Knock {
@@ -417,6 +456,7 @@ impl Knock {
             kind,
             remote: Some(remote),
             auth: Box::new(auth),
+            cert: None,
         }
     }
 }
@@ -427,7 +467,31 @@ impl Default for Knock {
             kind: InterchangeKind::DefaultControl,
             auth: Box::new(Substance::Empty),
             remote: None,
+            cert: None,
+        }
+    }
+}
+
+/// identity names extracted from a client certificate that passed TLS verification
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ClientCert {
+    pub subject: Option<String>,
+    pub sans: Vec<String>,
+}
+
+impl ClientCert {
+    pub fn new(subject: Option<String>, sans: Vec<String>) -> Self {
+        Self { subject, sans }
+    }
+
+    /// the subject common name followed by every subject alternative name
+    pub fn names(&self) -> Vec<String> {
+        let mut names = vec![];
+        if let Some(subject) = self.subject.as_ref() {
+            names.push(subject.clone());
         }
+        names.extend(self.sans.iter().cloned());
+        names
     }
 }
 
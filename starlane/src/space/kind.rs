@@ -133,6 +133,7 @@ pub enum BaseKind {
     Artifact,
     Control,
     Portal,
+    Remote,
     Star,
     Driver,
     Global,
@@ -297,6 +298,7 @@ pub enum Kind {
     Bundle,
     Control,
     Portal,
+    Remote,
     Driver,
     #[strum(to_string = "File<{0}>")]
     File(FileSubKind),
@@ -328,6 +330,7 @@ impl ToBaseKind for Kind {
             Kind::Bundle => BaseKind::Bundle,
             Kind::Control => BaseKind::Control,
             Kind::Portal => BaseKind::Portal,
+            Kind::Remote => BaseKind::Remote,
             Kind::UserBase(_) => BaseKind::UserBase,
             Kind::File(_) => BaseKind::File,
             Kind::Artifact(_) => BaseKind::Artifact,
@@ -426,6 +429,7 @@ impl Kind {
         match self {
             Kind::Mechtron => &MECHTRON_WAVE_TRAVERSAL_PLAN,
             Kind::Portal => &PORTAL_WAVE_TRAVERSAL_PLAN,
+            Kind::Remote => &PORTAL_WAVE_TRAVERSAL_PLAN,
             Kind::Control => &CONTROL_WAVE_TRAVERSAL_PLAN,
             Kind::Star(_) => &STAR_WAVE_TRAVERSAL_PLAN,
             _ => &STD_WAVE_TRAVERSAL_PLAN,
@@ -455,6 +459,7 @@ impl TryFrom<KindParts> for Kind {
             }
             BaseKind::UserBase => {
                 match value.sub.ok_or("UserBase<?> requires a Sub Kind")?.as_str() {
+                    "Local" => Kind::UserBase(UserBaseSubKind::Local),
                     "OAuth" => Kind::UserBase(UserBaseSubKind::OAuth(
                         value
                             .specific
@@ -462,7 +467,7 @@ impl TryFrom<KindParts> for Kind {
                     )),
                     what => {
                         return Err(ParseErrs::from(format!(
-                            "unexpected Database SubKind '{}'",
+                            "unexpected UserBase SubKind '{}'",
                             what
                         )));
                     }
@@ -491,6 +496,7 @@ impl TryFrom<KindParts> for Kind {
             BaseKind::Bundle => Kind::Bundle,
             BaseKind::Control => Kind::Control,
             BaseKind::Portal => Kind::Portal,
+            BaseKind::Remote => Kind::Remote,
             BaseKind::Repo => Kind::Repo,
             BaseKind::Driver => Kind::Driver,
             BaseKind::Global => Kind::Global,
@@ -595,22 +601,27 @@ impl Into<Option<String>> for StarSub {
     Serialize,
     Deserialize,
     strum_macros::Display,
+    strum_macros::EnumString,
     strum_macros::EnumIter,
 )]
 pub enum UserBaseSubKindBase {
     OAuth,
+    Local,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, strum_macros::Display)]
 pub enum UserBaseSubKind {
     #[strum(to_string = "OAuth<{0}>")]
     OAuth(Specific),
+    /// users and their password hashes are kept in the registry
+    Local,
 }
 
 impl UserBaseSubKind {
     pub fn specific(&self) -> Option<&Specific> {
         match self {
             UserBaseSubKind::OAuth(specific) => Option::Some(specific),
+            UserBaseSubKind::Local => None,
         }
     }
 }
@@ -706,6 +717,22 @@ impl Into<Option<String>> for ArtifactSubKind {
     }
 }
 
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Hash,
+    Serialize,
+    Deserialize,
+    strum_macros::Display,
+    strum_macros::EnumString,
+    strum_macros::EnumIter,
+)]
+pub enum DatabaseSubKindBase {
+    Relational,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, strum_macros::Display)]
 pub enum DatabaseSubKind {
     #[strum(to_string = "Relational<{0}>")]
@@ -2267,7 +2267,7 @@ pub fn publish<I: Span>(input: I) -> Res<I, CreateVar> {
 
     let create = CreateVar {
         template,
-        state: StateSrcVar::None,
+        state: StateSrcVar::FileRef(upload.name),
         properties: Default::default(),
         strategy: Strategy::Commit,
     };
@@ -5238,6 +5238,10 @@ fn get_command<I: Span>(input: I) -> Res<I, CommandVar> {
     tuple((tag("get"), space1, get))(input).map(|(next, (_, _, get))| (next, CommandVar::Get(get)))
 }
 
+fn drivers_command<I: Span>(input: I) -> Res<I, CommandVar> {
+    tag("drivers")(input).map(|(next, _)| (next, CommandVar::Drivers))
+}
+
 pub fn command_strategy<I: Span>(input: I) -> Res<I, Strategy> {
     opt(tuple((tag("?"), multispace0)))(input).map(|(next, hint)| match hint {
         None => (next, Strategy::Commit),
@@ -5254,6 +5258,7 @@ pub fn command<I: Span>(input: I) -> Res<I, CommandVar> {
             select_command,
             set_command,
             get_command,
+            drivers_command,
             fail,
         )),
     )(input)
@@ -5422,7 +5427,8 @@ pub mod cmd_test {
     use crate::space::{BaseKind, KindTemplate};
 
     use crate::space::parse::{
-        command, create_command, point_selector, publish_command, script, upload_blocks, CamelCase,
+        command, command_line, create_command, point_selector, publish_command, script,
+        upload_blocks, CamelCase,
     };
     /*
     #[mem]
@@ -5489,6 +5495,15 @@ pub mod cmd_test {
         Ok(())
     }
 
+    #[test]
+    pub fn test_drivers() -> Result<(), ParseErrs> {
+        let (_, command) = command_line(new_span("drivers;"))?;
+        match command {
+            CommandVar::Drivers => Ok(()),
+            _ => panic!("expected drivers command"),
+        }
+    }
+
     #[test]
     pub fn test_publish() -> Result<(), ParseErrs> {
         let input = r#"publish ^[ bundle.zip ]-> localhost:repo:tutorial:1.0.0"#;
@@ -6009,11 +6024,13 @@ pub fn resolve_kind<I: Span>(lex: KindLex) -> impl FnMut(I) -> Res<I, Kind> {
             BaseKind::Bundle => Ok((input, Kind::Bundle)),
             BaseKind::Control => Ok((input, Kind::Control)),
             BaseKind::Portal => Ok((input, Kind::Portal)),
+            BaseKind::Remote => Ok((input, Kind::Remote)),
             BaseKind::Repo => Ok((input, Kind::Repo)),
             BaseKind::Driver => Ok((input, Kind::Driver)),
             BaseKind::Global => Ok((input, Kind::Global)),
             BaseKind::Host => Ok((input, Kind::Host)),
             BaseKind::Guest => Ok((input, Kind::Guest)),
+            BaseKind::WebServer => Ok((input, Kind::WebServer)),
             _ => {
                 if lex.sub.is_none() {
                     let err = SpaceTree::from_error_kind(input.clone(), ErrorKind::Fail);
@@ -6051,6 +6068,7 @@ pub fn resolve_kind<I: Span>(lex: KindLex) -> impl FnMut(I) -> Res<I, Kind> {
                         }
                     },
                     BaseKind::UserBase => match sub.as_str() {
+                        "Local" => Ok((input, Kind::UserBase(UserBaseSubKind::Local))),
                         "OAuth" => match lex.specific.as_ref() {
                             Some(specific) => {
                                 return Ok((
@@ -6146,6 +6164,7 @@ pub fn resolve_sub<I: Span>(base: BaseKind) -> impl FnMut(I) -> Res<I, Sub> {
                 }
             },
             BaseKind::UserBase => match sub.as_str() {
+                "Local" => Ok((next, Sub::UserBase(UserBaseSubKind::Local))),
                 "OAuth" => {
                     let (next, specific) =
                         context("specific", delimited(tag("<"), specific, tag(">")))(next)?;
@@ -535,7 +535,7 @@ impl Ord for IndexedAccessGrant {
     fn cmp(&self, other: &Self) -> Ordering {
         if self.id < other.id {
             Ordering::Greater
-        } else if self.id < other.id {
+        } else if self.id > other.id {
             Ordering::Less
         } else {
             Ordering::Equal
@@ -561,7 +561,7 @@ impl PartialOrd<Self> for IndexedAccessGrant {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         if self.id < other.id {
             Some(Ordering::Greater)
-        } else if self.id < other.id {
+        } else if self.id > other.id {
             Some(Ordering::Less)
         } else {
             Some(Ordering::Equal)
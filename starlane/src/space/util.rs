@@ -441,11 +441,11 @@ where
 }
 
 pub fn uuid() -> Uuid {
-    todo!()
+    Uuid::from_unwrap(uuid::Uuid::new_v4())
 }
 
 pub fn timestamp() -> Timestamp {
-    todo!()
+    Timestamp::new(chrono::Utc::now().timestamp_millis())
 }
 
 
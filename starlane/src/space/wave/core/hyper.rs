@@ -16,6 +16,7 @@ use crate::space::util::ValueMatcher;
 pub enum HypMethod {
     Init,
     Assign,
+    Retract,
     Host,
     Provision,
     Knock,